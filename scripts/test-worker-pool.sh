@@ -0,0 +1,6 @@
+#!/usr/bin/env bash
+
+set -e
+
+echo "*** Testing worker pool on headless chrome"
+node ./tests/workerPool/run.js
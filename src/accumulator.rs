@@ -0,0 +1,475 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Revocation with a positive dynamic accumulator (Vitto & Biryukov).
+//!
+//! The accumulator keys are a BLS 12-381 G2 key pair `(alpha, Q = P^alpha)`. The
+//! accumulator of the set of revocation ids `y` is `V = V_0^prod(y + alpha)` in G1 and
+//! the witness of `y` is `C = V^(1 / (y + alpha))` such that `e(C, P^y * Q) = e(V, P)`.
+//!
+//! The issuer publishes each batch of additions and removals with its update
+//! polynomials, so holders update their witnesses for the whole batch at once. For the
+//! additions `y_1..y_m` to `V`, the witness of `x` becomes `C^d_A(x) * V^v_A(x)` with
+//! `d_A(x) = prod(y_i - x)` and `v_A(x) = sum_s prod_{i<s}(y_i + alpha) prod_{j>s}(y_j - x)`.
+//! For the removals `y_1..y_n` from `V`, it becomes `(C * V^-v_D(x))^(1 / d_D(x))` with
+//! `d_D(x) = prod(y_i - x)` and `v_D(x) = sum_s prod_{i<s}(y_i - x) / prod_{j<=s}(y_j + alpha)`.
+//! The update polynomials are `V^a_k` for the coefficients `a_k` of `v_A` and `v_D` in
+//! `x`, which do not reveal `alpha`.
+//!
+//! Membership is proven in zero knowledge for a hidden signed revocation id by the
+//! `accumulator` option of `createProof`, which randomises the witness `C' = C^r` and
+//! `V' = C'^-y * V^r` so that `e(C', Q) = e(V', P)`, with a proof of knowledge of `y`
+//! and `r` linked to the proof of knowledge of the signature.
+
+use crate::linked_proof;
+use crate::numeric_message::message_scalar;
+use crate::statements::{
+    write_point, write_scalar, ProverStatement, StatementReader, VerifierStatement,
+};
+use crate::utils::{pairing_product_is_one, secret_key_to_fr, set_panic_hook};
+
+use bbs::prelude::*;
+use ff_zeroize::Field;
+use pairing_plus::{
+    bls12_381::{Fr, G1, G2},
+    CurveProjective,
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, convert::TryInto};
+use wasm_bindgen::prelude::*;
+
+wasm_impl!(
+    AccumulatorUpdateRequest,
+    secretKey: SecretKey,
+    accumulator: Commitment,
+    additions: Vec<Vec<u8>>,
+    removals: Vec<Vec<u8>>
+);
+
+wasm_impl!(
+    /// A batch of additions and removals with its update polynomials, the additions
+    /// are applied before the removals
+    #[allow(non_snake_case)]
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    AccumulatorBatchUpdate,
+    additions: Vec<SignatureMessage>,
+    removals: Vec<SignatureMessage>,
+    additionPolynomial: Vec<Commitment>,
+    removalPolynomial: Vec<Commitment>
+);
+
+wasm_impl!(
+    AccumulatorUpdateResponse,
+    accumulator: Commitment,
+    update: AccumulatorBatchUpdate,
+    witnesses: Vec<Commitment>
+);
+
+wasm_impl!(
+    AccumulatorWitnessRequest,
+    secretKey: SecretKey,
+    accumulator: Commitment,
+    element: Vec<u8>
+);
+
+wasm_impl!(
+    AccumulatorVerifyWitnessRequest,
+    publicKey: DeterministicPublicKey,
+    accumulator: Commitment,
+    element: Vec<u8>,
+    witness: Commitment
+);
+
+wasm_impl!(
+    AccumulatorUpdateWitnessRequest,
+    element: Vec<u8>,
+    witness: Commitment,
+    updates: Vec<AccumulatorBatchUpdate>
+);
+
+wasm_impl!(
+    AccumulatorProofOptions,
+    revocationIndex: usize,
+    accumulator: Commitment,
+    witness: Commitment
+);
+
+wasm_impl!(
+    AccumulatorVerifyOptions,
+    revocationIndex: usize,
+    accumulator: Commitment,
+    publicKey: DeterministicPublicKey
+);
+
+/// Creates a new empty accumulator
+#[wasm_bindgen(js_name = accumulatorInitialize)]
+pub async fn accumulator_initialize() -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let accumulator = Commitment::from(*GeneratorG1::random().as_ref());
+    Ok(serde_wasm_bindgen::to_value(&accumulator).unwrap())
+}
+
+/// Adds and removes revocation ids from the accumulator, returning the new accumulator,
+/// the batch update to publish to holders and the witnesses of the added revocation ids
+#[wasm_bindgen(js_name = accumulatorUpdate)]
+pub async fn accumulator_update(request: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let request: AccumulatorUpdateRequest = request.try_into()?;
+    let alpha = map_err!(secret_key_to_fr(&request.secretKey))?;
+    let additions: Vec<SignatureMessage> = request.additions.iter().map(message_scalar).collect();
+    let removals: Vec<SignatureMessage> = request.removals.iter().map(message_scalar).collect();
+
+    let mut accumulator = *request.accumulator.as_ref();
+    let addition_polynomial =
+        update_polynomial(&accumulator, &addition_coefficients(&additions, &alpha));
+    for y in &additions {
+        accumulator.mul_assign(element_exponent(y, &alpha));
+    }
+    let removal_polynomial =
+        update_polynomial(&accumulator, &removal_coefficients(&removals, &alpha)?);
+    for y in &removals {
+        accumulator.mul_assign(inverse_element_exponent(y, &alpha)?);
+    }
+    let witnesses = additions
+        .iter()
+        .map(|y| membership_witness(&accumulator, y, &alpha))
+        .collect::<Result<Vec<Commitment>, JsValue>>()?;
+    Ok(serde_wasm_bindgen::to_value(&AccumulatorUpdateResponse {
+        accumulator: Commitment::from(accumulator),
+        update: AccumulatorBatchUpdate {
+            additions,
+            removals,
+            additionPolynomial: addition_polynomial,
+            removalPolynomial: removal_polynomial,
+        },
+        witnesses,
+    })
+    .unwrap())
+}
+
+/// Creates the witness of a revocation id in the accumulator
+#[wasm_bindgen(js_name = accumulatorMembershipWitness)]
+pub async fn accumulator_membership_witness(request: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let request: AccumulatorWitnessRequest = request.try_into()?;
+    let alpha = map_err!(secret_key_to_fr(&request.secretKey))?;
+    let witness = membership_witness(
+        request.accumulator.as_ref(),
+        &message_scalar(&request.element),
+        &alpha,
+    )?;
+    Ok(serde_wasm_bindgen::to_value(&witness).unwrap())
+}
+
+/// Checks the witness of a revocation id against the accumulator
+#[wasm_bindgen(js_name = accumulatorVerifyWitness)]
+pub async fn accumulator_verify_witness(request: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let request: AccumulatorVerifyWitnessRequest = request.try_into()?;
+    let y = message_scalar(&request.element);
+    let mut p = G2::one();
+    p.mul_assign(*y.as_ref());
+    p.add_assign(request.publicKey.as_ref());
+    let mut accumulator = *request.accumulator.as_ref();
+    accumulator.negate();
+    Ok(JsValue::from_bool(pairing_product_is_one(&[
+        (*request.witness.as_ref(), p),
+        (accumulator, G2::one()),
+    ])))
+}
+
+/// Updates the witness of a revocation id with the batch updates published since the
+/// witness was created, returning the witness for the latest accumulator
+#[wasm_bindgen(js_name = accumulatorUpdateWitness)]
+pub async fn accumulator_update_witness(request: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let request: AccumulatorUpdateWitnessRequest = request.try_into()?;
+    let x = message_scalar(&request.element);
+    let mut witness = *request.witness.as_ref();
+    for update in &request.updates {
+        if update.additionPolynomial.len() != update.additions.len()
+            || update.removalPolynomial.len() != update.removals.len()
+        {
+            return Err(JsValue::from("Invalid accumulator update"));
+        }
+        // C' = C^d_A(x) * V^v_A(x), d_A(x) is zero when the revocation id is added again
+        let d = difference_product(&update.additions, &x);
+        if d.is_zero() {
+            return Err(JsValue::from("The revocation id was added again"));
+        }
+        witness.mul_assign(d);
+        witness.add_assign(&evaluate_update_polynomial(&update.additionPolynomial, &x));
+
+        // C' = (C * V^-v_D(x))^(1 / d_D(x)), d_D(x) is zero when the revocation id is removed
+        let d = difference_product(&update.removals, &x)
+            .inverse()
+            .ok_or_else(|| JsValue::from("The revocation id was removed"))?;
+        witness.sub_assign(&evaluate_update_polynomial(&update.removalPolynomial, &x));
+        witness.mul_assign(d);
+    }
+    Ok(serde_wasm_bindgen::to_value(&Commitment::from(witness)).unwrap())
+}
+
+/// Proves the hidden revocation id is in the accumulator
+pub(crate) struct AccumulatorProver<'a> {
+    options: &'a AccumulatorProofOptions,
+    randomness: SignatureMessage,
+    randomness_blinding: ProofNonce,
+    witness: G1,
+    witness_product: G1,
+    commitment: G1,
+}
+
+impl<'a> AccumulatorProver<'a> {
+    pub(crate) fn new(options: &'a AccumulatorProofOptions) -> Self {
+        Self {
+            options,
+            randomness: SignatureMessage::random(),
+            randomness_blinding: ProofNonce::random(),
+            witness: G1::zero(),
+            witness_product: G1::zero(),
+            commitment: G1::zero(),
+        }
+    }
+}
+
+impl ProverStatement for AccumulatorProver<'_> {
+    fn linked(&self) -> Vec<usize> {
+        vec![self.options.revocationIndex]
+    }
+
+    fn commit(
+        &mut self,
+        messages: &[SignatureMessage],
+        blindings: &BTreeMap<usize, ProofNonce>,
+        bytes: &mut Vec<u8>,
+    ) -> Result<(), BBSError> {
+        // C' = C^r, V' = C'^-y * V^r
+        let index = self.options.revocationIndex;
+        let mut witness = *self.options.witness.as_ref();
+        witness.mul_assign(*self.randomness.as_ref());
+        let bases = membership_bases(&witness, self.options.accumulator.as_ref());
+        self.witness_product = linked_proof::multi_exp(&bases, &[messages[index], self.randomness]);
+        self.commitment = linked_proof::multi_exp(
+            &bases,
+            &[
+                SignatureMessage::from(*blindings[&index].as_ref()),
+                SignatureMessage::from(*self.randomness_blinding.as_ref()),
+            ],
+        );
+        self.witness = witness;
+        linked_proof::append_points(
+            bytes,
+            &[bases[0], bases[1], self.witness_product, self.commitment],
+        );
+        Ok(())
+    }
+
+    fn prove(&self, challenge: &ProofChallenge, proof: &mut Vec<u8>) {
+        write_point(proof, &self.witness);
+        write_point(proof, &self.witness_product);
+        write_point(proof, &self.commitment);
+        write_scalar(
+            proof,
+            &linked_proof::response(&self.randomness_blinding, challenge, &self.randomness),
+        );
+    }
+}
+
+/// Checks the hidden revocation id is in the accumulator
+pub(crate) struct AccumulatorVerifier<'a> {
+    options: &'a AccumulatorVerifyOptions,
+    bases: [G1; 2],
+    witness_product: G1,
+    commitment: G1,
+    randomness_response: SignatureMessage,
+}
+
+impl<'a> AccumulatorVerifier<'a> {
+    pub(crate) fn new(options: &'a AccumulatorVerifyOptions) -> Self {
+        Self {
+            options,
+            bases: [G1::zero(), G1::zero()],
+            witness_product: G1::zero(),
+            commitment: G1::zero(),
+            randomness_response: SignatureMessage::default(),
+        }
+    }
+}
+
+impl VerifierStatement for AccumulatorVerifier<'_> {
+    fn linked(&self) -> Vec<usize> {
+        vec![self.options.revocationIndex]
+    }
+
+    fn read(&mut self, reader: &mut StatementReader, bytes: &mut Vec<u8>) -> Result<(), BBSError> {
+        let witness = reader.point()?;
+        self.witness_product = reader.point()?;
+        self.commitment = reader.point()?;
+        self.randomness_response = reader.scalar()?;
+        self.bases = membership_bases(&witness, self.options.accumulator.as_ref());
+        linked_proof::append_points(
+            bytes,
+            &[
+                self.bases[0],
+                self.bases[1],
+                self.witness_product,
+                self.commitment,
+            ],
+        );
+        Ok(())
+    }
+
+    fn verify(
+        &self,
+        responses: &BTreeMap<usize, SignatureMessage>,
+        challenge: &ProofChallenge,
+    ) -> bool {
+        // C'^-1 is the first base
+        if self.bases[0].is_zero() {
+            return false;
+        }
+        // e(C', Q) = e(V', P)
+        let mut negated_witness = self.bases[0];
+        negated_witness.negate();
+        let mut negated_product = self.witness_product;
+        negated_product.negate();
+        pairing_product_is_one(&[
+            (negated_witness, *self.options.publicKey.as_ref()),
+            (negated_product, G2::one()),
+        ]) && linked_proof::recompute_commitment(
+            &self.bases,
+            &[
+                responses[&self.options.revocationIndex],
+                self.randomness_response,
+            ],
+            &self.witness_product,
+            challenge,
+        ) == self.commitment
+    }
+}
+
+fn element_exponent(y: &SignatureMessage, alpha: &Fr) -> Fr {
+    let mut exponent = *y.as_ref();
+    exponent.add_assign(alpha);
+    exponent
+}
+
+fn inverse_element_exponent(y: &SignatureMessage, alpha: &Fr) -> Result<Fr, JsValue> {
+    element_exponent(y, alpha)
+        .inverse()
+        .ok_or_else(|| JsValue::from("Invalid revocation id"))
+}
+
+fn membership_witness(
+    accumulator: &G1,
+    y: &SignatureMessage,
+    alpha: &Fr,
+) -> Result<Commitment, JsValue> {
+    let mut witness = *accumulator;
+    witness.mul_assign(inverse_element_exponent(y, alpha)?);
+    Ok(Commitment::from(witness))
+}
+
+/// The bases `[C'^-1, V]` of `V' = C'^-y * V^r`
+fn membership_bases(witness: &G1, accumulator: &G1) -> [G1; 2] {
+    let mut negated_witness = *witness;
+    negated_witness.negate();
+    [negated_witness, *accumulator]
+}
+
+/// Multiply the polynomial in `x` with the coefficients `polynomial` by `(y - x)`
+fn multiply_by_difference(polynomial: &mut Vec<Fr>, y: &SignatureMessage) {
+    polynomial.push(Fr::zero());
+    for k in (0..polynomial.len()).rev() {
+        // p'_k = y * p_k - p_(k - 1)
+        polynomial[k].mul_assign(y.as_ref());
+        if k > 0 {
+            let previous = polynomial[k - 1];
+            polynomial[k].sub_assign(&previous);
+        }
+    }
+}
+
+/// Add `scale * polynomial` to `sum`
+fn add_scaled(sum: &mut [Fr], polynomial: &[Fr], scale: &Fr) {
+    for (s, p) in sum.iter_mut().zip(polynomial.iter()) {
+        let mut term = *p;
+        term.mul_assign(scale);
+        s.add_assign(&term);
+    }
+}
+
+/// The coefficients of `v_A(x) = sum_s prod_{i<s}(y_i + alpha) prod_{j>s}(y_j - x)`
+fn addition_coefficients(additions: &[SignatureMessage], alpha: &Fr) -> Vec<Fr> {
+    let mut coefficients = vec![Fr::zero(); additions.len()];
+    let mut scale = Fr::one();
+    for (s, y) in additions.iter().enumerate() {
+        let mut polynomial = vec![Fr::one()];
+        for later in &additions[s + 1..] {
+            multiply_by_difference(&mut polynomial, later);
+        }
+        add_scaled(&mut coefficients, &polynomial, &scale);
+        scale.mul_assign(&element_exponent(y, alpha));
+    }
+    coefficients
+}
+
+/// The coefficients of `v_D(x) = sum_s prod_{i<s}(y_i - x) / prod_{j<=s}(y_j + alpha)`
+fn removal_coefficients(removals: &[SignatureMessage], alpha: &Fr) -> Result<Vec<Fr>, JsValue> {
+    let mut coefficients = vec![Fr::zero(); removals.len()];
+    let mut polynomial = vec![Fr::one()];
+    let mut scale = Fr::one();
+    for y in removals {
+        scale.mul_assign(&inverse_element_exponent(y, alpha)?);
+        add_scaled(&mut coefficients, &polynomial, &scale);
+        multiply_by_difference(&mut polynomial, y);
+    }
+    Ok(coefficients)
+}
+
+/// The update polynomial `V^a_k` for the coefficients `a_k`
+fn update_polynomial(accumulator: &G1, coefficients: &[Fr]) -> Vec<Commitment> {
+    coefficients
+        .iter()
+        .map(|a| {
+            let mut point = *accumulator;
+            point.mul_assign(*a);
+            Commitment::from(point)
+        })
+        .collect()
+}
+
+/// `prod(V^(a_k * x^k))`, which is `V^v(x)` for an update polynomial
+fn evaluate_update_polynomial(polynomial: &[Commitment], x: &SignatureMessage) -> G1 {
+    let mut power = Fr::one();
+    let mut bases = Vec::with_capacity(polynomial.len());
+    let mut powers = Vec::with_capacity(polynomial.len());
+    for point in polynomial {
+        bases.push(*point.as_ref());
+        powers.push(SignatureMessage::from(power));
+        power.mul_assign(x.as_ref());
+    }
+    linked_proof::multi_exp(&bases, &powers)
+}
+
+/// `prod(y_i - x)`
+fn difference_product(elements: &[SignatureMessage], x: &SignatureMessage) -> Fr {
+    let mut product = Fr::one();
+    for y in elements {
+        let mut difference = *y.as_ref();
+        difference.sub_assign(x.as_ref());
+        product.mul_assign(&difference);
+    }
+    product
+}
@@ -0,0 +1,345 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! The `BbsBlsSignature2020` and `BbsBlsSignatureProof2020` linked data proof suites.
+//!
+//! Documents are JSON-LD. The signed messages are the canonical N-Quads statements of
+//! the proof options followed by those of the document without its proof, signed like
+//! `blsSign`. The proof options take the `@context` of the document unless they have
+//! their own.
+//!
+//! A derived proof reveals the statements of the document that match a JSON-LD frame,
+//! like the `deriveProof` of `jsonld-signatures-bbs`. The blank nodes of the canonical
+//! document are skolemized as `<urn:bnid:_:c14nN>` before framing, so the revealed
+//! document keeps the canonical labels the statements were signed with. All the proof
+//! statements are revealed. The proof values and the nonce are base64 encoded.
+//!
+//! Remote contexts are never fetched. They are resolved from the bundled credentials,
+//! security and BBS contexts and the `contexts` of the request.
+
+use crate::bls12381::BlsKeyPair;
+use crate::json_ld::{self, Loader};
+use crate::linked_proof;
+use crate::numeric_message::message_scalar;
+use crate::rdf_canonize;
+use crate::utils::{general_error, set_panic_hook, verify_response};
+use crate::PoKOfSignatureProofWrapper;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use bbs::prelude::*;
+use rand::{thread_rng, RngCore};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    convert::{TryFrom, TryInto},
+};
+use wasm_bindgen::prelude::*;
+
+const SIGNATURE_TYPE: &str = "BbsBlsSignature2020";
+const PROOF_TYPE: &str = "BbsBlsSignatureProof2020";
+const SKOLEM_PREFIX: &str = "urn:bnid:_:";
+const NONCE_SIZE: usize = 50;
+
+/// A JSON-LD document or proof as a JSON object
+type Document = Map<String, Value>;
+
+wasm_impl!(
+    BbsBlsSignature2020SignRequest,
+    keyPair: BlsKeyPair,
+    document: Map<String, Value>,
+    proof: Map<String, Value>,
+    contexts: Option<BTreeMap<String, Value>>
+);
+
+wasm_impl!(BbsBlsSignature2020Response, document: Map<String, Value>);
+
+wasm_impl!(
+    BbsBlsSignature2020VerifyRequest,
+    publicKey: DeterministicPublicKey,
+    document: Map<String, Value>,
+    contexts: Option<BTreeMap<String, Value>>
+);
+
+wasm_impl!(
+    BbsBlsSignatureProof2020DeriveRequest,
+    publicKey: DeterministicPublicKey,
+    document: Map<String, Value>,
+    revealDocument: Map<String, Value>,
+    nonce: Option<String>,
+    contexts: Option<BTreeMap<String, Value>>
+);
+
+wasm_impl!(
+    BbsBlsSignatureProof2020DeriveResponse,
+    document: Map<String, Value>
+);
+
+wasm_impl!(
+    BbsBlsSignatureProof2020VerifyRequest,
+    publicKey: DeterministicPublicKey,
+    document: Map<String, Value>,
+    contexts: Option<BTreeMap<String, Value>>
+);
+
+/// Signs a JSON-LD document with a BLS 12-381 key pair, returning the document with
+/// a `BbsBlsSignature2020` proof made from the given proof options
+#[wasm_bindgen(js_name = signBbsBlsSignature2020)]
+pub async fn sign_bbs_bls_signature_2020(request: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let request: BbsBlsSignature2020SignRequest = request.try_into()?;
+    let secret_key = match request.keyPair.secretKey {
+        Some(sk) => sk,
+        None => return Err(JsValue::from("Failed to sign")),
+    };
+    let public_key = match request.keyPair.publicKey {
+        Some(pk) => map_err!(DeterministicPublicKey::try_from(pk.as_slice()))?,
+        None => return Err(JsValue::from("Failed to convert key")),
+    };
+    let mut document = request.document;
+    if document.contains_key("proof") {
+        return Err(JsValue::from("The document already has a proof"));
+    }
+    let loader = map_err!(Loader::new(request.contexts.as_ref()))?;
+    let mut proof = request.proof;
+    proof.insert("type".to_string(), Value::from(SIGNATURE_TYPE));
+    proof.remove("proofValue");
+
+    let mut messages = map_err!(proof_statements(&proof, &document, &loader))?;
+    messages.append(&mut map_err!(document_statements(&document, &loader))?);
+    let messages: Vec<SignatureMessage> = messages.iter().map(message_scalar).collect();
+    let pk = map_err!(public_key.to_public_key(messages.len()))?;
+    let signature = map_err!(Signature::new(messages.as_slice(), &secret_key, &pk))?;
+    proof.insert(
+        "proofValue".to_string(),
+        Value::from(STANDARD.encode(signature.to_bytes_compressed_form())),
+    );
+    document.insert("proof".to_string(), Value::Object(proof));
+    Ok(to_response(&BbsBlsSignature2020Response { document }))
+}
+
+/// Verifies the `BbsBlsSignature2020` proof of a JSON-LD document
+#[wasm_bindgen(js_name = verifyBbsBlsSignature2020)]
+pub async fn verify_bbs_bls_signature_2020(request: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    Ok(verify_response(request, check_signature))
+}
+
+/// Derives a `BbsBlsSignatureProof2020` proof from a document signed with
+/// `BbsBlsSignature2020`, returning the document framed by `revealDocument` with the
+/// proof of its statements
+#[wasm_bindgen(js_name = deriveBbsBlsSignatureProof2020)]
+pub async fn derive_bbs_bls_signature_proof_2020(request: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let request: BbsBlsSignatureProof2020DeriveRequest = request.try_into()?;
+    let loader = map_err!(Loader::new(request.contexts.as_ref()))?;
+    let (document, mut proof) = map_err!(split_proof(request.document, SIGNATURE_TYPE))?;
+    let signature = map_err!(proof_value(&mut proof)
+        .and_then(|s| decode_base64(&s))
+        .and_then(|s| Signature::try_from(s.as_slice())))?;
+    let nonce = match &request.nonce {
+        Some(nonce) => map_err!(decode_base64(nonce))?,
+        None => {
+            let mut nonce = vec![0u8; NONCE_SIZE];
+            thread_rng().fill_bytes(&mut nonce);
+            nonce
+        }
+    };
+
+    let proof_statements = map_err!(proof_statements(&proof, &document, &loader))?;
+    let canonical = map_err!(canonical_quads(&document, &loader))?;
+    let document_statements: Vec<String> = canonical.iter().map(statement).collect();
+
+    // Framing the skolemized document keeps the canonical labels of its blank nodes
+    let skolemized: Vec<rdf_canonize::Quad> = canonical
+        .iter()
+        .map(|q| q.skolemize(SKOLEM_PREFIX))
+        .collect();
+    let framed = map_err!(json_ld::from_rdf(&skolemized).and_then(|expanded| {
+        json_ld::frame(&expanded, &Value::Object(request.revealDocument), &loader)
+    }))?;
+    let mut framed = match framed {
+        Value::Object(framed) => framed,
+        _ => return Err(JsValue::from("The framed document is not an object")),
+    };
+    let mut revealed: BTreeSet<usize> = (0..proof_statements.len()).collect();
+    for statement in map_err!(revealed_statements(&framed, &loader))? {
+        match document_statements.iter().position(|s| *s == statement) {
+            Some(i) => revealed.insert(proof_statements.len() + i),
+            None => return Err(JsValue::from("A revealed statement is not in the document")),
+        };
+    }
+
+    let messages: Vec<SignatureMessage> = proof_statements
+        .iter()
+        .chain(document_statements.iter())
+        .map(message_scalar)
+        .collect();
+    let pk = map_err!(request.publicKey.to_public_key(messages.len()))?;
+    let pok = map_err!(linked_proof::init_pok(
+        &signature,
+        &pk,
+        &messages,
+        &revealed,
+        &BTreeMap::new(),
+    ))?;
+    let challenge = linked_proof::challenge(pok.to_bytes(), &[], &nonce);
+    let pok_proof = map_err!(pok.gen_proof(&challenge))?;
+
+    proof.insert("type".to_string(), Value::from(PROOF_TYPE));
+    proof.insert("nonce".to_string(), Value::from(STANDARD.encode(&nonce)));
+    proof.insert(
+        "proofValue".to_string(),
+        Value::from(STANDARD.encode(
+            PoKOfSignatureProofWrapper::new(messages.len(), &revealed, pok_proof).to_bytes(),
+        )),
+    );
+    framed.insert("proof".to_string(), Value::Object(proof));
+    Ok(to_response(&BbsBlsSignatureProof2020DeriveResponse {
+        document: framed,
+    }))
+}
+
+/// Verifies the `BbsBlsSignatureProof2020` proof of a derived JSON-LD document
+#[wasm_bindgen(js_name = verifyBbsBlsSignatureProof2020)]
+pub async fn verify_bbs_bls_signature_proof_2020(request: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    Ok(verify_response(request, check_signature_proof))
+}
+
+fn check_signature(request: BbsBlsSignature2020VerifyRequest) -> Result<bool, BBSError> {
+    let loader = Loader::new(request.contexts.as_ref())?;
+    let (document, mut proof) = split_proof(request.document, SIGNATURE_TYPE)?;
+    let signature = Signature::try_from(decode_base64(&proof_value(&mut proof)?)?.as_slice())?;
+    let mut messages = proof_statements(&proof, &document, &loader)?;
+    messages.append(&mut document_statements(&document, &loader)?);
+    let messages: Vec<SignatureMessage> = messages.iter().map(message_scalar).collect();
+    let pk = request.publicKey.to_public_key(messages.len())?;
+    signature.verify(messages.as_slice(), &pk)
+}
+
+fn check_signature_proof(request: BbsBlsSignatureProof2020VerifyRequest) -> Result<bool, BBSError> {
+    let loader = Loader::new(request.contexts.as_ref())?;
+    let (document, mut proof) = split_proof(request.document, PROOF_TYPE)?;
+    let proof_bytes = decode_base64(&proof_value(&mut proof)?)?;
+    if proof_bytes.len() < 2 {
+        return Err(general_error("Invalid proof value"));
+    }
+    let pok = PoKOfSignatureProofWrapper::try_from(proof_bytes.as_slice())
+        .map_err(|_| general_error("Invalid proof value"))?;
+    let nonce = match proof.remove("nonce") {
+        Some(Value::String(nonce)) => decode_base64(&nonce)?,
+        _ => return Err(general_error("The proof has no nonce")),
+    };
+    let message_count = u16::from_be_bytes([pok.bit_vector[0], pok.bit_vector[1]]) as usize;
+
+    // The proof statements are those of the signature the proof was derived from, and
+    // the revealed document statements keep the order of the signed document
+    proof.insert("type".to_string(), Value::from(SIGNATURE_TYPE));
+    let mut messages = proof_statements(&proof, &document, &loader)?;
+    let mut document_statements = revealed_statements(&document, &loader)?;
+    document_statements.sort();
+    messages.append(&mut document_statements);
+
+    let (revealed, pok) = pok.unwrap();
+    if revealed.len() != messages.len() {
+        return Ok(false);
+    }
+    let revealed_messages: BTreeMap<usize, SignatureMessage> = revealed
+        .iter()
+        .zip(messages.iter())
+        .map(|(i, m)| (*i, message_scalar(m)))
+        .collect();
+    let pk = request.publicKey.to_public_key(message_count)?;
+    let challenge =
+        linked_proof::challenge(pok.get_bytes_for_challenge(revealed, &pk), &[], &nonce);
+    linked_proof::verify_pok(&pok, &pk, &revealed_messages, &challenge)
+}
+
+/// Separates a document from its proof, which must be a single proof of `proof_type`
+fn split_proof(mut document: Document, proof_type: &str) -> Result<(Document, Document), BBSError> {
+    let proof = match document.remove("proof") {
+        Some(Value::Object(proof)) => proof,
+        Some(_) => return Err(general_error("The document must have a single proof")),
+        None => return Err(general_error("The document has no proof")),
+    };
+    if proof.get("type").and_then(Value::as_str) != Some(proof_type) {
+        return Err(general_error(&format!("The proof is not a {}", proof_type)));
+    }
+    Ok((document, proof))
+}
+
+fn proof_value(proof: &mut Document) -> Result<String, BBSError> {
+    match proof.remove("proofValue") {
+        Some(Value::String(value)) => Ok(value),
+        _ => Err(general_error("The proof has no proof value")),
+    }
+}
+
+/// The canonical statements of the proof options, in the context of the document
+fn proof_statements(
+    proof: &Document,
+    document: &Document,
+    loader: &Loader,
+) -> Result<Vec<String>, BBSError> {
+    let mut proof = proof.clone();
+    if !proof.contains_key("@context") {
+        if let Some(context) = document.get("@context") {
+            proof.insert("@context".to_string(), context.clone());
+        }
+    }
+    document_statements(&proof, loader)
+}
+
+fn document_statements(document: &Document, loader: &Loader) -> Result<Vec<String>, BBSError> {
+    Ok(canonical_quads(document, loader)?
+        .iter()
+        .map(statement)
+        .collect())
+}
+
+fn canonical_quads(
+    document: &Document,
+    loader: &Loader,
+) -> Result<Vec<rdf_canonize::Quad>, BBSError> {
+    let quads = json_ld::to_rdf(&Value::Object(document.clone()), loader)?;
+    Ok(rdf_canonize::canonicalize(&quads)?.0)
+}
+
+/// Revealed documents have their blank nodes skolemized with the canonical labels of
+/// the signed document, which are restored after canonicalization
+fn revealed_statements(document: &Document, loader: &Loader) -> Result<Vec<String>, BBSError> {
+    Ok(canonical_quads(document, loader)?
+        .iter()
+        .map(|q| statement(&q.deskolemize()))
+        .collect())
+}
+
+fn statement(quad: &rdf_canonize::Quad) -> String {
+    let mut nquad = quad.to_nquad();
+    nquad.pop();
+    nquad
+}
+
+fn decode_base64(value: &str) -> Result<Vec<u8>, BBSError> {
+    STANDARD
+        .decode(value)
+        .map_err(|_| general_error("The value is not base64 encoded"))
+}
+
+/// Documents are returned as plain objects rather than maps
+fn to_response<T: Serialize>(response: &T) -> JsValue {
+    response
+        .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .unwrap()
+}
@@ -11,10 +11,24 @@
  * limitations under the License.
  */
 
-use crate::utils::set_panic_hook;
+use crate::accumulator::{AccumulatorProofOptions, AccumulatorVerifyOptions};
+use crate::designated_verifier::DesignatedVerifierOptions;
+use crate::holder_binding::{HolderBindingProofOptions, HolderBindingVerifyOptions};
+use crate::message_commitment::MessageCommitmentOptions;
+use crate::numeric_message::message_scalar;
+use crate::predicate::NotEqualPredicate;
+use crate::pseudonym::PseudonymOptions;
+use crate::rate_limit::{RateLimitProofOptions, RateLimitVerifyOptions};
+use crate::set_membership::SetMembershipOptions;
+use crate::statements::{ProofStatements, VerifyStatements};
+use crate::status_list::{StatusListProofOptions, StatusListVerifyOptions};
+use crate::utils::{set_panic_hook, zeroize_copy, zeroize_proof_messages};
+use crate::verifiable_encryption::VerifiableEncryptionOptions;
 
 use crate::{bls12381::BbsKeyPair, BbsVerifyResponse, PoKOfSignatureProofWrapper};
 use bbs::prelude::*;
+#[cfg(any(not(target_arch = "wasm32"), target_feature = "atomics"))]
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::{
     collections::{BTreeMap, BTreeSet},
@@ -22,9 +36,16 @@ use std::{
     iter::FromIterator,
 };
 use wasm_bindgen::prelude::*;
+use zeroize::Zeroize;
 
 wasm_impl!(BbsSignRequest, keyPair: BbsKeyPair, messages: Vec<Vec<u8>>);
 
+wasm_impl!(
+    BbsSignBatchRequest,
+    keyPair: BbsKeyPair,
+    messages: Vec<Vec<Vec<u8>>>
+);
+
 wasm_impl!(
     BbsVerifyRequest,
     publicKey: PublicKey,
@@ -79,7 +100,17 @@ wasm_impl!(
     publicKey: PublicKey,
     messages: Vec<Vec<u8>>,
     revealed: Vec<usize>,
-    nonce: Vec<u8>
+    nonce: Vec<u8>,
+    holderBinding: Option<HolderBindingProofOptions>,
+    pseudonym: Option<PseudonymOptions>,
+    accumulator: Option<AccumulatorProofOptions>,
+    statusList: Option<StatusListProofOptions>,
+    verifiableEncryption: Option<VerifiableEncryptionOptions>,
+    committed: Option<Vec<MessageCommitmentOptions>>,
+    notEqual: Option<Vec<NotEqualPredicate>>,
+    setMembership: Option<SetMembershipOptions>,
+    rateLimit: Option<RateLimitProofOptions>,
+    designatedVerifier: Option<DesignatedVerifierOptions>
 );
 
 wasm_impl!(
@@ -87,9 +118,45 @@ wasm_impl!(
     proof: PoKOfSignatureProofWrapper,
     publicKey: PublicKey,
     messages: Vec<Vec<u8>>,
-    nonce: Vec<u8>
+    nonce: Vec<u8>,
+    holderBinding: Option<HolderBindingVerifyOptions>,
+    pseudonym: Option<PseudonymOptions>,
+    accumulator: Option<AccumulatorVerifyOptions>,
+    statusList: Option<StatusListVerifyOptions>,
+    verifiableEncryption: Option<VerifiableEncryptionOptions>,
+    committed: Option<Vec<usize>>,
+    notEqual: Option<Vec<NotEqualPredicate>>,
+    setMembership: Option<SetMembershipOptions>,
+    rateLimit: Option<RateLimitVerifyOptions>,
+    designatedVerifier: Option<DesignatedVerifierOptions>
 );
 
+// The hidden messages and blinding factors are wiped when the requests are dropped
+
+impl Drop for BlindSignatureContextRequest {
+    fn drop(&mut self) {
+        self.messages.zeroize();
+    }
+}
+
+impl Drop for BlindSignatureContextResponse {
+    fn drop(&mut self) {
+        zeroize_copy(&mut self.blindingFactor);
+    }
+}
+
+impl Drop for UnblindSignatureRequest {
+    fn drop(&mut self) {
+        zeroize_copy(&mut self.blindingFactor);
+    }
+}
+
+impl Drop for CreateProofRequest {
+    fn drop(&mut self) {
+        self.messages.zeroize();
+    }
+}
+
 #[wasm_bindgen(js_name = sign)]
 pub async fn bbs_sign(request: JsValue) -> Result<JsValue, JsValue> {
     let request: BbsSignRequest = request.try_into()?;
@@ -97,17 +164,86 @@ pub async fn bbs_sign(request: JsValue) -> Result<JsValue, JsValue> {
         .keyPair
         .secretKey
         .ok_or_else(|| JsValue::from("Failed to sign"))?;
-    let messages: Vec<SignatureMessage> = request
-        .messages
-        .iter()
-        .map(|m| SignatureMessage::hash(m))
-        .collect();
+    let messages: Vec<SignatureMessage> =
+        request.messages.iter().map(|m| message_scalar(m)).collect();
     match Signature::new(messages.as_slice(), &sk, &request.keyPair.publicKey) {
         Ok(sig) => Ok(serde_wasm_bindgen::to_value(&sig).unwrap()),
         Err(_e) => Err(JsValue::from("Failed to sign")),
     }
 }
 
+/// Signs many sets of messages with the same key pair, returning the signatures
+/// in the same order as the message sets
+#[wasm_bindgen(js_name = signBatch)]
+pub async fn bbs_sign_batch(request: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let request: BbsSignBatchRequest = request.try_into()?;
+    let sk = request
+        .keyPair
+        .secretKey
+        .ok_or_else(|| JsValue::from("Failed to sign"))?;
+    let message_count = request.keyPair.publicKey.message_count();
+    if let Some(i) = request
+        .messages
+        .iter()
+        .position(|m| m.len() != message_count)
+    {
+        return Err(JsValue::from(&format!(
+            "Expected {} messages at index {}, found {}",
+            message_count,
+            i,
+            request.messages[i].len()
+        )));
+    }
+    match sign_batch(&request.messages, &sk, &request.keyPair.publicKey) {
+        Ok(signatures) => Ok(serde_wasm_bindgen::to_value(&signatures).unwrap()),
+        Err(i) => Err(JsValue::from(&format!(
+            "Failed to sign messages at index {}",
+            i
+        ))),
+    }
+}
+
+/// Signs each set of messages in parallel on native targets, and on WASM built with the
+/// `atomics` target feature once `initThreadPool` has started the worker threads.
+/// On failure the index of the first set of messages that could not be signed is returned
+#[cfg(any(not(target_arch = "wasm32"), target_feature = "atomics"))]
+fn sign_batch(
+    batch: &[Vec<Vec<u8>>],
+    sk: &SecretKey,
+    pk: &PublicKey,
+) -> Result<Vec<Signature>, usize> {
+    batch
+        .par_iter()
+        .enumerate()
+        .map(|(i, messages)| sign_messages(messages, sk, pk).map_err(|_| i))
+        .collect()
+}
+
+/// Signs each set of messages sequentially, as the WASM build has no threads.
+/// On failure the index of the first set of messages that could not be signed is returned
+#[cfg(all(target_arch = "wasm32", not(target_feature = "atomics")))]
+fn sign_batch(
+    batch: &[Vec<Vec<u8>>],
+    sk: &SecretKey,
+    pk: &PublicKey,
+) -> Result<Vec<Signature>, usize> {
+    batch
+        .iter()
+        .enumerate()
+        .map(|(i, messages)| sign_messages(messages, sk, pk).map_err(|_| i))
+        .collect()
+}
+
+fn sign_messages(
+    messages: &[Vec<u8>],
+    sk: &SecretKey,
+    pk: &PublicKey,
+) -> Result<Signature, BBSError> {
+    let messages: Vec<SignatureMessage> = messages.iter().map(message_scalar).collect();
+    Signature::new(messages.as_slice(), sk, pk)
+}
+
 #[wasm_bindgen(js_name = verify)]
 pub async fn bbs_verify(request: JsValue) -> Result<JsValue, JsValue> {
     let res = request.try_into();
@@ -124,11 +260,8 @@ pub async fn bbs_verify(request: JsValue) -> Result<JsValue, JsValue> {
         }
     };
 
-    let messages: Vec<SignatureMessage> = request
-        .messages
-        .iter()
-        .map(|m| SignatureMessage::hash(m))
-        .collect();
+    let messages: Vec<SignatureMessage> =
+        request.messages.iter().map(|m| message_scalar(m)).collect();
     match request
         .signature
         .verify(messages.as_slice(), &request.publicKey)
@@ -150,87 +283,111 @@ pub async fn bbs_verify(request: JsValue) -> Result<JsValue, JsValue> {
 pub async fn bbs_blind_signature_commitment(request: JsValue) -> Result<JsValue, JsValue> {
     set_panic_hook();
     let request: BlindSignatureContextRequest = serde_wasm_bindgen::from_value(request)?;
-    if request.messages.len() != request.blinded.len() {
+    let response = blind_signature_commitment(
+        &request.publicKey,
+        &request.messages,
+        &request.blinded,
+        &request.nonce,
+    )?;
+    Ok(serde_wasm_bindgen::to_value(&response).unwrap())
+}
+
+#[wasm_bindgen(js_name = verifyBlind)]
+pub async fn bbs_verify_blind_signature_proof(request: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let request: BlindSignatureVerifyContextRequest = request.try_into()?;
+    let ctx = BlindSignatureContext {
+        commitment: request.commitment,
+        challenge_hash: request.challengeHash,
+        proof_of_hidden_messages: request.proofOfHiddenMessages,
+    };
+    verify_blind_signature_proof(ctx, &request.publicKey, &request.blinded, &request.nonce)
+}
+
+#[wasm_bindgen(js_name = blindSign)]
+pub async fn bbs_blind_sign(request: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let request: BlindSignContextRequest = request.try_into()?;
+    blind_sign(
+        &request.commitment,
+        &request.publicKey,
+        &request.secretKey,
+        &request.messages,
+        &request.known,
+    )
+}
+
+pub(crate) fn blind_signature_commitment(
+    pk: &PublicKey,
+    messages: &[Vec<u8>],
+    blinded: &[usize],
+    nonce: &[u8],
+) -> Result<BlindSignatureContextResponse, JsValue> {
+    if messages.len() != blinded.len() {
         return Err(JsValue::from("messages.len() != blinded.len()"));
     }
-    if request
-        .blinded
-        .iter()
-        .any(|b| *b > request.publicKey.message_count())
-    {
+    if blinded.iter().any(|b| *b > pk.message_count()) {
         return Err(JsValue::from("blinded value is out of bounds"));
     }
-    let mut messages = BTreeMap::new();
-    for i in 0..request.blinded.len() {
-        messages.insert(
-            request.blinded[i],
-            SignatureMessage::hash(&request.messages[i]),
-        );
+    let mut hidden = BTreeMap::new();
+    for i in 0..blinded.len() {
+        hidden.insert(blinded[i], message_scalar(&messages[i]));
     }
-    let nonce = ProofNonce::hash(&request.nonce);
-    match Prover::new_blind_signature_context(&request.publicKey, &messages, &nonce) {
+    let nonce = ProofNonce::hash(nonce);
+    let result = Prover::new_blind_signature_context(pk, &hidden, &nonce);
+    hidden.values_mut().for_each(zeroize_copy);
+    match result {
         Err(e) => Err(JsValue::from(&format!("{:?}", e))),
-        Ok((cx, bf)) => {
+        Ok((cx, mut bf)) => {
             let response = BlindSignatureContextResponse {
                 commitment: cx.commitment,
                 proofOfHiddenMessages: cx.proof_of_hidden_messages,
                 challengeHash: cx.challenge_hash,
                 blindingFactor: bf,
             };
-            Ok(serde_wasm_bindgen::to_value(&response).unwrap())
+            zeroize_copy(&mut bf);
+            Ok(response)
         }
     }
 }
 
-#[wasm_bindgen(js_name = verifyBlind)]
-pub async fn bbs_verify_blind_signature_proof(request: JsValue) -> Result<JsValue, JsValue> {
-    set_panic_hook();
-    let request: BlindSignatureVerifyContextRequest = request.try_into()?;
-    let total = request.publicKey.message_count();
-    if request.blinded.iter().any(|b| *b > total) {
+pub(crate) fn verify_blind_signature_proof(
+    ctx: BlindSignatureContext,
+    pk: &PublicKey,
+    blinded: &BTreeSet<usize>,
+    nonce: &[u8],
+) -> Result<JsValue, JsValue> {
+    let total = pk.message_count();
+    if blinded.iter().any(|b| *b > total) {
         return Err(JsValue::from("blinded value is out of bounds"));
     }
-    let messages = (0..total)
-        .filter(|i| !request.blinded.contains(i))
-        .collect();
-    let nonce = ProofNonce::hash(&request.nonce);
-    let ctx = BlindSignatureContext {
-        commitment: request.commitment,
-        challenge_hash: request.challengeHash,
-        proof_of_hidden_messages: request.proofOfHiddenMessages,
-    };
-    match ctx.verify(&messages, &request.publicKey, &nonce) {
+    let messages = (0..total).filter(|i| !blinded.contains(i)).collect();
+    let nonce = ProofNonce::hash(nonce);
+    match ctx.verify(&messages, pk, &nonce) {
         Err(e) => Err(JsValue::from(&format!("{:?}", e))),
         Ok(b) => Ok(JsValue::from_bool(b)),
     }
 }
 
-#[wasm_bindgen(js_name = blindSign)]
-pub async fn bbs_blind_sign(request: JsValue) -> Result<JsValue, JsValue> {
-    set_panic_hook();
-    let request: BlindSignContextRequest = request.try_into()?;
-    if request.messages.len() != request.known.len() {
+pub(crate) fn blind_sign(
+    commitment: &Commitment,
+    pk: &PublicKey,
+    sk: &SecretKey,
+    messages: &[Vec<u8>],
+    known: &[usize],
+) -> Result<JsValue, JsValue> {
+    if messages.len() != known.len() {
         return Err(JsValue::from("messages.len() != known.len()"));
     }
-    if request
-        .known
-        .iter()
-        .any(|k| *k > request.publicKey.message_count())
-    {
+    if known.iter().any(|k| *k > pk.message_count()) {
         return Err(JsValue::from("known value is out of bounds"));
     }
-    let messages: BTreeMap<usize, SignatureMessage> = request
-        .known
+    let messages: BTreeMap<usize, SignatureMessage> = known
         .iter()
-        .zip(request.messages.iter())
-        .map(|(k, m)| (*k, SignatureMessage::hash(m)))
+        .zip(messages.iter())
+        .map(|(k, m)| (*k, message_scalar(m)))
         .collect();
-    match BlindSignature::new(
-        &request.commitment,
-        &messages,
-        &request.secretKey,
-        &request.publicKey,
-    ) {
+    match BlindSignature::new(commitment, &messages, sk, pk) {
         Ok(s) => Ok(serde_wasm_bindgen::to_value(&s).unwrap()),
         Err(e) => Err(JsValue::from(&format!("{:?}", e))),
     }
@@ -250,6 +407,28 @@ pub async fn bbs_get_unblinded_signature(request: JsValue) -> Result<JsValue, Js
 pub async fn bbs_create_proof(request: JsValue) -> Result<JsValue, JsValue> {
     set_panic_hook();
     let request: CreateProofRequest = request.try_into()?;
+    let statements = ProofStatements {
+        holder_binding: request.holderBinding.as_ref(),
+        pseudonym: request.pseudonym.as_ref(),
+        accumulator: request.accumulator.as_ref(),
+        status_list: request.statusList.as_ref(),
+        verifiable_encryption: request.verifiableEncryption.as_ref(),
+        committed: request.committed.as_deref(),
+        not_equal: request.notEqual.as_deref(),
+        set_membership: request.setMembership.as_ref(),
+        rate_limit: request.rateLimit.as_ref(),
+        designated_verifier: request.designatedVerifier.as_ref(),
+    };
+    if !statements.is_empty() {
+        let proof = map_err!(statements.create_proof(
+            &request.signature,
+            &request.publicKey,
+            &request.messages,
+            &request.revealed,
+            &request.nonce,
+        ))?;
+        return Ok(serde_wasm_bindgen::to_value(&proof).unwrap());
+    }
     if request
         .revealed
         .iter()
@@ -257,20 +436,20 @@ pub async fn bbs_create_proof(request: JsValue) -> Result<JsValue, JsValue> {
     {
         return Err(JsValue::from("revealed value is out of bounds"));
     }
-    let revealed: BTreeSet<usize> = BTreeSet::from_iter(request.revealed.into_iter());
+    let revealed: BTreeSet<usize> = BTreeSet::from_iter(request.revealed.iter().copied());
     let mut messages = Vec::new();
     for i in 0..request.messages.len() {
         if revealed.contains(&i) {
-            messages.push(ProofMessage::Revealed(SignatureMessage::hash(
-                &request.messages[i],
-            )));
+            messages.push(ProofMessage::Revealed(message_scalar(&request.messages[i])));
         } else {
             messages.push(ProofMessage::Hidden(HiddenMessage::ProofSpecificBlinding(
-                SignatureMessage::hash(&request.messages[i]),
+                message_scalar(&request.messages[i]),
             )));
         }
     }
-    match PoKOfSignature::init(&request.signature, &request.publicKey, messages.as_slice()) {
+    let pok = PoKOfSignature::init(&request.signature, &request.publicKey, messages.as_slice());
+    zeroize_proof_messages(&mut messages);
+    match pok {
         Err(e) => return Err(JsValue::from(&format!("{:?}", e))),
         Ok(pok) => {
             let mut challenge_bytes = pok.to_bytes();
@@ -311,6 +490,27 @@ pub async fn bbs_verify_proof(request: JsValue) -> Result<JsValue, JsValue> {
             .unwrap())
         }
     };
+    let statements = VerifyStatements {
+        holder_binding: request.holderBinding.as_ref(),
+        pseudonym: request.pseudonym.as_ref(),
+        accumulator: request.accumulator.as_ref(),
+        status_list: request.statusList.as_ref(),
+        verifiable_encryption: request.verifiableEncryption.as_ref(),
+        committed: request.committed.as_deref(),
+        not_equal: request.notEqual.as_deref(),
+        set_membership: request.setMembership.as_ref(),
+        rate_limit: request.rateLimit.as_ref(),
+        designated_verifier: request.designatedVerifier.as_ref(),
+    };
+    if !statements.is_empty() || !request.proof.statements.is_empty() {
+        return Ok(serde_wasm_bindgen::to_value(&statements.verify_proof(
+            request.proof,
+            &request.publicKey,
+            &request.messages,
+            &request.nonce,
+        ))
+        .unwrap());
+    }
 
     let nonce = if request.nonce.is_empty() {
         ProofNonce::default()
@@ -330,10 +530,7 @@ pub async fn bbs_verify_proof(request: JsValue) -> Result<JsValue, JsValue> {
         .collect::<Vec<&usize>>();
     let mut revealed_messages = BTreeMap::new();
     for i in 0..revealed_vec.len() {
-        revealed_messages.insert(
-            *revealed_vec[i],
-            SignatureMessage::hash(messages[i].clone()),
-        );
+        revealed_messages.insert(*revealed_vec[i], message_scalar(messages[i].clone()));
     }
 
     let signature_proof = SignatureProof {
@@ -11,8 +11,22 @@
  * limitations under the License.
  */
 
-use crate::utils::set_panic_hook;
+use crate::numeric_message::message_scalar;
+use crate::utils::{set_panic_hook, zeroize_proof_messages};
 
+use crate::accumulator::{AccumulatorProofOptions, AccumulatorVerifyOptions};
+use crate::bbs_plus::{blind_sign, blind_signature_commitment, verify_blind_signature_proof};
+use crate::designated_verifier::DesignatedVerifierOptions;
+use crate::holder_binding::{HolderBindingProofOptions, HolderBindingVerifyOptions};
+use crate::message_commitment::MessageCommitmentOptions;
+use crate::multikey::deserialize_public_key;
+use crate::predicate::NotEqualPredicate;
+use crate::pseudonym::PseudonymOptions;
+use crate::rate_limit::{RateLimitProofOptions, RateLimitVerifyOptions};
+use crate::set_membership::SetMembershipOptions;
+use crate::statements::{ProofStatements, VerifyStatements};
+use crate::status_list::{StatusListProofOptions, StatusListVerifyOptions};
+use crate::verifiable_encryption::VerifiableEncryptionOptions;
 use crate::{BbsVerifyResponse, PoKOfSignatureProofWrapper};
 use bbs::prelude::*;
 use pairing_plus::{
@@ -29,6 +43,7 @@ use std::{
     iter::FromIterator,
 };
 use wasm_bindgen::prelude::*;
+use zeroize::{Zeroize, Zeroizing};
 
 wasm_impl!(
     /// Convenience struct for interfacing with JS.
@@ -62,6 +77,7 @@ wasm_impl!(
 
 wasm_impl!(
     BlsBbsVerifyRequest,
+    #[serde(deserialize_with = "deserialize_public_key")]
     publicKey: DeterministicPublicKey,
     signature: Signature,
     messages: Vec<Vec<u8>>
@@ -73,17 +89,82 @@ wasm_impl!(
     publicKey: DeterministicPublicKey,
     messages: Vec<Vec<u8>>,
     revealed: Vec<usize>,
-    nonce: Vec<u8>
+    nonce: Vec<u8>,
+    holderBinding: Option<HolderBindingProofOptions>,
+    pseudonym: Option<PseudonymOptions>,
+    accumulator: Option<AccumulatorProofOptions>,
+    statusList: Option<StatusListProofOptions>,
+    verifiableEncryption: Option<VerifiableEncryptionOptions>,
+    committed: Option<Vec<MessageCommitmentOptions>>,
+    notEqual: Option<Vec<NotEqualPredicate>>,
+    setMembership: Option<SetMembershipOptions>,
+    rateLimit: Option<RateLimitProofOptions>,
+    designatedVerifier: Option<DesignatedVerifierOptions>
 );
 
 wasm_impl!(
     BlsVerifyProofContext,
     proof: PoKOfSignatureProofWrapper,
+    #[serde(deserialize_with = "deserialize_public_key")]
     publicKey: DeterministicPublicKey,
     messages: Vec<Vec<u8>>,
+    nonce: Vec<u8>,
+    holderBinding: Option<HolderBindingVerifyOptions>,
+    pseudonym: Option<PseudonymOptions>,
+    accumulator: Option<AccumulatorVerifyOptions>,
+    statusList: Option<StatusListVerifyOptions>,
+    verifiableEncryption: Option<VerifiableEncryptionOptions>,
+    committed: Option<Vec<usize>>,
+    notEqual: Option<Vec<NotEqualPredicate>>,
+    setMembership: Option<SetMembershipOptions>,
+    rateLimit: Option<RateLimitVerifyOptions>,
+    designatedVerifier: Option<DesignatedVerifierOptions>
+);
+
+wasm_impl!(
+    BlsBlindSignatureContextRequest,
+    publicKey: DeterministicPublicKey,
+    messageCount: usize,
+    messages: Vec<Vec<u8>>,
+    blinded: Vec<usize>,
     nonce: Vec<u8>
 );
 
+wasm_impl!(
+    BlsBlindSignatureVerifyContextRequest,
+    commitment: Commitment,
+    proofOfHiddenMessages: ProofG1,
+    challengeHash: ProofChallenge,
+    publicKey: DeterministicPublicKey,
+    messageCount: usize,
+    blinded: BTreeSet<usize>,
+    nonce: Vec<u8>
+);
+
+wasm_impl!(
+    BlsBlindSignContextRequest,
+    commitment: Commitment,
+    publicKey: DeterministicPublicKey,
+    messageCount: usize,
+    secretKey: SecretKey,
+    messages: Vec<Vec<u8>>,
+    known: Vec<usize>
+);
+
+// The hidden messages are wiped when the requests are dropped
+
+impl Drop for BlsCreateProofRequest {
+    fn drop(&mut self) {
+        self.messages.zeroize();
+    }
+}
+
+impl Drop for BlsBlindSignatureContextRequest {
+    fn drop(&mut self) {
+        self.messages.zeroize();
+    }
+}
+
 /// Generate a BLS 12-381 key pair.
 ///
 /// * seed: UIntArray with 32 element
@@ -155,11 +236,8 @@ pub async fn bls_sign(request: JsValue) -> Result<JsValue, JsValue> {
     if request.keyPair.secretKey.is_none() {
         return Err(JsValue::from_str("Failed to sign"));
     }
-    let messages: Vec<SignatureMessage> = request
-        .messages
-        .iter()
-        .map(|m| SignatureMessage::hash(m))
-        .collect();
+    let messages: Vec<SignatureMessage> =
+        request.messages.iter().map(|m| message_scalar(m)).collect();
     match Signature::new(
         messages.as_slice(),
         &request.keyPair.secretKey.unwrap(),
@@ -170,6 +248,48 @@ pub async fn bls_sign(request: JsValue) -> Result<JsValue, JsValue> {
     }
 }
 
+/// Creates a commitment to the messages that the holder wants signed blindly with the
+/// message count of the BBS key expanded from a BLS 12-381 public key
+#[wasm_bindgen(js_name = blsBlindSignCommitment)]
+pub async fn bls_blind_signature_commitment(request: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let request: BlsBlindSignatureContextRequest = request.try_into()?;
+    let pk = request.publicKey.to_public_key(request.messageCount)?;
+    let response =
+        blind_signature_commitment(&pk, &request.messages, &request.blinded, &request.nonce)?;
+    Ok(serde_wasm_bindgen::to_value(&response).unwrap())
+}
+
+/// Verifies the proof of the hidden messages in a blind signature commitment
+/// with a BLS 12-381 public key
+#[wasm_bindgen(js_name = blsVerifyBlind)]
+pub async fn bls_verify_blind_signature_proof(request: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let request: BlsBlindSignatureVerifyContextRequest = request.try_into()?;
+    let pk = request.publicKey.to_public_key(request.messageCount)?;
+    let ctx = BlindSignatureContext {
+        commitment: request.commitment,
+        challenge_hash: request.challengeHash,
+        proof_of_hidden_messages: request.proofOfHiddenMessages,
+    };
+    verify_blind_signature_proof(ctx, &pk, &request.blinded, &request.nonce)
+}
+
+/// Signs the known messages and a blind signature commitment with a BLS 12-381 key pair
+#[wasm_bindgen(js_name = blsBlindSign)]
+pub async fn bls_blind_sign(request: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let request: BlsBlindSignContextRequest = request.try_into()?;
+    let pk = request.publicKey.to_public_key(request.messageCount)?;
+    blind_sign(
+        &request.commitment,
+        &pk,
+        &request.secretKey,
+        &request.messages,
+        &request.known,
+    )
+}
+
 /// Verifies a BBS+ signature for a set of messages with a with a BLS 12-381 public key
 #[wasm_bindgen(js_name = blsVerify)]
 pub async fn bls_verify(request: JsValue) -> Result<JsValue, JsValue> {
@@ -194,11 +314,8 @@ pub async fn bls_verify(request: JsValue) -> Result<JsValue, JsValue> {
         .unwrap());
     }
     let pk = result.publicKey.to_public_key(result.messages.len())?;
-    let messages: Vec<SignatureMessage> = result
-        .messages
-        .iter()
-        .map(|m| SignatureMessage::hash(m))
-        .collect();
+    let messages: Vec<SignatureMessage> =
+        result.messages.iter().map(|m| message_scalar(m)).collect();
     match result.signature.verify(messages.as_slice(), &pk) {
         Err(e) => Ok(serde_wasm_bindgen::to_value(&BbsVerifyResponse {
             verified: false,
@@ -218,24 +335,49 @@ pub async fn bls_verify(request: JsValue) -> Result<JsValue, JsValue> {
 pub async fn bls_create_proof(request: JsValue) -> Result<JsValue, JsValue> {
     set_panic_hook();
     let request: BlsCreateProofRequest = request.try_into()?;
+    let statements = ProofStatements {
+        holder_binding: request.holderBinding.as_ref(),
+        pseudonym: request.pseudonym.as_ref(),
+        accumulator: request.accumulator.as_ref(),
+        status_list: request.statusList.as_ref(),
+        verifiable_encryption: request.verifiableEncryption.as_ref(),
+        committed: request.committed.as_deref(),
+        not_equal: request.notEqual.as_deref(),
+        set_membership: request.setMembership.as_ref(),
+        rate_limit: request.rateLimit.as_ref(),
+        designated_verifier: request.designatedVerifier.as_ref(),
+    };
+    if !statements.is_empty() {
+        let pk = request
+            .publicKey
+            .to_public_key(statements.message_count(request.messages.len()))?;
+        let proof = map_err!(statements.create_proof(
+            &request.signature,
+            &pk,
+            &request.messages,
+            &request.revealed,
+            &request.nonce,
+        ))?;
+        return Ok(serde_wasm_bindgen::to_value(&proof).unwrap());
+    }
     if request.revealed.iter().any(|r| *r > request.messages.len()) {
         return Err(JsValue::from("revealed value is out of bounds"));
     }
     let pk = request.publicKey.to_public_key(request.messages.len())?;
-    let revealed: BTreeSet<usize> = BTreeSet::from_iter(request.revealed.into_iter());
+    let revealed: BTreeSet<usize> = BTreeSet::from_iter(request.revealed.iter().copied());
     let mut messages = Vec::new();
     for i in 0..request.messages.len() {
         if revealed.contains(&i) {
-            messages.push(ProofMessage::Revealed(SignatureMessage::hash(
-                &request.messages[i],
-            )));
+            messages.push(ProofMessage::Revealed(message_scalar(&request.messages[i])));
         } else {
             messages.push(ProofMessage::Hidden(HiddenMessage::ProofSpecificBlinding(
-                SignatureMessage::hash(&request.messages[i]),
+                message_scalar(&request.messages[i]),
             )));
         }
     }
-    match PoKOfSignature::init(&request.signature, &pk, messages.as_slice()) {
+    let pok = PoKOfSignature::init(&request.signature, &pk, messages.as_slice());
+    zeroize_proof_messages(&mut messages);
+    match pok {
         Err(e) => return Err(JsValue::from(&format!("{:?}", e))),
         Ok(pok) => {
             let mut challenge_bytes = pok.to_bytes();
@@ -282,6 +424,27 @@ pub async fn bls_verify_proof(request: JsValue) -> Result<JsValue, JsValue> {
     };
     let message_count = u16::from_be_bytes(*array_ref![request.proof.bit_vector, 0, 2]) as usize;
     let pk = request.publicKey.to_public_key(message_count)?;
+    let statements = VerifyStatements {
+        holder_binding: request.holderBinding.as_ref(),
+        pseudonym: request.pseudonym.as_ref(),
+        accumulator: request.accumulator.as_ref(),
+        status_list: request.statusList.as_ref(),
+        verifiable_encryption: request.verifiableEncryption.as_ref(),
+        committed: request.committed.as_deref(),
+        not_equal: request.notEqual.as_deref(),
+        set_membership: request.setMembership.as_ref(),
+        rate_limit: request.rateLimit.as_ref(),
+        designated_verifier: request.designatedVerifier.as_ref(),
+    };
+    if !statements.is_empty() || !request.proof.statements.is_empty() {
+        return Ok(serde_wasm_bindgen::to_value(&statements.verify_proof(
+            request.proof,
+            &pk,
+            &request.messages,
+            &request.nonce,
+        ))
+        .unwrap());
+    }
     let messages = request.messages.clone();
     let (revealed, proof) = request.proof.unwrap();
     if messages.len() != revealed.len() {
@@ -306,10 +469,7 @@ pub async fn bls_verify_proof(request: JsValue) -> Result<JsValue, JsValue> {
         .collect::<Vec<&usize>>();
     let mut revealed_messages = BTreeMap::new();
     for i in 0..revealed_vec.len() {
-        revealed_messages.insert(
-            *revealed_vec[i],
-            SignatureMessage::hash(messages[i].clone()),
-        );
+        revealed_messages.insert(*revealed_vec[i], message_scalar(messages[i].clone()));
     }
 
     let signature_proof = SignatureProof {
@@ -327,35 +487,44 @@ pub async fn bls_verify_proof(request: JsValue) -> Result<JsValue, JsValue> {
 fn bls_generate_keypair<G: CurveProjective<Engine = Bls12, Scalar = Fr> + SerDes>(
     seed: Option<Vec<u8>>,
 ) -> JsValue {
-    let seed_data = match seed {
-        Some(s) => s.to_vec(),
+    let seed_data = Zeroizing::new(match seed {
+        Some(s) => s,
         None => {
             let mut rng = thread_rng();
-            let mut s = vec![0u8, 32];
+            let mut s = vec![0u8; 32];
             rng.fill_bytes(s.as_mut_slice());
             s
         }
+    });
+
+    let (pk_bytes, sk) = key_pair_from_seed::<G>(seed_data.as_slice());
+    let keypair = BlsKeyPair {
+        publicKey: Some(pk_bytes),
+        secretKey: Some(sk),
     };
+    serde_wasm_bindgen::to_value(&keypair).unwrap()
+}
 
-    let sk = gen_sk(seed_data.as_slice());
+/// The compressed public key and the secret key generated from a seed
+pub(crate) fn key_pair_from_seed<G: CurveProjective<Engine = Bls12, Scalar = Fr> + SerDes>(
+    seed: &[u8],
+) -> (Vec<u8>, SecretKey) {
+    let mut sk = gen_sk(seed);
     let mut pk = G::one();
     pk.mul_assign(sk);
 
     let mut pk_bytes = Vec::new();
     pk.serialize(&mut pk_bytes, true).unwrap();
-
-    let keypair = BlsKeyPair {
-        publicKey: Some(pk_bytes),
-        secretKey: Some(SecretKey::from(sk)),
-    };
-    serde_wasm_bindgen::to_value(&keypair).unwrap()
+    let secret_key = SecretKey::from(sk);
+    sk.zeroize();
+    (pk_bytes, secret_key)
 }
 
 fn gen_sk(msg: &[u8]) -> Fr {
     use sha2::digest::generic_array::{typenum::U48, GenericArray};
     const SALT: &[u8] = b"BLS-SIG-KEYGEN-SALT-";
     // copy of `msg` with appended zero byte
-    let mut msg_prime = Vec::<u8>::with_capacity(msg.as_ref().len() + 1);
+    let mut msg_prime = Zeroizing::new(Vec::<u8>::with_capacity(msg.as_ref().len() + 1));
     msg_prime.extend_from_slice(msg.as_ref());
     msg_prime.extend_from_slice(&[0]);
     // `result` has enough length to hold the output from HKDF expansion
@@ -363,5 +532,7 @@ fn gen_sk(msg: &[u8]) -> Fr {
     assert!(hkdf::Hkdf::<sha2::Sha256>::new(Some(SALT), &msg_prime[..])
         .expand(&[0, 48], &mut result)
         .is_ok());
-    Fr::from_okm(&result)
+    let sk = Fr::from_okm(&result);
+    result.as_mut_slice().zeroize();
+    sk
 }
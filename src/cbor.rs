@@ -0,0 +1,208 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Minimal CBOR (RFC 8949) encoding of the data items used by the proof encodings.
+//!
+//! Only definite length items are supported, and integers are encoded in their
+//! shortest form.
+
+use crate::utils::general_error;
+
+use bbs::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Value {
+    Unsigned(u64),
+    /// The negative integer `-1 - n`
+    Negative(u64),
+    Bytes(Vec<u8>),
+    Text(String),
+    Array(Vec<Value>),
+    Map(Vec<(Value, Value)>),
+}
+
+impl Value {
+    pub(crate) fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.encode(&mut out);
+        out
+    }
+
+    fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            Value::Unsigned(n) => write_head(out, 0, *n),
+            Value::Negative(n) => write_head(out, 1, *n),
+            Value::Bytes(b) => {
+                write_head(out, 2, b.len() as u64);
+                out.extend_from_slice(b);
+            }
+            Value::Text(t) => {
+                write_head(out, 3, t.len() as u64);
+                out.extend_from_slice(t.as_bytes());
+            }
+            Value::Array(items) => {
+                write_head(out, 4, items.len() as u64);
+                for item in items {
+                    item.encode(out);
+                }
+            }
+            Value::Map(entries) => {
+                write_head(out, 5, entries.len() as u64);
+                for (k, v) in entries {
+                    k.encode(out);
+                    v.encode(out);
+                }
+            }
+        }
+    }
+
+    /// Decode a single data item that must span all of `bytes`
+    pub(crate) fn from_bytes(bytes: &[u8]) -> Result<Value, BBSError> {
+        let mut pos = 0;
+        let value = decode(bytes, &mut pos, 0)?;
+        if pos != bytes.len() {
+            return Err(general_error("Trailing bytes after CBOR data item"));
+        }
+        Ok(value)
+    }
+
+    pub(crate) fn as_unsigned(&self) -> Result<u64, BBSError> {
+        match self {
+            Value::Unsigned(n) => Ok(*n),
+            _ => Err(general_error("Expected a CBOR unsigned integer")),
+        }
+    }
+
+    pub(crate) fn as_bytes(&self) -> Result<&[u8], BBSError> {
+        match self {
+            Value::Bytes(b) => Ok(b),
+            _ => Err(general_error("Expected a CBOR byte string")),
+        }
+    }
+
+    pub(crate) fn as_array(&self) -> Result<&[Value], BBSError> {
+        match self {
+            Value::Array(items) => Ok(items),
+            _ => Err(general_error("Expected a CBOR array")),
+        }
+    }
+
+    pub(crate) fn as_map(&self) -> Result<&[(Value, Value)], BBSError> {
+        match self {
+            Value::Map(entries) => Ok(entries),
+            _ => Err(general_error("Expected a CBOR map")),
+        }
+    }
+
+    /// The value of the first entry of a map with the given key
+    pub(crate) fn get(&self, key: &Value) -> Result<Option<&Value>, BBSError> {
+        Ok(self
+            .as_map()?
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v))
+    }
+}
+
+/// Nesting limit so malicious input cannot exhaust the stack
+const MAX_DEPTH: usize = 16;
+
+fn write_head(out: &mut Vec<u8>, major: u8, n: u64) {
+    let major = major << 5;
+    if n < 24 {
+        out.push(major | n as u8);
+    } else if n <= u8::MAX as u64 {
+        out.push(major | 24);
+        out.push(n as u8);
+    } else if n <= u16::MAX as u64 {
+        out.push(major | 25);
+        out.extend_from_slice(&(n as u16).to_be_bytes());
+    } else if n <= u32::MAX as u64 {
+        out.push(major | 26);
+        out.extend_from_slice(&(n as u32).to_be_bytes());
+    } else {
+        out.push(major | 27);
+        out.extend_from_slice(&n.to_be_bytes());
+    }
+}
+
+fn take<'a>(bytes: &'a [u8], pos: &mut usize, len: usize) -> Result<&'a [u8], BBSError> {
+    if bytes.len() - *pos < len {
+        return Err(general_error("Unexpected end of CBOR data"));
+    }
+    let slice = &bytes[*pos..*pos + len];
+    *pos += len;
+    Ok(slice)
+}
+
+fn read_head(bytes: &[u8], pos: &mut usize) -> Result<(u8, u64), BBSError> {
+    let initial = take(bytes, pos, 1)?[0];
+    let (major, info) = (initial >> 5, initial & 0x1f);
+    let n = match info {
+        0..=23 => info as u64,
+        24 => take(bytes, pos, 1)?[0] as u64,
+        25 => {
+            let b = take(bytes, pos, 2)?;
+            u16::from_be_bytes([b[0], b[1]]) as u64
+        }
+        26 => {
+            let b = take(bytes, pos, 4)?;
+            u32::from_be_bytes([b[0], b[1], b[2], b[3]]) as u64
+        }
+        27 => {
+            let b = take(bytes, pos, 8)?;
+            let mut n = [0u8; 8];
+            n.copy_from_slice(b);
+            u64::from_be_bytes(n)
+        }
+        _ => return Err(general_error("Unsupported CBOR length encoding")),
+    };
+    Ok((major, n))
+}
+
+fn decode(bytes: &[u8], pos: &mut usize, depth: usize) -> Result<Value, BBSError> {
+    if depth > MAX_DEPTH {
+        return Err(general_error("CBOR data is nested too deeply"));
+    }
+    let (major, n) = read_head(bytes, pos)?;
+    // Every item takes at least one byte, so no count can exceed the remaining bytes
+    if n > (bytes.len() - *pos) as u64 && major > 1 {
+        return Err(general_error("Unexpected end of CBOR data"));
+    }
+    let count = n as usize;
+    match major {
+        0 => Ok(Value::Unsigned(n)),
+        1 => Ok(Value::Negative(n)),
+        2 => Ok(Value::Bytes(take(bytes, pos, count)?.to_vec())),
+        3 => String::from_utf8(take(bytes, pos, count)?.to_vec())
+            .map(Value::Text)
+            .map_err(|_| general_error("Invalid UTF-8 in CBOR text string")),
+        4 => {
+            let mut items = Vec::with_capacity(count);
+            for _ in 0..count {
+                items.push(decode(bytes, pos, depth + 1)?);
+            }
+            Ok(Value::Array(items))
+        }
+        5 => {
+            let mut entries = Vec::with_capacity(count);
+            for _ in 0..count {
+                let k = decode(bytes, pos, depth + 1)?;
+                let v = decode(bytes, pos, depth + 1)?;
+                entries.push((k, v));
+            }
+            Ok(Value::Map(entries))
+        }
+        _ => Err(general_error("Unsupported CBOR major type")),
+    }
+}
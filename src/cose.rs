@@ -0,0 +1,402 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! CBOR encodings of keys, signatures and proofs.
+//!
+//! A BLS 12-381 key pair is encoded as a COSE_Key (RFC 9052) with the `OKP` key type,
+//! the `Bls12381G1` (13) or `Bls12381G2` (14) curve, the compressed public key as `x`
+//! and the secret key, when present, as `d`. A BBS key pair is the map
+//! `{1: public key, 2: secret key, 3: message count}` without the secret key when it is
+//! absent, a BBS public key is the array `[w, h0, [h_1, ..., h_n]]` of compressed
+//! points, a signature is the byte string of its compressed form and a proof is the
+//! array `[message count, [revealed indices], proof]`.
+
+use crate::bls12381::{BbsKeyPair, BlsKeyPair};
+use crate::cbor::Value;
+use crate::utils::{general_error, set_panic_hook};
+use crate::{bitvector_to_revealed, PoKOfSignatureProofWrapper};
+
+use bbs::prelude::*;
+use pairing_plus::{
+    bls12_381::{Bls12, Fr, G1, G2},
+    serdes::SerDes,
+    CurveProjective,
+};
+use std::{
+    collections::BTreeSet,
+    convert::{TryFrom, TryInto},
+};
+use wasm_bindgen::prelude::*;
+
+const COSE_KEY_KTY: Value = Value::Unsigned(1);
+const COSE_KEY_CRV: Value = Value::Negative(0);
+const COSE_KEY_X: Value = Value::Negative(1);
+const COSE_KEY_D: Value = Value::Negative(3);
+const COSE_KTY_OKP: Value = Value::Unsigned(1);
+const COSE_CRV_BLS12381_G1: u64 = 13;
+const COSE_CRV_BLS12381_G2: u64 = 14;
+
+const BBS_KEY_PAIR_PUBLIC_KEY: Value = Value::Unsigned(1);
+const BBS_KEY_PAIR_SECRET_KEY: Value = Value::Unsigned(2);
+const BBS_KEY_PAIR_MESSAGE_COUNT: Value = Value::Unsigned(3);
+
+/// Conversion to and from the CBOR encodings
+pub trait CborEncoding: Sized {
+    /// Encode as CBOR
+    fn to_cbor(&self) -> Result<Vec<u8>, BBSError>;
+
+    /// Decode from CBOR, checking the points and keys
+    fn from_cbor(bytes: &[u8]) -> Result<Self, BBSError>;
+}
+
+impl CborEncoding for BlsKeyPair {
+    fn to_cbor(&self) -> Result<Vec<u8>, BBSError> {
+        let public_key = match &self.publicKey {
+            Some(pk) => pk,
+            None => return Err(general_error("A COSE_Key requires the public key")),
+        };
+        let crv = match public_key.len() {
+            G1_COMPRESSED_SIZE => COSE_CRV_BLS12381_G1,
+            G2_COMPRESSED_SIZE => COSE_CRV_BLS12381_G2,
+            _ => return Err(general_error("Invalid BLS 12-381 public key")),
+        };
+        let mut entries = vec![
+            (COSE_KEY_KTY, COSE_KTY_OKP),
+            (COSE_KEY_CRV, Value::Unsigned(crv)),
+            (COSE_KEY_X, Value::Bytes(public_key.clone())),
+        ];
+        if let Some(sk) = &self.secretKey {
+            entries.push((
+                COSE_KEY_D,
+                Value::Bytes(sk.to_bytes_compressed_form().to_vec()),
+            ));
+        }
+        Ok(Value::Map(entries).to_bytes())
+    }
+
+    fn from_cbor(bytes: &[u8]) -> Result<Self, BBSError> {
+        let key = Value::from_bytes(bytes)?;
+        if key.get(&COSE_KEY_KTY)? != Some(&COSE_KTY_OKP) {
+            return Err(general_error("Expected an OKP COSE_Key"));
+        }
+        let public_key = required(&key, &COSE_KEY_X)?.as_bytes()?;
+        let secret_key = match key.get(&COSE_KEY_D)? {
+            Some(d) => Some(d.as_bytes()?),
+            None => None,
+        };
+        let secret_key = match required(&key, &COSE_KEY_CRV)? {
+            Value::Unsigned(COSE_CRV_BLS12381_G1) => {
+                check_key_pair::<G1>(public_key, secret_key, G1_COMPRESSED_SIZE)?
+            }
+            Value::Unsigned(COSE_CRV_BLS12381_G2) => {
+                check_key_pair::<G2>(public_key, secret_key, G2_COMPRESSED_SIZE)?
+            }
+            _ => return Err(general_error("Unsupported COSE_Key curve")),
+        };
+        Ok(BlsKeyPair {
+            publicKey: Some(public_key.to_vec()),
+            secretKey: secret_key,
+        })
+    }
+}
+
+impl CborEncoding for BbsKeyPair {
+    fn to_cbor(&self) -> Result<Vec<u8>, BBSError> {
+        let mut entries = vec![(BBS_KEY_PAIR_PUBLIC_KEY, public_key_value(&self.publicKey))];
+        if let Some(sk) = &self.secretKey {
+            entries.push((
+                BBS_KEY_PAIR_SECRET_KEY,
+                Value::Bytes(sk.to_bytes_compressed_form().to_vec()),
+            ));
+        }
+        entries.push((
+            BBS_KEY_PAIR_MESSAGE_COUNT,
+            Value::Unsigned(self.messageCount as u64),
+        ));
+        Ok(Value::Map(entries).to_bytes())
+    }
+
+    fn from_cbor(bytes: &[u8]) -> Result<Self, BBSError> {
+        let key_pair = Value::from_bytes(bytes)?;
+        let public_key = public_key_from_value(required(&key_pair, &BBS_KEY_PAIR_PUBLIC_KEY)?)?;
+        let message_count = required(&key_pair, &BBS_KEY_PAIR_MESSAGE_COUNT)?.as_unsigned()?;
+        if message_count != public_key.message_count() as u64 {
+            return Err(general_error(
+                "The message count does not match the public key",
+            ));
+        }
+        let secret_key = match key_pair.get(&BBS_KEY_PAIR_SECRET_KEY)? {
+            Some(sk) => {
+                let sk = secret_key_from_bytes(sk.as_bytes()?)?;
+                let (dpk, _) =
+                    DeterministicPublicKey::new(Some(KeyGenOption::FromSecretKey(sk.clone())));
+                if dpk.to_bytes_compressed_form() != public_key.w.to_bytes_compressed_form() {
+                    return Err(general_error(
+                        "The secret key does not match the public key",
+                    ));
+                }
+                Some(sk)
+            }
+            None => None,
+        };
+        Ok(BbsKeyPair {
+            publicKey: public_key,
+            secretKey: secret_key,
+            messageCount: message_count as usize,
+        })
+    }
+}
+
+impl CborEncoding for PublicKey {
+    fn to_cbor(&self) -> Result<Vec<u8>, BBSError> {
+        Ok(public_key_value(self).to_bytes())
+    }
+
+    fn from_cbor(bytes: &[u8]) -> Result<Self, BBSError> {
+        public_key_from_value(&Value::from_bytes(bytes)?)
+    }
+}
+
+impl CborEncoding for Signature {
+    fn to_cbor(&self) -> Result<Vec<u8>, BBSError> {
+        Ok(Value::Bytes(self.to_bytes_compressed_form().to_vec()).to_bytes())
+    }
+
+    fn from_cbor(bytes: &[u8]) -> Result<Self, BBSError> {
+        let value = Value::from_bytes(bytes)?;
+        let signature = value.as_bytes()?;
+        if signature.len() != SIGNATURE_COMPRESSED_SIZE {
+            return Err(general_error("Invalid signature"));
+        }
+        Signature::try_from(signature)
+    }
+}
+
+impl CborEncoding for PoKOfSignatureProofWrapper {
+    fn to_cbor(&self) -> Result<Vec<u8>, BBSError> {
+        let message_count = u16::from_be_bytes([self.bit_vector[0], self.bit_vector[1]]);
+        let revealed = bitvector_to_revealed(&self.bit_vector[2..]);
+        let mut items = vec![
+            Value::Unsigned(message_count as u64),
+            Value::Array(
+                revealed
+                    .into_iter()
+                    .map(|i| Value::Unsigned(i as u64))
+                    .collect(),
+            ),
+            Value::Bytes(self.proof.to_bytes_compressed_form()),
+        ];
+        // The proofs of the statements are a fourth item when there are any
+        if !self.statements.is_empty() {
+            items.push(Value::Bytes(self.statements.clone()));
+        }
+        Ok(Value::Array(items).to_bytes())
+    }
+
+    fn from_cbor(bytes: &[u8]) -> Result<Self, BBSError> {
+        let value = Value::from_bytes(bytes)?;
+        let (message_count, revealed, proof, statements) = match value.as_array()? {
+            [message_count, revealed, proof] => (message_count, revealed, proof, None),
+            [message_count, revealed, proof, statements] => {
+                (message_count, revealed, proof, Some(statements.as_bytes()?))
+            }
+            _ => return Err(general_error("Invalid proof")),
+        };
+        let message_count = message_count.as_unsigned()?;
+        if message_count > u16::MAX as u64 {
+            return Err(general_error("Invalid proof"));
+        }
+        let mut indices = BTreeSet::new();
+        for i in revealed.as_array()? {
+            let i = i.as_unsigned()?;
+            if i >= message_count || !indices.insert(i as usize) {
+                return Err(general_error("Invalid revealed index"));
+            }
+        }
+        let proof = PoKOfSignatureProof::try_from(proof.as_bytes()?)?;
+        let mut wrapper = PoKOfSignatureProofWrapper::new(message_count as usize, &indices, proof);
+        match statements {
+            Some([]) => return Err(general_error("Invalid proof")),
+            Some(statements) => wrapper.statements = statements.to_vec(),
+            None => {}
+        }
+        Ok(wrapper)
+    }
+}
+
+/// Encodes a BLS 12-381 key pair as a COSE_Key
+#[wasm_bindgen(js_name = blsKeyPairToCoseKey)]
+pub async fn bls_key_pair_to_cose_key(key_pair: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let key_pair: BlsKeyPair = key_pair.try_into()?;
+    to_js(key_pair.to_cbor())
+}
+
+/// Decodes a BLS 12-381 key pair from a COSE_Key
+#[wasm_bindgen(js_name = blsKeyPairFromCoseKey)]
+pub async fn bls_key_pair_from_cose_key(cose_key: Vec<u8>) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let key_pair = map_err!(BlsKeyPair::from_cbor(&cose_key))?;
+    Ok(serde_wasm_bindgen::to_value(&key_pair).unwrap())
+}
+
+/// Encodes a BBS key pair as CBOR
+#[wasm_bindgen(js_name = bbsKeyPairToCbor)]
+pub async fn bbs_key_pair_to_cbor(key_pair: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let key_pair: BbsKeyPair = key_pair.try_into()?;
+    to_js(key_pair.to_cbor())
+}
+
+/// Decodes a BBS key pair from CBOR
+#[wasm_bindgen(js_name = bbsKeyPairFromCbor)]
+pub async fn bbs_key_pair_from_cbor(cbor: Vec<u8>) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let key_pair = map_err!(BbsKeyPair::from_cbor(&cbor))?;
+    Ok(serde_wasm_bindgen::to_value(&key_pair).unwrap())
+}
+
+/// Encodes a BBS public key as CBOR
+#[wasm_bindgen(js_name = bbsPublicKeyToCbor)]
+pub async fn bbs_public_key_to_cbor(public_key: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let public_key: PublicKey = map_err!(serde_wasm_bindgen::from_value(public_key))?;
+    to_js(public_key.to_cbor())
+}
+
+/// Decodes a BBS public key from CBOR
+#[wasm_bindgen(js_name = bbsPublicKeyFromCbor)]
+pub async fn bbs_public_key_from_cbor(cbor: Vec<u8>) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let public_key = map_err!(PublicKey::from_cbor(&cbor))?;
+    Ok(serde_wasm_bindgen::to_value(&public_key).unwrap())
+}
+
+/// Encodes a BBS+ signature as CBOR
+#[wasm_bindgen(js_name = signatureToCbor)]
+pub async fn signature_to_cbor(signature: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let signature: Signature = map_err!(serde_wasm_bindgen::from_value(signature))?;
+    to_js(signature.to_cbor())
+}
+
+/// Decodes a BBS+ signature from CBOR
+#[wasm_bindgen(js_name = signatureFromCbor)]
+pub async fn signature_from_cbor(cbor: Vec<u8>) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let signature = map_err!(Signature::from_cbor(&cbor))?;
+    Ok(serde_wasm_bindgen::to_value(&signature).unwrap())
+}
+
+/// Encodes a BBS+ PoK as CBOR
+#[wasm_bindgen(js_name = proofToCbor)]
+pub async fn proof_to_cbor(proof: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let proof: PoKOfSignatureProofWrapper = map_err!(serde_wasm_bindgen::from_value(proof))?;
+    to_js(proof.to_cbor())
+}
+
+/// Decodes a BBS+ PoK from CBOR
+#[wasm_bindgen(js_name = proofFromCbor)]
+pub async fn proof_from_cbor(cbor: Vec<u8>) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let proof = map_err!(PoKOfSignatureProofWrapper::from_cbor(&cbor))?;
+    Ok(serde_wasm_bindgen::to_value(&proof).unwrap())
+}
+
+fn to_js(cbor: Result<Vec<u8>, BBSError>) -> Result<JsValue, JsValue> {
+    Ok(js_sys::Uint8Array::from(map_err!(cbor)?.as_slice()).into())
+}
+
+fn required<'a>(map: &'a Value, key: &Value) -> Result<&'a Value, BBSError> {
+    map.get(key)?
+        .ok_or_else(|| general_error("A required CBOR map entry is missing"))
+}
+
+/// Checks the public key is a point of the group and, when there is a secret key, that
+/// it is the public key of the secret key
+pub(crate) fn check_key_pair<G: CurveProjective<Engine = Bls12, Scalar = Fr> + SerDes>(
+    public_key: &[u8],
+    secret_key: Option<&[u8]>,
+    size: usize,
+) -> Result<Option<SecretKey>, BBSError> {
+    if public_key.len() != size {
+        return Err(general_error("Invalid BLS 12-381 public key"));
+    }
+    let point = G::deserialize(&mut &public_key[..], true)
+        .map_err(|_| general_error("Invalid BLS 12-381 public key"))?;
+    if point.is_zero() {
+        return Err(general_error("Invalid BLS 12-381 public key"));
+    }
+    let secret_key = match secret_key {
+        Some(sk) => sk,
+        None => return Ok(None),
+    };
+    let sk = secret_key_from_bytes(secret_key)?;
+    let scalar = Fr::deserialize(&mut &sk.to_bytes_compressed_form()[..], true)
+        .map_err(|_| general_error("Invalid BLS 12-381 secret key"))?;
+    let mut expected = G::one();
+    expected.mul_assign(scalar);
+    if expected != point {
+        return Err(general_error(
+            "The secret key does not match the public key",
+        ));
+    }
+    Ok(Some(sk))
+}
+
+fn secret_key_from_bytes(bytes: &[u8]) -> Result<SecretKey, BBSError> {
+    if bytes.len() != FR_COMPRESSED_SIZE {
+        return Err(general_error("Invalid BLS 12-381 secret key"));
+    }
+    SecretKey::try_from(bytes).map_err(|_| general_error("Invalid BLS 12-381 secret key"))
+}
+
+fn public_key_value(public_key: &PublicKey) -> Value {
+    Value::Array(vec![
+        Value::Bytes(public_key.w.to_bytes_compressed_form().to_vec()),
+        Value::Bytes(public_key.h0.to_bytes_compressed_form().to_vec()),
+        Value::Array(
+            public_key
+                .h
+                .iter()
+                .map(|h| Value::Bytes(h.to_bytes_compressed_form().to_vec()))
+                .collect(),
+        ),
+    ])
+}
+
+fn public_key_from_value(value: &Value) -> Result<PublicKey, BBSError> {
+    let (w, h0, h) = match value.as_array()? {
+        [w, h0, h] => (w.as_bytes()?, h0.as_bytes()?, h.as_array()?),
+        _ => return Err(general_error("Invalid BBS public key")),
+    };
+    if w.len() != G2_COMPRESSED_SIZE || h0.len() != G1_COMPRESSED_SIZE {
+        return Err(general_error("Invalid BBS public key"));
+    }
+    let mut generators = Vec::with_capacity(h.len());
+    for h in h {
+        let h = h.as_bytes()?;
+        if h.len() != G1_COMPRESSED_SIZE {
+            return Err(general_error("Invalid BBS public key"));
+        }
+        generators.push(GeneratorG1::try_from(h)?);
+    }
+    let public_key = PublicKey {
+        w: GeneratorG2::try_from(w)?,
+        h0: GeneratorG1::try_from(h0)?,
+        h: generators,
+    };
+    public_key.validate()?;
+    Ok(public_key)
+}
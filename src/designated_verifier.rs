@@ -0,0 +1,162 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Designated verifier proofs.
+//!
+//! The `designatedVerifier` option of `createProof` turns the proof into an
+//! OR-composition of the proof of knowledge of a signature, with all the other
+//! statements of the proof, and a proof of knowledge of the secret key `x` of the
+//! verifier's BLS 12-381 G2 public key `Y = P^x`. The challenges of both branches sum to
+//! the challenge of the proof. The holder proves the signature branch and simulates the
+//! key branch, while the verifier could have produced the same transcript with its
+//! secret key, so the proof convinces the verifier but nobody else.
+
+use crate::statements::{write_scalar, StatementReader};
+use crate::utils::general_error;
+
+use bbs::prelude::*;
+use ff_zeroize::Field;
+use pairing_plus::{bls12_381::G2, serdes::SerDes, CurveProjective};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+wasm_impl!(
+    DesignatedVerifierOptions,
+    verifierPublicKey: DeterministicPublicKey
+);
+
+/// Simulates the key branch of a designated verifier proof
+pub(crate) struct DesignatedVerifierProver {
+    verifier_public_key: G2,
+    commitment: G2,
+    challenge: ProofChallenge,
+    response: SignatureMessage,
+}
+
+impl DesignatedVerifierProver {
+    pub(crate) fn new(options: &DesignatedVerifierOptions) -> Result<Self, BBSError> {
+        let verifier_public_key = *options.verifierPublicKey.as_ref();
+        if verifier_public_key.is_zero() {
+            return Err(general_error("Invalid verifier public key"));
+        }
+        // The key branch is simulated from a random challenge and response
+        let challenge = ProofChallenge::random();
+        let response = SignatureMessage::random();
+        Ok(Self {
+            verifier_public_key,
+            commitment: key_commitment(&verifier_public_key, &response, &challenge),
+            challenge,
+            response,
+        })
+    }
+
+    /// Append the bytes of the key branch to hash into the challenge
+    pub(crate) fn commit(&self, bytes: &mut Vec<u8>) {
+        bytes.append(&mut verifier_bytes(
+            &self.verifier_public_key,
+            &self.commitment,
+        ));
+    }
+
+    /// The challenge of the signature branch for the challenge of the proof
+    pub(crate) fn signature_challenge(&self, challenge: &ProofChallenge) -> ProofChallenge {
+        signature_challenge(challenge, &self.challenge)
+    }
+
+    /// Append the proof of the key branch
+    pub(crate) fn prove(&self, proof: &mut Vec<u8>) {
+        self.commitment.serialize(proof, true).unwrap();
+        write_scalar(proof, &SignatureMessage::from(*self.challenge.as_ref()));
+        write_scalar(proof, &self.response);
+    }
+}
+
+/// Checks the key branch of a designated verifier proof
+pub(crate) struct DesignatedVerifierVerifier {
+    verifier_public_key: G2,
+    commitment: G2,
+    challenge: ProofChallenge,
+    response: SignatureMessage,
+}
+
+impl DesignatedVerifierVerifier {
+    pub(crate) fn new(options: &DesignatedVerifierOptions) -> Self {
+        Self {
+            verifier_public_key: *options.verifierPublicKey.as_ref(),
+            commitment: G2::zero(),
+            challenge: ProofChallenge::default(),
+            response: SignatureMessage::default(),
+        }
+    }
+
+    /// Read the proof of the key branch, appending the bytes to hash into the challenge
+    pub(crate) fn read(
+        &mut self,
+        reader: &mut StatementReader,
+        bytes: &mut Vec<u8>,
+    ) -> Result<(), BBSError> {
+        self.commitment = reader.g2_point()?;
+        self.challenge = ProofChallenge::from(*reader.scalar()?.as_ref());
+        self.response = reader.scalar()?;
+        bytes.append(&mut verifier_bytes(
+            &self.verifier_public_key,
+            &self.commitment,
+        ));
+        Ok(())
+    }
+
+    /// The challenge of the signature branch for the challenge of the proof
+    pub(crate) fn signature_challenge(&self, challenge: &ProofChallenge) -> ProofChallenge {
+        signature_challenge(challenge, &self.challenge)
+    }
+
+    /// Check the key branch for its challenge
+    pub(crate) fn verify(&self) -> bool {
+        !self.verifier_public_key.is_zero()
+            && key_commitment(&self.verifier_public_key, &self.response, &self.challenge)
+                == self.commitment
+    }
+}
+
+/// The challenge of the signature branch, so both branch challenges sum to the
+/// challenge of the proof
+fn signature_challenge(
+    challenge: &ProofChallenge,
+    key_challenge: &ProofChallenge,
+) -> ProofChallenge {
+    let mut signature_challenge = *challenge.as_ref();
+    signature_challenge.sub_assign(key_challenge.as_ref());
+    ProofChallenge::from(signature_challenge)
+}
+
+/// The commitment `P^response * Y^challenge` of the key branch
+fn key_commitment(
+    verifier_public_key: &G2,
+    response: &SignatureMessage,
+    challenge: &ProofChallenge,
+) -> G2 {
+    let mut commitment = G2::one();
+    commitment.mul_assign(*response.as_ref());
+    let mut y = *verifier_public_key;
+    y.mul_assign(*challenge.as_ref());
+    commitment.add_assign(&y);
+    commitment
+}
+
+fn verifier_bytes(verifier_public_key: &G2, verifier_commitment: &G2) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for p in &[G2::one(), *verifier_public_key, *verifier_commitment] {
+        p.serialize(&mut bytes, false).unwrap();
+    }
+    bytes
+}
@@ -0,0 +1,228 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Holder binding through a link secret.
+//!
+//! The holder generates a link secret once and has it signed blindly at a fixed
+//! message index of each credential. Verifiers know the holder by a Pedersen
+//! commitment `g^m * h^r` to the link secret `m`. The `holderBinding` option of
+//! `createProof` inserts the link secret hidden at its index and proves it is the value
+//! committed to by the verifier supplied commitment, which the `holderBinding` option of
+//! `verifyProof` checks.
+
+use crate::bbs_plus::{blind_signature_commitment, BlindSignatureContextResponse};
+use crate::linked_proof;
+use crate::numeric_message::message_scalar;
+use crate::statements::{
+    write_point, write_scalar, ProverStatement, StatementReader, VerifierStatement,
+};
+use crate::utils::{set_panic_hook, zeroize_copy};
+
+use bbs::prelude::*;
+use pairing_plus::{bls12_381::G1, CurveProjective};
+use rand::{thread_rng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, convert::TryInto};
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroize;
+
+const LINK_SECRET_LENGTH: usize = 32;
+const GENERATOR_DST: &[u8] = b"BBS_LINK_SECRET_COMMITMENT_GENERATOR_";
+
+wasm_impl!(
+    LinkSecretBlindSignCommitmentRequest,
+    publicKey: PublicKey,
+    linkSecret: Vec<u8>,
+    linkSecretIndex: usize,
+    nonce: Vec<u8>
+);
+
+wasm_impl!(LinkSecretCommitmentRequest, linkSecret: Vec<u8>);
+
+wasm_impl!(
+    LinkSecretCommitment,
+    commitment: Commitment,
+    blindingFactor: SignatureBlinding
+);
+
+wasm_impl!(
+    HolderBindingProofOptions,
+    linkSecret: Vec<u8>,
+    linkSecretIndex: usize,
+    commitment: Commitment,
+    blindingFactor: SignatureBlinding
+);
+
+wasm_impl!(
+    HolderBindingVerifyOptions,
+    linkSecretIndex: usize,
+    commitment: Commitment
+);
+
+// The link secret and the blinding factor are wiped when the options are dropped
+impl Drop for HolderBindingProofOptions {
+    fn drop(&mut self) {
+        self.linkSecret.zeroize();
+        zeroize_copy(&mut self.blindingFactor);
+    }
+}
+
+/// Generates a new random link secret
+#[wasm_bindgen(js_name = generateLinkSecret)]
+pub async fn generate_link_secret() -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let mut link_secret = vec![0u8; LINK_SECRET_LENGTH];
+    thread_rng().fill_bytes(link_secret.as_mut_slice());
+    Ok(js_sys::Uint8Array::from(link_secret.as_slice()).into())
+}
+
+/// Creates the blind signature commitment to a link secret at `linkSecretIndex`,
+/// to be signed by the issuer with `blindSign`
+#[wasm_bindgen(js_name = linkSecretBlindSignCommitment)]
+pub async fn link_secret_blind_sign_commitment(request: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let request: LinkSecretBlindSignCommitmentRequest = request.try_into()?;
+    let response: BlindSignatureContextResponse = blind_signature_commitment(
+        &request.publicKey,
+        &[request.linkSecret],
+        &[request.linkSecretIndex],
+        &request.nonce,
+    )?;
+    Ok(serde_wasm_bindgen::to_value(&response).unwrap())
+}
+
+/// Creates the commitment to a link secret that verifiers know the holder by.
+/// The blinding factor must be kept by the holder to create proofs
+#[wasm_bindgen(js_name = createLinkSecretCommitment)]
+pub async fn create_link_secret_commitment(request: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let request: LinkSecretCommitmentRequest = request.try_into()?;
+    let blinding_factor = SignatureBlinding::random();
+    let commitment = linked_proof::multi_exp(
+        &linked_proof::generators(GENERATOR_DST),
+        &[
+            message_scalar(&request.linkSecret),
+            SignatureMessage::from(*blinding_factor.as_ref()),
+        ],
+    );
+    Ok(serde_wasm_bindgen::to_value(&LinkSecretCommitment {
+        commitment: Commitment::from(commitment),
+        blindingFactor: blinding_factor,
+    })
+    .unwrap())
+}
+
+/// Proves the hidden link secret is the value committed to by the verifier supplied
+/// commitment, with a proof of knowledge of the blinding factor
+pub(crate) struct HolderBindingProver<'a> {
+    options: &'a HolderBindingProofOptions,
+    blinding: ProofNonce,
+    commitment: G1,
+}
+
+impl<'a> HolderBindingProver<'a> {
+    pub(crate) fn new(options: &'a HolderBindingProofOptions) -> Self {
+        Self {
+            options,
+            blinding: ProofNonce::random(),
+            commitment: G1::zero(),
+        }
+    }
+}
+
+impl ProverStatement for HolderBindingProver<'_> {
+    fn linked(&self) -> Vec<usize> {
+        vec![self.options.linkSecretIndex]
+    }
+
+    fn commit(
+        &mut self,
+        _messages: &[SignatureMessage],
+        blindings: &BTreeMap<usize, ProofNonce>,
+        bytes: &mut Vec<u8>,
+    ) -> Result<(), BBSError> {
+        self.commitment = linked_proof::multi_exp(
+            &linked_proof::generators(GENERATOR_DST),
+            &[
+                SignatureMessage::from(*blindings[&self.options.linkSecretIndex].as_ref()),
+                SignatureMessage::from(*self.blinding.as_ref()),
+            ],
+        );
+        append_binding_bytes(bytes, &self.options.commitment, &self.commitment);
+        Ok(())
+    }
+
+    fn prove(&self, challenge: &ProofChallenge, proof: &mut Vec<u8>) {
+        write_point(proof, &self.commitment);
+        write_scalar(
+            proof,
+            &linked_proof::response(
+                &self.blinding,
+                challenge,
+                &SignatureMessage::from(*self.options.blindingFactor.as_ref()),
+            ),
+        );
+    }
+}
+
+/// Checks the hidden link secret is the value committed to by the commitment the
+/// verifier knows the holder by
+pub(crate) struct HolderBindingVerifier<'a> {
+    options: &'a HolderBindingVerifyOptions,
+    commitment: G1,
+    blinding_factor_response: SignatureMessage,
+}
+
+impl<'a> HolderBindingVerifier<'a> {
+    pub(crate) fn new(options: &'a HolderBindingVerifyOptions) -> Self {
+        Self {
+            options,
+            commitment: G1::zero(),
+            blinding_factor_response: SignatureMessage::default(),
+        }
+    }
+}
+
+impl VerifierStatement for HolderBindingVerifier<'_> {
+    fn linked(&self) -> Vec<usize> {
+        vec![self.options.linkSecretIndex]
+    }
+
+    fn read(&mut self, reader: &mut StatementReader, bytes: &mut Vec<u8>) -> Result<(), BBSError> {
+        self.commitment = reader.point()?;
+        self.blinding_factor_response = reader.scalar()?;
+        append_binding_bytes(bytes, &self.options.commitment, &self.commitment);
+        Ok(())
+    }
+
+    fn verify(
+        &self,
+        responses: &BTreeMap<usize, SignatureMessage>,
+        challenge: &ProofChallenge,
+    ) -> bool {
+        linked_proof::recompute_commitment(
+            &linked_proof::generators(GENERATOR_DST),
+            &[
+                responses[&self.options.linkSecretIndex],
+                self.blinding_factor_response,
+            ],
+            self.options.commitment.as_ref(),
+            challenge,
+        ) == self.commitment
+    }
+}
+
+fn append_binding_bytes(bytes: &mut Vec<u8>, commitment: &Commitment, binding_commitment: &G1) {
+    let [g, h] = linked_proof::generators(GENERATOR_DST);
+    linked_proof::append_points(bytes, &[g, h, *commitment.as_ref(), *binding_commitment]);
+}
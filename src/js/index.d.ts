@@ -16,12 +16,68 @@ import {
   Bls12381ToBbsRequest,
   BbsKeyPair,
   BbsSignRequest,
+  BbsSignBatchRequest,
   BlsBbsSignRequest,
   BbsVerifyRequest,
   BbsVerifyResult,
+  BbsVerifyProofResult,
   BlsBbsVerifyRequest,
   BbsCreateProofRequest,
   BbsVerifyProofRequest,
+  BlsVerifyProofRequest,
+  BbsWorkerPool,
+  BbsWorkerPoolOptions,
+  ThresholdKeyGenRound1Request,
+  ThresholdKeyDealing,
+  ThresholdKeyGenRound2Request,
+  ThresholdKeyShare,
+  ThresholdPresignRound1Request,
+  ThresholdPresignDealing,
+  ThresholdPresignRound2Request,
+  ThresholdPresignRound2Response,
+  ThresholdPresignRound3Request,
+  ThresholdPresignRound3Response,
+  ThresholdPresignFinalizeRequest,
+  ThresholdPresignature,
+  ThresholdPartialSignRequest,
+  ThresholdPartialSignature,
+  ThresholdCombineRequest,
+  LinkSecretBlindSignCommitmentRequest,
+  LinkSecretCommitmentRequest,
+  LinkSecretCommitment,
+  BbsBlindSignContext,
+  AccumulatorUpdateRequest,
+  AccumulatorUpdateResponse,
+  AccumulatorWitnessRequest,
+  AccumulatorVerifyWitnessRequest,
+  AccumulatorUpdateWitnessRequest,
+  StatusListSignRequest,
+  StatusListGap,
+  LinearRelationCreateProofRequest,
+  LinearRelationProof,
+  LinearRelationVerifyProofRequest,
+  VerifiableEncryptionDecryptRequest,
+  VerifiableEncryptionDecryptResponse,
+  BbsBlsSignature2020SignRequest,
+  BbsBlsSignature2020Response,
+  BbsBlsSignature2020VerifyRequest,
+  BbsBlsSignatureProof2020DeriveRequest,
+  BbsBlsSignatureProof2020DeriveResponse,
+  BbsBlsSignatureProof2020VerifyRequest,
+  JwpIssueRequest,
+  JwpResponse,
+  JwpVerifyRequest,
+  JwpPresentRequest,
+  JwpParseRequest,
+  JwpParseResponse,
+  JwkExportRequest,
+  Jwk,
+  DidKeyDocument,
+  KeystoreExportRequest,
+  KeystoreImportRequest,
+  KeyHandle,
+  KeyPossessionProofRequest,
+  KeyPossessionVerifyRequest,
 } from "./types";
 
 export * from "./types";
@@ -48,6 +104,10 @@ export function bls12381toBbs(
 
 export function sign(request: BbsSignRequest): Promise<Uint8Array>;
 
+export function signBatch(
+  request: BbsSignBatchRequest
+): Promise<Uint8Array[]>;
+
 export function blsSign(request: BlsBbsSignRequest): Promise<Uint8Array>;
 
 export function verify(request: BbsVerifyRequest): Promise<BbsVerifyResult>;
@@ -62,12 +122,194 @@ export function createProof(
 
 export function verifyProof(
   request: BbsVerifyProofRequest
-): Promise<BbsVerifyResult>;
+): Promise<BbsVerifyProofResult>;
 
 export function blsCreateProof(
   request: BbsCreateProofRequest
 ): Promise<Uint8Array>;
 
 export function blsVerifyProof(
-  request: BbsVerifyProofRequest
+  request: BlsVerifyProofRequest
+): Promise<BbsVerifyProofResult>;
+
+export function thresholdKeyGenRound1(
+  request: ThresholdKeyGenRound1Request
+): Promise<ThresholdKeyDealing>;
+
+export function thresholdKeyGenRound2(
+  request: ThresholdKeyGenRound2Request
+): Promise<ThresholdKeyShare>;
+
+export function thresholdPresignRound1(
+  request: ThresholdPresignRound1Request
+): Promise<ThresholdPresignDealing>;
+
+export function thresholdPresignRound2(
+  request: ThresholdPresignRound2Request
+): Promise<ThresholdPresignRound2Response>;
+
+export function thresholdPresignRound3(
+  request: ThresholdPresignRound3Request
+): Promise<ThresholdPresignRound3Response>;
+
+export function thresholdPresignFinalize(
+  request: ThresholdPresignFinalizeRequest
+): Promise<ThresholdPresignature>;
+
+export function thresholdPartialSign(
+  request: ThresholdPartialSignRequest
+): Promise<ThresholdPartialSignature>;
+
+export function thresholdCombineSignatures(
+  request: ThresholdCombineRequest
+): Promise<Uint8Array>;
+
+export function generateLinkSecret(): Promise<Uint8Array>;
+
+export function linkSecretBlindSignCommitment(
+  request: LinkSecretBlindSignCommitmentRequest
+): Promise<BbsBlindSignContext>;
+
+export function createLinkSecretCommitment(
+  request: LinkSecretCommitmentRequest
+): Promise<LinkSecretCommitment>;
+
+export function accumulatorInitialize(): Promise<Uint8Array>;
+
+export function accumulatorUpdate(
+  request: AccumulatorUpdateRequest
+): Promise<AccumulatorUpdateResponse>;
+
+export function accumulatorMembershipWitness(
+  request: AccumulatorWitnessRequest
+): Promise<Uint8Array>;
+
+export function accumulatorVerifyWitness(
+  request: AccumulatorVerifyWitnessRequest
+): Promise<boolean>;
+
+export function accumulatorUpdateWitness(
+  request: AccumulatorUpdateWitnessRequest
+): Promise<Uint8Array>;
+
+export function signStatusList(
+  request: StatusListSignRequest
+): Promise<StatusListGap[]>;
+
+export function encodeNumericMessage(
+  value: number | bigint | string
+): Promise<Uint8Array>;
+
+export function decodeNumericMessage(message: Uint8Array): Promise<bigint>;
+
+export function createLinearRelationProof(
+  request: LinearRelationCreateProofRequest
+): Promise<LinearRelationProof>;
+
+export function verifyLinearRelationProof(
+  request: LinearRelationVerifyProofRequest
+): Promise<BbsVerifyResult>;
+
+export function decryptVerifiableEncryption(
+  request: VerifiableEncryptionDecryptRequest
+): Promise<VerifiableEncryptionDecryptResponse>;
+
+export function generateBlindingFactor(): Promise<Uint8Array>;
+
+export function messageCommitmentGenerators(): Promise<Uint8Array[]>;
+
+export function signBbsBlsSignature2020(
+  request: BbsBlsSignature2020SignRequest
+): Promise<BbsBlsSignature2020Response>;
+
+export function verifyBbsBlsSignature2020(
+  request: BbsBlsSignature2020VerifyRequest
+): Promise<BbsVerifyResult>;
+
+export function deriveBbsBlsSignatureProof2020(
+  request: BbsBlsSignatureProof2020DeriveRequest
+): Promise<BbsBlsSignatureProof2020DeriveResponse>;
+
+export function verifyBbsBlsSignatureProof2020(
+  request: BbsBlsSignatureProof2020VerifyRequest
+): Promise<BbsVerifyResult>;
+
+export function createIssuedJwp(request: JwpIssueRequest): Promise<JwpResponse>;
+
+export function verifyIssuedJwp(
+  request: JwpVerifyRequest
+): Promise<BbsVerifyResult>;
+
+export function createPresentedJwp(
+  request: JwpPresentRequest
+): Promise<JwpResponse>;
+
+export function verifyPresentedJwp(
+  request: JwpVerifyRequest
+): Promise<BbsVerifyResult>;
+
+export function parseJwp(request: JwpParseRequest): Promise<JwpParseResponse>;
+
+export function blsKeyPairToCoseKey(keyPair: BlsKeyPair): Promise<Uint8Array>;
+
+export function blsKeyPairFromCoseKey(coseKey: Uint8Array): Promise<BlsKeyPair>;
+
+export function bbsKeyPairToCbor(keyPair: BbsKeyPair): Promise<Uint8Array>;
+
+export function bbsKeyPairFromCbor(cbor: Uint8Array): Promise<BbsKeyPair>;
+
+export function bbsPublicKeyToCbor(publicKey: Uint8Array): Promise<Uint8Array>;
+
+export function bbsPublicKeyFromCbor(cbor: Uint8Array): Promise<Uint8Array>;
+
+export function signatureToCbor(signature: Uint8Array): Promise<Uint8Array>;
+
+export function signatureFromCbor(cbor: Uint8Array): Promise<Uint8Array>;
+
+export function proofToCbor(proof: Uint8Array): Promise<Uint8Array>;
+
+export function proofFromCbor(cbor: Uint8Array): Promise<Uint8Array>;
+
+export function exportJwk(request: JwkExportRequest): Promise<Jwk>;
+
+export function importJwk(jwk: Jwk): Promise<BlsKeyPair>;
+
+export function encodeMultikey(publicKey: Uint8Array): Promise<string>;
+
+export function decodeMultikey(multikey: string): Promise<Uint8Array>;
+
+export function createDidKeyDocument(
+  publicKey: Uint8Array | string
+): Promise<DidKeyDocument>;
+
+export function exportEncryptedKey(
+  request: KeystoreExportRequest
+): Promise<string>;
+
+export function importEncryptedKey(
+  request: KeystoreImportRequest
+): Promise<BlsKeyPair>;
+
+export function generateBls12381G1KeyHandle(
+  seed?: Uint8Array
+): Promise<KeyHandle>;
+
+export function generateBls12381G2KeyHandle(
+  seed?: Uint8Array
+): Promise<KeyHandle>;
+
+export function importKeyHandle(keyPair: BlsKeyPair): Promise<KeyHandle>;
+
+export function importEncryptedKeyHandle(
+  request: KeystoreImportRequest
+): Promise<KeyHandle>;
+
+export function createKeyPossessionProof(
+  request: KeyPossessionProofRequest
+): Promise<Uint8Array>;
+
+export function verifyKeyPossessionProof(
+  request: KeyPossessionVerifyRequest
 ): Promise<BbsVerifyResult>;
+
+export function createWorkerPool(options: BbsWorkerPoolOptions): BbsWorkerPool;
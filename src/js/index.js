@@ -24,9 +24,53 @@ let useWasm = !(
     process.env.BBS_SIGNATURES_MODE === BBS_SIGNATURES_MODES.nodejs)
 );
 
+// The options of proof requests for the statements about the hidden messages,
+// which the node module does not implement
+const PROOF_STATEMENTS = [
+  "holderBinding",
+  "pseudonym",
+  "accumulator",
+  "statusList",
+  "verifiableEncryption",
+  "committed",
+  "notEqual",
+  "setMembership",
+  "rateLimit",
+  "designatedVerifier",
+];
+// Numeric messages are signed as their values, which the node module does not
+// implement
+const NUMERIC_MESSAGE_PREFIX = Buffer.from("\xffBBS_NUMERIC_MESSAGE", "latin1");
+const NUMERIC_MESSAGE_LENGTH = NUMERIC_MESSAGE_PREFIX.length + 32;
+
+const isNumericMessage = (message) =>
+  message != null &&
+  message.length === NUMERIC_MESSAGE_LENGTH &&
+  NUMERIC_MESSAGE_PREFIX.every((byte, i) => message[i] === byte);
+
+const requiresWasm = (request) =>
+  request !== null &&
+  typeof request === "object" &&
+  (PROOF_STATEMENTS.some((statement) => request[statement] !== undefined) ||
+    (Array.isArray(request.messages) &&
+      request.messages.some(isNumericMessage)));
+
 try {
   if (!useWasm) {
-    module.exports = require("@mattrglobal/node-bbs-signatures");
+    // The node module only implements the original operations, the others
+    // are served by the WASM module
+    const wasmModule = require("./wasm_module");
+    const nodeModule = require("@mattrglobal/node-bbs-signatures");
+    module.exports = { ...wasmModule, ...nodeModule };
+    // As are the requests with proof statements or numeric messages
+    Object.keys(nodeModule)
+      .filter((operation) => typeof wasmModule[operation] === "function")
+      .forEach((operation) => {
+        module.exports[operation] = (request) =>
+          requiresWasm(request)
+            ? wasmModule[operation](request)
+            : nodeModule[operation](request);
+      });
   }
 } catch {
   if (process.env.BBS_SIGNATURES_MODE === BBS_SIGNATURES_MODES.nodejs) {
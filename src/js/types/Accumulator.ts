@@ -0,0 +1,139 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/**
+ * A request to add and remove revocation ids from an accumulator
+ */
+export interface AccumulatorUpdateRequest {
+  /**
+   * Secret key of the accumulator, a BLS12-381 G2 key pair secret key
+   */
+  readonly secretKey: Uint8Array;
+  /**
+   * The current accumulator
+   */
+  readonly accumulator: Uint8Array;
+  /**
+   * Revocation ids to add
+   */
+  readonly additions: readonly Uint8Array[];
+  /**
+   * Revocation ids to remove
+   */
+  readonly removals: readonly Uint8Array[];
+}
+
+/**
+ * A batch of additions and removals published for holders to update their witnesses,
+ * the additions are applied before the removals
+ */
+export interface AccumulatorBatchUpdate {
+  /**
+   * The hashed revocation ids that were added
+   */
+  readonly additions: readonly Uint8Array[];
+  /**
+   * The hashed revocation ids that were removed
+   */
+  readonly removals: readonly Uint8Array[];
+  /**
+   * The update polynomial of the additions
+   */
+  readonly additionPolynomial: readonly Uint8Array[];
+  /**
+   * The update polynomial of the removals
+   */
+  readonly removalPolynomial: readonly Uint8Array[];
+}
+
+/**
+ * The result of updating an accumulator
+ */
+export interface AccumulatorUpdateResponse {
+  /**
+   * The new accumulator
+   */
+  readonly accumulator: Uint8Array;
+  /**
+   * The batch update to publish to holders
+   */
+  readonly update: AccumulatorBatchUpdate;
+  /**
+   * Witnesses of the added revocation ids, in order
+   */
+  readonly witnesses: readonly Uint8Array[];
+}
+
+/**
+ * A request to create the witness of a revocation id
+ */
+export interface AccumulatorWitnessRequest {
+  readonly secretKey: Uint8Array;
+  readonly accumulator: Uint8Array;
+  readonly element: Uint8Array;
+}
+
+/**
+ * A request to check the witness of a revocation id
+ */
+export interface AccumulatorVerifyWitnessRequest {
+  /**
+   * Public key of the accumulator, a BLS12-381 G2 public key
+   */
+  readonly publicKey: Uint8Array;
+  readonly accumulator: Uint8Array;
+  readonly element: Uint8Array;
+  readonly witness: Uint8Array;
+}
+
+/**
+ * A request to update the witness of a revocation id from published batch updates
+ */
+export interface AccumulatorUpdateWitnessRequest {
+  readonly element: Uint8Array;
+  readonly witness: Uint8Array;
+  /**
+   * The batch updates published since the witness was created, in order
+   */
+  readonly updates: readonly AccumulatorBatchUpdate[];
+}
+
+/**
+ * Proves the hidden revocation id of a proof is in an accumulator
+ */
+export interface AccumulatorProofOptions {
+  /**
+   * Zero based index of the hidden revocation id in the signed messages
+   */
+  readonly revocationIndex: number;
+  readonly accumulator: Uint8Array;
+  /**
+   * The witness of the revocation id for `accumulator`
+   */
+  readonly witness: Uint8Array;
+}
+
+/**
+ * Checks the hidden revocation id of a proof is in an accumulator
+ */
+export interface AccumulatorVerifyOptions {
+  readonly revocationIndex: number;
+  /**
+   * The accumulator to check membership against
+   */
+  readonly accumulator: Uint8Array;
+  /**
+   * Public key of the accumulator
+   */
+  readonly publicKey: Uint8Array;
+}
@@ -0,0 +1,116 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+import { BlsKeyPair } from "./BlsKeyPair";
+
+/**
+ * A JSON-LD document as a plain object
+ */
+export type JsonLdDocument = { readonly [name: string]: unknown };
+
+/**
+ * Context documents keyed by their URL, used in addition to the bundled
+ * credentials, security and BBS contexts. Remote contexts are never fetched
+ */
+export type JsonLdContexts = { readonly [url: string]: JsonLdDocument };
+
+/**
+ * A request to sign a JSON-LD document with `BbsBlsSignature2020`
+ */
+export interface BbsBlsSignature2020SignRequest {
+  /**
+   * BLS 12-381 G2 key pair of the issuer
+   */
+  readonly keyPair: BlsKeyPair;
+  /**
+   * The JSON-LD document without a proof
+   */
+  readonly document: JsonLdDocument;
+  /**
+   * The proof options, such as `verificationMethod`, `proofPurpose` and
+   * `created`. They take the `@context` of the document unless they have their
+   * own
+   */
+  readonly proof: JsonLdDocument;
+  readonly contexts?: JsonLdContexts;
+}
+
+/**
+ * The signed JSON-LD document with its `BbsBlsSignature2020` proof
+ */
+export interface BbsBlsSignature2020Response {
+  readonly document: JsonLdDocument;
+}
+
+/**
+ * A request to verify the `BbsBlsSignature2020` proof of a JSON-LD document
+ */
+export interface BbsBlsSignature2020VerifyRequest {
+  /**
+   * BLS 12-381 G2 public key of the issuer
+   */
+  readonly publicKey: Uint8Array;
+  /**
+   * The signed JSON-LD document
+   */
+  readonly document: JsonLdDocument;
+  readonly contexts?: JsonLdContexts;
+}
+
+/**
+ * A request to derive a `BbsBlsSignatureProof2020` proof from a signed JSON-LD
+ * document
+ */
+export interface BbsBlsSignatureProof2020DeriveRequest {
+  /**
+   * BLS 12-381 G2 public key of the issuer
+   */
+  readonly publicKey: Uint8Array;
+  /**
+   * The JSON-LD document signed with `BbsBlsSignature2020`
+   */
+  readonly document: JsonLdDocument;
+  /**
+   * The JSON-LD frame selecting the statements to reveal
+   */
+  readonly revealDocument: JsonLdDocument;
+  /**
+   * A base64 encoded nonce, generated when not given
+   */
+  readonly nonce?: string;
+  readonly contexts?: JsonLdContexts;
+}
+
+/**
+ * The framed JSON-LD document with its `BbsBlsSignatureProof2020` proof. Blank
+ * nodes are identified as `urn:bnid:_:c14nN`
+ */
+export interface BbsBlsSignatureProof2020DeriveResponse {
+  readonly document: JsonLdDocument;
+}
+
+/**
+ * A request to verify the `BbsBlsSignatureProof2020` proof of a derived JSON-LD
+ * document
+ */
+export interface BbsBlsSignatureProof2020VerifyRequest {
+  /**
+   * BLS 12-381 G2 public key of the issuer
+   */
+  readonly publicKey: Uint8Array;
+  /**
+   * The derived JSON-LD document
+   */
+  readonly document: JsonLdDocument;
+  readonly contexts?: JsonLdContexts;
+}
@@ -11,6 +11,17 @@
  * limitations under the License.
  */
 
+import { HolderBindingProofOptions } from "./HolderBinding";
+import { PseudonymOptions } from "./Pseudonym";
+import { AccumulatorProofOptions } from "./Accumulator";
+import { StatusListProofOptions } from "./StatusList";
+import { VerifiableEncryptionOptions } from "./VerifiableEncryption";
+import { MessageCommitmentOptions } from "./MessageCommitment";
+import { NotEqualPredicate } from "./Predicate";
+import { SetMembershipOptions } from "./SetMembership";
+import { RateLimitProofOptions } from "./RateLimit";
+import { DesignatedVerifierOptions } from "./DesignatedVerifier";
+
 /**
  * A request to create a BBS proof from a supplied BBS signature
  */
@@ -35,4 +46,45 @@ export interface BbsCreateProofRequest {
    * A nonce for the resulting proof
    */
   readonly nonce: Uint8Array;
+  /**
+   * Binds the proof to the holder through the link secret (OPTIONAL)
+   */
+  readonly holderBinding?: HolderBindingProofOptions;
+  /**
+   * Proves the pseudonym of the holder for a scope (OPTIONAL)
+   */
+  readonly pseudonym?: PseudonymOptions;
+  /**
+   * Proves the hidden revocation id is in an accumulator (OPTIONAL)
+   */
+  readonly accumulator?: AccumulatorProofOptions;
+  /**
+   * Proves the hidden status list index is unset in a status list (OPTIONAL)
+   */
+  readonly statusList?: StatusListProofOptions;
+  /**
+   * Encrypts the hidden message to an auditor (OPTIONAL)
+   */
+  readonly verifiableEncryption?: VerifiableEncryptionOptions;
+  /**
+   * Commits to hidden messages, in the order the verifier expects them (OPTIONAL)
+   */
+  readonly committed?: readonly MessageCommitmentOptions[];
+  /**
+   * Proves hidden messages do not equal public values (OPTIONAL)
+   */
+  readonly notEqual?: readonly NotEqualPredicate[];
+  /**
+   * Proves a hidden message is one of a public set of values (OPTIONAL)
+   */
+  readonly setMembership?: SetMembershipOptions;
+  /**
+   * Proves the one-show tag of the holder for an epoch (OPTIONAL)
+   */
+  readonly rateLimit?: RateLimitProofOptions;
+  /**
+   * Only convinces the designated verifier of the proof and its statements
+   * (OPTIONAL)
+   */
+  readonly designatedVerifier?: DesignatedVerifierOptions;
 }
@@ -0,0 +1,28 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+import { BbsKeyPair } from "./BbsKeyPair";
+
+/**
+ * A request to create BBS signatures for many sets of messages with one key pair
+ */
+export interface BbsSignBatchRequest {
+  /**
+   * BBS key pair
+   */
+  readonly keyPair: BbsKeyPair;
+  /**
+   * Sets of messages to sign, a signature is produced for each set in order
+   */
+  readonly messages: readonly (readonly Uint8Array[])[];
+}
@@ -11,6 +11,16 @@
  * limitations under the License.
  */
 
+import { HolderBindingVerifyOptions } from "./HolderBinding";
+import { PseudonymOptions } from "./Pseudonym";
+import { AccumulatorVerifyOptions } from "./Accumulator";
+import { StatusListVerifyOptions } from "./StatusList";
+import { VerifiableEncryptionOptions } from "./VerifiableEncryption";
+import { NotEqualPredicate } from "./Predicate";
+import { SetMembershipOptions } from "./SetMembership";
+import { RateLimitVerifyOptions } from "./RateLimit";
+import { DesignatedVerifierOptions } from "./DesignatedVerifier";
+
 /**
  * A request to verify a BBS proof
  */
@@ -31,4 +41,47 @@ export interface BbsVerifyProofRequest {
    * Nonce included in the proof for the un-revealed attributes (OPTIONAL)
    */
   readonly nonce: Uint8Array;
+  /**
+   * Checks the holder binding of the proof (OPTIONAL)
+   */
+  readonly holderBinding?: HolderBindingVerifyOptions;
+  /**
+   * Checks the pseudonym of the holder for a scope, returning it (OPTIONAL)
+   */
+  readonly pseudonym?: PseudonymOptions;
+  /**
+   * Checks the hidden revocation id is in an accumulator (OPTIONAL)
+   */
+  readonly accumulator?: AccumulatorVerifyOptions;
+  /**
+   * Checks the hidden status list index is unset in a status list (OPTIONAL)
+   */
+  readonly statusList?: StatusListVerifyOptions;
+  /**
+   * Checks the hidden message is encrypted to an auditor, returning the ciphertext
+   * (OPTIONAL)
+   */
+  readonly verifiableEncryption?: VerifiableEncryptionOptions;
+  /**
+   * Zero based indices of the committed hidden messages, returning the commitments
+   * (OPTIONAL)
+   */
+  readonly committed?: readonly number[];
+  /**
+   * Checks hidden messages do not equal public values, with the predicates the proof
+   * was created for in the same order (OPTIONAL)
+   */
+  readonly notEqual?: readonly NotEqualPredicate[];
+  /**
+   * Checks a hidden message is one of a public set of values (OPTIONAL)
+   */
+  readonly setMembership?: SetMembershipOptions;
+  /**
+   * Checks the one-show tag of the holder for an epoch, returning it (OPTIONAL)
+   */
+  readonly rateLimit?: RateLimitVerifyOptions;
+  /**
+   * Checks a proof created for the designated verifier (OPTIONAL)
+   */
+  readonly designatedVerifier?: DesignatedVerifierOptions;
 }
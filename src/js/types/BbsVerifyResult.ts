@@ -11,6 +11,8 @@
  * limitations under the License.
  */
 
+import { ElGamalCiphertext } from "./VerifiableEncryption";
+
 /**
  * The result returned from a verify operation
  */
@@ -24,3 +26,27 @@ export interface BbsVerifyResult {
    */
   readonly error?: string;
 }
+
+/**
+ * The result returned from a proof verify operation, with the outputs of the statements
+ * of the proof when it verifies
+ */
+export interface BbsVerifyProofResult extends BbsVerifyResult {
+  /**
+   * The pseudonym of the holder for the scope of the `pseudonym` option
+   */
+  readonly pseudonym?: Uint8Array;
+  /**
+   * The chunks of the hidden message encrypted to the auditor of the
+   * `verifiableEncryption` option
+   */
+  readonly ciphertext?: readonly ElGamalCiphertext[];
+  /**
+   * The commitments `g^m * h^s` to the hidden messages of the `committed` option
+   */
+  readonly commitments?: readonly Uint8Array[];
+  /**
+   * The one-show tag of the holder for the epoch of the `rateLimit` option
+   */
+  readonly tag?: Uint8Array;
+}
@@ -0,0 +1,69 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+import { BlsKeyPair } from "./BlsKeyPair";
+import { Bls12381ToBbsRequest } from "./Bls12381ToBbsRequest";
+import { BbsKeyPair } from "./BbsKeyPair";
+import { BbsSignRequest } from "./BbsSignRequest";
+import { BbsSignBatchRequest } from "./BbsSignBatchRequest";
+import { BlsBbsSignRequest } from "./BlsBbsSignRequest";
+import { BbsVerifyRequest } from "./BbsVerifyRequest";
+import { BlsBbsVerifyRequest } from "./BlsBbsVerifyRequest";
+import { BbsCreateProofRequest } from "./BbsCreateProofRequest";
+import { BbsVerifyProofRequest } from "./BbsVerifyProofRequest";
+import { BbsVerifyResult, BbsVerifyProofResult } from "./BbsVerifyResult";
+
+/**
+ * Options for creating a pool of workers
+ */
+export interface BbsWorkerPoolOptions {
+  /**
+   * URL of the bundled worker script (built from `worker.js` in this package)
+   */
+  readonly workerUrl: string | URL;
+  /**
+   * Number of workers in the pool, defaults to 2
+   */
+  readonly size?: number;
+  /**
+   * Options passed through to each `Worker` constructor
+   */
+  readonly workerOptions?: WorkerOptions;
+}
+
+/**
+ * A pool of dedicated workers that run operations off the main thread,
+ * resulting byte arrays are transferred back rather than copied
+ */
+export interface BbsWorkerPool {
+  /**
+   * Number of workers in the pool
+   */
+  readonly size: number;
+  generateBls12381G1KeyPair(seed?: Uint8Array): Promise<Required<BlsKeyPair>>;
+  generateBls12381G2KeyPair(seed?: Uint8Array): Promise<Required<BlsKeyPair>>;
+  bls12381toBbs(request: Bls12381ToBbsRequest): Promise<BbsKeyPair>;
+  sign(request: BbsSignRequest): Promise<Uint8Array>;
+  signBatch(request: BbsSignBatchRequest): Promise<Uint8Array[]>;
+  blsSign(request: BlsBbsSignRequest): Promise<Uint8Array>;
+  verify(request: BbsVerifyRequest): Promise<BbsVerifyResult>;
+  blsVerify(request: BlsBbsVerifyRequest): Promise<BbsVerifyResult>;
+  createProof(request: BbsCreateProofRequest): Promise<Uint8Array>;
+  blsCreateProof(request: BbsCreateProofRequest): Promise<Uint8Array>;
+  verifyProof(request: BbsVerifyProofRequest): Promise<BbsVerifyProofResult>;
+  blsVerifyProof(request: BbsVerifyProofRequest): Promise<BbsVerifyProofResult>;
+  /**
+   * Terminates all workers, rejecting any queued or in flight operations
+   */
+  terminate(): Promise<void>;
+}
@@ -16,9 +16,10 @@
  */
 export interface BlsBbsVerifyRequest {
   /**
-   * BLS12-381 public key of the signer of the signature
+   * BLS12-381 public key of the signer of the signature, as bytes, a multikey or a
+   * did:key identifier
    */
-  readonly publicKey: Uint8Array;
+  readonly publicKey: Uint8Array | string;
   /**
    * Raw signature value
    */
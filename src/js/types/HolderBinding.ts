@@ -0,0 +1,96 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/**
+ * A request to create the blind signature commitment to a link secret for issuance
+ */
+export interface LinkSecretBlindSignCommitmentRequest {
+  /**
+   * BBS public key of the issuer
+   */
+  readonly publicKey: Uint8Array;
+  /**
+   * The holder's link secret
+   */
+  readonly linkSecret: Uint8Array;
+  /**
+   * The zero based index of the link secret in the signed messages
+   */
+  readonly linkSecretIndex: number;
+  /**
+   * A nonce supplied by the issuer
+   */
+  readonly nonce: Uint8Array;
+}
+
+/**
+ * A request to create the commitment to a link secret that verifiers know the holder by
+ */
+export interface LinkSecretCommitmentRequest {
+  /**
+   * The holder's link secret
+   */
+  readonly linkSecret: Uint8Array;
+}
+
+/**
+ * A commitment to a link secret
+ */
+export interface LinkSecretCommitment {
+  /**
+   * The commitment, to share with verifiers
+   */
+  readonly commitment: Uint8Array;
+  /**
+   * The blinding factor of the commitment, to be kept by the holder
+   */
+  readonly blindingFactor: Uint8Array;
+}
+
+/**
+ * The holder binding option of a proof request, proving the link secret hidden at its
+ * index is the value committed to by the commitment the verifier knows the holder by
+ */
+export interface HolderBindingProofOptions {
+  /**
+   * The holder's link secret, which is not part of the messages of the request
+   */
+  readonly linkSecret: Uint8Array;
+  /**
+   * The zero based index of the link secret in the signed messages, which the revealed
+   * indices count
+   */
+  readonly linkSecretIndex: number;
+  /**
+   * The commitment to the link secret supplied by the verifier
+   */
+  readonly commitment: Uint8Array;
+  /**
+   * The blinding factor of the commitment
+   */
+  readonly blindingFactor: Uint8Array;
+}
+
+/**
+ * The holder binding option of a proof verification request
+ */
+export interface HolderBindingVerifyOptions {
+  /**
+   * The zero based index of the link secret in the signed messages
+   */
+  readonly linkSecretIndex: number;
+  /**
+   * The commitment to the holder's link secret
+   */
+  readonly commitment: Uint8Array;
+}
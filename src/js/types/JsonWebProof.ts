@@ -0,0 +1,102 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+import { BlsKeyPair } from "./BlsKeyPair";
+
+/**
+ * A request to create a JSON Web Proof in the issued form
+ */
+export interface JwpIssueRequest {
+  /**
+   * BLS 12-381 G2 key pair of the issuer
+   */
+  readonly keyPair: BlsKeyPair;
+  /**
+   * The issuer protected header, whose `alg` is set to `mattr.global/bbs-plus`
+   */
+  readonly header: { readonly [name: string]: unknown };
+  /**
+   * The payloads to sign
+   */
+  readonly payloads: readonly Uint8Array[];
+}
+
+/**
+ * A JSON Web Proof in the compact and JSON serializations
+ */
+export interface JwpResponse {
+  readonly compact: string;
+  readonly json: string;
+}
+
+/**
+ * A request to verify a JSON Web Proof
+ */
+export interface JwpVerifyRequest {
+  /**
+   * BLS 12-381 G2 public key of the issuer
+   */
+  readonly publicKey: Uint8Array;
+  /**
+   * The JSON Web Proof in the compact or JSON serialization
+   */
+  readonly jwp: string;
+}
+
+/**
+ * A request to create a JSON Web Proof in the presented form
+ */
+export interface JwpPresentRequest {
+  /**
+   * BLS 12-381 G2 public key of the issuer
+   */
+  readonly publicKey: Uint8Array;
+  /**
+   * The JSON Web Proof in the issued form, in either serialization
+   */
+  readonly jwp: string;
+  /**
+   * The presentation protected header, whose `alg` is set to `mattr.global/bbs-plus`
+   */
+  readonly header: { readonly [name: string]: unknown };
+  /**
+   * Indices of the payloads to disclose
+   */
+  readonly disclosed: readonly number[];
+}
+
+/**
+ * A request to parse a JSON Web Proof without verifying it
+ */
+export interface JwpParseRequest {
+  readonly jwp: string;
+}
+
+/**
+ * The decoded parts of a JSON Web Proof
+ */
+export interface JwpParseResponse {
+  /**
+   * The presentation protected header as JSON, present in the presented form only
+   */
+  readonly presentationHeader?: string;
+  /**
+   * The issuer protected header as JSON
+   */
+  readonly issuerHeader: string;
+  /**
+   * The payloads, `null` for a payload that is not disclosed
+   */
+  readonly payloads: readonly (Uint8Array | null)[];
+  readonly proof: Uint8Array;
+}
@@ -0,0 +1,45 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+import { BlsKeyPair } from "./BlsKeyPair";
+
+/**
+ * A BLS 12-381 JSON Web Key
+ */
+export interface Jwk {
+  readonly kty: "OKP" | "EC";
+  readonly crv: "BLS12381_G1" | "BLS12381_G2";
+  /**
+   * The compressed public key for `OKP`, or the x coordinate of the public key for `EC`
+   */
+  readonly x: string;
+  /**
+   * The y coordinate of the public key for `EC`
+   */
+  readonly y?: string;
+  /**
+   * The secret key
+   */
+  readonly d?: string;
+}
+
+/**
+ * A request to export a BLS 12-381 key pair as a JSON Web Key
+ */
+export interface JwkExportRequest {
+  readonly keyPair: BlsKeyPair;
+  /**
+   * The key type, `OKP` when not given
+   */
+  readonly kty?: "OKP" | "EC";
+}
@@ -0,0 +1,88 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/**
+ * A request to sign a set of messages with a key handle
+ */
+export interface KeyHandleSignRequest {
+  readonly messages: readonly Uint8Array[];
+}
+
+/**
+ * A request to sign the known messages and a blind signature commitment with a key handle
+ */
+export interface KeyHandleBlindSignRequest {
+  /**
+   * The resulting commitment of the blinded messages to sign
+   */
+  readonly commitment: Uint8Array;
+  /**
+   * The total number of messages, blinded and known
+   */
+  readonly messageCount: number;
+  /**
+   * The known messages to sign
+   */
+  readonly messages: readonly Uint8Array[];
+  /**
+   * The indices of the known messages
+   */
+  readonly known: readonly number[];
+}
+
+/**
+ * A request to encrypt the key pair of a key handle as an EIP-2335 keystore
+ */
+export interface KeyHandleExportRequest {
+  readonly password: string;
+  /**
+   * The key derivation function, `pbkdf2` when not given
+   */
+  readonly kdf?: "pbkdf2" | "scrypt";
+  /**
+   * The PBKDF2 iterations or the scrypt `n`, 262144 when not given
+   */
+  readonly cost?: number;
+  readonly description?: string;
+}
+
+/**
+ * A BLS 12-381 key pair whose secret key stays in WASM memory, signing on behalf of
+ * callers without exposing the secret key to JavaScript
+ */
+export interface KeyHandle {
+  /**
+   * The compressed G1 or G2 public key
+   */
+  readonly publicKey: Uint8Array;
+  /**
+   * Creates a BBS signature, requiring a G2 key
+   */
+  sign(request: KeyHandleSignRequest): Promise<Uint8Array>;
+  /**
+   * Creates a blind BBS signature, requiring a G2 key
+   */
+  blindSign(request: KeyHandleBlindSignRequest): Promise<Uint8Array>;
+  /**
+   * Creates a proof of possession of the secret key, requiring a G2 key
+   */
+  createKeyPossessionProof(): Promise<Uint8Array>;
+  /**
+   * Encrypts the key pair with a password, returning an EIP-2335 keystore as JSON
+   */
+  exportEncryptedKey(request: KeyHandleExportRequest): Promise<string>;
+  /**
+   * Zeroizes the secret key and releases the handle, after which it cannot be used
+   */
+  free(): void;
+}
@@ -0,0 +1,38 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+import { BlsKeyPair } from "./BlsKeyPair";
+
+/**
+ * A request to create a proof of possession of the secret key of a BLS 12-381 G2 key pair
+ */
+export interface KeyPossessionProofRequest {
+  /**
+   * The G2 key pair, with both the public and secret key
+   */
+  readonly keyPair: BlsKeyPair;
+}
+
+/**
+ * A request to verify a proof of possession of the secret key of a BLS 12-381 G2 public key
+ */
+export interface KeyPossessionVerifyRequest {
+  /**
+   * The G2 public key as bytes, a multikey or a did:key identifier
+   */
+  readonly publicKey: Uint8Array | string;
+  /**
+   * The compressed G1 proof
+   */
+  readonly proof: Uint8Array;
+}
@@ -0,0 +1,42 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+import { BlsKeyPair } from "./BlsKeyPair";
+
+/**
+ * A request to encrypt a BLS 12-381 key pair as an EIP-2335 keystore
+ */
+export interface KeystoreExportRequest {
+  readonly keyPair: BlsKeyPair;
+  readonly password: string;
+  /**
+   * The key derivation function, `pbkdf2` when not given
+   */
+  readonly kdf?: "pbkdf2" | "scrypt";
+  /**
+   * The PBKDF2 iterations or the scrypt `n`, 262144 when not given
+   */
+  readonly cost?: number;
+  readonly description?: string;
+}
+
+/**
+ * A request to decrypt a BLS 12-381 key pair from an EIP-2335 keystore
+ */
+export interface KeystoreImportRequest {
+  /**
+   * The keystore as JSON
+   */
+  readonly keystore: string;
+  readonly password: string;
+}
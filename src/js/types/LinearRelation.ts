@@ -0,0 +1,145 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/**
+ * The proof that a commitment commits to a bit
+ */
+export interface BitProof {
+  readonly commitment: Uint8Array;
+  readonly zeroCommitment: Uint8Array;
+  readonly oneCommitment: Uint8Array;
+  readonly zeroChallenge: Uint8Array;
+  readonly zeroResponse: Uint8Array;
+  readonly oneResponse: Uint8Array;
+}
+
+/**
+ * The proof that a value derived from hidden messages is in `[0, 2^64)`
+ */
+export interface RangeProof {
+  /**
+   * The proofs of the 64 bits of the value
+   */
+  readonly bits: readonly BitProof[];
+  readonly commitment: Uint8Array;
+  readonly blindingResponse: Uint8Array;
+}
+
+/**
+ * A term `coefficient * m` of a linear relation
+ */
+export interface LinearRelationTerm {
+  /**
+   * Zero based index of the credential holding the message
+   */
+  readonly credential: number;
+  /**
+   * Zero based index of the hidden message in the credential
+   */
+  readonly messageIndex: number;
+  readonly coefficient: number;
+}
+
+/**
+ * The relation `sum of terms = constant` over hidden message scalars, or the comparison
+ * of the sum of terms with the constant
+ */
+export interface LinearRelation {
+  readonly terms: readonly LinearRelationTerm[];
+  readonly constant: number;
+  /**
+   * How the sum of terms compares to the constant, `=` by default (OPTIONAL). The other
+   * comparisons are meaningful for numeric messages
+   */
+  readonly comparison?: "=" | ">" | ">=" | "<" | "<=";
+}
+
+/**
+ * A signed credential to create a linear relation proof over
+ */
+export interface LinearRelationCredential {
+  /**
+   * BBS signature
+   */
+  readonly signature: Uint8Array;
+  /**
+   * BBS public key
+   */
+  readonly publicKey: Uint8Array;
+  /**
+   * All the signed messages
+   */
+  readonly messages: readonly Uint8Array[];
+  /**
+   * Zero based indices of the messages to reveal
+   */
+  readonly revealed: readonly number[];
+}
+
+/**
+ * A request to create proofs of knowledge of signatures with linear relations between
+ * their hidden messages
+ */
+export interface LinearRelationCreateProofRequest {
+  readonly credentials: readonly LinearRelationCredential[];
+  /**
+   * A nonce for the resulting proof
+   */
+  readonly nonce: Uint8Array;
+  readonly relations: readonly LinearRelation[];
+}
+
+/**
+ * Proofs of knowledge of signatures with linear relations between their hidden messages
+ */
+export interface LinearRelationProof {
+  /**
+   * The proof of knowledge of each signature, in the order of the credentials
+   */
+  readonly proofs: readonly Uint8Array[];
+  /**
+   * The range proof of each comparison, in the order of the relations
+   */
+  readonly inequalities: readonly RangeProof[];
+}
+
+/**
+ * A credential a linear relation proof is verified against
+ */
+export interface LinearRelationVerifyCredential {
+  /**
+   * BBS public key
+   */
+  readonly publicKey: Uint8Array;
+  /**
+   * The revealed messages
+   */
+  readonly messages: readonly Uint8Array[];
+}
+
+/**
+ * A request to verify proofs of knowledge of signatures with linear relations between
+ * their hidden messages
+ */
+export interface LinearRelationVerifyProofRequest {
+  readonly proof: LinearRelationProof;
+  readonly credentials: readonly LinearRelationVerifyCredential[];
+  /**
+   * The nonce of the proof
+   */
+  readonly nonce: Uint8Array;
+  /**
+   * The relations the proof was created for, in the same order
+   */
+  readonly relations: readonly LinearRelation[];
+}
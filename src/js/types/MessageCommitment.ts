@@ -0,0 +1,27 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/**
+ * Commits to a hidden message of a proof as `g^m * h^s`, which the verifier gets
+ * in the result
+ */
+export interface MessageCommitmentOptions {
+  /**
+   * Zero based index of the hidden message to commit to
+   */
+  readonly messageIndex: number;
+  /**
+   * The blinding factor `s` of the commitment, kept by the holder to open it
+   */
+  readonly blindingFactor: Uint8Array;
+}
@@ -0,0 +1,38 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/**
+ * A `Multikey` verification method of a did:key document
+ */
+export interface DidKeyVerificationMethod {
+  readonly id: string;
+  readonly type: "Multikey";
+  readonly controller: string;
+  /**
+   * The base58btc multikey of the public key
+   */
+  readonly publicKeyMultibase: string;
+}
+
+/**
+ * A did:key document with a single verification method
+ */
+export interface DidKeyDocument {
+  readonly "@context": readonly string[];
+  readonly id: string;
+  readonly verificationMethod: readonly DidKeyVerificationMethod[];
+  readonly authentication: readonly string[];
+  readonly assertionMethod: readonly string[];
+  readonly capabilityDelegation: readonly string[];
+  readonly capabilityInvocation: readonly string[];
+}
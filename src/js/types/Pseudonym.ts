@@ -0,0 +1,26 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/**
+ * The pseudonym option of a proof request, for the pseudonym of the holder for a scope
+ */
+export interface PseudonymOptions {
+  /**
+   * Identifier of the verifier the pseudonym is for
+   */
+  readonly scope: Uint8Array;
+  /**
+   * Zero based index of the hidden message the pseudonym is derived from
+   */
+  readonly secretIndex: number;
+}
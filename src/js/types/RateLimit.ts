@@ -0,0 +1,50 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/**
+ * Proves the one-show tag for a hidden counter in an epoch, derived from the hidden
+ * secret of a proof
+ */
+export interface RateLimitProofOptions {
+  /**
+   * Zero based index of the hidden secret in the signed messages
+   */
+  readonly secretIndex: number;
+  /**
+   * The epoch of the tag, such as a date
+   */
+  readonly epoch: Uint8Array;
+  /**
+   * The counter of the presentation in the epoch, below `limit`
+   */
+  readonly counter: number;
+  /**
+   * The number of presentations allowed in an epoch
+   */
+  readonly limit: number;
+}
+
+/**
+ * Checks the one-show tag of a proof for an epoch, returning it
+ */
+export interface RateLimitVerifyOptions {
+  readonly secretIndex: number;
+  /**
+   * The epoch of the tag
+   */
+  readonly epoch: Uint8Array;
+  /**
+   * The number of presentations allowed in an epoch
+   */
+  readonly limit: number;
+}
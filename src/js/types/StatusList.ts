@@ -0,0 +1,74 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/**
+ * A request to sign the gaps between the set bits of a W3C Bitstring Status List
+ */
+export interface StatusListSignRequest {
+  /**
+   * Secret key of the status list publisher, a BLS12-381 G2 key pair secret key
+   */
+  readonly secretKey: Uint8Array;
+  /**
+   * The multibase base64url encoded, GZIP compressed bitstring of the status list
+   */
+  readonly encodedList: string;
+}
+
+/**
+ * The signature of the unset status list indices between `start` and `end`, both
+ * excluded, where `-1` and the length of the list are the outer bounds
+ */
+export interface StatusListGap {
+  readonly start: number;
+  readonly end: number;
+  readonly signature: Uint8Array;
+}
+
+/**
+ * Proves the hidden status list index of a proof is unset in a status list
+ */
+export interface StatusListProofOptions {
+  /**
+   * Zero based index of the hidden message holding the status list index as a
+   * numeric message
+   */
+  readonly statusMessageIndex: number;
+  /**
+   * The multibase base64url encoded, GZIP compressed bitstring of the status list
+   */
+  readonly encodedList: string;
+  /**
+   * Public key of the status list publisher, a BLS12-381 G2 public key
+   */
+  readonly publicKey: Uint8Array;
+  /**
+   * The signed gap of the status list holding the status list index
+   */
+  readonly gap: StatusListGap;
+}
+
+/**
+ * Checks the hidden status list index of a proof is unset in a status list
+ */
+export interface StatusListVerifyOptions {
+  readonly statusMessageIndex: number;
+  /**
+   * The multibase base64url encoded, GZIP compressed bitstring of the status list
+   */
+  readonly encodedList: string;
+  /**
+   * Public key of the status list publisher
+   */
+  readonly publicKey: Uint8Array;
+}
@@ -0,0 +1,273 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/**
+ * A request to start the distributed key generation as one of the participants
+ */
+export interface ThresholdKeyGenRound1Request {
+  /**
+   * Number of participants required to sign
+   */
+  readonly threshold: number;
+  /**
+   * Total number of participants
+   */
+  readonly participants: number;
+  /**
+   * Index of this participant, from 1 to `participants`
+   */
+  readonly index: number;
+}
+
+/**
+ * The dealing of a participant in the distributed key generation
+ */
+export interface ThresholdKeyDealing {
+  /**
+   * Index of the dealing participant
+   */
+  readonly index: number;
+  /**
+   * Commitments to the dealt polynomial, to broadcast to all participants
+   */
+  readonly commitments: readonly Uint8Array[];
+  /**
+   * Shares dealt to each participant, `shares[j - 1]` must be sent privately to participant `j`
+   */
+  readonly shares: readonly Uint8Array[];
+}
+
+/**
+ * A dealing received from another participant
+ */
+export interface ThresholdReceivedKeyDealing {
+  /**
+   * Index of the dealing participant
+   */
+  readonly from: number;
+  /**
+   * Commitments broadcast by the dealing participant
+   */
+  readonly commitments: readonly Uint8Array[];
+  /**
+   * Share privately sent to this participant
+   */
+  readonly share: Uint8Array;
+}
+
+/**
+ * A request to complete the distributed key generation as one of the participants
+ */
+export interface ThresholdKeyGenRound2Request {
+  readonly threshold: number;
+  readonly participants: number;
+  readonly index: number;
+  /**
+   * Dealings received from every participant, including this participant's own
+   */
+  readonly dealings: readonly ThresholdReceivedKeyDealing[];
+}
+
+/**
+ * A participant's share of the issuer secret key
+ */
+export interface ThresholdKeyShare {
+  readonly index: number;
+  readonly threshold: number;
+  readonly participants: number;
+  /**
+   * Share of the issuer secret key, must be kept private
+   */
+  readonly secretKeyShare: Uint8Array;
+  /**
+   * BLS12-381 G2 public key of the issuer
+   */
+  readonly publicKey: Uint8Array;
+}
+
+/**
+ * A request to start presigning as one of the presigning parties
+ */
+export interface ThresholdPresignRound1Request {
+  readonly threshold: number;
+  readonly index: number;
+  /**
+   * Indices of the presigning parties, at least `2 * threshold - 1`
+   */
+  readonly parties: readonly number[];
+}
+
+/**
+ * Shares of the random presignature values dealt to a presigning party
+ */
+export interface ThresholdPresignShare {
+  readonly a: Uint8Array;
+  readonly e: Uint8Array;
+  readonly s: Uint8Array;
+}
+
+/**
+ * The dealing of a party in presigning
+ */
+export interface ThresholdPresignDealing {
+  readonly index: number;
+  /**
+   * `shares[k]` must be sent privately to party `parties[k]`
+   */
+  readonly shares: readonly ThresholdPresignShare[];
+}
+
+/**
+ * A request for the second round of presigning
+ */
+export interface ThresholdPresignRound2Request {
+  readonly threshold: number;
+  readonly parties: readonly number[];
+  readonly keyShare: ThresholdKeyShare;
+  /**
+   * Shares received from every presigning party, in the order of `parties`
+   */
+  readonly shares: readonly ThresholdPresignShare[];
+}
+
+/**
+ * Private state of a party after the second round of presigning
+ */
+export interface ThresholdPresignRound2State {
+  readonly index: number;
+  readonly a: Uint8Array;
+  readonly e: Uint8Array;
+  readonly s: Uint8Array;
+}
+
+/**
+ * Reshares of the presignature products sent to a presigning party
+ */
+export interface ThresholdPresignReshare {
+  readonly delta: Uint8Array;
+  readonly alpha: Uint8Array;
+}
+
+/**
+ * Result of the second round of presigning
+ */
+export interface ThresholdPresignRound2Response {
+  readonly state: ThresholdPresignRound2State;
+  /**
+   * `reshares[k]` must be sent privately to party `parties[k]`
+   */
+  readonly reshares: readonly ThresholdPresignReshare[];
+}
+
+/**
+ * A request for the third round of presigning
+ */
+export interface ThresholdPresignRound3Request {
+  readonly threshold: number;
+  readonly parties: readonly number[];
+  readonly state: ThresholdPresignRound2State;
+  /**
+   * Reshares received from every presigning party, in the order of `parties`
+   */
+  readonly reshares: readonly ThresholdPresignReshare[];
+}
+
+/**
+ * Private state of a party after the third round of presigning
+ */
+export interface ThresholdPresignRound3State {
+  readonly index: number;
+  readonly a: Uint8Array;
+  readonly alpha: Uint8Array;
+}
+
+/**
+ * A party's opening of the public presignature values, to broadcast to all parties
+ */
+export interface ThresholdPresignOpening {
+  readonly index: number;
+  readonly delta: Uint8Array;
+  readonly e: Uint8Array;
+  readonly s: Uint8Array;
+}
+
+/**
+ * Result of the third round of presigning
+ */
+export interface ThresholdPresignRound3Response {
+  readonly state: ThresholdPresignRound3State;
+  readonly opening: ThresholdPresignOpening;
+}
+
+/**
+ * A request to finalize presigning
+ */
+export interface ThresholdPresignFinalizeRequest {
+  readonly threshold: number;
+  readonly state: ThresholdPresignRound3State;
+  /**
+   * Openings broadcast by at least `threshold` parties
+   */
+  readonly openings: readonly ThresholdPresignOpening[];
+}
+
+/**
+ * A party's presignature, must only be used to sign once
+ */
+export interface ThresholdPresignature {
+  readonly index: number;
+  readonly a: Uint8Array;
+  readonly alpha: Uint8Array;
+  readonly delta: Uint8Array;
+  readonly e: Uint8Array;
+  readonly s: Uint8Array;
+}
+
+/**
+ * A request to create a partial signature
+ */
+export interface ThresholdPartialSignRequest {
+  readonly presignature: ThresholdPresignature;
+  /**
+   * BLS12-381 G2 public key of the issuer
+   */
+  readonly publicKey: Uint8Array;
+  readonly messages: readonly Uint8Array[];
+}
+
+/**
+ * A party's partial signature
+ */
+export interface ThresholdPartialSignature {
+  readonly index: number;
+  readonly partial: Uint8Array;
+  readonly delta: Uint8Array;
+  readonly e: Uint8Array;
+  readonly s: Uint8Array;
+}
+
+/**
+ * A request to combine partial signatures into a BBS+ signature
+ */
+export interface ThresholdCombineRequest {
+  readonly threshold: number;
+  /**
+   * BLS12-381 G2 public key of the issuer
+   */
+  readonly publicKey: Uint8Array;
+  readonly messages: readonly Uint8Array[];
+  /**
+   * Partial signatures from at least `threshold` parties sharing one presigning ceremony
+   */
+  readonly partialSignatures: readonly ThresholdPartialSignature[];
+}
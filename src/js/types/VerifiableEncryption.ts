@@ -0,0 +1,71 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/**
+ * Encrypts a hidden message of a proof to an auditor, and checks it is
+ */
+export interface VerifiableEncryptionOptions {
+  /**
+   * Zero based index of the hidden message to encrypt
+   */
+  readonly messageIndex: number;
+  /**
+   * BLS 12-381 G1 public key of the auditor
+   */
+  readonly auditorPublicKey: Uint8Array;
+}
+
+/**
+ * ElGamal encryption of a 16 bit chunk of the scalar of a message
+ */
+export interface ElGamalCiphertext {
+  readonly ephemeralKey: Uint8Array;
+  readonly maskedMessage: Uint8Array;
+}
+
+/**
+ * A request for the auditor to decrypt an encrypted message
+ */
+export interface VerifiableEncryptionDecryptRequest {
+  /**
+   * BLS 12-381 G1 secret key of the auditor
+   */
+  readonly secretKey: Uint8Array;
+  /**
+   * The encrypted chunks returned by `verifyProof`
+   */
+  readonly ciphertext: readonly ElGamalCiphertext[];
+  /**
+   * Candidate values to match the decrypted message against (OPTIONAL)
+   */
+  readonly candidates?: readonly Uint8Array[];
+}
+
+/**
+ * A decrypted message
+ */
+export interface VerifiableEncryptionDecryptResponse {
+  /**
+   * The scalar the message is signed as, its value for a numeric message and its
+   * hash otherwise
+   */
+  readonly message: Uint8Array;
+  /**
+   * The numeric message, when the scalar is the value of one
+   */
+  readonly numericMessage?: Uint8Array;
+  /**
+   * Index of the candidate value the message matches, if any
+   */
+  readonly candidateIndex?: number;
+}
@@ -17,6 +17,7 @@ export { BbsBlindSignRequest } from "./BbsBlindSignRequest";
 export { BbsCreateProofRequest } from "./BbsCreateProofRequest";
 export { BbsKeyPair } from "./BbsKeyPair";
 export { BbsSignRequest } from "./BbsSignRequest";
+export { BbsSignBatchRequest } from "./BbsSignBatchRequest";
 export { BbsVerifyBlindSignContextRequest } from "./BbsVerifyBlindSignContextRequest";
 export { BbsVerifyProofRequest } from "./BbsVerifyProofRequest";
 export { BbsVerifyRequest } from "./BbsVerifyRequest";
@@ -24,4 +25,106 @@ export { BlsKeyPair, DEFAULT_BLS12381_PRIVATE_KEY_LENGTH, DEFAULT_BLS12381_PUBLI
 export { Bls12381ToBbsRequest } from "./Bls12381ToBbsRequest";
 export { BlsBbsSignRequest } from "./BlsBbsSignRequest";
 export { BlsBbsVerifyRequest } from "./BlsBbsVerifyRequest";
-export { BbsVerifyResult } from "./BbsVerifyResult";
+export { BlsVerifyProofRequest } from "./BlsVerifyProofRequest";
+export { BbsVerifyResult, BbsVerifyProofResult } from "./BbsVerifyResult";
+export { BbsWorkerPool, BbsWorkerPoolOptions } from "./BbsWorkerPool";
+export {
+  ThresholdKeyGenRound1Request,
+  ThresholdKeyDealing,
+  ThresholdReceivedKeyDealing,
+  ThresholdKeyGenRound2Request,
+  ThresholdKeyShare,
+  ThresholdPresignRound1Request,
+  ThresholdPresignShare,
+  ThresholdPresignDealing,
+  ThresholdPresignRound2Request,
+  ThresholdPresignRound2State,
+  ThresholdPresignReshare,
+  ThresholdPresignRound2Response,
+  ThresholdPresignRound3Request,
+  ThresholdPresignRound3State,
+  ThresholdPresignOpening,
+  ThresholdPresignRound3Response,
+  ThresholdPresignFinalizeRequest,
+  ThresholdPresignature,
+  ThresholdPartialSignRequest,
+  ThresholdPartialSignature,
+  ThresholdCombineRequest,
+} from "./ThresholdBbs";
+export {
+  LinkSecretBlindSignCommitmentRequest,
+  LinkSecretCommitmentRequest,
+  LinkSecretCommitment,
+  HolderBindingProofOptions,
+  HolderBindingVerifyOptions,
+} from "./HolderBinding";
+export { PseudonymOptions } from "./Pseudonym";
+export {
+  AccumulatorUpdateRequest,
+  AccumulatorBatchUpdate,
+  AccumulatorUpdateResponse,
+  AccumulatorWitnessRequest,
+  AccumulatorVerifyWitnessRequest,
+  AccumulatorUpdateWitnessRequest,
+  AccumulatorProofOptions,
+  AccumulatorVerifyOptions,
+} from "./Accumulator";
+export {
+  StatusListSignRequest,
+  StatusListGap,
+  StatusListProofOptions,
+  StatusListVerifyOptions,
+} from "./StatusList";
+export { SetMembershipOptions } from "./SetMembership";
+export { NotEqualPredicate } from "./Predicate";
+export {
+  LinearRelationTerm,
+  LinearRelation,
+  LinearRelationCredential,
+  LinearRelationCreateProofRequest,
+  LinearRelationProof,
+  LinearRelationVerifyCredential,
+  LinearRelationVerifyProofRequest,
+  BitProof,
+  RangeProof,
+} from "./LinearRelation";
+export {
+  VerifiableEncryptionOptions,
+  ElGamalCiphertext,
+  VerifiableEncryptionDecryptRequest,
+  VerifiableEncryptionDecryptResponse,
+} from "./VerifiableEncryption";
+export { DesignatedVerifierOptions } from "./DesignatedVerifier";
+export { RateLimitProofOptions, RateLimitVerifyOptions } from "./RateLimit";
+export { MessageCommitmentOptions } from "./MessageCommitment";
+export {
+  BbsBlsSignature2020SignRequest,
+  BbsBlsSignature2020Response,
+  BbsBlsSignature2020VerifyRequest,
+  BbsBlsSignatureProof2020DeriveRequest,
+  BbsBlsSignatureProof2020DeriveResponse,
+  BbsBlsSignatureProof2020VerifyRequest,
+  JsonLdDocument,
+  JsonLdContexts,
+} from "./BbsBlsSignature2020";
+export {
+  JwpIssueRequest,
+  JwpResponse,
+  JwpVerifyRequest,
+  JwpPresentRequest,
+  JwpParseRequest,
+  JwpParseResponse,
+} from "./JsonWebProof";
+export { Jwk, JwkExportRequest } from "./Jwk";
+export { KeystoreExportRequest, KeystoreImportRequest } from "./Keystore";
+export {
+  KeyHandle,
+  KeyHandleBlindSignRequest,
+  KeyHandleExportRequest,
+  KeyHandleSignRequest,
+} from "./KeyHandle";
+export {
+  KeyPossessionProofRequest,
+  KeyPossessionVerifyRequest,
+} from "./KeyPossession";
+export { DidKeyDocument, DidKeyVerificationMethod } from "./Multikey";
@@ -87,6 +87,11 @@ module.exports.sign = async (request) => {
   return await throwErrorOnRejectedPromise(wasm.sign(request));
 };
 
+module.exports.signBatch = async (request) => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(wasm.signBatch(request));
+};
+
 module.exports.blsSign = async (request) => {
   await initialize();
   return await throwErrorOnRejectedPromise(wasm.blsSign(request));
@@ -127,3 +132,395 @@ module.exports.blsVerifyProof = async (request) => {
   await initialize();
   return await throwErrorOnRejectedPromise(wasm.blsVerifyProof(request));
 };
+
+module.exports.thresholdKeyGenRound1 = async (request) => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(wasm.thresholdKeyGenRound1(request));
+};
+
+module.exports.thresholdKeyGenRound2 = async (request) => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(wasm.thresholdKeyGenRound2(request));
+};
+
+module.exports.thresholdPresignRound1 = async (request) => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(
+    wasm.thresholdPresignRound1(request)
+  );
+};
+
+module.exports.thresholdPresignRound2 = async (request) => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(
+    wasm.thresholdPresignRound2(request)
+  );
+};
+
+module.exports.thresholdPresignRound3 = async (request) => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(
+    wasm.thresholdPresignRound3(request)
+  );
+};
+
+module.exports.thresholdPresignFinalize = async (request) => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(
+    wasm.thresholdPresignFinalize(request)
+  );
+};
+
+module.exports.thresholdPartialSign = async (request) => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(wasm.thresholdPartialSign(request));
+};
+
+module.exports.thresholdCombineSignatures = async (request) => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(
+    wasm.thresholdCombineSignatures(request)
+  );
+};
+
+module.exports.generateLinkSecret = async () => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(wasm.generateLinkSecret());
+};
+
+module.exports.linkSecretBlindSignCommitment = async (request) => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(
+    wasm.linkSecretBlindSignCommitment(request)
+  );
+};
+
+module.exports.createLinkSecretCommitment = async (request) => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(
+    wasm.createLinkSecretCommitment(request)
+  );
+};
+
+module.exports.accumulatorInitialize = async () => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(wasm.accumulatorInitialize());
+};
+
+module.exports.accumulatorUpdate = async (request) => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(wasm.accumulatorUpdate(request));
+};
+
+module.exports.accumulatorMembershipWitness = async (request) => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(
+    wasm.accumulatorMembershipWitness(request)
+  );
+};
+
+module.exports.accumulatorVerifyWitness = async (request) => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(
+    wasm.accumulatorVerifyWitness(request)
+  );
+};
+
+module.exports.accumulatorUpdateWitness = async (request) => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(
+    wasm.accumulatorUpdateWitness(request)
+  );
+};
+
+module.exports.signStatusList = async (request) => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(wasm.signStatusList(request));
+};
+
+module.exports.encodeNumericMessage = async (value) => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(wasm.encodeNumericMessage(value));
+};
+
+module.exports.decodeNumericMessage = async (message) => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(wasm.decodeNumericMessage(message));
+};
+
+module.exports.createLinearRelationProof = async (request) => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(
+    wasm.createLinearRelationProof(request)
+  );
+};
+
+module.exports.verifyLinearRelationProof = async (request) => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(
+    wasm.verifyLinearRelationProof(request)
+  );
+};
+
+module.exports.decryptVerifiableEncryption = async (request) => {
+  await initialize();
+  var result = await throwErrorOnRejectedPromise(
+    wasm.decryptVerifiableEncryption(request)
+  );
+  return {
+    message: new Uint8Array(result.message),
+    numericMessage: result.numericMessage
+      ? new Uint8Array(result.numericMessage)
+      : undefined,
+    candidateIndex: result.candidateIndex,
+  };
+};
+
+module.exports.generateBlindingFactor = async () => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(wasm.generateBlindingFactor());
+};
+
+module.exports.messageCommitmentGenerators = async () => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(wasm.messageCommitmentGenerators());
+};
+
+module.exports.signBbsBlsSignature2020 = async (request) => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(
+    wasm.signBbsBlsSignature2020(request)
+  );
+};
+
+module.exports.verifyBbsBlsSignature2020 = async (request) => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(
+    wasm.verifyBbsBlsSignature2020(request)
+  );
+};
+
+module.exports.deriveBbsBlsSignatureProof2020 = async (request) => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(
+    wasm.deriveBbsBlsSignatureProof2020(request)
+  );
+};
+
+module.exports.verifyBbsBlsSignatureProof2020 = async (request) => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(
+    wasm.verifyBbsBlsSignatureProof2020(request)
+  );
+};
+
+module.exports.createIssuedJwp = async (request) => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(wasm.createIssuedJwp(request));
+};
+
+module.exports.verifyIssuedJwp = async (request) => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(wasm.verifyIssuedJwp(request));
+};
+
+module.exports.createPresentedJwp = async (request) => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(wasm.createPresentedJwp(request));
+};
+
+module.exports.verifyPresentedJwp = async (request) => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(wasm.verifyPresentedJwp(request));
+};
+
+module.exports.parseJwp = async (request) => {
+  await initialize();
+  var result = await throwErrorOnRejectedPromise(wasm.parseJwp(request));
+  return {
+    presentationHeader: result.presentationHeader,
+    issuerHeader: result.issuerHeader,
+    payloads: result.payloads.map((payload) =>
+      payload ? new Uint8Array(payload) : null
+    ),
+    proof: new Uint8Array(result.proof),
+  };
+};
+
+// Returns the keys of a BLS key pair as Uint8Arrays
+const toBlsKeyPair = (result) => ({
+  publicKey: new Uint8Array(result.publicKey),
+  secretKey: result.secretKey ? new Uint8Array(result.secretKey) : undefined,
+});
+
+module.exports.blsKeyPairToCoseKey = async (keyPair) => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(wasm.blsKeyPairToCoseKey(keyPair));
+};
+
+module.exports.blsKeyPairFromCoseKey = async (coseKey) => {
+  await initialize();
+  return toBlsKeyPair(
+    await throwErrorOnRejectedPromise(wasm.blsKeyPairFromCoseKey(coseKey))
+  );
+};
+
+module.exports.bbsKeyPairToCbor = async (keyPair) => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(wasm.bbsKeyPairToCbor(keyPair));
+};
+
+module.exports.bbsKeyPairFromCbor = async (cbor) => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(wasm.bbsKeyPairFromCbor(cbor));
+};
+
+module.exports.bbsPublicKeyToCbor = async (publicKey) => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(wasm.bbsPublicKeyToCbor(publicKey));
+};
+
+module.exports.bbsPublicKeyFromCbor = async (cbor) => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(wasm.bbsPublicKeyFromCbor(cbor));
+};
+
+module.exports.signatureToCbor = async (signature) => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(wasm.signatureToCbor(signature));
+};
+
+module.exports.signatureFromCbor = async (cbor) => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(wasm.signatureFromCbor(cbor));
+};
+
+module.exports.proofToCbor = async (proof) => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(wasm.proofToCbor(proof));
+};
+
+module.exports.proofFromCbor = async (cbor) => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(wasm.proofFromCbor(cbor));
+};
+
+module.exports.exportJwk = async (request) => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(wasm.exportJwk(request));
+};
+
+module.exports.importJwk = async (jwk) => {
+  await initialize();
+  return toBlsKeyPair(await throwErrorOnRejectedPromise(wasm.importJwk(jwk)));
+};
+
+module.exports.encodeMultikey = async (publicKey) => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(wasm.encodeMultikey(publicKey));
+};
+
+module.exports.decodeMultikey = async (multikey) => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(wasm.decodeMultikey(multikey));
+};
+
+module.exports.createDidKeyDocument = async (publicKey) => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(
+    wasm.createDidKeyDocument(publicKey)
+  );
+};
+
+module.exports.exportEncryptedKey = async (request) => {
+  await initialize();
+  // EIP-2335 passwords are NFKD normalized before the key derivation
+  return await throwErrorOnRejectedPromise(
+    wasm.exportEncryptedKey({
+      ...request,
+      password: request.password.normalize("NFKD"),
+    })
+  );
+};
+
+module.exports.importEncryptedKey = async (request) => {
+  await initialize();
+  return toBlsKeyPair(
+    await throwErrorOnRejectedPromise(
+      wasm.importEncryptedKey({
+        ...request,
+        password: request.password.normalize("NFKD"),
+      })
+    )
+  );
+};
+
+// Runs a synchronous key handle operation, rejecting with an error like the
+// functions above
+const callKeyHandle = (operation) =>
+  throwErrorOnRejectedPromise((async () => operation())());
+
+// The secret key of a key handle stays in WASM memory, so only its public key
+// and operations are exposed
+const wrapKeyHandle = (handle) => ({
+  publicKey: new Uint8Array(handle.publicKey),
+  sign: (request) => callKeyHandle(() => handle.sign(request)),
+  blindSign: (request) => callKeyHandle(() => handle.blindSign(request)),
+  createKeyPossessionProof: () =>
+    callKeyHandle(() => handle.createKeyPossessionProof()),
+  exportEncryptedKey: (request) =>
+    callKeyHandle(() =>
+      handle.exportEncryptedKey({
+        ...request,
+        password: request.password.normalize("NFKD"),
+      })
+    ),
+  free: () => handle.free(),
+});
+
+module.exports.generateBls12381G1KeyHandle = async (seed) => {
+  await initialize();
+  return wrapKeyHandle(wasm.KeyHandle.generateG1(seed));
+};
+
+module.exports.generateBls12381G2KeyHandle = async (seed) => {
+  await initialize();
+  return wrapKeyHandle(wasm.KeyHandle.generateG2(seed));
+};
+
+module.exports.importKeyHandle = async (keyPair) => {
+  await initialize();
+  return wrapKeyHandle(
+    await callKeyHandle(() => wasm.KeyHandle.fromKeyPair(keyPair))
+  );
+};
+
+module.exports.importEncryptedKeyHandle = async (request) => {
+  await initialize();
+  return wrapKeyHandle(
+    await callKeyHandle(() =>
+      wasm.KeyHandle.fromEncryptedKey({
+        ...request,
+        password: request.password.normalize("NFKD"),
+      })
+    )
+  );
+};
+
+module.exports.createKeyPossessionProof = async (request) => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(
+    wasm.createKeyPossessionProof(request)
+  );
+};
+
+module.exports.verifyKeyPossessionProof = async (request) => {
+  await initialize();
+  return await throwErrorOnRejectedPromise(
+    wasm.verifyKeyPossessionProof(request)
+  );
+};
+
+// Workers load their own instance of the WASM module, so the pool is
+// exposed alongside the direct API rather than replacing it
+module.exports.createWorkerPool = require("./worker_pool").createWorkerPool;
@@ -0,0 +1,47 @@
+"use strict";
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// Entry point for a dedicated worker used by the worker pool, this script is
+// expected to be bundled as its own worker entry by the consuming application
+const bbs = require("./wasm_module");
+const { WORKER_POOL_OPERATIONS } = require("./worker_pool");
+
+// Collects the underlying buffers of any byte arrays in the result, including
+// those nested in arrays and objects, so they can be transferred back to the
+// main thread rather than copied
+const transferablesOf = (result) => {
+  const buffers = new Set();
+  const collect = (value) => {
+    if (value instanceof Uint8Array) {
+      buffers.add(value.buffer);
+    } else if (value && typeof value === "object") {
+      Object.values(value).forEach(collect);
+    }
+  };
+  collect(result);
+  return [...buffers];
+};
+
+self.onmessage = async (event) => {
+  const { id, operation, request } = event.data;
+  try {
+    if (!WORKER_POOL_OPERATIONS.includes(operation)) {
+      throw new Error(`Unsupported worker pool operation: ${operation}`);
+    }
+    const result = await bbs[operation](request);
+    self.postMessage({ id, result }, transferablesOf(result));
+  } catch (ex) {
+    self.postMessage({ id, error: ex && ex.message ? ex.message : `${ex}` });
+  }
+};
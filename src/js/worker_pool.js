@@ -0,0 +1,160 @@
+"use strict";
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/**
+ * Operations that may be dispatched to the workers of a worker pool
+ */
+const WORKER_POOL_OPERATIONS = [
+  "generateBls12381G1KeyPair",
+  "generateBls12381G2KeyPair",
+  "bls12381toBbs",
+  "sign",
+  "signBatch",
+  "blsSign",
+  "verify",
+  "blsVerify",
+  "createProof",
+  "blsCreateProof",
+  "verifyProof",
+  "blsVerifyProof",
+];
+
+const DEFAULT_WORKER_POOL_SIZE = 2;
+
+const WORKER_NOT_SUPPORTED_ERROR =
+  "ERROR: Web Workers are not supported in this environment";
+const WORKER_POOL_TERMINATED_ERROR = "ERROR: The worker pool was terminated";
+const WORKER_POOL_NO_WORKERS_ERROR =
+  "ERROR: Every worker of the worker pool failed";
+
+/**
+ * Creates a pool of dedicated workers that each load the WASM module and
+ * run the long-running operations off the main thread.
+ *
+ * `workerUrl` must point at a bundled copy of `worker.js`
+ */
+const createWorkerPool = ({ workerUrl, size, workerOptions } = {}) => {
+  if (typeof Worker === "undefined") {
+    throw new Error(WORKER_NOT_SUPPORTED_ERROR);
+  }
+  if (!workerUrl) {
+    throw new Error("A workerUrl is required to create a worker pool");
+  }
+
+  const poolSize = size && size > 0 ? size : DEFAULT_WORKER_POOL_SIZE;
+  const workers = [];
+  const idle = [];
+  const queue = [];
+  const pending = new Map();
+  let nextId = 0;
+  let terminated = false;
+
+  const dispatch = () => {
+    while (idle.length > 0 && queue.length > 0) {
+      const worker = idle.shift();
+      const job = queue.shift();
+      pending.set(job.id, { ...job, worker });
+      worker.postMessage({
+        id: job.id,
+        operation: job.operation,
+        request: job.request,
+      });
+    }
+  };
+
+  const settle = (worker, { id, result, error }) => {
+    const job = pending.get(id);
+    if (!job) {
+      return;
+    }
+    pending.delete(id);
+    idle.push(worker);
+    if (error !== undefined) {
+      job.reject(new Error(error));
+    } else {
+      job.resolve(result);
+    }
+    dispatch();
+  };
+
+  // A worker that raised an error outside of an operation, for example when
+  // its script failed to load, is dropped from the pool rather than reused
+  const fail = (worker, error) => {
+    const message = error.message || `${error}`;
+    for (const [id, job] of pending) {
+      if (job.worker === worker) {
+        pending.delete(id);
+        job.reject(new Error(message));
+      }
+    }
+    worker.terminate();
+    [idle, workers].forEach((list) => {
+      const index = list.indexOf(worker);
+      if (index !== -1) {
+        list.splice(index, 1);
+      }
+    });
+    if (workers.length === 0) {
+      queue.splice(0).forEach((job) => job.reject(new Error(message)));
+    }
+    dispatch();
+  };
+
+  for (let i = 0; i < poolSize; i++) {
+    const worker = new Worker(workerUrl, workerOptions);
+    worker.onmessage = (event) => settle(worker, event.data);
+    worker.onerror = (error) => fail(worker, error);
+    workers.push(worker);
+    idle.push(worker);
+  }
+
+  const run = (operation, request) =>
+    new Promise((resolve, reject) => {
+      if (terminated) {
+        reject(new Error(WORKER_POOL_TERMINATED_ERROR));
+        return;
+      }
+      if (workers.length === 0) {
+        reject(new Error(WORKER_POOL_NO_WORKERS_ERROR));
+        return;
+      }
+      queue.push({ id: nextId++, operation, request, resolve, reject });
+      dispatch();
+    });
+
+  const pool = {
+    size: poolSize,
+    terminate: async () => {
+      terminated = true;
+      workers.forEach((worker) => worker.terminate());
+      queue
+        .splice(0)
+        .forEach((job) => job.reject(new Error(WORKER_POOL_TERMINATED_ERROR)));
+      for (const [id, job] of pending) {
+        pending.delete(id);
+        job.reject(new Error(WORKER_POOL_TERMINATED_ERROR));
+      }
+    },
+  };
+  WORKER_POOL_OPERATIONS.forEach((operation) => {
+    pool[operation] = (request) => run(operation, request);
+  });
+  return pool;
+};
+
+module.exports = {
+  WORKER_POOL_OPERATIONS,
+  DEFAULT_WORKER_POOL_SIZE,
+  createWorkerPool,
+};
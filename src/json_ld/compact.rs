@@ -0,0 +1,860 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! The compaction algorithm with IRI and value compaction.
+
+use super::context::relativize;
+use super::{
+    add_value, as_array, error, has_keyword_form, is_graph_object, is_keyword, is_list_object,
+    is_node_reference, is_simple_graph_object, is_value_object, Context, Loader,
+};
+
+use bbs::prelude::*;
+use serde_json::{Map, Value};
+use std::{collections::HashMap, rc::Rc};
+
+/// The preferred term of each IRI by container, type and language
+#[derive(Debug, Default)]
+pub(crate) struct InverseContext {
+    entries: HashMap<String, HashMap<String, TypeLanguageMap>>,
+}
+
+#[derive(Debug, Default)]
+struct TypeLanguageMap {
+    language: HashMap<String, String>,
+    types: HashMap<String, String>,
+    any: HashMap<String, String>,
+}
+
+impl TypeLanguageMap {
+    fn get(&self, type_or_language: &str) -> &HashMap<String, String> {
+        match type_or_language {
+            "@type" => &self.types,
+            "@any" => &self.any,
+            _ => &self.language,
+        }
+    }
+}
+
+fn add_preferred(map: &mut HashMap<String, String>, key: &str, term: &str) {
+    map.entry(key.to_string())
+        .or_insert_with(|| term.to_string());
+}
+
+impl InverseContext {
+    fn new(active: &Context) -> Self {
+        let default_language = active.language.as_deref().unwrap_or("@none");
+        let mut terms: Vec<&String> = active.terms.keys().collect();
+        terms.sort_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)));
+        let mut inverse = InverseContext::default();
+        for term in terms {
+            let definition = &active.terms[term];
+            let iri = match &definition.iri {
+                Some(iri) => iri,
+                None => continue,
+            };
+            let container = match definition.container.is_empty() {
+                true => "@none".to_string(),
+                false => definition.container.concat(),
+            };
+            let map = inverse
+                .entries
+                .entry(iri.clone())
+                .or_default()
+                .entry(container)
+                .or_default();
+            add_preferred(&mut map.any, "@none", term);
+            if definition.reverse {
+                add_preferred(&mut map.types, "@reverse", term);
+            } else if definition.type_mapping.as_deref() == Some("@none") {
+                add_preferred(&mut map.language, "@none", term);
+                add_preferred(&mut map.types, "@none", term);
+            } else if let Some(type_mapping) = &definition.type_mapping {
+                add_preferred(&mut map.types, type_mapping, term);
+            } else if let (Some(language), Some(direction)) =
+                (&definition.language, &definition.direction)
+            {
+                let key = match (language, direction) {
+                    (Some(l), Some(d)) => format!("{}_{}", l, d),
+                    (Some(l), None) => l.clone(),
+                    (None, Some(d)) => format!("_{}", d),
+                    (None, None) => "@null".to_string(),
+                };
+                add_preferred(&mut map.language, &key, term);
+            } else if let Some(language) = &definition.language {
+                add_preferred(
+                    &mut map.language,
+                    language.as_deref().unwrap_or("@null"),
+                    term,
+                );
+            } else if let Some(direction) = &definition.direction {
+                let key = direction
+                    .as_ref()
+                    .map_or("@none".to_string(), |d| format!("_{}", d));
+                add_preferred(&mut map.language, &key, term);
+            } else if let Some(direction) = &active.direction {
+                add_preferred(&mut map.language, &format!("_{}", direction), term);
+                add_preferred(&mut map.language, "@none", term);
+                add_preferred(&mut map.types, "@none", term);
+            } else {
+                add_preferred(&mut map.language, default_language, term);
+                add_preferred(&mut map.language, "@none", term);
+                add_preferred(&mut map.types, "@none", term);
+            }
+        }
+        inverse
+    }
+}
+
+impl Context {
+    fn inverse(&self) -> Rc<InverseContext> {
+        self.inverse
+            .get_or_init(|| Rc::new(InverseContext::new(self)))
+            .clone()
+    }
+
+    /// IRI compaction of `iri` as a property or type when `vocab` is set, and as a
+    /// node identifier otherwise
+    pub(crate) fn compact_iri(
+        &self,
+        iri: &str,
+        value: Option<&Value>,
+        vocab: bool,
+        reverse: bool,
+    ) -> Result<String, BBSError> {
+        let inverse = self.inverse();
+        if vocab {
+            if let Some(containers) = inverse.entries.get(iri) {
+                if is_keyword(iri) {
+                    if let Some(term) = containers.get("@none").and_then(|m| m.types.get("@none")) {
+                        return Ok(term.clone());
+                    }
+                }
+                if let Some(term) = self.select_term(value, containers, reverse)? {
+                    return Ok(term);
+                }
+            }
+            if let Some(vocab) = &self.vocab {
+                if let Some(suffix) = iri.strip_prefix(vocab.as_str()) {
+                    if !suffix.is_empty() && self.term(suffix).is_none() {
+                        return Ok(suffix.to_string());
+                    }
+                }
+            }
+        }
+
+        let mut choice: Option<String> = None;
+        for (term, definition) in &self.terms {
+            let prefix_iri = match &definition.iri {
+                Some(prefix_iri) if definition.prefix && prefix_iri != iri => prefix_iri,
+                _ => continue,
+            };
+            let suffix = match iri.strip_prefix(prefix_iri.as_str()) {
+                Some(suffix) => suffix,
+                None => continue,
+            };
+            let curie = format!("{}:{}", term, suffix);
+            let shorter = choice
+                .as_ref()
+                .is_none_or(|c| curie.len() < c.len() || (curie.len() == c.len() && curie < *c));
+            let usable = match self.term(&curie) {
+                None => true,
+                Some(d) => value.is_none() && d.iri.as_deref() == Some(iri),
+            };
+            if shorter && usable {
+                choice = Some(curie);
+            }
+        }
+        if let Some(choice) = choice {
+            return Ok(choice);
+        }
+        for (term, definition) in &self.terms {
+            if definition.prefix && iri.starts_with(&format!("{}:", term)) {
+                return Err(error("IRI confused with prefix"));
+            }
+        }
+        if !vocab {
+            if let Some(base) = &self.base {
+                let relative = relativize(base, iri);
+                if has_keyword_form(&relative) {
+                    return Ok(format!("./{}", relative));
+                }
+                return Ok(relative);
+            }
+        }
+        Ok(iri.to_string())
+    }
+
+    /// The alias of a keyword
+    fn alias(&self, keyword: &str) -> Result<String, BBSError> {
+        self.compact_iri(keyword, None, true, false)
+    }
+
+    fn select_term(
+        &self,
+        value: Option<&Value>,
+        container_map: &HashMap<String, TypeLanguageMap>,
+        reverse: bool,
+    ) -> Result<Option<String>, BBSError> {
+        let mut containers: Vec<&str> = Vec::new();
+        let mut value = value.unwrap_or(&Value::Null);
+        let has = |v: &Value, key: &str| v.get(key).is_some();
+        if value.is_object() && has(value, "@index") && !has(value, "@graph") {
+            containers.extend(["@index", "@index@set"]);
+        }
+        if let Some(preserved) = value.get("@preserve").and_then(|p| p.get(0)) {
+            value = preserved;
+        }
+        if is_graph_object(value) {
+            if has(value, "@index") {
+                containers.extend(["@graph@index", "@graph@index@set", "@index", "@index@set"]);
+            }
+            if has(value, "@id") {
+                containers.extend(["@graph@id", "@graph@id@set"]);
+            }
+            containers.extend(["@graph", "@graph@set", "@set"]);
+            if !has(value, "@index") {
+                containers.extend(["@graph@index", "@graph@index@set", "@index", "@index@set"]);
+            }
+            if !has(value, "@id") {
+                containers.extend(["@graph@id", "@graph@id@set"]);
+            }
+        } else if value.is_object() && !is_value_object(value) {
+            containers.extend(["@id", "@id@set", "@type", "@set@type"]);
+        }
+
+        let mut type_or_language = "@language";
+        let mut type_or_language_value = "@null".to_string();
+        if reverse {
+            type_or_language = "@type";
+            type_or_language_value = "@reverse".to_string();
+            containers.push("@set");
+        } else if is_list_object(value) {
+            if !has(value, "@index") {
+                containers.push("@list");
+            }
+            let list = as_array(&value["@list"]);
+            if list.is_empty() {
+                type_or_language = "@any";
+                type_or_language_value = "@none".to_string();
+            } else {
+                let mut common_language: Option<String> = None;
+                let mut common_type: Option<String> = None;
+                for item in &list {
+                    let mut item_language = "@none".to_string();
+                    let mut item_type = "@none".to_string();
+                    if is_value_object(item) {
+                        if let Some(direction) = item.get("@direction").and_then(Value::as_str) {
+                            let language = item.get("@language").and_then(Value::as_str);
+                            item_language = format!("{}_{}", language.unwrap_or(""), direction);
+                        } else if let Some(language) = item.get("@language").and_then(Value::as_str)
+                        {
+                            item_language = language.to_lowercase();
+                        } else if let Some(t) = item.get("@type").and_then(Value::as_str) {
+                            item_type = t.to_string();
+                        } else {
+                            item_language = "@null".to_string();
+                        }
+                    } else {
+                        item_type = "@id".to_string();
+                    }
+                    match &common_language {
+                        None => common_language = Some(item_language),
+                        Some(l) if *l != item_language && is_value_object(item) => {
+                            common_language = Some("@none".to_string())
+                        }
+                        _ => {}
+                    }
+                    match &common_type {
+                        None => common_type = Some(item_type),
+                        Some(t) if *t != item_type => common_type = Some("@none".to_string()),
+                        _ => {}
+                    }
+                    if common_language.as_deref() == Some("@none")
+                        && common_type.as_deref() == Some("@none")
+                    {
+                        break;
+                    }
+                }
+                let common_language = common_language.unwrap_or_else(|| "@none".to_string());
+                let common_type = common_type.unwrap_or_else(|| "@none".to_string());
+                if common_type != "@none" {
+                    type_or_language = "@type";
+                    type_or_language_value = common_type;
+                } else {
+                    type_or_language_value = common_language;
+                }
+            }
+        } else {
+            if is_value_object(value) {
+                let language = value.get("@language").and_then(Value::as_str);
+                let direction = value.get("@direction").and_then(Value::as_str);
+                let indexed = has(value, "@index");
+                match (language, direction) {
+                    (Some(language), Some(d)) if !indexed => {
+                        containers.extend(["@language", "@language@set"]);
+                        type_or_language_value = format!("{}_{}", language, d);
+                    }
+                    (Some(language), None) if !indexed => {
+                        containers.extend(["@language", "@language@set"]);
+                        type_or_language_value = language.to_string();
+                    }
+                    (None, Some(d)) if !indexed => {
+                        type_or_language_value = format!("_{}", d);
+                    }
+                    _ => {
+                        if let Some(t) = value.get("@type").and_then(Value::as_str) {
+                            type_or_language = "@type";
+                            type_or_language_value = t.to_string();
+                        }
+                    }
+                }
+            } else {
+                type_or_language = "@type";
+                type_or_language_value = "@id".to_string();
+            }
+            containers.push("@set");
+        }
+        containers.push("@none");
+        if value.is_object() && !has(value, "@index") {
+            containers.extend(["@index", "@index@set"]);
+        }
+        if is_value_object(value) && value.as_object().is_some_and(|v| v.len() == 1) {
+            containers.extend(["@language", "@language@set"]);
+        }
+
+        let mut preferred = Vec::new();
+        if (type_or_language_value == "@id" || type_or_language_value == "@reverse")
+            && has(value, "@id")
+        {
+            if type_or_language_value == "@reverse" {
+                preferred.push("@reverse".to_string());
+            }
+            let id = value["@id"].as_str().unwrap_or_default();
+            let term = self.compact_iri(id, None, true, false)?;
+            if self.term(&term).and_then(|d| d.iri.as_deref()) == Some(id) {
+                preferred.extend(["@vocab".to_string(), "@id".to_string()]);
+            } else {
+                preferred.extend(["@id".to_string(), "@vocab".to_string()]);
+            }
+        } else {
+            if let Some(i) = type_or_language_value.find('_') {
+                preferred.push(type_or_language_value.clone());
+                preferred.push(type_or_language_value[i..].to_string());
+            } else {
+                preferred.push(type_or_language_value);
+            }
+        }
+        preferred.push("@none".to_string());
+
+        for container in containers {
+            if let Some(map) = container_map.get(container) {
+                let map = map.get(type_or_language);
+                for preference in &preferred {
+                    if let Some(term) = map.get(preference) {
+                        return Ok(Some(term.clone()));
+                    }
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Value compaction of a value object or node reference
+    fn compact_value(
+        &self,
+        active_property: Option<&str>,
+        value: &Value,
+    ) -> Result<Value, BBSError> {
+        let definition = active_property.and_then(|p| self.term(p));
+        let type_mapping = definition.and_then(|d| d.type_mapping.as_deref());
+        if !is_value_object(value) {
+            let id = value["@id"].as_str().unwrap_or_default();
+            let compacted = self.compact_iri(id, None, type_mapping == Some("@vocab"), false)?;
+            let expanded_property = active_property.and_then(|p| self.expand_iri(p, false, true));
+            if type_mapping == Some("@id")
+                || type_mapping == Some("@vocab")
+                || expanded_property.as_deref() == Some("@graph")
+            {
+                return Ok(Value::String(compacted));
+            }
+            let mut result = Map::new();
+            result.insert(self.alias("@id")?, Value::String(compacted));
+            return Ok(Value::Object(result));
+        }
+
+        let language = match definition.and_then(|d| d.language.clone()) {
+            Some(language) => language,
+            None => self.language.clone(),
+        };
+        let direction = match definition.and_then(|d| d.direction.clone()) {
+            Some(direction) => direction,
+            None => self.direction.clone(),
+        };
+        let object = value.as_object().unwrap();
+        let preserve_index =
+            object.contains_key("@index") && !self.has_container(active_property, "@index");
+        let value_language = object.get("@language").and_then(Value::as_str);
+        let value_direction = object.get("@direction").and_then(Value::as_str);
+        if !preserve_index && type_mapping != Some("@none") {
+            let value_type = object.get("@type").and_then(Value::as_str);
+            if value_type.is_some() && value_type == type_mapping {
+                return Ok(object["@value"].clone());
+            }
+            if value_language.is_some() && value_language == language.as_deref() {
+                if value_direction.is_none() || value_direction == direction.as_deref() {
+                    return Ok(object["@value"].clone());
+                }
+            } else if value_direction.is_some()
+                && value_direction == direction.as_deref()
+                && value_language.is_none()
+            {
+                return Ok(object["@value"].clone());
+            }
+        }
+        let value_only = object.len() == 1
+            || (object.len() == 2 && object.contains_key("@index") && !preserve_index);
+        let null_language = definition.is_some_and(|d| d.language == Some(None));
+        if value_only
+            && type_mapping != Some("@none")
+            && (self.language.is_none() || !object["@value"].is_string() || null_language)
+        {
+            return Ok(object["@value"].clone());
+        }
+        let mut result = Map::new();
+        if preserve_index {
+            result.insert(self.alias("@index")?, object["@index"].clone());
+        }
+        if let Some(t) = object.get("@type").and_then(Value::as_str) {
+            let t = self.compact_iri(t, None, true, false)?;
+            result.insert(self.alias("@type")?, Value::String(t));
+        } else if let Some(language) = object.get("@language") {
+            result.insert(self.alias("@language")?, language.clone());
+        }
+        if let Some(direction) = object.get("@direction") {
+            result.insert(self.alias("@direction")?, direction.clone());
+        }
+        result.insert(self.alias("@value")?, object["@value"].clone());
+        Ok(Value::Object(result))
+    }
+}
+
+/// Compacts an expanded document with a context, as the `jsonld` library does with
+/// `compactArrays` set
+pub(crate) fn compact(
+    expanded: &Value,
+    context: &Value,
+    base: Option<&str>,
+    loader: &Loader,
+    graph: bool,
+) -> Result<Value, BBSError> {
+    let active = Rc::new(Context::new(base).process(context, base, loader, false, true)?);
+    let compactor = Compactor { loader };
+    let mut compacted = compactor.element(&active, None, expanded)?;
+    match compacted {
+        Value::Array(ref mut items) if !graph => match items.len() {
+            0 => compacted = Value::Object(Map::new()),
+            1 => compacted = items.remove(0),
+            _ => {}
+        },
+        Value::Object(_) if graph => compacted = Value::Array(vec![compacted]),
+        _ => {}
+    }
+
+    let contexts: Vec<Value> = as_array(context)
+        .into_iter()
+        .filter(|c| c.as_object().is_none_or(|c| !c.is_empty()))
+        .collect();
+    let output_context = match contexts.len() {
+        0 => None,
+        1 => contexts.into_iter().next(),
+        _ => Some(Value::Array(contexts)),
+    };
+    let mut result = Map::new();
+    if let Some(output_context) = output_context {
+        result.insert("@context".to_string(), output_context);
+    }
+    match compacted {
+        Value::Array(items) => {
+            result.insert(active.alias("@graph")?, Value::Array(items));
+        }
+        Value::Object(object) => result.extend(object),
+        _ => {}
+    }
+    Ok(Value::Object(result))
+}
+
+struct Compactor<'a> {
+    loader: &'a Loader,
+}
+
+impl<'a> Compactor<'a> {
+    fn element(
+        &self,
+        active: &Rc<Context>,
+        active_property: Option<&str>,
+        element: &Value,
+    ) -> Result<Value, BBSError> {
+        if let Value::Array(items) = element {
+            let mut result = Vec::new();
+            for item in items {
+                let compacted = self.element(active, active_property, item)?;
+                if !compacted.is_null() {
+                    result.push(compacted);
+                }
+            }
+            let no_container = active_property
+                .and_then(|p| active.term(p))
+                .is_none_or(|d| d.container.is_empty());
+            if result.len() == 1 && no_container {
+                return Ok(result.remove(0));
+            }
+            return Ok(Value::Array(result));
+        }
+        let object = match element {
+            Value::Object(object) => object,
+            scalar => return Ok(scalar.clone()),
+        };
+
+        let property_scoped = active_property
+            .and_then(|p| active.term(p))
+            .and_then(|d| d.context.clone().map(|c| (c, d.base_url.clone())));
+        let mut input_active = active.clone();
+        if let Some((context, url)) = &property_scoped {
+            input_active =
+                Rc::new(active.process(context, url.as_deref(), self.loader, true, true)?);
+        }
+        if is_value_object(element) || is_node_reference(element) {
+            return input_active.compact_value(active_property, element);
+        }
+        if is_list_object(element) && input_active.has_container(active_property, "@list") {
+            return self.element(&input_active, active_property, &element["@list"]);
+        }
+
+        let inside_reverse = active_property == Some("@reverse");
+        let mut active = input_active.clone();
+        if let Some(previous) = &active.previous {
+            active = previous.clone();
+        }
+        if let Some((context, url)) = &property_scoped {
+            active = Rc::new(active.process(context, url.as_deref(), self.loader, true, true)?);
+        }
+        let mut types: Vec<&str> = object
+            .get("@type")
+            .and_then(Value::as_array)
+            .map(|t| t.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+        types.sort_unstable();
+        let type_context = active.clone();
+        for t in types {
+            let compacted = type_context.compact_iri(t, None, true, false)?;
+            if let Some(definition) = input_active.term(&compacted) {
+                if let Some(context) = &definition.context {
+                    active = Rc::new(active.process(
+                        context,
+                        definition.base_url.as_deref(),
+                        self.loader,
+                        false,
+                        false,
+                    )?);
+                }
+            }
+        }
+
+        let mut result = Map::new();
+        let mut keys: Vec<&String> = object.keys().collect();
+        keys.sort();
+        for expanded_property in keys {
+            let expanded_value = &object[expanded_property];
+            match expanded_property.as_str() {
+                "@id" => {
+                    let mut ids = as_array(expanded_value)
+                        .iter()
+                        .map(|id| {
+                            active
+                                .compact_iri(id.as_str().unwrap_or_default(), None, false, false)
+                                .map(Value::String)
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    let id = match ids.len() {
+                        1 => ids.remove(0),
+                        _ => Value::Array(ids),
+                    };
+                    result.insert(active.alias("@id")?, id);
+                    continue;
+                }
+                "@type" => {
+                    let mut compacted = as_array(expanded_value)
+                        .iter()
+                        .map(|t| {
+                            input_active
+                                .compact_iri(t.as_str().unwrap_or_default(), None, true, false)
+                                .map(Value::String)
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    let alias = active.alias("@type")?;
+                    let as_set = active.has_container(Some(&alias), "@set");
+                    let compacted = match compacted.len() {
+                        1 => compacted.remove(0),
+                        _ => Value::Array(compacted),
+                    };
+                    add_value(&mut result, &alias, compacted, as_set);
+                    continue;
+                }
+                "@reverse" => {
+                    let compacted = self.element(&active, Some("@reverse"), expanded_value)?;
+                    let mut remaining = Map::new();
+                    if let Value::Object(compacted) = compacted {
+                        for (property, value) in compacted {
+                            match active.term(&property) {
+                                Some(d) if d.reverse => {
+                                    let as_set = d.has_container("@set");
+                                    add_value(&mut result, &property, value, as_set);
+                                }
+                                _ => {
+                                    remaining.insert(property, value);
+                                }
+                            }
+                        }
+                    }
+                    if !remaining.is_empty() {
+                        let alias = active.alias("@reverse")?;
+                        add_value(&mut result, &alias, Value::Object(remaining), false);
+                    }
+                    continue;
+                }
+                "@index" => {
+                    if !active.has_container(active_property, "@index") {
+                        let alias = active.alias("@index")?;
+                        add_value(&mut result, &alias, expanded_value.clone(), false);
+                    }
+                    continue;
+                }
+                "@graph" | "@list" | "@included" => {}
+                keyword if is_keyword(keyword) => {
+                    let alias = active.alias(keyword)?;
+                    add_value(&mut result, &alias, expanded_value.clone(), false);
+                    continue;
+                }
+                _ => {}
+            }
+
+            let items = expanded_value
+                .as_array()
+                .ok_or_else(|| error("invalid expanded value"))?;
+            if items.is_empty() {
+                let property = active.compact_iri(
+                    expanded_property,
+                    Some(expanded_value),
+                    true,
+                    inside_reverse,
+                )?;
+                let target = nest_target(&active, &mut result, &property)?;
+                add_value(target, &property, Value::Array(Vec::new()), true);
+            }
+            for item in items {
+                let property =
+                    active.compact_iri(expanded_property, Some(item), true, inside_reverse)?;
+                let definition = active.term(&property).cloned().unwrap_or_default();
+                let container = |c: &str| definition.has_container(c);
+                let target = nest_target(&active, &mut result, &property)?;
+                let is_list = is_list_object(item);
+                let is_graph = is_graph_object(item);
+                let inner = match (is_list, is_graph) {
+                    (true, _) => &item["@list"],
+                    (_, true) => &item["@graph"],
+                    _ => item,
+                };
+                let mut compacted = self.element(&active, Some(&property), inner)?;
+
+                if is_list {
+                    let list = Value::Array(as_array(&compacted));
+                    if container("@list") {
+                        target.insert(property, list);
+                        continue;
+                    }
+                    let mut wrapped = Map::new();
+                    wrapped.insert(active.alias("@list")?, list);
+                    if let Some(index) = item.get("@index") {
+                        wrapped.insert(active.alias("@index")?, index.clone());
+                    }
+                    compacted = Value::Object(wrapped);
+                }
+
+                let as_set = container("@set");
+                if is_graph {
+                    if container("@graph")
+                        && (container("@id")
+                            || (container("@index") && is_simple_graph_object(item)))
+                    {
+                        let key = match item.get(if container("@id") { "@id" } else { "@index" }) {
+                            Some(Value::String(key)) if container("@id") => {
+                                active.compact_iri(key, None, false, false)?
+                            }
+                            Some(Value::String(key)) => key.clone(),
+                            _ => active.alias("@none")?,
+                        };
+                        let map = target
+                            .entry(property)
+                            .or_insert_with(|| Value::Object(Map::new()));
+                        if let Value::Object(map) = map {
+                            add_value(map, &key, compacted, as_set);
+                        }
+                    } else if container("@graph") && is_simple_graph_object(item) {
+                        if matches!(&compacted, Value::Array(items) if items.len() > 1) {
+                            let mut included = Map::new();
+                            included.insert(active.alias("@included")?, compacted);
+                            compacted = Value::Object(included);
+                        }
+                        add_value(target, &property, compacted, as_set);
+                    } else {
+                        if let Value::Array(items) = &mut compacted {
+                            if items.len() == 1 {
+                                compacted = items.remove(0);
+                            }
+                        }
+                        let mut wrapped = Map::new();
+                        wrapped.insert(active.alias("@graph")?, compacted);
+                        if let Some(id) = item.get("@id").and_then(Value::as_str) {
+                            let id = active.compact_iri(id, None, false, false)?;
+                            wrapped.insert(active.alias("@id")?, Value::String(id));
+                        }
+                        if let Some(index) = item.get("@index") {
+                            wrapped.insert(active.alias("@index")?, index.clone());
+                        }
+                        add_value(target, &property, Value::Object(wrapped), as_set);
+                    }
+                } else if container("@language")
+                    || container("@index")
+                    || container("@id")
+                    || container("@type")
+                {
+                    let mut key = None;
+                    if container("@language") {
+                        if is_value_object(&compacted) {
+                            compacted = compacted["@value"].clone();
+                        }
+                        key = item.get("@language").cloned();
+                    } else if container("@index") {
+                        let index_key = definition.index.as_deref().unwrap_or("@index");
+                        if index_key == "@index" {
+                            key = item.get("@index").cloned();
+                            if let Value::Object(c) = &mut compacted {
+                                c.remove(&active.alias("@index")?);
+                            }
+                        } else {
+                            let expanded_key = active
+                                .expand_iri(index_key, false, true)
+                                .ok_or_else(|| error("invalid term definition"))?;
+                            let container_key =
+                                active.compact_iri(&expanded_key, None, true, false)?;
+                            if let Value::Object(c) = &mut compacted {
+                                let mut values = c.remove(&container_key).map(|v| as_array(&v));
+                                if let Some(values) = &mut values {
+                                    if values.first().is_some_and(Value::is_string) {
+                                        key = Some(values.remove(0));
+                                    }
+                                    match values.len() {
+                                        0 => {}
+                                        1 => {
+                                            c.insert(container_key, values.remove(0));
+                                        }
+                                        _ => {
+                                            c.insert(container_key, Value::Array(values.clone()));
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    } else if container("@id") {
+                        if let Value::Object(c) = &mut compacted {
+                            key = c.remove(&active.alias("@id")?);
+                        }
+                    } else {
+                        let type_key = active.alias("@type")?;
+                        if let Value::Object(c) = &mut compacted {
+                            let mut types = c
+                                .remove(&type_key)
+                                .map(|t| as_array(&t))
+                                .unwrap_or_default();
+                            if !types.is_empty() {
+                                key = Some(types.remove(0));
+                            }
+                            match types.len() {
+                                0 => {}
+                                1 => {
+                                    c.insert(type_key, types.remove(0));
+                                }
+                                _ => {
+                                    c.insert(type_key, Value::Array(types));
+                                }
+                            }
+                        }
+                        if compacted.as_object().is_some_and(|c| c.len() == 1) {
+                            if let Some(id) = item.get("@id") {
+                                let mut reference = Map::new();
+                                reference.insert("@id".to_string(), id.clone());
+                                compacted = self.element(
+                                    &active,
+                                    Some(&property),
+                                    &Value::Object(reference),
+                                )?;
+                            }
+                        }
+                    }
+                    let key = match key {
+                        Some(Value::String(key)) => key,
+                        _ => active.alias("@none")?,
+                    };
+                    let map = target
+                        .entry(property)
+                        .or_insert_with(|| Value::Object(Map::new()));
+                    if let Value::Object(map) = map {
+                        add_value(map, &key, compacted, as_set);
+                    }
+                } else {
+                    let as_array = as_set
+                        || container("@list")
+                        || matches!(&compacted, Value::Array(items) if items.is_empty())
+                        || expanded_property == "@list"
+                        || expanded_property == "@graph";
+                    add_value(target, &property, compacted, as_array);
+                }
+            }
+        }
+        Ok(Value::Object(result))
+    }
+}
+
+/// The map the values of `property` go in, which is the nested map of its `@nest`
+/// entry if it has one
+fn nest_target<'m>(
+    active: &Context,
+    result: &'m mut Map<String, Value>,
+    property: &str,
+) -> Result<&'m mut Map<String, Value>, BBSError> {
+    let nest = match active.term(property).and_then(|d| d.nest.clone()) {
+        Some(nest) => nest,
+        None => return Ok(result),
+    };
+    if nest != "@nest" && active.expand_iri(&nest, false, true).as_deref() != Some("@nest") {
+        return Err(error("invalid @nest value"));
+    }
+    let entry = result
+        .entry(nest)
+        .or_insert_with(|| Value::Object(Map::new()));
+    if !entry.is_object() {
+        *entry = Value::Object(Map::new());
+    }
+    Ok(entry.as_object_mut().unwrap())
+}
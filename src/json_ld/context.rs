@@ -0,0 +1,876 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Context processing and IRI expansion.
+
+use super::compact::InverseContext;
+use super::{
+    error, has_keyword_form, is_absolute_iri, is_blank_node, is_keyword, Loader,
+    MAX_REMOTE_CONTEXTS,
+};
+
+use bbs::prelude::*;
+use serde_json::{Map, Value};
+use std::{
+    cell::OnceCell,
+    collections::{BTreeMap, HashMap},
+    rc::Rc,
+};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct TermDefinition {
+    /// `None` when the term is mapped to `null`
+    pub(crate) iri: Option<String>,
+    pub(crate) prefix: bool,
+    pub(crate) protected: bool,
+    pub(crate) reverse: bool,
+    pub(crate) base_url: Option<String>,
+    pub(crate) context: Option<Value>,
+    /// The container keywords in code point order
+    pub(crate) container: Vec<String>,
+    pub(crate) direction: Option<Option<String>>,
+    pub(crate) index: Option<String>,
+    pub(crate) language: Option<Option<String>>,
+    pub(crate) nest: Option<String>,
+    pub(crate) type_mapping: Option<String>,
+}
+
+impl TermDefinition {
+    pub(crate) fn has_container(&self, container: &str) -> bool {
+        self.container.iter().any(|c| c == container)
+    }
+}
+
+/// An active context
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Context {
+    pub(crate) base: Option<String>,
+    original_base: Option<String>,
+    pub(crate) vocab: Option<String>,
+    pub(crate) language: Option<String>,
+    pub(crate) direction: Option<String>,
+    pub(crate) terms: BTreeMap<String, TermDefinition>,
+    /// The context to revert to for node objects when a type-scoped context is not
+    /// propagated
+    pub(crate) previous: Option<Rc<Context>>,
+    pub(crate) inverse: OnceCell<Rc<InverseContext>>,
+}
+
+impl Context {
+    pub(crate) fn new(base: Option<&str>) -> Self {
+        Self {
+            base: base.map(String::from),
+            original_base: base.map(String::from),
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn term(&self, term: &str) -> Option<&TermDefinition> {
+        self.terms.get(term)
+    }
+
+    /// Whether the term definition of `term` has the container keyword `container`
+    pub(crate) fn has_container(&self, term: Option<&str>, container: &str) -> bool {
+        term.and_then(|t| self.term(t))
+            .is_some_and(|d| d.has_container(container))
+    }
+
+    /// The active context updated with a local context
+    pub(crate) fn process(
+        &self,
+        local: &Value,
+        base_url: Option<&str>,
+        loader: &Loader,
+        override_protected: bool,
+        propagate: bool,
+    ) -> Result<Context, BBSError> {
+        process_context(
+            self,
+            local,
+            base_url,
+            &[],
+            loader,
+            override_protected,
+            propagate,
+            true,
+        )
+    }
+
+    /// IRI expansion of `value`, `None` when it expands to `null`
+    pub(crate) fn expand_iri(
+        &self,
+        value: &str,
+        document_relative: bool,
+        vocab: bool,
+    ) -> Option<String> {
+        if is_keyword(value) {
+            return Some(value.to_string());
+        }
+        if has_keyword_form(value) {
+            return None;
+        }
+        if let Some(definition) = self.term(value) {
+            if let Some(iri) = &definition.iri {
+                if is_keyword(iri) {
+                    return Some(iri.clone());
+                }
+            }
+            if vocab {
+                return definition.iri.clone();
+            }
+        }
+        if let Some(i) = colon_position(value) {
+            let (prefix, suffix) = (&value[..i], &value[i + 1..]);
+            if prefix == "_" || suffix.starts_with("//") {
+                return Some(value.to_string());
+            }
+            if let Some(TermDefinition {
+                iri: Some(iri),
+                prefix: true,
+                ..
+            }) = self.term(prefix)
+            {
+                return Some(format!("{}{}", iri, suffix));
+            }
+            if is_absolute_iri(value) {
+                return Some(value.to_string());
+            }
+        }
+        if vocab {
+            if let Some(vocab) = &self.vocab {
+                return Some(format!("{}{}", vocab, value));
+            }
+        }
+        if document_relative {
+            if let Some(base) = &self.base {
+                return Some(resolve(base, value));
+            }
+        }
+        Some(value.to_string())
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn process_context(
+    active: &Context,
+    local: &Value,
+    base_url: Option<&str>,
+    remote_contexts: &[String],
+    loader: &Loader,
+    override_protected: bool,
+    mut propagate: bool,
+    validate_scoped: bool,
+) -> Result<Context, BBSError> {
+    let mut result = active.clone();
+    result.inverse = OnceCell::new();
+    if let Some(value) = local.get("@propagate") {
+        propagate = value
+            .as_bool()
+            .ok_or_else(|| error("invalid @propagate value"))?;
+    }
+    if !propagate && result.previous.is_none() {
+        result.previous = Some(Rc::new(active.clone()));
+    }
+    let contexts = match local {
+        Value::Array(contexts) => contexts.clone(),
+        context => vec![context.clone()],
+    };
+    for context in contexts {
+        let context = match context {
+            Value::Null => {
+                if !override_protected && result.terms.values().any(|d| d.protected) {
+                    return Err(error("invalid context nullification"));
+                }
+                let previous = result;
+                result = Context::new(active.original_base.as_deref());
+                if !propagate {
+                    result.previous = Some(Rc::new(previous));
+                }
+                continue;
+            }
+            Value::String(url) => {
+                let url = match base_url {
+                    Some(base) => resolve(base, &url),
+                    None => url,
+                };
+                if !validate_scoped && remote_contexts.contains(&url) {
+                    continue;
+                }
+                if remote_contexts.len() >= MAX_REMOTE_CONTEXTS {
+                    return Err(error("context overflow"));
+                }
+                let loaded = loader
+                    .load(&url)?
+                    .get("@context")
+                    .ok_or_else(|| error("invalid remote context"))?;
+                let mut remote_contexts = remote_contexts.to_vec();
+                remote_contexts.push(url.clone());
+                result = process_context(
+                    &result,
+                    loaded,
+                    Some(&url),
+                    &remote_contexts,
+                    loader,
+                    override_protected,
+                    true,
+                    validate_scoped,
+                )?;
+                continue;
+            }
+            Value::Object(context) => context,
+            _ => return Err(error("invalid local context")),
+        };
+        let context = import_context(context, base_url, loader)?;
+
+        if let Some(version) = context.get("@version") {
+            if version.as_f64() != Some(1.1) {
+                return Err(error("invalid @version value"));
+            }
+        }
+        if remote_contexts.is_empty() {
+            match context.get("@base") {
+                None => {}
+                Some(Value::Null) => result.base = None,
+                Some(Value::String(base)) if is_absolute_iri(base) => {
+                    result.base = Some(base.clone())
+                }
+                Some(Value::String(base)) => match &result.base {
+                    Some(current) => result.base = Some(resolve(current, base)),
+                    None => return Err(error("invalid base IRI")),
+                },
+                Some(_) => return Err(error("invalid base IRI")),
+            }
+        }
+        match context.get("@vocab") {
+            None => {}
+            Some(Value::Null) => result.vocab = None,
+            Some(Value::String(vocab)) => result.vocab = result.expand_iri(vocab, true, true),
+            Some(_) => return Err(error("invalid vocab mapping")),
+        }
+        match context.get("@language") {
+            None => {}
+            Some(Value::Null) => result.language = None,
+            Some(Value::String(language)) => result.language = Some(language.to_lowercase()),
+            Some(_) => return Err(error("invalid default language")),
+        }
+        match context.get("@direction") {
+            None => {}
+            Some(Value::Null) => result.direction = None,
+            Some(Value::String(d)) if d == "ltr" || d == "rtl" => {
+                result.direction = Some(d.clone())
+            }
+            Some(_) => return Err(error("invalid base direction")),
+        }
+        if let Some(value) = context.get("@propagate") {
+            if !value.is_boolean() {
+                return Err(error("invalid @propagate value"));
+            }
+        }
+        let protected = match context.get("@protected") {
+            None => false,
+            Some(Value::Bool(protected)) => *protected,
+            Some(_) => return Err(error("invalid @protected value")),
+        };
+
+        let mut definer = Definer {
+            local: &context,
+            defined: HashMap::new(),
+            loader,
+            remote_contexts,
+            base_url,
+            protected,
+            override_protected,
+        };
+        for term in context.keys() {
+            if !matches!(
+                term.as_str(),
+                "@base"
+                    | "@direction"
+                    | "@import"
+                    | "@language"
+                    | "@propagate"
+                    | "@protected"
+                    | "@version"
+                    | "@vocab"
+            ) {
+                definer.create_term_definition(&mut result, term)?;
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Merges the context referenced by `@import` under the entries of `context`
+fn import_context(
+    context: Map<String, Value>,
+    base_url: Option<&str>,
+    loader: &Loader,
+) -> Result<Map<String, Value>, BBSError> {
+    let import = match context.get("@import") {
+        None => return Ok(context),
+        Some(Value::String(import)) => import,
+        Some(_) => return Err(error("invalid @import value")),
+    };
+    let url = match base_url {
+        Some(base) => resolve(base, import),
+        None => import.clone(),
+    };
+    let mut imported = loader
+        .load(&url)?
+        .get("@context")
+        .and_then(Value::as_object)
+        .cloned()
+        .ok_or_else(|| error("invalid remote context"))?;
+    if imported.contains_key("@import") {
+        return Err(error("invalid context entry"));
+    }
+    imported.extend(context);
+    Ok(imported)
+}
+
+/// Creates the term definitions of a local context
+struct Definer<'a> {
+    local: &'a Map<String, Value>,
+    defined: HashMap<String, bool>,
+    loader: &'a Loader,
+    remote_contexts: &'a [String],
+    base_url: Option<&'a str>,
+    protected: bool,
+    override_protected: bool,
+}
+
+impl<'a> Definer<'a> {
+    fn create_term_definition(&mut self, active: &mut Context, term: &str) -> Result<(), BBSError> {
+        match self.defined.get(term) {
+            Some(true) => return Ok(()),
+            Some(false) => return Err(error("cyclic IRI mapping")),
+            None => {}
+        }
+        if term.is_empty() {
+            return Err(error("invalid term definition"));
+        }
+        self.defined.insert(term.to_string(), false);
+        let value = self.local[term].clone();
+
+        if term == "@type" {
+            let valid = match &value {
+                Value::Object(v) => {
+                    !v.is_empty()
+                        && v.iter()
+                            .all(|(k, v)| (k == "@container" && v == "@set") || k == "@protected")
+                }
+                _ => false,
+            };
+            if !valid {
+                return Err(error("keyword redefinition"));
+            }
+        } else if is_keyword(term) {
+            return Err(error("keyword redefinition"));
+        } else if has_keyword_form(term) {
+            self.defined.insert(term.to_string(), true);
+            return Ok(());
+        }
+
+        let previous = active.terms.remove(term);
+        let (value, simple_term) = match value {
+            Value::Null => (single("@id", Value::Null), false),
+            Value::String(id) => (single("@id", Value::String(id)), true),
+            Value::Object(value) => (value, false),
+            _ => return Err(error("invalid term definition")),
+        };
+        let mut definition = TermDefinition {
+            protected: match value.get("@protected") {
+                None => self.protected,
+                Some(Value::Bool(protected)) => *protected,
+                Some(_) => return Err(error("invalid @protected value")),
+            },
+            ..Default::default()
+        };
+
+        if let Some(type_mapping) = value.get("@type") {
+            let type_mapping = type_mapping
+                .as_str()
+                .ok_or_else(|| error("invalid type mapping"))?;
+            let type_mapping = self
+                .expand_iri(active, type_mapping, false, true)?
+                .ok_or_else(|| error("invalid type mapping"))?;
+            let valid = matches!(type_mapping.as_str(), "@id" | "@json" | "@none" | "@vocab")
+                || (is_absolute_iri(&type_mapping) && !is_blank_node(&type_mapping));
+            if !valid {
+                return Err(error("invalid type mapping"));
+            }
+            definition.type_mapping = Some(type_mapping);
+        }
+
+        if let Some(reverse) = value.get("@reverse") {
+            if value.contains_key("@id") || value.contains_key("@nest") {
+                return Err(error("invalid reverse property"));
+            }
+            let reverse = reverse
+                .as_str()
+                .ok_or_else(|| error("invalid IRI mapping"))?;
+            if has_keyword_form(reverse) {
+                self.defined.insert(term.to_string(), true);
+                return Ok(());
+            }
+            match self.expand_iri(active, reverse, false, true)? {
+                Some(iri) if iri.contains(':') => definition.iri = Some(iri),
+                _ => return Err(error("invalid IRI mapping")),
+            }
+            match value.get("@container") {
+                None | Some(Value::Null) => {}
+                Some(Value::String(c)) if c == "@set" || c == "@index" => {
+                    definition.container = vec![c.clone()]
+                }
+                Some(_) => return Err(error("invalid reverse property")),
+            }
+            definition.reverse = true;
+            active.terms.insert(term.to_string(), definition);
+            self.defined.insert(term.to_string(), true);
+            return Ok(());
+        }
+
+        match value.get("@id") {
+            Some(Value::Null) => {}
+            Some(Value::String(id)) if id != term => {
+                if !is_keyword(id) && has_keyword_form(id) {
+                    self.defined.insert(term.to_string(), true);
+                    return Ok(());
+                }
+                let iri = match self.expand_iri(active, id, false, true)? {
+                    Some(iri) if is_keyword(&iri) || iri.contains(':') => iri,
+                    _ => return Err(error("invalid IRI mapping")),
+                };
+                if iri == "@context" {
+                    return Err(error("invalid keyword alias"));
+                }
+                definition.iri = Some(iri.clone());
+                let inner_colon = term.find(':').is_some_and(|i| i > 0 && i < term.len() - 1);
+                if inner_colon || term.contains('/') {
+                    self.defined.insert(term.to_string(), true);
+                    if self.expand_iri(active, term, false, true)?.as_ref() != Some(&iri) {
+                        return Err(error("invalid IRI mapping"));
+                    }
+                }
+                if !term.contains(':')
+                    && !term.contains('/')
+                    && simple_term
+                    && (iri.ends_with(|c| ":/?#[]@".contains(c)) || is_blank_node(&iri))
+                {
+                    definition.prefix = true;
+                }
+            }
+            Some(Value::String(_)) | None => {
+                if let Some(i) = colon_position(term) {
+                    let (prefix, suffix) = (&term[..i], &term[i + 1..]);
+                    if self.local.contains_key(prefix) {
+                        self.create_term_definition(active, prefix)?;
+                    }
+                    definition.iri = match active.term(prefix).and_then(|d| d.iri.as_ref()) {
+                        Some(iri) => Some(format!("{}{}", iri, suffix)),
+                        None => Some(term.to_string()),
+                    };
+                } else if term.contains('/') {
+                    match active.expand_iri(term, true, true) {
+                        Some(iri) if is_absolute_iri(&iri) => definition.iri = Some(iri),
+                        _ => return Err(error("invalid IRI mapping")),
+                    }
+                } else if term == "@type" {
+                    definition.iri = Some(term.to_string());
+                } else if let Some(vocab) = &active.vocab {
+                    definition.iri = Some(format!("{}{}", vocab, term));
+                } else {
+                    return Err(error("invalid IRI mapping"));
+                }
+            }
+            Some(_) => return Err(error("invalid IRI mapping")),
+        }
+
+        if let Some(container) = value.get("@container") {
+            let mut container = match container {
+                Value::String(c) => vec![c.clone()],
+                Value::Array(items) => items
+                    .iter()
+                    .map(|c| c.as_str().map(String::from))
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or_else(|| error("invalid container mapping"))?,
+                _ => return Err(error("invalid container mapping")),
+            };
+            container.sort();
+            container.dedup();
+            if !valid_container(&container) {
+                return Err(error("invalid container mapping"));
+            }
+            if container.iter().any(|c| c == "@type") {
+                match definition.type_mapping.as_deref() {
+                    None => definition.type_mapping = Some("@id".to_string()),
+                    Some("@id") | Some("@vocab") => {}
+                    Some(_) => return Err(error("invalid type mapping")),
+                }
+            }
+            definition.container = container;
+        }
+
+        if let Some(index) = value.get("@index") {
+            let index = index
+                .as_str()
+                .ok_or_else(|| error("invalid term definition"))?;
+            if !definition.has_container("@index") || is_keyword(index) || has_keyword_form(index) {
+                return Err(error("invalid term definition"));
+            }
+            definition.index = Some(index.to_string());
+        }
+
+        if let Some(context) = value.get("@context") {
+            process_context(
+                active,
+                context,
+                self.base_url,
+                self.remote_contexts,
+                self.loader,
+                true,
+                true,
+                false,
+            )
+            .map_err(|_| error("invalid scoped context"))?;
+            definition.context = Some(context.clone());
+            definition.base_url = self.base_url.map(String::from);
+        }
+
+        if !value.contains_key("@type") {
+            match value.get("@language") {
+                None => {}
+                Some(Value::Null) => definition.language = Some(None),
+                Some(Value::String(l)) => definition.language = Some(Some(l.to_lowercase())),
+                Some(_) => return Err(error("invalid language mapping")),
+            }
+            match value.get("@direction") {
+                None => {}
+                Some(Value::Null) => definition.direction = Some(None),
+                Some(Value::String(d)) if d == "ltr" || d == "rtl" => {
+                    definition.direction = Some(Some(d.clone()))
+                }
+                Some(_) => return Err(error("invalid base direction")),
+            }
+        }
+
+        if let Some(nest) = value.get("@nest") {
+            match nest.as_str() {
+                Some(nest) if !is_keyword(nest) || nest == "@nest" => {
+                    definition.nest = Some(nest.to_string())
+                }
+                _ => return Err(error("invalid @nest value")),
+            }
+        }
+
+        if let Some(prefix) = value.get("@prefix") {
+            if term.contains(':') || term.contains('/') {
+                return Err(error("invalid term definition"));
+            }
+            definition.prefix = prefix
+                .as_bool()
+                .ok_or_else(|| error("invalid @prefix value"))?;
+            if definition.prefix && definition.iri.as_deref().is_some_and(is_keyword) {
+                return Err(error("invalid term definition"));
+            }
+        }
+
+        let allowed = [
+            "@id",
+            "@reverse",
+            "@container",
+            "@context",
+            "@direction",
+            "@index",
+            "@language",
+            "@nest",
+            "@prefix",
+            "@protected",
+            "@type",
+        ];
+        if value.keys().any(|k| !allowed.contains(&k.as_str())) {
+            return Err(error("invalid term definition"));
+        }
+
+        if !self.override_protected {
+            if let Some(previous) = previous {
+                if previous.protected {
+                    let mut compared = definition.clone();
+                    compared.protected = true;
+                    if compared != previous {
+                        return Err(error("protected term redefinition"));
+                    }
+                    definition = previous;
+                }
+            }
+        }
+        active.terms.insert(term.to_string(), definition);
+        self.defined.insert(term.to_string(), true);
+        Ok(())
+    }
+
+    /// IRI expansion that first defines the terms of the local context it uses
+    fn expand_iri(
+        &mut self,
+        active: &mut Context,
+        value: &str,
+        document_relative: bool,
+        vocab: bool,
+    ) -> Result<Option<String>, BBSError> {
+        if is_keyword(value) {
+            return Ok(Some(value.to_string()));
+        }
+        if has_keyword_form(value) {
+            return Ok(None);
+        }
+        if self.local.contains_key(value) && self.defined.get(value) != Some(&true) {
+            self.create_term_definition(active, value)?;
+        }
+        if let Some(definition) = active.term(value) {
+            if vocab || definition.iri.as_deref().is_some_and(is_keyword) {
+                return Ok(definition.iri.clone());
+            }
+        }
+        if let Some(i) = colon_position(value) {
+            let prefix = &value[..i];
+            if prefix != "_"
+                && !value[i + 1..].starts_with("//")
+                && self.local.contains_key(prefix)
+                && self.defined.get(prefix) != Some(&true)
+            {
+                self.create_term_definition(active, prefix)?;
+            }
+        }
+        Ok(active.expand_iri(value, document_relative, vocab))
+    }
+}
+
+fn single(key: &str, value: Value) -> Map<String, Value> {
+    let mut map = Map::new();
+    map.insert(key.to_string(), value);
+    map
+}
+
+fn valid_container(container: &[String]) -> bool {
+    let has = |c: &str| container.iter().any(|v| v == c);
+    let known = [
+        "@graph",
+        "@id",
+        "@index",
+        "@language",
+        "@list",
+        "@set",
+        "@type",
+    ];
+    if container.iter().any(|c| !known.contains(&c.as_str())) {
+        return false;
+    }
+    if has("@list") {
+        return container.len() == 1;
+    }
+    if has("@graph") {
+        return container
+            .iter()
+            .all(|c| matches!(c.as_str(), "@graph" | "@id" | "@index" | "@set"))
+            && !(has("@id") && has("@index"));
+    }
+    container.iter().filter(|c| *c != "@set").count() <= 1
+}
+
+/// The position of the first colon after the first character
+fn colon_position(value: &str) -> Option<usize> {
+    match value.find(':') {
+        Some(0) => value[1..].find(':').map(|i| i + 1),
+        position => position,
+    }
+}
+
+struct IriParts<'a> {
+    scheme: Option<&'a str>,
+    authority: Option<&'a str>,
+    path: &'a str,
+    query: Option<&'a str>,
+    fragment: Option<&'a str>,
+}
+
+fn parse_iri(iri: &str) -> IriParts<'_> {
+    let (rest, fragment) = match iri.find('#') {
+        Some(i) => (&iri[..i], Some(&iri[i + 1..])),
+        None => (iri, None),
+    };
+    let (mut rest, query) = match rest.find('?') {
+        Some(i) => (&rest[..i], Some(&rest[i + 1..])),
+        None => (rest, None),
+    };
+    let mut scheme = None;
+    if let Some(i) = rest.find(':') {
+        if is_absolute_iri(rest) && !rest[..i].contains('/') {
+            scheme = Some(&rest[..i]);
+            rest = &rest[i + 1..];
+        }
+    }
+    let mut authority = None;
+    if let Some(after) = rest.strip_prefix("//") {
+        let end = after.find('/').unwrap_or(after.len());
+        authority = Some(&after[..end]);
+        rest = &after[end..];
+    }
+    IriParts {
+        scheme,
+        authority,
+        path: rest,
+        query,
+        fragment,
+    }
+}
+
+/// Resolves an IRI reference against a base IRI (RFC 3986 section 5.2)
+pub(crate) fn resolve(base: &str, reference: &str) -> String {
+    let r = parse_iri(reference);
+    if r.scheme.is_some() {
+        return compose(
+            r.scheme,
+            r.authority,
+            &remove_dot_segments(r.path),
+            r.query,
+            r.fragment,
+        );
+    }
+    let b = parse_iri(base);
+    let (authority, path, query) = if r.authority.is_some() {
+        (r.authority, remove_dot_segments(r.path), r.query)
+    } else if r.path.is_empty() {
+        (b.authority, b.path.to_string(), r.query.or(b.query))
+    } else if r.path.starts_with('/') {
+        (b.authority, remove_dot_segments(r.path), r.query)
+    } else {
+        let merged = if b.authority.is_some() && b.path.is_empty() {
+            format!("/{}", r.path)
+        } else {
+            match b.path.rfind('/') {
+                Some(i) => format!("{}{}", &b.path[..=i], r.path),
+                None => r.path.to_string(),
+            }
+        };
+        (b.authority, remove_dot_segments(&merged), r.query)
+    };
+    compose(b.scheme, authority, &path, query, r.fragment)
+}
+
+fn compose(
+    scheme: Option<&str>,
+    authority: Option<&str>,
+    path: &str,
+    query: Option<&str>,
+    fragment: Option<&str>,
+) -> String {
+    let mut iri = String::new();
+    if let Some(scheme) = scheme {
+        iri.push_str(scheme);
+        iri.push(':');
+    }
+    if let Some(authority) = authority {
+        iri.push_str("//");
+        iri.push_str(authority);
+    }
+    iri.push_str(path);
+    if let Some(query) = query {
+        iri.push('?');
+        iri.push_str(query);
+    }
+    if let Some(fragment) = fragment {
+        iri.push('#');
+        iri.push_str(fragment);
+    }
+    iri
+}
+
+fn remove_dot_segments(path: &str) -> String {
+    let mut input = path;
+    let mut output: Vec<&str> = Vec::new();
+    while !input.is_empty() {
+        if let Some(rest) = input.strip_prefix("../") {
+            input = rest;
+        } else if let Some(rest) = input.strip_prefix("./") {
+            input = rest;
+        } else if input.starts_with("/./") {
+            input = &input[2..];
+        } else if input == "/." {
+            input = "/";
+        } else if input.starts_with("/../") || input == "/.." {
+            input = if input == "/.." { "/" } else { &input[3..] };
+            output.pop();
+        } else if input == "." || input == ".." {
+            input = "";
+        } else {
+            let start = usize::from(input.starts_with('/'));
+            let end = input[start..].find('/').map_or(input.len(), |i| i + start);
+            output.push(&input[..end]);
+            input = &input[end..];
+        }
+    }
+    output.concat()
+}
+
+/// The IRI relative to a base IRI, as the inverse of `resolve`
+pub(crate) fn relativize(base: &str, iri: &str) -> String {
+    let b = parse_iri(base);
+    let mut root = String::new();
+    if !base.is_empty() {
+        if let Some(scheme) = b.scheme {
+            root.push_str(scheme);
+            root.push(':');
+        }
+        root.push_str("//");
+        root.push_str(b.authority.unwrap_or(""));
+    } else if !iri.starts_with("//") {
+        root.push_str("//");
+    }
+    let rest = match iri.strip_prefix(root.as_str()) {
+        Some(rest) => rest,
+        None => return iri.to_string(),
+    };
+    let r = parse_iri(rest);
+    let base_path = remove_dot_segments(b.path);
+    let iri_path = remove_dot_segments(r.path);
+    let mut base_segments: Vec<&str> = base_path.split('/').collect();
+    let mut iri_segments: Vec<&str> = iri_path.split('/').collect();
+    let last = usize::from(r.fragment.is_none() && r.query.is_none());
+    while !base_segments.is_empty() && iri_segments.len() > last {
+        if base_segments[0] != iri_segments[0] {
+            break;
+        }
+        base_segments.remove(0);
+        iri_segments.remove(0);
+    }
+    let mut relative = String::new();
+    if !base_segments.is_empty() {
+        base_segments.pop();
+        for _ in base_segments {
+            relative.push_str("../");
+        }
+    }
+    relative.push_str(&iri_segments.join("/"));
+    if let Some(query) = r.query {
+        relative.push('?');
+        relative.push_str(query);
+    }
+    if let Some(fragment) = r.fragment {
+        relative.push('#');
+        relative.push_str(fragment);
+    }
+    if relative.is_empty() {
+        relative.push_str("./");
+    }
+    relative
+}
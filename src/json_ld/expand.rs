@@ -0,0 +1,707 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! The expansion algorithm, including the frame expansion of framing.
+
+use super::{
+    add_value, as_array, error, is_absolute_iri, is_blank_node, is_graph_object, is_keyword,
+    is_list_object, is_node_object, is_value_object, Context, Loader,
+};
+
+use bbs::prelude::*;
+use serde_json::{json, Map, Value};
+use std::{collections::BTreeSet, rc::Rc};
+
+/// Expands a JSON-LD document, or a frame when `frame_expansion` is set
+pub(crate) fn expand(
+    document: &Value,
+    base: Option<&str>,
+    loader: &Loader,
+    frame_expansion: bool,
+) -> Result<Vec<Value>, BBSError> {
+    let expander = Expander {
+        loader,
+        frame_expansion,
+    };
+    let active = Rc::new(Context::new(base));
+    let mut expanded = expander.element(&active, None, document, base, frame_expansion, false)?;
+    if let Value::Object(object) = &mut expanded {
+        if object.len() == 1 {
+            if let Some(graph) = object.remove("@graph") {
+                expanded = graph;
+            }
+        }
+    }
+    Ok(as_array(&expanded))
+}
+
+struct Expander<'a> {
+    loader: &'a Loader,
+    frame_expansion: bool,
+}
+
+impl<'a> Expander<'a> {
+    fn element(
+        &self,
+        active: &Rc<Context>,
+        active_property: Option<&str>,
+        element: &Value,
+        base_url: Option<&str>,
+        frame_expansion: bool,
+        from_map: bool,
+    ) -> Result<Value, BBSError> {
+        let frame_expansion = frame_expansion && active_property != Some("@default");
+        let definition = active_property.and_then(|p| active.term(p)).cloned();
+        let property_scoped = definition
+            .as_ref()
+            .and_then(|d| d.context.clone().map(|c| (c, d.base_url.clone())));
+        let object = match element {
+            Value::Null => return Ok(Value::Null),
+            Value::Array(items) => {
+                let mut result = Vec::new();
+                for item in items {
+                    let mut expanded = self.element(
+                        active,
+                        active_property,
+                        item,
+                        base_url,
+                        frame_expansion,
+                        from_map,
+                    )?;
+                    if expanded.is_array()
+                        && definition
+                            .as_ref()
+                            .is_some_and(|d| d.has_container("@list"))
+                    {
+                        expanded = json!({ "@list": expanded });
+                    }
+                    match expanded {
+                        Value::Array(items) => result.extend(items),
+                        Value::Null => {}
+                        item => result.push(item),
+                    }
+                }
+                return Ok(Value::Array(result));
+            }
+            Value::Object(object) => object,
+            scalar => {
+                let active_property = match active_property {
+                    None | Some("@graph") => return Ok(Value::Null),
+                    Some(p) => p,
+                };
+                let active = match property_scoped {
+                    Some((context, url)) => Rc::new(active.process(
+                        &context,
+                        url.as_deref(),
+                        self.loader,
+                        true,
+                        true,
+                    )?),
+                    None => active.clone(),
+                };
+                return Ok(expand_value(&active, active_property, scalar));
+            }
+        };
+
+        let mut active = active.clone();
+        if let Some(previous) = &active.previous {
+            let expands_to = |key: &str, keyword: &str| {
+                active.expand_iri(key, false, true).as_deref() == Some(keyword)
+            };
+            let is_value = object.keys().any(|k| expands_to(k, "@value"));
+            let is_reference = object.len() == 1 && object.keys().all(|k| expands_to(k, "@id"));
+            if !from_map && !is_value && !is_reference {
+                active = previous.clone();
+            }
+        }
+        if let Some((context, url)) = property_scoped {
+            active = Rc::new(active.process(&context, url.as_deref(), self.loader, true, true)?);
+        }
+        if let Some(context) = object.get("@context") {
+            active = Rc::new(active.process(context, base_url, self.loader, false, true)?);
+        }
+
+        let type_scoped = active.clone();
+        let type_keys: Vec<&String> = object
+            .keys()
+            .filter(|k| active.expand_iri(k, false, true).as_deref() == Some("@type"))
+            .collect();
+        for key in &type_keys {
+            let mut terms: Vec<&str> = match &object[key.as_str()] {
+                Value::Array(items) => items.iter().filter_map(Value::as_str).collect(),
+                value => value.as_str().into_iter().collect(),
+            };
+            terms.sort_unstable();
+            for term in terms {
+                if let Some(definition) = type_scoped.term(term) {
+                    if let Some(context) = &definition.context {
+                        active = Rc::new(active.process(
+                            context,
+                            definition.base_url.as_deref(),
+                            self.loader,
+                            false,
+                            false,
+                        )?);
+                    }
+                }
+            }
+        }
+        let input_type = type_keys
+            .first()
+            .and_then(|key| as_array(&object[key.as_str()]).last().cloned())
+            .and_then(|t| t.as_str().and_then(|t| active.expand_iri(t, false, true)));
+
+        let mut result = Map::new();
+        self.entries(
+            &active,
+            &type_scoped,
+            active_property,
+            object,
+            &mut result,
+            base_url,
+            input_type.as_deref(),
+            frame_expansion,
+        )?;
+
+        if let Some(value) = result.get("@value") {
+            let valid_keys = result.keys().all(|k| {
+                matches!(
+                    k.as_str(),
+                    "@direction" | "@index" | "@language" | "@type" | "@value"
+                )
+            });
+            if !valid_keys
+                || (result.contains_key("@type")
+                    && (result.contains_key("@language") || result.contains_key("@direction")))
+            {
+                return Err(error("invalid value object"));
+            }
+            if result.get("@type").and_then(Value::as_str) == Some("@json") {
+                // JSON literals keep any value
+            } else if value.is_null() {
+                return Ok(Value::Null);
+            } else if !value.is_string() && result.contains_key("@language") && !frame_expansion {
+                return Err(error("invalid language-tagged value"));
+            } else if let Some(datatype) = result.get("@type") {
+                let valid = match datatype {
+                    Value::String(t) => {
+                        is_absolute_iri(t) && !is_blank_node(t) && !t.contains(char::is_whitespace)
+                    }
+                    _ => frame_expansion,
+                };
+                if !valid {
+                    return Err(error("invalid typed value"));
+                }
+            }
+        } else if let Some(types) = result.get_mut("@type") {
+            if !types.is_array() {
+                *types = Value::Array(vec![types.take()]);
+            }
+        } else if result.contains_key("@set") || result.contains_key("@list") {
+            if result.len() > 2 || (result.len() == 2 && !result.contains_key("@index")) {
+                return Err(error("invalid set or list object"));
+            }
+            if let Some(set) = result.remove("@set") {
+                return Ok(set);
+            }
+        }
+
+        if result.len() == 1 && result.contains_key("@language") {
+            return Ok(Value::Null);
+        }
+        if (active_property.is_none() || active_property == Some("@graph"))
+            && !self.frame_expansion
+            && (result.is_empty()
+                || result.contains_key("@value")
+                || result.contains_key("@list")
+                || (result.len() == 1 && result.contains_key("@id")))
+        {
+            return Ok(Value::Null);
+        }
+        Ok(Value::Object(result))
+    }
+
+    /// Expands the entries of a map, and of the maps nested in it, into `result`
+    #[allow(clippy::too_many_arguments)]
+    fn entries(
+        &self,
+        active: &Rc<Context>,
+        type_scoped: &Rc<Context>,
+        active_property: Option<&str>,
+        element: &Map<String, Value>,
+        result: &mut Map<String, Value>,
+        base_url: Option<&str>,
+        input_type: Option<&str>,
+        frame_expansion: bool,
+    ) -> Result<(), BBSError> {
+        let mut nests = BTreeSet::new();
+        for (key, value) in element {
+            if key == "@context" {
+                continue;
+            }
+            let expanded_property = match active.expand_iri(key, false, true) {
+                Some(p) if p.contains(':') || is_keyword(&p) => p,
+                _ => continue,
+            };
+
+            if is_keyword(&expanded_property) {
+                if active_property == Some("@reverse") {
+                    return Err(error("invalid reverse property map"));
+                }
+                if result.contains_key(&expanded_property)
+                    && expanded_property != "@included"
+                    && expanded_property != "@type"
+                {
+                    return Err(error("colliding keywords"));
+                }
+                let expanded_value = match expanded_property.as_str() {
+                    "@id" => match value {
+                        Value::String(id) => active
+                            .expand_iri(id, true, false)
+                            .map_or(Value::Null, Value::String),
+                        Value::Object(o) if frame_expansion && o.is_empty() => json!([{}]),
+                        Value::Array(ids) if frame_expansion => Value::Array(
+                            ids.iter()
+                                .map(|id| match id {
+                                    Value::String(id) => Ok(active
+                                        .expand_iri(id, true, false)
+                                        .map_or(Value::Null, Value::String)),
+                                    _ => Err(error("invalid @id value")),
+                                })
+                                .collect::<Result<_, _>>()?,
+                        ),
+                        _ => return Err(error("invalid @id value")),
+                    },
+                    "@type" => {
+                        let expand_type = |t: &Value| match t {
+                            Value::String(t) => Ok(type_scoped
+                                .expand_iri(t, true, true)
+                                .map_or(Value::Null, Value::String)),
+                            _ => Err(error("invalid type value")),
+                        };
+                        let types = match value {
+                            Value::String(_) => expand_type(value)?,
+                            Value::Array(types) => Value::Array(
+                                types.iter().map(expand_type).collect::<Result<_, _>>()?,
+                            ),
+                            Value::Object(o) if frame_expansion && o.is_empty() => value.clone(),
+                            Value::Object(o) if frame_expansion && o.contains_key("@default") => {
+                                let mut default = o.clone();
+                                if let Some(t) = o["@default"].as_str() {
+                                    default.insert(
+                                        "@default".to_string(),
+                                        type_scoped
+                                            .expand_iri(t, true, true)
+                                            .map_or(Value::Null, Value::String),
+                                    );
+                                }
+                                Value::Object(default)
+                            }
+                            _ => return Err(error("invalid type value")),
+                        };
+                        match result.get("@type") {
+                            Some(existing) => {
+                                let mut all = as_array(existing);
+                                all.extend(as_array(&types));
+                                Value::Array(all)
+                            }
+                            None => types,
+                        }
+                    }
+                    "@graph" => Value::Array(as_array(&self.element(
+                        active,
+                        Some("@graph"),
+                        value,
+                        base_url,
+                        frame_expansion,
+                        false,
+                    )?)),
+                    "@included" => {
+                        let node_like = |item: &Value| match item {
+                            Value::Object(item) => item.keys().all(|k| {
+                                let k = active.expand_iri(k, false, true);
+                                k.as_deref() != Some("@value") && k.as_deref() != Some("@list")
+                            }),
+                            _ => false,
+                        };
+                        if !as_array(value).iter().all(node_like) {
+                            return Err(error("invalid @included value"));
+                        }
+                        let included = as_array(&self.element(
+                            active,
+                            active_property,
+                            value,
+                            base_url,
+                            frame_expansion,
+                            false,
+                        )?);
+                        if !included.iter().all(is_node_object) {
+                            return Err(error("invalid @included value"));
+                        }
+                        let mut all = result.get("@included").map(as_array).unwrap_or_default();
+                        all.extend(included);
+                        Value::Array(all)
+                    }
+                    "@value" => {
+                        let valid = input_type == Some("@json")
+                            || match value {
+                                Value::Object(o) => frame_expansion && o.is_empty(),
+                                Value::Array(items) => {
+                                    frame_expansion
+                                        && items.iter().all(|v| !v.is_object() && !v.is_array())
+                                }
+                                _ => true,
+                            };
+                        if !valid {
+                            return Err(error("invalid value object value"));
+                        }
+                        if value.is_null() {
+                            result.insert("@value".to_string(), Value::Null);
+                            continue;
+                        }
+                        value.clone()
+                    }
+                    "@language" => match value {
+                        Value::String(language) => Value::String(language.to_lowercase()),
+                        Value::Object(o) if frame_expansion && o.is_empty() => value.clone(),
+                        Value::Array(items)
+                            if frame_expansion && items.iter().all(Value::is_string) =>
+                        {
+                            value.clone()
+                        }
+                        _ => return Err(error("invalid language-tagged string")),
+                    },
+                    "@direction" => match value.as_str() {
+                        Some("ltr") | Some("rtl") => value.clone(),
+                        _ if frame_expansion => value.clone(),
+                        _ => return Err(error("invalid base direction")),
+                    },
+                    "@index" => match value {
+                        Value::String(_) => value.clone(),
+                        _ => return Err(error("invalid @index value")),
+                    },
+                    "@list" => {
+                        if active_property.is_none() || active_property == Some("@graph") {
+                            continue;
+                        }
+                        Value::Array(as_array(&self.element(
+                            active,
+                            active_property,
+                            value,
+                            base_url,
+                            frame_expansion,
+                            false,
+                        )?))
+                    }
+                    "@set" => self.element(
+                        active,
+                        active_property,
+                        value,
+                        base_url,
+                        frame_expansion,
+                        false,
+                    )?,
+                    "@reverse" => {
+                        if !value.is_object() {
+                            return Err(error("invalid @reverse value"));
+                        }
+                        let expanded = self.element(
+                            active,
+                            Some("@reverse"),
+                            value,
+                            base_url,
+                            frame_expansion,
+                            false,
+                        )?;
+                        if let Some(Value::Object(reverse)) = expanded.get("@reverse") {
+                            for (property, item) in reverse {
+                                add_value(result, property, item.clone(), true);
+                            }
+                        }
+                        if let Value::Object(expanded) = expanded {
+                            for (property, items) in expanded {
+                                if property == "@reverse" {
+                                    continue;
+                                }
+                                let reverse_map = result
+                                    .entry("@reverse")
+                                    .or_insert_with(|| json!({}))
+                                    .as_object_mut()
+                                    .unwrap();
+                                for item in as_array(&items) {
+                                    if is_value_object(&item) || is_list_object(&item) {
+                                        return Err(error("invalid reverse property value"));
+                                    }
+                                    add_value(reverse_map, &property, item, true);
+                                }
+                            }
+                        }
+                        continue;
+                    }
+                    "@nest" => {
+                        nests.insert(key.clone());
+                        continue;
+                    }
+                    "@default" if frame_expansion => self.element(
+                        active,
+                        Some("@default"),
+                        value,
+                        base_url,
+                        frame_expansion,
+                        false,
+                    )?,
+                    "@embed" | "@explicit" | "@omitDefault" | "@requireAll" if frame_expansion => {
+                        Value::Array(as_array(value))
+                    }
+                    _ => continue,
+                };
+                if !expanded_value.is_null() || expanded_property == "@id" {
+                    result.insert(expanded_property, expanded_value);
+                }
+                continue;
+            }
+
+            let definition = active.term(key).cloned().unwrap_or_default();
+            let mut expanded_value = if definition.type_mapping.as_deref() == Some("@json") {
+                json!({ "@value": value, "@type": "@json" })
+            } else if definition.has_container("@language") && value.is_object() {
+                let direction = definition
+                    .direction
+                    .clone()
+                    .unwrap_or_else(|| active.direction.clone());
+                let mut items = Vec::new();
+                for (language, values) in value.as_object().unwrap() {
+                    for item in as_array(values) {
+                        let item = match item {
+                            Value::String(item) => item,
+                            Value::Null => continue,
+                            _ => return Err(error("invalid language map value")),
+                        };
+                        let mut v = json!({ "@value": item });
+                        if active.expand_iri(language, false, true).as_deref() != Some("@none") {
+                            v["@language"] = Value::String(language.to_lowercase());
+                        }
+                        if let Some(direction) = &direction {
+                            v["@direction"] = Value::String(direction.clone());
+                        }
+                        items.push(v);
+                    }
+                }
+                Value::Array(items)
+            } else if (definition.has_container("@index")
+                || definition.has_container("@type")
+                || definition.has_container("@id"))
+                && value.is_object()
+            {
+                self.index_map(
+                    active,
+                    key,
+                    &definition,
+                    value.as_object().unwrap(),
+                    base_url,
+                )?
+            } else {
+                self.element(active, Some(key), value, base_url, frame_expansion, false)?
+            };
+            if expanded_value.is_null() {
+                continue;
+            }
+            if definition.has_container("@list") && !is_list_object(&expanded_value) {
+                expanded_value = json!({ "@list": as_array(&expanded_value) });
+            }
+            if definition.has_container("@graph")
+                && !definition.has_container("@id")
+                && !definition.has_container("@index")
+            {
+                expanded_value = Value::Array(
+                    as_array(&expanded_value)
+                        .into_iter()
+                        .map(|v| json!({ "@graph": as_array(&v) }))
+                        .collect(),
+                );
+            }
+            if definition.reverse {
+                let reverse_map = result
+                    .entry("@reverse")
+                    .or_insert_with(|| json!({}))
+                    .as_object_mut()
+                    .unwrap();
+                for item in as_array(&expanded_value) {
+                    if is_value_object(&item) || is_list_object(&item) {
+                        return Err(error("invalid reverse property value"));
+                    }
+                    add_value(reverse_map, &expanded_property, item, true);
+                }
+            } else {
+                add_value(result, &expanded_property, expanded_value, true);
+            }
+        }
+
+        for key in nests {
+            let mut nest_active = active.clone();
+            if let Some(definition) = active.term(&key) {
+                if let Some(context) = &definition.context {
+                    nest_active = Rc::new(active.process(
+                        context,
+                        definition.base_url.as_deref(),
+                        self.loader,
+                        true,
+                        true,
+                    )?);
+                }
+            }
+            for nested in as_array(&element[&key]) {
+                let nested = match nested {
+                    Value::Object(nested) => nested,
+                    _ => return Err(error("invalid @nest value")),
+                };
+                if nested
+                    .keys()
+                    .any(|k| nest_active.expand_iri(k, false, true).as_deref() == Some("@value"))
+                {
+                    return Err(error("invalid @nest value"));
+                }
+                self.entries(
+                    &nest_active,
+                    type_scoped,
+                    active_property,
+                    &nested,
+                    result,
+                    base_url,
+                    input_type,
+                    frame_expansion,
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Expands the values of an index, id or type map
+    fn index_map(
+        &self,
+        active: &Rc<Context>,
+        key: &str,
+        definition: &super::context::TermDefinition,
+        value: &Map<String, Value>,
+        base_url: Option<&str>,
+    ) -> Result<Value, BBSError> {
+        let index_key = definition.index.as_deref().unwrap_or("@index");
+        let mut items = Vec::new();
+        for (index, index_value) in value {
+            let mut map_context = active.clone();
+            if definition.has_container("@id") || definition.has_container("@type") {
+                if let Some(previous) = &active.previous {
+                    map_context = previous.clone();
+                }
+            }
+            if definition.has_container("@type") {
+                if let Some(index_definition) = map_context.term(index) {
+                    if let Some(context) = &index_definition.context {
+                        map_context = Rc::new(map_context.process(
+                            context,
+                            index_definition.base_url.as_deref(),
+                            self.loader,
+                            false,
+                            true,
+                        )?);
+                    }
+                }
+            }
+            let expanded_index = active.expand_iri(index, false, true);
+            let is_none = expanded_index.as_deref() == Some("@none");
+            let index_values = self.element(
+                &map_context,
+                Some(key),
+                &Value::Array(as_array(index_value)),
+                base_url,
+                self.frame_expansion,
+                true,
+            )?;
+            for mut item in as_array(&index_values) {
+                if definition.has_container("@graph") && !is_graph_object(&item) {
+                    item = json!({ "@graph": as_array(&item) });
+                }
+                if definition.has_container("@index") && index_key != "@index" && !is_none {
+                    let reexpanded = expand_value(active, index_key, &Value::String(index.clone()));
+                    let expanded_index_key = active
+                        .expand_iri(index_key, false, true)
+                        .ok_or_else(|| error("invalid term definition"))?;
+                    let mut values = vec![reexpanded];
+                    values.extend(
+                        item.get(&expanded_index_key)
+                            .map(as_array)
+                            .unwrap_or_default(),
+                    );
+                    if is_value_object(&item) {
+                        return Err(error("invalid value object"));
+                    }
+                    item[expanded_index_key.as_str()] = Value::Array(values);
+                } else if definition.has_container("@index")
+                    && item.get("@index").is_none()
+                    && !is_none
+                {
+                    item["@index"] = Value::String(index.clone());
+                } else if definition.has_container("@id") && item.get("@id").is_none() && !is_none {
+                    item["@id"] = active
+                        .expand_iri(index, true, false)
+                        .map_or(Value::Null, Value::String);
+                } else if definition.has_container("@type") && !is_none {
+                    let mut types = vec![expanded_index.clone().map_or(Value::Null, Value::String)];
+                    types.extend(item.get("@type").map(as_array).unwrap_or_default());
+                    item["@type"] = Value::Array(types);
+                }
+                items.push(item);
+            }
+        }
+        Ok(Value::Array(items))
+    }
+}
+
+/// Value expansion of a scalar
+pub(crate) fn expand_value(active: &Context, active_property: &str, value: &Value) -> Value {
+    let definition = active.term(active_property);
+    let type_mapping = definition.and_then(|d| d.type_mapping.as_deref());
+    if let Value::String(s) = value {
+        if type_mapping == Some("@id") {
+            return json!({ "@id": active.expand_iri(s, true, false) });
+        }
+        if type_mapping == Some("@vocab") {
+            return json!({ "@id": active.expand_iri(s, true, true) });
+        }
+    }
+    let mut result = Map::new();
+    result.insert("@value".to_string(), value.clone());
+    match type_mapping {
+        Some(t) if t != "@id" && t != "@vocab" && t != "@none" => {
+            result.insert("@type".to_string(), Value::String(t.to_string()));
+        }
+        _ if value.is_string() => {
+            let language = match definition.and_then(|d| d.language.clone()) {
+                Some(language) => language,
+                None => active.language.clone(),
+            };
+            let direction = match definition.and_then(|d| d.direction.clone()) {
+                Some(direction) => direction,
+                None => active.direction.clone(),
+            };
+            if let Some(language) = language {
+                result.insert("@language".to_string(), Value::String(language));
+            }
+            if let Some(direction) = direction {
+                result.insert("@direction".to_string(), Value::String(direction));
+            }
+        }
+        _ => {}
+    }
+    Value::Object(result)
+}
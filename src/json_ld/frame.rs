@@ -0,0 +1,610 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! The framing algorithm, with the `@once` embedding and blank node identifier
+//! pruning of JSON-LD 1.1.
+
+use super::rdf::{node_map, BlankNodeIssuer, NodeMap};
+use super::{
+    add_unique, add_value, as_array, compact, error, expand, is_absolute_iri, is_blank_node,
+    is_empty_object, is_keyword, is_list_object, is_node_reference, is_value_object, Context,
+    Loader,
+};
+
+use bbs::prelude::*;
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Embed {
+    Always,
+    Never,
+    Once,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Flags {
+    embed: Embed,
+    explicit: bool,
+    require_all: bool,
+}
+
+/// Frames a JSON-LD document and compacts the result with the context of the frame
+pub(crate) fn frame(input: &Value, frame: &Value, loader: &Loader) -> Result<Value, BBSError> {
+    let frame_object = frame.as_object().ok_or_else(|| error("invalid frame"))?;
+    let expanded = Value::Array(expand(input, None, loader, false)?);
+    let expanded_frame = expand(frame, None, loader, true)?;
+    let context = frame_object
+        .get("@context")
+        .cloned()
+        .unwrap_or_else(|| json!({}));
+    let frame_context = Context::new(None).process(&context, None, loader, false, true)?;
+    let merged = !frame_object
+        .keys()
+        .any(|k| frame_context.expand_iri(k, false, true).as_deref() == Some("@graph"));
+
+    let mut issuer = BlankNodeIssuer::default();
+    let mut graph_map = node_map(&expanded, &mut issuer)?;
+    let graph = match merged {
+        true => {
+            let merged = merge_graphs(&graph_map);
+            graph_map.insert("@merged".to_string(), merged);
+            "@merged"
+        }
+        false => "@default",
+    };
+    let mut framer = Framer {
+        subjects: graph_map[graph].clone(),
+        graph_map,
+        unique_embeds: HashMap::new(),
+        subject_stack: Vec::new(),
+        bnode_counts: HashMap::new(),
+        default_flags: Flags {
+            embed: Embed::Once,
+            explicit: false,
+            require_all: false,
+        },
+    };
+    let ids: Vec<String> = framer.subjects.keys().cloned().collect();
+    let framed = framer.frame(&ids, &expanded_frame, graph, false, None)?;
+
+    let prune: HashSet<String> = framer
+        .bnode_counts
+        .into_iter()
+        .filter(|(_, count)| *count == 1)
+        .map(|(id, _)| id)
+        .collect();
+    let framed = cleanup_preserve(Value::Array(framed), &prune);
+    let compacted = compact(&framed, &context, None, loader, false)?;
+    Ok(cleanup_null(compacted))
+}
+
+/// Merges the nodes of all graphs into a single node map
+fn merge_graphs(graph_map: &NodeMap) -> BTreeMap<String, Map<String, Value>> {
+    let mut merged: BTreeMap<String, Map<String, Value>> = BTreeMap::new();
+    for graph in graph_map.values() {
+        for (id, node) in graph {
+            let merged_node = merged.entry(id.clone()).or_insert_with(|| {
+                let mut node = Map::new();
+                node.insert("@id".to_string(), Value::String(id.clone()));
+                node
+            });
+            let mut properties: Vec<&String> = node.keys().collect();
+            properties.sort();
+            for property in properties {
+                if is_keyword(property) && property != "@type" {
+                    merged_node.insert(property.clone(), node[property].clone());
+                    continue;
+                }
+                for value in as_array(&node[property]) {
+                    add_unique(merged_node, property, value);
+                }
+            }
+        }
+    }
+    merged
+}
+
+struct Framer {
+    graph_map: NodeMap,
+    /// The nodes of the merged or default graph that node patterns match against
+    subjects: BTreeMap<String, Map<String, Value>>,
+    unique_embeds: HashMap<String, HashSet<String>>,
+    subject_stack: Vec<(String, String)>,
+    /// The number of node objects output for each blank node
+    bnode_counts: HashMap<String, usize>,
+    default_flags: Flags,
+}
+
+impl Framer {
+    /// Frames the `subjects` of `graph` that match `frame`, returning the node
+    /// objects to add to the parent in order
+    fn frame(
+        &mut self,
+        subjects: &[String],
+        frame: &[Value],
+        graph: &str,
+        embedded: bool,
+        property: Option<&str>,
+    ) -> Result<Vec<Value>, BBSError> {
+        validate_frame(frame)?;
+        let frame = frame[0].as_object().unwrap();
+        let flags = Flags {
+            embed: embed_flag(frame, self.default_flags.embed)?,
+            explicit: bool_flag(frame, "@explicit", self.default_flags.explicit),
+            require_all: bool_flag(frame, "@requireAll", self.default_flags.require_all),
+        };
+
+        let mut matches = Vec::new();
+        for id in subjects {
+            if let Some(subject) = self.graph_map.get(graph).and_then(|g| g.get(id)) {
+                if self.filter_subject(subject, frame, &flags) {
+                    matches.push((id.clone(), subject.clone()));
+                }
+            }
+        }
+        matches.sort_by(|a, b| a.0.cmp(&b.0));
+        matches.dedup_by(|a, b| a.0 == b.0);
+
+        let mut outputs = Vec::new();
+        for (id, subject) in matches {
+            if property.is_none() {
+                self.unique_embeds.clear();
+            }
+            let mut output = Map::new();
+            output.insert("@id".to_string(), Value::String(id.clone()));
+            if is_blank_node(&id) {
+                *self.bnode_counts.entry(id.clone()).or_default() += 1;
+            }
+            let already_embedded = self
+                .unique_embeds
+                .get(graph)
+                .is_some_and(|embeds| embeds.contains(&id));
+            if !embedded && already_embedded {
+                continue;
+            }
+            if embedded
+                && (flags.embed == Embed::Never
+                    || self
+                        .subject_stack
+                        .iter()
+                        .rev()
+                        .any(|(s, g)| *s == id && g == graph))
+            {
+                outputs.push(Value::Object(output));
+                continue;
+            }
+            if embedded && flags.embed == Embed::Once && already_embedded {
+                outputs.push(Value::Object(output));
+                continue;
+            }
+            self.unique_embeds
+                .entry(graph.to_string())
+                .or_default()
+                .insert(id.clone());
+            self.subject_stack.push((id.clone(), graph.to_string()));
+
+            if self.graph_map.contains_key(&id) {
+                let (recurse, subframe) = match frame.get("@graph") {
+                    None => (graph != "@merged", json!({})),
+                    Some(subframe) => {
+                        let subframe = as_array(subframe)
+                            .into_iter()
+                            .next()
+                            .filter(Value::is_object)
+                            .unwrap_or_else(|| json!({}));
+                        (id != "@merged" && id != "@default", subframe)
+                    }
+                };
+                if recurse {
+                    let ids: Vec<String> = self.graph_map[&id].keys().cloned().collect();
+                    let framed = self.frame(&ids, &[subframe], &id, false, Some("@graph"))?;
+                    add_outputs(&mut output, "@graph", framed);
+                }
+            }
+
+            if let Some(included) = frame.get("@included") {
+                let framed = self.frame(
+                    subjects,
+                    &as_array(included),
+                    graph,
+                    false,
+                    Some("@included"),
+                )?;
+                add_outputs(&mut output, "@included", framed);
+            }
+
+            let mut properties: Vec<&String> = subject.keys().collect();
+            properties.sort();
+            for prop in properties {
+                if is_keyword(prop) {
+                    output.insert(prop.clone(), subject[prop].clone());
+                    if prop == "@type" {
+                        for t in as_array(&subject[prop]) {
+                            if let Some(t) = t.as_str().filter(|t| is_blank_node(t)) {
+                                *self.bnode_counts.entry(t.to_string()).or_default() += 1;
+                            }
+                        }
+                    }
+                    continue;
+                }
+                if flags.explicit && !frame.contains_key(prop) {
+                    continue;
+                }
+                let subframe = match frame.get(prop) {
+                    Some(subframe) => as_array(subframe),
+                    None => implicit_frame(&flags),
+                };
+                for o in as_array(&subject[prop]) {
+                    if is_list_object(&o) {
+                        let list_frame = subframe
+                            .first()
+                            .and_then(|f| f.get("@list"))
+                            .map(as_array)
+                            .unwrap_or_else(|| implicit_frame(&flags));
+                        let mut list = Vec::new();
+                        for item in as_array(&o["@list"]) {
+                            if is_node_reference(&item) {
+                                let id = item["@id"].as_str().unwrap_or_default().to_string();
+                                list.extend(self.frame(
+                                    &[id],
+                                    &list_frame,
+                                    graph,
+                                    true,
+                                    Some("@list"),
+                                )?);
+                            } else {
+                                list.push(item);
+                            }
+                        }
+                        add_value(&mut output, prop, json!({ "@list": list }), true);
+                    } else if is_node_reference(&o) {
+                        let id = o["@id"].as_str().unwrap_or_default().to_string();
+                        let framed = self.frame(&[id], &subframe, graph, true, Some(prop))?;
+                        add_outputs(&mut output, prop, framed);
+                    } else if subframe.first().is_some_and(|f| value_match(f, &o)) {
+                        add_value(&mut output, prop, o, true);
+                    }
+                }
+            }
+
+            let mut frame_properties: Vec<&String> = frame.keys().collect();
+            frame_properties.sort();
+            for prop in frame_properties {
+                let next = as_array(&frame[prop])
+                    .into_iter()
+                    .next()
+                    .unwrap_or_else(|| json!({}));
+                if prop == "@type" {
+                    if next.get("@default").is_none() {
+                        continue;
+                    }
+                } else if is_keyword(prop) {
+                    continue;
+                }
+                let next = next.as_object().cloned().unwrap_or_default();
+                let omit_default = bool_flag(&next, "@omitDefault", false);
+                if !omit_default && !output.contains_key(prop) {
+                    let preserve = match next.get("@default") {
+                        Some(default) => as_array(default),
+                        None => vec![Value::String("@null".to_string())],
+                    };
+                    output.insert(prop.clone(), json!([{ "@preserve": preserve }]));
+                }
+            }
+
+            if let Some(Value::Object(reverse)) = frame.get("@reverse") {
+                let mut reverse_properties: Vec<&String> = reverse.keys().collect();
+                reverse_properties.sort();
+                for reverse_prop in reverse_properties {
+                    let subframe = as_array(&reverse[reverse_prop]);
+                    let referencing: Vec<String> = self
+                        .subjects
+                        .iter()
+                        .filter(|(_, node)| {
+                            node.get(reverse_prop.as_str()).is_some_and(|values| {
+                                as_array(values)
+                                    .iter()
+                                    .any(|v| v.get("@id").and_then(Value::as_str) == Some(&id))
+                            })
+                        })
+                        .map(|(s, _)| s.clone())
+                        .collect();
+                    for s in referencing {
+                        let framed = self.frame(&[s], &subframe, graph, true, property)?;
+                        let reverse_map = output
+                            .entry("@reverse")
+                            .or_insert_with(|| json!({}))
+                            .as_object_mut()
+                            .unwrap();
+                        add_value(reverse_map, reverse_prop, Value::Array(framed), true);
+                    }
+                }
+            }
+
+            outputs.push(Value::Object(output));
+            self.subject_stack.pop();
+        }
+        Ok(outputs)
+    }
+
+    fn filter_subject(
+        &self,
+        subject: &Map<String, Value>,
+        frame: &Map<String, Value>,
+        flags: &Flags,
+    ) -> bool {
+        let mut wildcard = true;
+        let mut matches_some = false;
+        for (key, frame_value) in frame {
+            let node_values = subject.get(key).map(as_array).unwrap_or_default();
+            let frame_values = as_array(frame_value);
+            let mut match_this = false;
+            if key == "@id" {
+                match_this = match frame_values.first() {
+                    None => true,
+                    Some(first) if is_empty_object(first) => true,
+                    Some(_) => node_values
+                        .first()
+                        .is_some_and(|id| frame_values.contains(id)),
+                };
+                if !flags.require_all {
+                    return match_this;
+                }
+            } else if key == "@type" {
+                wildcard = false;
+                if frame_values.is_empty() {
+                    if !node_values.is_empty() {
+                        return false;
+                    }
+                    match_this = true;
+                } else if frame_values.len() == 1 && is_empty_object(&frame_values[0]) {
+                    match_this = !node_values.is_empty();
+                } else {
+                    for t in &frame_values {
+                        if t.get("@default").is_some() {
+                            match_this = true;
+                        } else {
+                            match_this = match_this || node_values.contains(t);
+                        }
+                    }
+                    if !flags.require_all {
+                        return match_this;
+                    }
+                }
+            } else if is_keyword(key) {
+                continue;
+            } else {
+                let this_frame = frame_values.first();
+                let has_default = this_frame.is_some_and(|f| f.get("@default").is_some());
+                wildcard = false;
+                if node_values.is_empty() && has_default {
+                    continue;
+                }
+                if !node_values.is_empty() && frame_values.is_empty() {
+                    return false;
+                }
+                match this_frame {
+                    None => {
+                        if !node_values.is_empty() {
+                            return false;
+                        }
+                        match_this = true;
+                    }
+                    Some(this_frame) if is_list_object(this_frame) => {
+                        let list_value = as_array(&this_frame["@list"]).into_iter().next();
+                        let node_list = node_values
+                            .first()
+                            .filter(|v| is_list_object(v))
+                            .map(|v| as_array(&v["@list"]));
+                        if let (Some(list_value), Some(node_list)) = (list_value, node_list) {
+                            if is_value_object(&list_value) {
+                                match_this = node_list.iter().any(|v| value_match(&list_value, v));
+                            } else if is_node_object_pattern(&list_value) {
+                                match_this = node_list
+                                    .iter()
+                                    .any(|v| self.node_match(&list_value, v, flags));
+                            }
+                        }
+                    }
+                    Some(this_frame) if is_value_object(this_frame) => {
+                        match_this = node_values.iter().any(|v| value_match(this_frame, v));
+                    }
+                    Some(this_frame) if is_node_reference(this_frame) => {
+                        match_this = node_values
+                            .iter()
+                            .any(|v| self.node_match(this_frame, v, flags));
+                    }
+                    Some(this_frame) if this_frame.is_object() => {
+                        match_this = !node_values.is_empty();
+                    }
+                    Some(_) => {}
+                }
+            }
+            if !match_this && flags.require_all {
+                return false;
+            }
+            matches_some = matches_some || match_this;
+        }
+        wildcard || matches_some
+    }
+
+    fn node_match(&self, pattern: &Value, value: &Value, flags: &Flags) -> bool {
+        let id = match value.get("@id").and_then(Value::as_str) {
+            Some(id) => id,
+            None => return false,
+        };
+        match (self.subjects.get(id), pattern.as_object()) {
+            (Some(node), Some(pattern)) => self.filter_subject(node, pattern, flags),
+            _ => false,
+        }
+    }
+}
+
+/// Adds framed node objects to an array entry, which is only created for the first
+fn add_outputs(output: &mut Map<String, Value>, property: &str, framed: Vec<Value>) {
+    for item in framed {
+        add_value(output, property, item, true);
+    }
+}
+
+fn validate_frame(frame: &[Value]) -> Result<(), BBSError> {
+    if frame.len() != 1 || !frame[0].is_object() {
+        return Err(error("invalid frame"));
+    }
+    for key in ["@id", "@type"] {
+        if let Some(values) = frame[0].get(key) {
+            for value in as_array(values) {
+                let valid = match &value {
+                    Value::String(iri) => is_absolute_iri(iri) && !is_blank_node(iri),
+                    v => v.is_object(),
+                };
+                if !valid {
+                    return Err(error(&format!("invalid {} in frame", key)));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn embed_flag(frame: &Map<String, Value>, default: Embed) -> Result<Embed, BBSError> {
+    let value = match frame.get("@embed").map(as_array) {
+        Some(values) if !values.is_empty() => values[0].clone(),
+        _ => return Ok(default),
+    };
+    match value {
+        Value::Bool(true) => Ok(Embed::Once),
+        Value::Bool(false) => Ok(Embed::Never),
+        Value::String(embed) => match embed.as_str() {
+            "@always" => Ok(Embed::Always),
+            "@never" => Ok(Embed::Never),
+            "@once" => Ok(Embed::Once),
+            _ => Err(error("invalid @embed value")),
+        },
+        _ => Err(error("invalid @embed value")),
+    }
+}
+
+fn bool_flag(frame: &Map<String, Value>, flag: &str, default: bool) -> bool {
+    match frame.get(flag).map(as_array) {
+        Some(values) if !values.is_empty() => values[0].as_bool().unwrap_or(default),
+        _ => default,
+    }
+}
+
+fn implicit_frame(flags: &Flags) -> Vec<Value> {
+    let embed = match flags.embed {
+        Embed::Always => "@always",
+        Embed::Never => "@never",
+        Embed::Once => "@once",
+    };
+    vec![json!({
+        "@embed": [embed],
+        "@explicit": [flags.explicit],
+        "@requireAll": [flags.require_all],
+    })]
+}
+
+/// A node object or node reference used as a pattern
+fn is_node_object_pattern(value: &Value) -> bool {
+    value.is_object()
+        && !is_value_object(value)
+        && value.get("@set").is_none()
+        && !is_list_object(value)
+}
+
+/// Whether a value object matches a value pattern, where `{}` matches any value,
+/// type or language
+fn value_match(pattern: &Value, value: &Value) -> bool {
+    let patterns = |key: &str| -> Vec<Value> {
+        match pattern.get(key) {
+            None | Some(Value::Null) | Some(Value::Bool(false)) => Vec::new(),
+            Some(Value::String(s)) if s.is_empty() => Vec::new(),
+            Some(v) => as_array(v),
+        }
+    };
+    let (v2, t2, l2) = (patterns("@value"), patterns("@type"), patterns("@language"));
+    if v2.is_empty() && t2.is_empty() && l2.is_empty() {
+        return true;
+    }
+    let matches = |patterns: &[Value], value: Option<&Value>, optional: bool| {
+        (optional && value.is_none() && patterns.is_empty())
+            || value.is_some_and(|v| patterns.contains(v))
+            || (value.is_some() || !optional) && patterns.first().is_some_and(is_empty_object)
+    };
+    matches(&v2, value.get("@value"), false)
+        && matches(&t2, value.get("@type"), true)
+        && matches(&l2, value.get("@language"), true)
+}
+
+/// Replaces the `@preserve` defaults with their values and removes the identifiers of
+/// blank nodes that are only output once
+fn cleanup_preserve(input: Value, prune: &HashSet<String>) -> Value {
+    match input {
+        Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .map(|item| cleanup_preserve(item, prune))
+                .collect(),
+        ),
+        Value::Object(mut object) => {
+            if let Some(preserve) = object.remove("@preserve") {
+                return as_array(&preserve)
+                    .into_iter()
+                    .next()
+                    .unwrap_or(Value::Null);
+            }
+            if object.contains_key("@value") {
+                return Value::Object(object);
+            }
+            if let Some(list) = object.remove("@list") {
+                object.insert("@list".to_string(), cleanup_preserve(list, prune));
+                return Value::Object(object);
+            }
+            if let Some(Value::String(id)) = object.get("@id") {
+                if prune.contains(id) {
+                    object.remove("@id");
+                }
+            }
+            Value::Object(
+                object
+                    .into_iter()
+                    .map(|(k, v)| (k, cleanup_preserve(v, prune)))
+                    .collect(),
+            )
+        }
+        value => value,
+    }
+}
+
+/// Replaces the `@null` placeholders of defaults with `null`, dropping them from arrays
+fn cleanup_null(input: Value) -> Value {
+    match input {
+        Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .map(cleanup_null)
+                .filter(|v| !v.is_null())
+                .collect(),
+        ),
+        Value::String(s) if s == "@null" => Value::Null,
+        Value::Object(object) => Value::Object(
+            object
+                .into_iter()
+                .map(|(k, v)| (k, cleanup_null(v)))
+                .collect(),
+        ),
+        value => value,
+    }
+}
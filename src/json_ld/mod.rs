@@ -0,0 +1,255 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! JSON-LD 1.1 processing for the linked data proof suites.
+//!
+//! Implements context processing, expansion, compaction, the conversions to and from
+//! RDF and framing as specified by the JSON-LD 1.1 Processing Algorithms and API and
+//! JSON-LD 1.1 Framing, with the defaults of the `jsonld` JavaScript library. Remote
+//! contexts are never fetched. They are resolved from the bundled credentials and
+//! security vocabulary contexts and the contexts given by the caller.
+
+mod compact;
+mod context;
+mod expand;
+mod frame;
+mod rdf;
+
+pub(crate) use compact::compact;
+pub(crate) use context::Context;
+pub(crate) use expand::expand;
+pub(crate) use frame::frame;
+pub(crate) use rdf::{from_rdf, to_rdf};
+
+use crate::utils::general_error;
+
+use bbs::prelude::*;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+/// Bounds the nesting of remote contexts so cyclic contexts fail
+const MAX_REMOTE_CONTEXTS: usize = 32;
+
+const BUNDLED_CONTEXTS: [(&str, &str); 4] = [
+    (
+        "https://www.w3.org/2018/credentials/v1",
+        include_str!("contexts/credentials-v1.jsonld"),
+    ),
+    (
+        "https://w3id.org/security/v1",
+        include_str!("contexts/security-v1.jsonld"),
+    ),
+    (
+        "https://w3id.org/security/v2",
+        include_str!("contexts/security-v2.jsonld"),
+    ),
+    (
+        "https://w3id.org/security/bbs/v1",
+        include_str!("contexts/bbs-v1.jsonld"),
+    ),
+];
+
+/// Resolves remote contexts without network access
+#[derive(Debug, Default)]
+pub(crate) struct Loader {
+    documents: BTreeMap<String, Value>,
+}
+
+impl Loader {
+    /// A loader for the bundled contexts and the `contexts` given by the caller,
+    /// which map context URLs to their documents
+    pub(crate) fn new(contexts: Option<&BTreeMap<String, Value>>) -> Result<Self, BBSError> {
+        let mut documents = BTreeMap::new();
+        for (url, document) in BUNDLED_CONTEXTS.iter() {
+            documents.insert(url.to_string(), serde_json::from_str(document).unwrap());
+        }
+        for (url, document) in contexts.into_iter().flatten() {
+            if !document.is_object() {
+                return Err(error("loading remote context failed"));
+            }
+            documents.insert(url.clone(), document.clone());
+        }
+        Ok(Self { documents })
+    }
+
+    fn load(&self, url: &str) -> Result<&Value, BBSError> {
+        self.documents.get(url).ok_or_else(|| {
+            general_error(&format!(
+                "JSON-LD error: loading remote context failed: {} is not available",
+                url
+            ))
+        })
+    }
+}
+
+pub(crate) fn error(code: &str) -> BBSError {
+    general_error(&format!("JSON-LD error: {}", code))
+}
+
+const KEYWORDS: [&str; 29] = [
+    "@base",
+    "@container",
+    "@context",
+    "@default",
+    "@direction",
+    "@embed",
+    "@explicit",
+    "@graph",
+    "@id",
+    "@import",
+    "@included",
+    "@index",
+    "@json",
+    "@language",
+    "@list",
+    "@nest",
+    "@none",
+    "@omitDefault",
+    "@prefix",
+    "@preserve",
+    "@propagate",
+    "@protected",
+    "@requireAll",
+    "@reverse",
+    "@set",
+    "@type",
+    "@value",
+    "@version",
+    "@vocab",
+];
+
+pub(crate) fn is_keyword(value: &str) -> bool {
+    KEYWORDS.contains(&value)
+}
+
+/// `@` followed by letters, which is reserved for future keywords
+pub(crate) fn has_keyword_form(value: &str) -> bool {
+    value.len() > 1 && value.starts_with('@') && value[1..].chars().all(|c| c.is_ascii_alphabetic())
+}
+
+/// An IRI with a scheme, or a blank node identifier
+pub(crate) fn is_absolute_iri(value: &str) -> bool {
+    match value.find(':') {
+        Some(i) if i > 0 => {
+            let scheme = &value[..i];
+            scheme == "_"
+                || (scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                    && scheme
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c)))
+        }
+        _ => false,
+    }
+}
+
+pub(crate) fn is_blank_node(value: &str) -> bool {
+    value.starts_with("_:")
+}
+
+/// The value as an array, with a single value wrapped and `null` as empty
+pub(crate) fn as_array(value: &Value) -> Vec<Value> {
+    match value {
+        Value::Array(items) => items.clone(),
+        Value::Null => Vec::new(),
+        v => vec![v.clone()],
+    }
+}
+
+/// Add a value to the entry of an object, turning the entry into an array when it
+/// has several values or `as_array` is set
+pub(crate) fn add_value(object: &mut Map<String, Value>, key: &str, value: Value, as_array: bool) {
+    if as_array {
+        match object.get_mut(key) {
+            None => {
+                object.insert(key.to_string(), Value::Array(Vec::new()));
+            }
+            Some(Value::Array(_)) => {}
+            Some(v) => *v = Value::Array(vec![v.take()]),
+        }
+    }
+    if let Value::Array(items) = value {
+        for item in items {
+            add_value(object, key, item, as_array);
+        }
+        return;
+    }
+    match object.get_mut(key) {
+        None => {
+            object.insert(key.to_string(), value);
+        }
+        Some(Value::Array(items)) => items.push(value),
+        Some(v) => *v = Value::Array(vec![v.take(), value]),
+    }
+}
+
+/// Add a value to an array entry unless an equal value is already there
+pub(crate) fn add_unique(object: &mut Map<String, Value>, key: &str, value: Value) {
+    let entry = object
+        .entry(key.to_string())
+        .or_insert_with(|| Value::Array(Vec::new()));
+    if !entry.is_array() {
+        *entry = Value::Array(vec![entry.take()]);
+    }
+    let items = entry.as_array_mut().unwrap();
+    if !items.contains(&value) {
+        items.push(value);
+    }
+}
+
+pub(crate) fn is_value_object(value: &Value) -> bool {
+    value.get("@value").is_some()
+}
+
+pub(crate) fn is_list_object(value: &Value) -> bool {
+    value.get("@list").is_some()
+}
+
+pub(crate) fn is_graph_object(value: &Value) -> bool {
+    match value.as_object() {
+        Some(object) => {
+            object.contains_key("@graph")
+                && object
+                    .keys()
+                    .all(|k| k == "@graph" || k == "@id" || k == "@index")
+        }
+        None => false,
+    }
+}
+
+/// A graph object without an `@id`
+pub(crate) fn is_simple_graph_object(value: &Value) -> bool {
+    is_graph_object(value) && value.get("@id").is_none()
+}
+
+/// A node object with only an `@id`
+pub(crate) fn is_node_reference(value: &Value) -> bool {
+    match value.as_object() {
+        Some(object) => object.len() == 1 && object.contains_key("@id"),
+        None => false,
+    }
+}
+
+pub(crate) fn is_node_object(value: &Value) -> bool {
+    match value.as_object() {
+        Some(object) => {
+            !object.contains_key("@value")
+                && !object.contains_key("@list")
+                && !object.contains_key("@set")
+        }
+        None => false,
+    }
+}
+
+pub(crate) fn is_empty_object(value: &Value) -> bool {
+    matches!(value, Value::Object(object) if object.is_empty())
+}
@@ -0,0 +1,707 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Node map generation and the conversions between JSON-LD and RDF datasets.
+
+use super::{
+    add_unique, add_value, as_array, error, expand, is_absolute_iri, is_blank_node, is_list_object,
+    is_value_object, Loader,
+};
+use crate::rdf_canonize::{Quad, Term};
+
+use bbs::prelude::*;
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+const RDF: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#";
+const XSD: &str = "http://www.w3.org/2001/XMLSchema#";
+
+/// Node maps by graph name, with the nodes of each graph by identifier
+pub(crate) type NodeMap = BTreeMap<String, BTreeMap<String, Map<String, Value>>>;
+
+/// Issues `_:b0`, `_:b1`, ... for the blank nodes of a document
+#[derive(Debug, Default)]
+pub(crate) struct BlankNodeIssuer {
+    issued: HashMap<String, String>,
+    counter: usize,
+}
+
+impl BlankNodeIssuer {
+    /// The new identifier of an existing blank node, or a fresh one for `None`
+    pub(crate) fn issue(&mut self, existing: Option<&str>) -> String {
+        if let Some(id) = existing.and_then(|e| self.issued.get(e)) {
+            return id.clone();
+        }
+        let id = format!("_:b{}", self.counter);
+        self.counter += 1;
+        if let Some(existing) = existing {
+            self.issued.insert(existing.to_string(), id.clone());
+        }
+        id
+    }
+}
+
+/// The subject the values of a node map entry are added to
+enum Subject<'a> {
+    None,
+    Id(&'a str),
+    /// The referenced node of a reverse property
+    Reverse(&'a Value),
+}
+
+/// Adds the nodes of an expanded element to the node map
+fn generate_node_map(
+    element: &Value,
+    node_map: &mut NodeMap,
+    graph: &str,
+    subject: Subject,
+    property: Option<&str>,
+    list: Option<&mut Vec<Value>>,
+    issuer: &mut BlankNodeIssuer,
+) -> Result<(), BBSError> {
+    if let Value::Array(items) = element {
+        let mut list = list;
+        for item in items {
+            let subject = match &subject {
+                Subject::None => Subject::None,
+                Subject::Id(id) => Subject::Id(id),
+                Subject::Reverse(r) => Subject::Reverse(r),
+            };
+            generate_node_map(
+                item,
+                node_map,
+                graph,
+                subject,
+                property,
+                list.as_deref_mut(),
+                issuer,
+            )?;
+        }
+        return Ok(());
+    }
+    let mut element = match element {
+        Value::Object(object) => object.clone(),
+        _ => return Ok(()),
+    };
+    node_map.entry(graph.to_string()).or_default();
+
+    if let Some(types) = element.get_mut("@type") {
+        if let Value::Array(types) = types {
+            for t in types.iter_mut() {
+                if let Some(b) = t.as_str().filter(|t| is_blank_node(t)) {
+                    *t = Value::String(issuer.issue(Some(b)));
+                }
+            }
+        } else if let Some(b) = types.as_str().filter(|t| is_blank_node(t)) {
+            *types = Value::String(issuer.issue(Some(b)));
+        }
+    }
+
+    let add_to_subject = |node_map: &mut NodeMap, value: Value, unique: bool| {
+        if let (Subject::Id(id), Some(property)) = (&subject, property) {
+            let node = node_map.get_mut(graph).unwrap().get_mut(*id).unwrap();
+            if unique {
+                add_unique(node, property, value);
+            } else {
+                add_value(node, property, value, true);
+            }
+        }
+    };
+
+    if is_value_object(&Value::Object(element.clone())) {
+        match list {
+            Some(list) => list.push(Value::Object(element)),
+            None => add_to_subject(node_map, Value::Object(element), true),
+        }
+        return Ok(());
+    }
+    if let Some(items) = element.get("@list") {
+        let mut result = Vec::new();
+        generate_node_map(
+            items,
+            node_map,
+            graph,
+            Subject::Id(match &subject {
+                Subject::Id(id) => id,
+                _ => "",
+            }),
+            property,
+            Some(&mut result),
+            issuer,
+        )?;
+        let result = json!({ "@list": result });
+        match list {
+            Some(list) => list.push(result),
+            None => add_to_subject(node_map, result, false),
+        }
+        return Ok(());
+    }
+
+    let id = match element.remove("@id") {
+        Some(Value::String(id)) if is_blank_node(&id) => issuer.issue(Some(&id)),
+        Some(Value::String(id)) => id,
+        Some(Value::Null) | None => issuer.issue(None),
+        Some(_) => return Err(error("invalid @id value")),
+    };
+    node_map
+        .get_mut(graph)
+        .unwrap()
+        .entry(id.clone())
+        .or_insert_with(|| {
+            let mut node = Map::new();
+            node.insert("@id".to_string(), Value::String(id.clone()));
+            node
+        });
+    let reference = json!({ "@id": id });
+    match &subject {
+        Subject::Reverse(referenced) => {
+            let node = node_map.get_mut(graph).unwrap().get_mut(&id).unwrap();
+            add_unique(node, property.unwrap_or_default(), (*referenced).clone());
+        }
+        _ if property.is_some() => match list {
+            Some(list) => list.push(reference.clone()),
+            None => add_to_subject(node_map, reference.clone(), true),
+        },
+        _ => {}
+    }
+
+    if let Some(types) = element.remove("@type") {
+        let node = node_map.get_mut(graph).unwrap().get_mut(&id).unwrap();
+        for t in as_array(&types) {
+            add_unique(node, "@type", t);
+        }
+    }
+    if let Some(index) = element.remove("@index") {
+        let node = node_map.get_mut(graph).unwrap().get_mut(&id).unwrap();
+        match node.get("@index") {
+            Some(existing) if *existing != index => return Err(error("conflicting indexes")),
+            _ => {
+                node.insert("@index".to_string(), index);
+            }
+        }
+    }
+    if let Some(Value::Object(reverse)) = element.remove("@reverse") {
+        for (property, values) in &reverse {
+            for value in as_array(values) {
+                generate_node_map(
+                    &value,
+                    node_map,
+                    graph,
+                    Subject::Reverse(&reference),
+                    Some(property),
+                    None,
+                    issuer,
+                )?;
+            }
+        }
+    }
+    if let Some(graph_value) = element.remove("@graph") {
+        generate_node_map(
+            &graph_value,
+            node_map,
+            &id,
+            Subject::None,
+            None,
+            None,
+            issuer,
+        )?;
+    }
+    if let Some(included) = element.remove("@included") {
+        generate_node_map(
+            &included,
+            node_map,
+            graph,
+            Subject::None,
+            None,
+            None,
+            issuer,
+        )?;
+    }
+    let mut properties: Vec<(String, Value)> = element.into_iter().collect();
+    properties.sort_by(|a, b| a.0.cmp(&b.0));
+    for (property, value) in properties {
+        let property = match is_blank_node(&property) {
+            true => issuer.issue(Some(&property)),
+            false => property,
+        };
+        let node = node_map.get_mut(graph).unwrap().get_mut(&id).unwrap();
+        if !node.contains_key(&property) {
+            node.insert(property.clone(), Value::Array(Vec::new()));
+        }
+        generate_node_map(
+            &value,
+            node_map,
+            graph,
+            Subject::Id(&id),
+            Some(&property),
+            None,
+            issuer,
+        )?;
+    }
+    Ok(())
+}
+
+/// The node map of an expanded document
+pub(crate) fn node_map(
+    expanded: &Value,
+    issuer: &mut BlankNodeIssuer,
+) -> Result<NodeMap, BBSError> {
+    let mut node_map = NodeMap::new();
+    node_map.insert("@default".to_string(), BTreeMap::new());
+    generate_node_map(
+        expanded,
+        &mut node_map,
+        "@default",
+        Subject::None,
+        None,
+        None,
+        issuer,
+    )?;
+    Ok(node_map)
+}
+
+/// Deserializes a JSON-LD document to an RDF dataset
+pub(crate) fn to_rdf(document: &Value, loader: &Loader) -> Result<Vec<Quad>, BBSError> {
+    let expanded = Value::Array(expand(document, None, loader, false)?);
+    let mut issuer = BlankNodeIssuer::default();
+    let node_map = node_map(&expanded, &mut issuer)?;
+    let mut quads = Vec::new();
+    for (graph_name, graph) in &node_map {
+        let graph_term = match graph_name.as_str() {
+            "@default" => Term::DefaultGraph,
+            name => match node_term(name) {
+                Some(term) => term,
+                None => continue,
+            },
+        };
+        for (id, node) in graph {
+            let subject = match node_term(id) {
+                Some(subject) => subject,
+                None => continue,
+            };
+            let mut properties: Vec<&String> = node.keys().collect();
+            properties.sort();
+            for property in properties {
+                let values = as_array(&node[property]);
+                if property == "@type" {
+                    for t in values {
+                        if let Some(object) = t.as_str().and_then(node_term) {
+                            quads.push(Quad {
+                                subject: subject.clone(),
+                                predicate: Term::Iri(format!("{}type", RDF)),
+                                object,
+                                graph: graph_term.clone(),
+                            });
+                        }
+                    }
+                    continue;
+                }
+                if property.starts_with('@') || is_blank_node(property) || !well_formed(property) {
+                    continue;
+                }
+                for item in values {
+                    if let Some(object) = object_to_rdf(&item, &mut issuer, &graph_term, &mut quads)
+                    {
+                        quads.push(Quad {
+                            subject: subject.clone(),
+                            predicate: Term::Iri(property.clone()),
+                            object,
+                            graph: graph_term.clone(),
+                        });
+                    }
+                }
+            }
+        }
+    }
+    let mut seen = HashSet::new();
+    quads.retain(|q| seen.insert(q.to_nquad()));
+    Ok(quads)
+}
+
+/// An absolute IRI without the characters IRIs cannot contain
+fn well_formed(iri: &str) -> bool {
+    is_absolute_iri(iri)
+        && iri.matches('#').count() <= 1
+        && !iri
+            .chars()
+            .any(|c| c.is_whitespace() || "<>\"{}|\\^`".contains(c))
+}
+
+fn node_term(id: &str) -> Option<Term> {
+    if let Some(label) = id.strip_prefix("_:") {
+        return Some(Term::BlankNode(label.to_string()));
+    }
+    if well_formed(id) {
+        return Some(Term::Iri(id.to_string()));
+    }
+    None
+}
+
+fn object_to_rdf(
+    item: &Value,
+    issuer: &mut BlankNodeIssuer,
+    graph: &Term,
+    quads: &mut Vec<Quad>,
+) -> Option<Term> {
+    if is_list_object(item) {
+        return Some(list_to_rdf(&as_array(&item["@list"]), issuer, graph, quads));
+    }
+    if !is_value_object(item) {
+        return item.get("@id").and_then(Value::as_str).and_then(node_term);
+    }
+    let value = &item["@value"];
+    let mut datatype = item.get("@type").and_then(Value::as_str).map(String::from);
+    if let Some(datatype) = &datatype {
+        if datatype != "@json" && !well_formed(datatype) {
+            return None;
+        }
+    }
+    let language = item.get("@language").and_then(Value::as_str);
+    if let Some(language) = language {
+        if !well_formed_language(language) {
+            return None;
+        }
+    }
+    let lexical = if datatype.as_deref() == Some("@json") {
+        datatype = Some(format!("{}JSON", RDF));
+        canonical_json(value)
+    } else {
+        match value {
+            Value::Bool(b) => {
+                datatype.get_or_insert_with(|| format!("{}boolean", XSD));
+                b.to_string()
+            }
+            Value::Number(n) => {
+                let v = n.as_f64().unwrap_or_default();
+                let is_double = n.is_f64() && (v.fract() != 0.0 || v.abs() >= 1e21);
+                if is_double || datatype.as_deref() == Some(&format!("{}double", XSD)) {
+                    datatype.get_or_insert_with(|| format!("{}double", XSD));
+                    canonical_double(v)
+                } else {
+                    datatype.get_or_insert_with(|| format!("{}integer", XSD));
+                    match n.is_f64() {
+                        true => es_number(v),
+                        false => n.to_string(),
+                    }
+                }
+            }
+            Value::String(s) => s.clone(),
+            _ => return None,
+        }
+    };
+    if language.is_some() && datatype.is_none() {
+        return Some(Term::Literal {
+            value: lexical,
+            datatype: None,
+            language: language.map(str::to_lowercase),
+        });
+    }
+    let datatype = datatype.filter(|d| *d != format!("{}string", XSD));
+    Some(Term::Literal {
+        value: lexical,
+        datatype,
+        language: None,
+    })
+}
+
+fn list_to_rdf(
+    items: &[Value],
+    issuer: &mut BlankNodeIssuer,
+    graph: &Term,
+    quads: &mut Vec<Quad>,
+) -> Term {
+    if items.is_empty() {
+        return Term::Iri(format!("{}nil", RDF));
+    }
+    let nodes: Vec<String> = items.iter().map(|_| issuer.issue(None)).collect();
+    for (i, item) in items.iter().enumerate() {
+        let subject = Term::BlankNode(nodes[i][2..].to_string());
+        if let Some(object) = object_to_rdf(item, issuer, graph, quads) {
+            quads.push(Quad {
+                subject: subject.clone(),
+                predicate: Term::Iri(format!("{}first", RDF)),
+                object,
+                graph: graph.clone(),
+            });
+        }
+        let rest = match nodes.get(i + 1) {
+            Some(next) => Term::BlankNode(next[2..].to_string()),
+            None => Term::Iri(format!("{}nil", RDF)),
+        };
+        quads.push(Quad {
+            subject,
+            predicate: Term::Iri(format!("{}rest", RDF)),
+            object: rest,
+            graph: graph.clone(),
+        });
+    }
+    Term::BlankNode(nodes[0][2..].to_string())
+}
+
+fn well_formed_language(language: &str) -> bool {
+    let mut parts = language.split('-');
+    let primary = parts.next().unwrap_or_default();
+    (1..=8).contains(&primary.len())
+        && primary.chars().all(|c| c.is_ascii_alphabetic())
+        && parts.all(|p| (1..=8).contains(&p.len()) && p.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+
+/// The canonical `xsd:double` lexical form, such as `1.1E0`
+fn canonical_double(value: f64) -> String {
+    let formatted = format!("{:.15e}", value);
+    let (mantissa, exponent) = formatted.split_once('e').unwrap_or((&formatted, "0"));
+    let mut mantissa = mantissa.trim_end_matches('0').to_string();
+    if mantissa.ends_with('.') {
+        mantissa.push('0');
+    }
+    format!("{}E{}", mantissa, exponent)
+}
+
+/// The JSON Canonicalization Scheme (RFC 8785) serialization of a JSON literal
+fn canonical_json(value: &Value) -> String {
+    match value {
+        Value::Array(items) => format!(
+            "[{}]",
+            items
+                .iter()
+                .map(canonical_json)
+                .collect::<Vec<_>>()
+                .join(",")
+        ),
+        Value::Object(object) => {
+            let mut entries: Vec<(&String, &Value)> = object.iter().collect();
+            entries.sort_by(|a, b| a.0.encode_utf16().cmp(b.0.encode_utf16()));
+            let entries: Vec<String> = entries
+                .into_iter()
+                .map(|(k, v)| format!("{}:{}", Value::String(k.clone()), canonical_json(v)))
+                .collect();
+            format!("{{{}}}", entries.join(","))
+        }
+        Value::Number(n) if n.is_f64() => es_number(n.as_f64().unwrap_or_default()),
+        v => v.to_string(),
+    }
+}
+
+/// The ECMAScript `Number.prototype.toString` form of a number, as JCS requires
+fn es_number(value: f64) -> String {
+    if value == 0.0 {
+        return "0".to_string();
+    }
+    let scientific = format!("{:e}", value.abs());
+    let (mantissa, exponent) = scientific.split_once('e').unwrap_or((&scientific, "0"));
+    let digits: String = mantissa.chars().filter(char::is_ascii_digit).collect();
+    let k = digits.len() as i32;
+    let n = exponent.parse::<i32>().unwrap_or_default() + 1;
+    let sign = if value < 0.0 { "-" } else { "" };
+    let formatted = if k <= n && n <= 21 {
+        format!("{}{}", digits, "0".repeat((n - k) as usize))
+    } else if 0 < n && n <= 21 {
+        format!("{}.{}", &digits[..n as usize], &digits[n as usize..])
+    } else if -6 < n && n <= 0 {
+        format!("0.{}{}", "0".repeat(-n as usize), digits)
+    } else {
+        let fraction = match k {
+            1 => String::new(),
+            _ => format!(".{}", &digits[1..]),
+        };
+        let exponent_sign = if n - 1 < 0 { "-" } else { "+" };
+        format!(
+            "{}{}e{}{}",
+            &digits[..1],
+            fraction,
+            exponent_sign,
+            (n - 1).abs()
+        )
+    };
+    format!("{}{}", sign, formatted)
+}
+
+/// Serializes an RDF dataset to expanded JSON-LD, without native types
+pub(crate) fn from_rdf(quads: &[Quad]) -> Result<Value, BBSError> {
+    let rdf_first = format!("{}first", RDF);
+    let rdf_rest = format!("{}rest", RDF);
+    let rdf_nil = format!("{}nil", RDF);
+    let rdf_list = Value::String(format!("{}List", RDF));
+
+    let mut graph_map: BTreeMap<String, BTreeMap<String, Map<String, Value>>> = BTreeMap::new();
+    graph_map.insert("@default".to_string(), BTreeMap::new());
+    // the uses of `rdf:nil` by graph, and the use of the blank nodes used only once
+    let mut nil_usages: HashMap<String, Vec<Usage>> = HashMap::new();
+    let mut referenced_once: HashMap<String, Option<Usage>> = HashMap::new();
+
+    for quad in quads {
+        let name = match &quad.graph {
+            Term::DefaultGraph => "@default".to_string(),
+            graph => term_id(graph),
+        };
+        if name != "@default" {
+            graph_map
+                .get_mut("@default")
+                .unwrap()
+                .entry(name.clone())
+                .or_insert_with(|| id_node(&name));
+        }
+        let graph = graph_map.entry(name.clone()).or_default();
+        let subject = term_id(&quad.subject);
+        let predicate = term_id(&quad.predicate);
+        graph
+            .entry(subject.clone())
+            .or_insert_with(|| id_node(&subject));
+        let object = match &quad.object {
+            Term::Iri(_) | Term::BlankNode(_) => {
+                let object = term_id(&quad.object);
+                graph
+                    .entry(object.clone())
+                    .or_insert_with(|| id_node(&object));
+                Some(object)
+            }
+            _ => None,
+        };
+        let node = graph.get_mut(&subject).unwrap();
+        if predicate == format!("{}type", RDF) {
+            if let Some(object) = &object {
+                add_unique(node, "@type", Value::String(object.clone()));
+                continue;
+            }
+        }
+        let value = literal_to_object(&quad.object)?;
+        add_unique(node, &predicate, value.clone());
+        let object = match object {
+            Some(object) => object,
+            None => continue,
+        };
+        let usage = Usage {
+            node: subject,
+            property: predicate.clone(),
+            index: node[&predicate]
+                .as_array()
+                .and_then(|items| items.iter().position(|v| *v == value))
+                .unwrap_or_default(),
+        };
+        if object == rdf_nil {
+            nil_usages.entry(name).or_default().push(usage);
+        } else if let Some(once) = referenced_once.get_mut(&object) {
+            *once = None;
+        } else if is_blank_node(&object) {
+            referenced_once.insert(object, Some(usage));
+        }
+    }
+
+    for (name, graph) in graph_map.iter_mut() {
+        for usage in nil_usages.remove(name).unwrap_or_default() {
+            let mut usage = usage;
+            let mut list = Vec::new();
+            let mut list_nodes = Vec::new();
+            while usage.property == rdf_rest {
+                let node = &graph[&usage.node];
+                let once = match referenced_once.get(&usage.node) {
+                    Some(Some(once)) => once,
+                    _ => break,
+                };
+                let single = |p: &str| matches!(node.get(p), Some(Value::Array(v)) if v.len() == 1);
+                let list_type = node
+                    .get("@type")
+                    .is_none_or(|t| as_array(t) == vec![rdf_list.clone()]);
+                let only_list_entries = node
+                    .keys()
+                    .all(|k| k == "@id" || k == "@type" || *k == rdf_first || *k == rdf_rest);
+                if !(single(&rdf_first) && single(&rdf_rest) && list_type && only_list_entries) {
+                    break;
+                }
+                list.push(node[&rdf_first][0].clone());
+                list_nodes.push(usage.node.clone());
+                usage = once.clone();
+                if !is_blank_node(&usage.node) {
+                    break;
+                }
+            }
+            list.reverse();
+            if let Some(Value::Array(items)) = graph
+                .get_mut(&usage.node)
+                .and_then(|n| n.get_mut(&usage.property))
+            {
+                if let Some(head) = items.get_mut(usage.index) {
+                    *head = json!({ "@list": list });
+                }
+            }
+            for node in list_nodes {
+                graph.remove(&node);
+            }
+        }
+    }
+
+    let mut result = Vec::new();
+    let default_graph = graph_map.remove("@default").unwrap_or_default();
+    for (subject, mut node) in default_graph {
+        if let Some(graph) = graph_map.get(&subject) {
+            let nodes = graph
+                .values()
+                .filter(|n| !(n.len() == 1 && n.contains_key("@id")))
+                .map(|n| Value::Object(n.clone()))
+                .collect();
+            node.insert("@graph".to_string(), Value::Array(nodes));
+        }
+        if node.len() == 1 && node.contains_key("@id") {
+            continue;
+        }
+        result.push(Value::Object(node));
+    }
+    Ok(Value::Array(result))
+}
+
+/// Where a node is used as the value of a property
+#[derive(Clone, Debug)]
+struct Usage {
+    node: String,
+    property: String,
+    index: usize,
+}
+
+fn term_id(term: &Term) -> String {
+    match term {
+        Term::Iri(iri) => iri.clone(),
+        Term::BlankNode(b) => format!("_:{}", b),
+        _ => String::new(),
+    }
+}
+
+fn id_node(id: &str) -> Map<String, Value> {
+    let mut node = Map::new();
+    node.insert("@id".to_string(), Value::String(id.to_string()));
+    node
+}
+
+fn literal_to_object(term: &Term) -> Result<Value, BBSError> {
+    let (value, datatype, language) = match term {
+        Term::Literal {
+            value,
+            datatype,
+            language,
+        } => (value, datatype, language),
+        term => return Ok(json!({ "@id": term_id(term) })),
+    };
+    let mut object = Map::new();
+    object.insert("@value".to_string(), Value::String(value.clone()));
+    if let Some(language) = language {
+        object.insert("@language".to_string(), Value::String(language.clone()));
+    } else if let Some(datatype) = datatype {
+        if *datatype == format!("{}JSON", RDF) {
+            let json = serde_json::from_str(value).map_err(|_| error("invalid JSON literal"))?;
+            object.insert("@value".to_string(), json);
+            object.insert("@type".to_string(), Value::String("@json".to_string()));
+        } else if *datatype != format!("{}string", XSD) {
+            object.insert("@type".to_string(), Value::String(datatype.clone()));
+        }
+    }
+    Ok(Value::Object(object))
+}
@@ -0,0 +1,386 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! JSON Web Proof (JWP) issued and presented forms.
+//!
+//! The issuer signs the encoded issuer protected header as the first message followed
+//! by the payloads, and the proof of the issued form is the signature. The holder
+//! reveals the header and the disclosed payloads with a proof of knowledge of the
+//! signature whose nonce is the encoded presentation protected header. Both forms have
+//! the compact serialization, with the payloads separated by `~`, an undisclosed
+//! payload as an empty string and a zero length payload as `_`, and the JSON
+//! serialization, with an undisclosed payload as `null`.
+//!
+//! The `alg` of the headers is the private `mattr.global/bbs-plus`, a collision
+//! resistant name under the MATTR domain, rather than an algorithm of the JWP
+//! registry. The registered `BBS` algorithm uses the IETF BBS ciphersuite, which
+//! these BBS+ signatures are not, and `BBS+` is not registered.
+
+use crate::bls12381::BlsKeyPair;
+use crate::linked_proof;
+use crate::numeric_message::message_scalar;
+use crate::utils::{general_error, set_panic_hook, verify_response};
+use crate::PoKOfSignatureProofWrapper;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use bbs::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    convert::{TryFrom, TryInto},
+};
+use wasm_bindgen::prelude::*;
+
+const JWP_ALG: &str = "mattr.global/bbs-plus";
+const ZERO_LENGTH_PAYLOAD: &str = "_";
+
+wasm_impl!(
+    JwpIssueRequest,
+    keyPair: BlsKeyPair,
+    header: Map<String, Value>,
+    payloads: Vec<Vec<u8>>
+);
+
+wasm_impl!(
+    JwpPresentRequest,
+    publicKey: DeterministicPublicKey,
+    jwp: String,
+    header: Map<String, Value>,
+    disclosed: Vec<usize>
+);
+
+wasm_impl!(JwpResponse, compact: String, json: String);
+
+wasm_impl!(
+    JwpVerifyRequest,
+    publicKey: DeterministicPublicKey,
+    jwp: String
+);
+
+wasm_impl!(JwpParseRequest, jwp: String);
+
+wasm_impl!(
+    JwpParseResponse,
+    presentationHeader: Option<String>,
+    issuerHeader: String,
+    payloads: Vec<Option<Vec<u8>>>,
+    proof: Vec<u8>
+);
+
+/// The JSON serialization of a JWP
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+struct JwpJson {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    presentation: Option<String>,
+    issuer: String,
+    payloads: Vec<Option<String>>,
+    proof: Vec<String>,
+}
+
+/// A JWP with its headers still encoded, as they are signed
+struct Jwp {
+    presentation: Option<String>,
+    issuer: String,
+    payloads: Vec<Option<Vec<u8>>>,
+    proof: Vec<u8>,
+}
+
+/// Signs the payloads and the issuer protected header, producing a JWP in the issued form
+#[wasm_bindgen(js_name = createIssuedJwp)]
+pub async fn create_issued_jwp(request: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let request: JwpIssueRequest = request.try_into()?;
+    let secret_key = match request.keyPair.secretKey {
+        Some(sk) => sk,
+        None => return Err(JsValue::from("Failed to sign")),
+    };
+    let public_key = match request.keyPair.publicKey {
+        Some(pk) => map_err!(DeterministicPublicKey::try_from(pk.as_slice()))?,
+        None => return Err(JsValue::from("Failed to convert key")),
+    };
+    if request.payloads.is_empty() {
+        return Err(JsValue::from("At least one payload is required"));
+    }
+    let issuer = encode_header(request.header);
+    let messages = messages(&issuer, request.payloads.iter());
+    let pk = map_err!(public_key.to_public_key(messages.len()))?;
+    let signature = map_err!(Signature::new(messages.as_slice(), &secret_key, &pk))?;
+    Ok(Jwp {
+        presentation: None,
+        issuer,
+        payloads: request.payloads.into_iter().map(Some).collect(),
+        proof: signature.to_bytes_compressed_form().to_vec(),
+    }
+    .to_response())
+}
+
+/// Verifies a JWP in the issued form
+#[wasm_bindgen(js_name = verifyIssuedJwp)]
+pub async fn verify_issued_jwp(request: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    Ok(verify_response(request, check_issued_jwp))
+}
+
+/// Derives a JWP in the presented form that discloses the payloads at the `disclosed`
+/// indices of a JWP in the issued form, bound to the presentation protected header
+#[wasm_bindgen(js_name = createPresentedJwp)]
+pub async fn create_presented_jwp(request: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let request: JwpPresentRequest = request.try_into()?;
+    let issued = map_err!(Jwp::parse(&request.jwp))?;
+    if issued.presentation.is_some() {
+        return Err(JsValue::from("The JWP is not in the issued form"));
+    }
+    let payloads: Vec<Vec<u8>> = match issued.payloads.into_iter().collect() {
+        Some(payloads) => payloads,
+        None => return Err(JsValue::from("The JWP is not in the issued form")),
+    };
+    if request.disclosed.iter().any(|i| *i >= payloads.len()) {
+        return Err(JsValue::from("disclosed value is out of bounds"));
+    }
+    let signature = map_err!(Signature::try_from(issued.proof.as_slice()))?;
+    let presentation = encode_header(request.header);
+
+    let messages = messages(&issued.issuer, payloads.iter());
+    let revealed: BTreeSet<usize> = std::iter::once(0)
+        .chain(request.disclosed.iter().map(|i| i + 1))
+        .collect();
+    let pk = map_err!(request.publicKey.to_public_key(messages.len()))?;
+    let pok = map_err!(linked_proof::init_pok(
+        &signature,
+        &pk,
+        &messages,
+        &revealed,
+        &BTreeMap::new(),
+    ))?;
+    let challenge = linked_proof::challenge(pok.to_bytes(), &[], presentation.as_bytes());
+    let proof = map_err!(pok.gen_proof(&challenge))?;
+
+    Ok(Jwp {
+        issuer: issued.issuer,
+        payloads: payloads
+            .into_iter()
+            .enumerate()
+            .map(|(i, p)| {
+                if revealed.contains(&(i + 1)) {
+                    Some(p)
+                } else {
+                    None
+                }
+            })
+            .collect(),
+        proof: PoKOfSignatureProofWrapper::new(messages.len(), &revealed, proof).to_bytes(),
+        presentation: Some(presentation),
+    }
+    .to_response())
+}
+
+/// Verifies a JWP in the presented form
+#[wasm_bindgen(js_name = verifyPresentedJwp)]
+pub async fn verify_presented_jwp(request: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    Ok(verify_response(request, check_presented_jwp))
+}
+
+/// Parses a JWP in either form and serialization without verifying it, decoding the
+/// headers to JSON text
+#[wasm_bindgen(js_name = parseJwp)]
+pub async fn parse_jwp(request: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let request: JwpParseRequest = request.try_into()?;
+    let jwp = map_err!(Jwp::parse(&request.jwp))?;
+    let presentation_header = match &jwp.presentation {
+        Some(header) => Some(map_err!(decode_header(header))?.to_string()),
+        None => None,
+    };
+    let issuer_header = map_err!(decode_header(&jwp.issuer))?.to_string();
+    Ok(serde_wasm_bindgen::to_value(&JwpParseResponse {
+        presentationHeader: presentation_header,
+        issuerHeader: issuer_header,
+        payloads: jwp.payloads,
+        proof: jwp.proof,
+    })
+    .unwrap())
+}
+
+fn check_issued_jwp(request: JwpVerifyRequest) -> Result<bool, BBSError> {
+    let jwp = Jwp::parse(&request.jwp)?;
+    if jwp.presentation.is_some() {
+        return Err(general_error("The JWP is not in the issued form"));
+    }
+    decode_header(&jwp.issuer)?;
+    let payloads: Vec<Vec<u8>> = match jwp.payloads.into_iter().collect() {
+        Some(payloads) => payloads,
+        None => return Err(general_error("The JWP is not in the issued form")),
+    };
+    let signature = Signature::try_from(jwp.proof.as_slice())?;
+    let messages = messages(&jwp.issuer, payloads.iter());
+    let pk = request.publicKey.to_public_key(messages.len())?;
+    signature.verify(messages.as_slice(), &pk)
+}
+
+fn check_presented_jwp(request: JwpVerifyRequest) -> Result<bool, BBSError> {
+    let jwp = Jwp::parse(&request.jwp)?;
+    let presentation = match &jwp.presentation {
+        Some(presentation) => presentation,
+        None => return Err(general_error("The JWP is not in the presented form")),
+    };
+    decode_header(presentation)?;
+    decode_header(&jwp.issuer)?;
+    if jwp.proof.len() < 2 {
+        return Err(general_error("Invalid JWP proof"));
+    }
+    let proof = PoKOfSignatureProofWrapper::try_from(jwp.proof.as_slice())
+        .map_err(|_| general_error("Invalid JWP proof"))?;
+    let message_count = u16::from_be_bytes([proof.bit_vector[0], proof.bit_vector[1]]) as usize;
+    if message_count != jwp.payloads.len() + 1 {
+        return Ok(false);
+    }
+
+    let mut revealed_messages = BTreeMap::new();
+    revealed_messages.insert(0, message_scalar(jwp.issuer.as_bytes()));
+    for (i, payload) in jwp.payloads.iter().enumerate() {
+        if let Some(payload) = payload {
+            revealed_messages.insert(i + 1, message_scalar(payload));
+        }
+    }
+    let (revealed, proof) = proof.unwrap();
+    if !revealed.iter().eq(revealed_messages.keys()) {
+        return Ok(false);
+    }
+    let pk = request.publicKey.to_public_key(message_count)?;
+    let challenge = linked_proof::challenge(
+        proof.get_bytes_for_challenge(revealed, &pk),
+        &[],
+        presentation.as_bytes(),
+    );
+    linked_proof::verify_pok(&proof, &pk, &revealed_messages, &challenge)
+}
+
+impl Jwp {
+    /// Parse the compact or, when it is a JSON object, the JSON serialization
+    fn parse(jwp: &str) -> Result<Self, BBSError> {
+        let jwp = jwp.trim();
+        if jwp.starts_with('{') {
+            let json: JwpJson = serde_json::from_str(jwp)
+                .map_err(|_| general_error("Invalid JWP JSON serialization"))?;
+            if json.proof.len() != 1 {
+                return Err(general_error("Expected a single JWP proof"));
+            }
+            return Ok(Self {
+                presentation: json.presentation,
+                issuer: json.issuer,
+                payloads: json
+                    .payloads
+                    .iter()
+                    .map(|p| p.as_deref().map(decode).transpose())
+                    .collect::<Result<_, _>>()?,
+                proof: decode(&json.proof[0])?,
+            });
+        }
+
+        let parts: Vec<&str> = jwp.split('.').collect();
+        let (presentation, issuer, payloads, proof) = match parts.as_slice() {
+            [issuer, payloads, proof] => (None, issuer, payloads, proof),
+            [presentation, issuer, payloads, proof] => {
+                (Some(presentation.to_string()), issuer, payloads, proof)
+            }
+            _ => return Err(general_error("Invalid JWP compact serialization")),
+        };
+        Ok(Self {
+            presentation,
+            issuer: issuer.to_string(),
+            payloads: payloads
+                .split('~')
+                .map(|p| match p {
+                    "" => Ok(None),
+                    ZERO_LENGTH_PAYLOAD => Ok(Some(Vec::new())),
+                    p => decode(p).map(Some),
+                })
+                .collect::<Result<_, _>>()?,
+            proof: decode(proof)?,
+        })
+    }
+
+    fn to_compact(&self) -> String {
+        let payloads: Vec<String> = self
+            .payloads
+            .iter()
+            .map(|p| match p {
+                None => String::new(),
+                Some(p) if p.is_empty() => ZERO_LENGTH_PAYLOAD.to_string(),
+                Some(p) => URL_SAFE_NO_PAD.encode(p),
+            })
+            .collect();
+        let mut parts: Vec<String> = self.presentation.iter().cloned().collect();
+        parts.push(self.issuer.clone());
+        parts.push(payloads.join("~"));
+        parts.push(URL_SAFE_NO_PAD.encode(&self.proof));
+        parts.join(".")
+    }
+
+    fn to_json(&self) -> String {
+        serde_json::to_string(&JwpJson {
+            presentation: self.presentation.clone(),
+            issuer: self.issuer.clone(),
+            payloads: self
+                .payloads
+                .iter()
+                .map(|p| p.as_ref().map(|p| URL_SAFE_NO_PAD.encode(p)))
+                .collect(),
+            proof: vec![URL_SAFE_NO_PAD.encode(&self.proof)],
+        })
+        .unwrap()
+    }
+
+    fn to_response(&self) -> JsValue {
+        serde_wasm_bindgen::to_value(&JwpResponse {
+            compact: self.to_compact(),
+            json: self.to_json(),
+        })
+        .unwrap()
+    }
+}
+
+/// The encoded header is signed as the first message, followed by the payloads
+fn messages<'a, I: Iterator<Item = &'a Vec<u8>>>(
+    header: &str,
+    payloads: I,
+) -> Vec<SignatureMessage> {
+    std::iter::once(message_scalar(header.as_bytes()))
+        .chain(payloads.map(message_scalar))
+        .collect()
+}
+
+fn encode_header(mut header: Map<String, Value>) -> String {
+    header.insert("alg".to_string(), Value::String(JWP_ALG.to_string()));
+    URL_SAFE_NO_PAD.encode(Value::Object(header).to_string())
+}
+
+/// Decode a protected header, which must be a JSON object with the `JWP_ALG` algorithm
+fn decode_header(header: &str) -> Result<Value, BBSError> {
+    let header: Value = serde_json::from_slice(&decode(header)?)
+        .map_err(|_| general_error("The JWP header is not JSON"))?;
+    if header.get("alg").and_then(Value::as_str) != Some(JWP_ALG) {
+        return Err(general_error("Unsupported JWP algorithm"));
+    }
+    Ok(header)
+}
+
+fn decode(value: &str) -> Result<Vec<u8>, BBSError> {
+    URL_SAFE_NO_PAD
+        .decode(value)
+        .map_err(|_| general_error("The value is not base64url encoded"))
+}
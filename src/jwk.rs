@@ -0,0 +1,184 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! JSON Web Key (RFC 7517) encodings of BLS 12-381 key pairs.
+//!
+//! The curves are `BLS12381_G1` and `BLS12381_G2`. With the `OKP` key type `x` is the
+//! compressed public key, and with the `EC` key type `x` and `y` are the coordinates of
+//! the uncompressed public key, each coordinate of a G2 point being the `c1 || c0`
+//! encoding of an Fp2 element. The secret key is `d` in both. Values are base64url
+//! encoded without padding.
+
+use crate::bls12381::BlsKeyPair;
+use crate::cose::check_key_pair;
+use crate::utils::{general_error, set_panic_hook};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use bbs::prelude::*;
+use pairing_plus::{
+    bls12_381::{Bls12, Fr, G1, G2},
+    serdes::SerDes,
+    CurveProjective,
+};
+use serde::{Deserialize, Serialize};
+use std::convert::TryInto;
+use wasm_bindgen::prelude::*;
+
+const KTY_OKP: &str = "OKP";
+const KTY_EC: &str = "EC";
+const CRV_BLS12381_G1: &str = "BLS12381_G1";
+const CRV_BLS12381_G2: &str = "BLS12381_G2";
+
+wasm_impl!(JwkExportRequest, keyPair: BlsKeyPair, kty: Option<String>);
+
+/// A BLS 12-381 JSON Web Key. Other members such as `kid` are ignored on import.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Jwk {
+    pub kty: String,
+    pub crv: String,
+    pub x: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub y: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub d: Option<String>,
+}
+
+try_from_impl!(Jwk);
+
+impl Jwk {
+    /// Encode a key pair with the `OKP` or `EC` key type, including the secret key when
+    /// the key pair has one
+    pub fn from_key_pair(key_pair: &BlsKeyPair, kty: &str) -> Result<Self, BBSError> {
+        let public_key = match &key_pair.publicKey {
+            Some(pk) => pk,
+            None => return Err(general_error("A JWK requires the public key")),
+        };
+        let (crv, coordinates) = match public_key.len() {
+            G1_COMPRESSED_SIZE => (CRV_BLS12381_G1, coordinates::<G1>(public_key, kty)?),
+            G2_COMPRESSED_SIZE => (CRV_BLS12381_G2, coordinates::<G2>(public_key, kty)?),
+            _ => return Err(general_error("Invalid BLS 12-381 public key")),
+        };
+        let (x, y) = coordinates;
+        Ok(Jwk {
+            kty: kty.to_string(),
+            crv: crv.to_string(),
+            x: URL_SAFE_NO_PAD.encode(x),
+            y: y.map(|y| URL_SAFE_NO_PAD.encode(y)),
+            d: key_pair
+                .secretKey
+                .as_ref()
+                .map(|sk| URL_SAFE_NO_PAD.encode(sk.to_bytes_compressed_form())),
+        })
+    }
+
+    /// Decode the key pair, checking the lengths, that the public key is a point of
+    /// the group and that the secret key belongs to it
+    pub fn to_key_pair(&self) -> Result<BlsKeyPair, BBSError> {
+        let x = decode(&self.x)?;
+        let y = match &self.y {
+            Some(y) => Some(decode(y)?),
+            None => None,
+        };
+        let d = match &self.d {
+            Some(d) => Some(decode(d)?),
+            None => None,
+        };
+        let (public_key, secret_key) = match self.crv.as_str() {
+            CRV_BLS12381_G1 => {
+                let public_key = public_key::<G1>(&self.kty, x, y, G1_COMPRESSED_SIZE)?;
+                let sk = check_key_pair::<G1>(&public_key, d.as_deref(), G1_COMPRESSED_SIZE)?;
+                (public_key, sk)
+            }
+            CRV_BLS12381_G2 => {
+                let public_key = public_key::<G2>(&self.kty, x, y, G2_COMPRESSED_SIZE)?;
+                let sk = check_key_pair::<G2>(&public_key, d.as_deref(), G2_COMPRESSED_SIZE)?;
+                (public_key, sk)
+            }
+            _ => return Err(general_error("Unsupported JWK curve")),
+        };
+        Ok(BlsKeyPair {
+            publicKey: Some(public_key),
+            secretKey: secret_key,
+        })
+    }
+}
+
+/// Exports a BLS 12-381 key pair as a JWK, with the `OKP` key type unless `kty` is `EC`
+#[wasm_bindgen(js_name = exportJwk)]
+pub async fn export_jwk(request: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let request: JwkExportRequest = request.try_into()?;
+    let kty = request.kty.as_deref().unwrap_or(KTY_OKP);
+    let jwk = map_err!(Jwk::from_key_pair(&request.keyPair, kty))?;
+    Ok(serde_wasm_bindgen::to_value(&jwk).unwrap())
+}
+
+/// Imports a BLS 12-381 key pair from a JWK
+#[wasm_bindgen(js_name = importJwk)]
+pub async fn import_jwk(jwk: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let jwk: Jwk = jwk.try_into()?;
+    let key_pair = map_err!(jwk.to_key_pair())?;
+    Ok(serde_wasm_bindgen::to_value(&key_pair).unwrap())
+}
+
+/// The `x` and `y` members for the key type
+fn coordinates<G: CurveProjective<Engine = Bls12, Scalar = Fr> + SerDes>(
+    public_key: &[u8],
+    kty: &str,
+) -> Result<(Vec<u8>, Option<Vec<u8>>), BBSError> {
+    match kty {
+        KTY_OKP => Ok((public_key.to_vec(), None)),
+        KTY_EC => {
+            let point = G::deserialize(&mut &public_key[..], true)
+                .map_err(|_| general_error("Invalid BLS 12-381 public key"))?;
+            let mut uncompressed = Vec::new();
+            point.serialize(&mut uncompressed, false).unwrap();
+            let y = uncompressed.split_off(public_key.len());
+            Ok((uncompressed, Some(y)))
+        }
+        _ => Err(general_error("Unsupported JWK key type")),
+    }
+}
+
+/// The compressed public key from the `x` and `y` members
+fn public_key<G: CurveProjective<Engine = Bls12, Scalar = Fr> + SerDes>(
+    kty: &str,
+    x: Vec<u8>,
+    y: Option<Vec<u8>>,
+    size: usize,
+) -> Result<Vec<u8>, BBSError> {
+    match (kty, y) {
+        (KTY_OKP, None) => Ok(x),
+        (KTY_EC, Some(y)) => {
+            if x.len() != size || y.len() != size {
+                return Err(general_error("Invalid BLS 12-381 public key"));
+            }
+            let mut uncompressed = x;
+            uncompressed.extend_from_slice(&y);
+            let point = G::deserialize(&mut uncompressed.as_slice(), false)
+                .map_err(|_| general_error("Invalid BLS 12-381 public key"))?;
+            let mut compressed = Vec::new();
+            point.serialize(&mut compressed, true).unwrap();
+            Ok(compressed)
+        }
+        (KTY_OKP, Some(_)) | (KTY_EC, None) => Err(general_error("Invalid JWK coordinates")),
+        _ => Err(general_error("Unsupported JWK key type")),
+    }
+}
+
+fn decode(value: &str) -> Result<Vec<u8>, BBSError> {
+    URL_SAFE_NO_PAD
+        .decode(value)
+        .map_err(|_| general_error("The value is not base64url encoded"))
+}
@@ -0,0 +1,228 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Opaque handles to BLS 12-381 key pairs kept in WASM memory.
+//!
+//! A `KeyHandle` is generated or imported inside the module and only its public key can
+//! be read back, so signing with it does not copy the secret key into the JavaScript
+//! heap. The secret key is a `SecretKey`, which zeroizes itself when dropped, so it is
+//! cleared from WASM memory when the handle is freed.
+
+use crate::bbs_plus::blind_sign;
+use crate::bls12381::{key_pair_from_seed, BlsKeyPair};
+use crate::cose::check_key_pair;
+use crate::key_possession::prove_key_possession;
+use crate::keystore::{Keystore, KeystoreImportRequest, DEFAULT_COST, KDF_PBKDF2};
+use crate::numeric_message::message_scalar;
+use crate::utils::{general_error, set_panic_hook};
+
+use bbs::prelude::*;
+use pairing_plus::{
+    bls12_381::{Bls12, Fr, G1, G2},
+    serdes::SerDes,
+    CurveProjective,
+};
+use rand::{thread_rng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::convert::{TryFrom, TryInto};
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroizing;
+
+const SEED_SIZE: usize = 32;
+
+wasm_impl!(KeyHandleSignRequest, messages: Vec<Vec<u8>>);
+
+wasm_impl!(
+    KeyHandleBlindSignRequest,
+    commitment: Commitment,
+    messageCount: usize,
+    messages: Vec<Vec<u8>>,
+    known: Vec<usize>
+);
+
+wasm_impl!(
+    KeyHandleExportRequest,
+    password: String,
+    kdf: Option<String>,
+    cost: Option<u32>,
+    description: Option<String>
+);
+
+/// A BLS 12-381 key pair whose secret key stays in WASM memory
+#[wasm_bindgen]
+pub struct KeyHandle {
+    public_key: Vec<u8>,
+    secret_key: SecretKey,
+}
+
+#[wasm_bindgen]
+impl KeyHandle {
+    /// Generates a BLS 12-381 G2 key pair, from the seed when one is given
+    #[wasm_bindgen(js_name = generateG2)]
+    pub fn generate_g2(seed: Option<Vec<u8>>) -> KeyHandle {
+        set_panic_hook();
+        Self::generate::<G2>(seed)
+    }
+
+    /// Generates a BLS 12-381 G1 key pair, from the seed when one is given
+    #[wasm_bindgen(js_name = generateG1)]
+    pub fn generate_g1(seed: Option<Vec<u8>>) -> KeyHandle {
+        set_panic_hook();
+        Self::generate::<G1>(seed)
+    }
+
+    /// Imports a BLS 12-381 key pair, deriving the G2 public key when only the secret
+    /// key is given
+    #[wasm_bindgen(js_name = fromKeyPair)]
+    pub fn from_key_pair(key_pair: JsValue) -> Result<KeyHandle, JsValue> {
+        set_panic_hook();
+        let key_pair: BlsKeyPair = key_pair.try_into()?;
+        map_err!(Self::try_from_key_pair(key_pair))
+    }
+
+    /// Imports a BLS 12-381 key pair from an EIP-2335 keystore
+    #[wasm_bindgen(js_name = fromEncryptedKey)]
+    pub fn from_encrypted_key(request: JsValue) -> Result<KeyHandle, JsValue> {
+        set_panic_hook();
+        let request: KeystoreImportRequest = request.try_into()?;
+        let keystore: Keystore = map_err!(serde_json::from_str(&request.keystore))?;
+        let key_pair = map_err!(keystore.decrypt(&request.password))?;
+        map_err!(Self::try_from_key_pair(key_pair))
+    }
+
+    /// The compressed G1 or G2 public key
+    #[wasm_bindgen(getter, js_name = publicKey)]
+    pub fn public_key(&self) -> Vec<u8> {
+        self.public_key.clone()
+    }
+
+    /// Signs a set of messages with the BBS key of the G2 public key for the number of
+    /// messages, as `blsSign` does
+    pub fn sign(&self, request: JsValue) -> Result<JsValue, JsValue> {
+        set_panic_hook();
+        let request: KeyHandleSignRequest = request.try_into()?;
+        let pk = self.bbs_public_key(request.messages.len())?;
+        let messages: Vec<SignatureMessage> = request.messages.iter().map(message_scalar).collect();
+        let signature = map_err!(Signature::new(messages.as_slice(), &self.secret_key, &pk))?;
+        Ok(serde_wasm_bindgen::to_value(&signature).unwrap())
+    }
+
+    /// Signs the known messages and a blind signature commitment, as `blsBlindSign` does
+    #[wasm_bindgen(js_name = blindSign)]
+    pub fn blind_sign(&self, request: JsValue) -> Result<JsValue, JsValue> {
+        set_panic_hook();
+        let request: KeyHandleBlindSignRequest = request.try_into()?;
+        let pk = self.bbs_public_key(request.messageCount)?;
+        blind_sign(
+            &request.commitment,
+            &pk,
+            &self.secret_key,
+            &request.messages,
+            &request.known,
+        )
+    }
+
+    /// Creates a proof of possession of the secret key of a G2 key, as
+    /// `createKeyPossessionProof` does
+    #[wasm_bindgen(js_name = createKeyPossessionProof)]
+    pub fn create_key_possession_proof(&self) -> Result<JsValue, JsValue> {
+        set_panic_hook();
+        if self.public_key.len() != G2_COMPRESSED_SIZE {
+            return Err(JsValue::from_str(
+                "A key possession proof requires a BLS 12-381 G2 key",
+            ));
+        }
+        let dpk = map_err!(DeterministicPublicKey::try_from(self.public_key.as_slice()))?;
+        let proof = map_err!(prove_key_possession(&dpk, &self.secret_key))?;
+        Ok(js_sys::Uint8Array::from(proof.as_slice()).into())
+    }
+
+    /// Encrypts the key pair with a password as an EIP-2335 keystore, as
+    /// `exportEncryptedKey` does
+    #[wasm_bindgen(js_name = exportEncryptedKey)]
+    pub fn export_encrypted_key(&self, request: JsValue) -> Result<JsValue, JsValue> {
+        set_panic_hook();
+        let request: KeyHandleExportRequest = request.try_into()?;
+        let key_pair = BlsKeyPair {
+            publicKey: Some(self.public_key.clone()),
+            secretKey: Some(self.secret_key.clone()),
+        };
+        let mut keystore = map_err!(Keystore::encrypt(
+            &key_pair,
+            &request.password,
+            request.kdf.as_deref().unwrap_or(KDF_PBKDF2),
+            request.cost.unwrap_or(DEFAULT_COST),
+        ))?;
+        keystore.description = request.description.unwrap_or_default();
+        Ok(JsValue::from_str(
+            &serde_json::to_string(&keystore).unwrap(),
+        ))
+    }
+}
+
+impl KeyHandle {
+    fn generate<G: CurveProjective<Engine = Bls12, Scalar = Fr> + SerDes>(
+        seed: Option<Vec<u8>>,
+    ) -> Self {
+        let seed = Zeroizing::new(seed.unwrap_or_else(|| {
+            let mut seed = vec![0u8; SEED_SIZE];
+            thread_rng().fill_bytes(&mut seed);
+            seed
+        }));
+        let (public_key, secret_key) = key_pair_from_seed::<G>(&seed);
+        Self {
+            public_key,
+            secret_key,
+        }
+    }
+
+    fn try_from_key_pair(key_pair: BlsKeyPair) -> Result<Self, BBSError> {
+        let secret_key = match key_pair.secretKey {
+            Some(sk) => sk,
+            None => return Err(general_error("A key handle requires the secret key")),
+        };
+        let public_key = match key_pair.publicKey {
+            Some(pk) => pk,
+            None => {
+                let (dpk, _) = DeterministicPublicKey::new(Some(KeyGenOption::FromSecretKey(
+                    secret_key.clone(),
+                )));
+                dpk.to_bytes_compressed_form().to_vec()
+            }
+        };
+        let sk_bytes = Zeroizing::new(secret_key.to_bytes_compressed_form());
+        let secret_key = match public_key.len() {
+            G1_COMPRESSED_SIZE => {
+                check_key_pair::<G1>(&public_key, Some(&sk_bytes[..]), G1_COMPRESSED_SIZE)?
+            }
+            G2_COMPRESSED_SIZE => {
+                check_key_pair::<G2>(&public_key, Some(&sk_bytes[..]), G2_COMPRESSED_SIZE)?
+            }
+            _ => return Err(general_error("Invalid BLS 12-381 public key")),
+        };
+        Ok(Self {
+            public_key,
+            secret_key: secret_key.unwrap(),
+        })
+    }
+
+    fn bbs_public_key(&self, message_count: usize) -> Result<PublicKey, JsValue> {
+        if self.public_key.len() != G2_COMPRESSED_SIZE {
+            return Err(JsValue::from_str(
+                "BBS signatures require a BLS 12-381 G2 key",
+            ));
+        }
+        let dpk = map_err!(DeterministicPublicKey::try_from(self.public_key.as_slice()))?;
+        map_err!(dpk.to_public_key(message_count))
+    }
+}
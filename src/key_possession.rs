@@ -0,0 +1,168 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Proofs of possession of the secret key of a BLS 12-381 G2 public key.
+//!
+//! This is `PopProve` and `PopVerify` of the BLS signature specification
+//! (draft-irtf-cfrg-bls-signature) with public keys in G2 and proofs in G1. The proof is
+//! `sk * H(pk)`, where `H` hashes the compressed public key to G1 with the POP domain
+//! separation tag, and it verifies when `e(proof, P2) = e(H(pk), pk)`. A registry that
+//! only accepts keys with a valid proof rules out rogue keys, which are chosen as a
+//! function of other keys without knowing their secret key, and keys substituted from
+//! another issuer.
+//!
+//! The hash to G1 is the one of `pairing-plus`, which follows an earlier draft of
+//! hash-to-curve than RFC 9380 (its `sgn0` compares against `(p - 1) / 2` rather than
+//! taking the parity), so these proofs verify with this library but not with
+//! implementations of the final ciphersuite.
+
+use crate::bls12381::BlsKeyPair;
+use crate::multikey::deserialize_public_key;
+use crate::utils::{
+    general_error, pairing_product_is_one, secret_key_to_fr, set_panic_hook, verify_response,
+};
+
+use bbs::prelude::*;
+use pairing_plus::{
+    bls12_381::{G1, G2},
+    hash_to_curve::HashToCurve,
+    hash_to_field::ExpandMsgXmd,
+    serdes::SerDes,
+    CurveProjective,
+};
+use serde::{Deserialize, Serialize};
+use std::convert::{TryFrom, TryInto};
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroize;
+
+/// The proof of possession tag of the `BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_POP_`
+/// ciphersuite
+const POP_DST: &[u8] = b"BLS_POP_BLS12381G1_XMD:SHA-256_SSWU_RO_POP_";
+
+wasm_impl!(KeyPossessionProofRequest, keyPair: BlsKeyPair);
+
+wasm_impl!(
+    KeyPossessionVerifyRequest,
+    #[serde(deserialize_with = "deserialize_public_key")]
+    publicKey: DeterministicPublicKey,
+    proof: Vec<u8>
+);
+
+/// Create the proof of possession of the secret key of a G2 public key
+pub fn prove_key_possession(
+    public_key: &DeterministicPublicKey,
+    secret_key: &SecretKey,
+) -> Result<Vec<u8>, BBSError> {
+    let pk = public_key_point(public_key)?;
+    let mut sk = secret_key_to_fr(secret_key)?;
+    let mut expected = G2::one();
+    expected.mul_assign(sk);
+    if expected != pk {
+        return Err(general_error(
+            "The secret key does not belong to the public key",
+        ));
+    }
+    let mut proof = hash_public_key(public_key);
+    proof.mul_assign(sk);
+    sk.zeroize();
+
+    let mut bytes = Vec::new();
+    proof.serialize(&mut bytes, true).unwrap();
+    Ok(bytes)
+}
+
+/// Verify the proof of possession of the secret key of a G2 public key
+pub fn verify_key_possession(
+    public_key: &DeterministicPublicKey,
+    proof: &[u8],
+) -> Result<bool, BBSError> {
+    let pk = public_key_point(public_key)?;
+    if proof.len() != G1_COMPRESSED_SIZE {
+        return Err(general_error("Invalid key possession proof"));
+    }
+    // Deserializing checks the proof is a point of the G1 subgroup
+    let proof = G1::deserialize(&mut &proof[..], true)
+        .map_err(|_| general_error("Invalid key possession proof"))?;
+    if proof.is_zero() {
+        return Ok(false);
+    }
+    let mut negated_generator = G2::one();
+    negated_generator.negate();
+    Ok(pairing_product_is_one(&[
+        (proof, negated_generator),
+        (hash_public_key(public_key), pk),
+    ]))
+}
+
+/// Creates a proof of possession of the secret key of a BLS 12-381 G2 key pair, to be
+/// given with the public key when it is registered
+#[wasm_bindgen(js_name = createKeyPossessionProof)]
+pub async fn create_key_possession_proof(request: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let request: KeyPossessionProofRequest = request.try_into()?;
+    let public_key = match &request.keyPair.publicKey {
+        Some(pk) if pk.len() == G2_COMPRESSED_SIZE => {
+            map_err!(DeterministicPublicKey::try_from(pk.as_slice()))?
+        }
+        Some(_) => {
+            return Err(JsValue::from_str(
+                "A key possession proof requires a BLS 12-381 G2 public key",
+            ))
+        }
+        None => {
+            return Err(JsValue::from_str(
+                "A key possession proof requires the public key",
+            ))
+        }
+    };
+    let secret_key = match &request.keyPair.secretKey {
+        Some(sk) => sk,
+        None => {
+            return Err(JsValue::from_str(
+                "A key possession proof requires the secret key",
+            ))
+        }
+    };
+    let proof = map_err!(prove_key_possession(&public_key, secret_key))?;
+    Ok(js_sys::Uint8Array::from(proof.as_slice()).into())
+}
+
+/// Verifies a proof of possession of the secret key of a BLS 12-381 G2 public key
+/// given as bytes, a multikey or a `did:key` identifier
+#[wasm_bindgen(js_name = verifyKeyPossessionProof)]
+pub async fn verify_key_possession_proof(request: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    Ok(verify_response(
+        request,
+        |request: KeyPossessionVerifyRequest| {
+            verify_key_possession(&request.publicKey, &request.proof)
+        },
+    ))
+}
+
+/// The public key as a G2 point, rejecting the identity which any proof would verify for
+fn public_key_point(public_key: &DeterministicPublicKey) -> Result<G2, BBSError> {
+    let pk = G2::deserialize(&mut &public_key.to_bytes_compressed_form()[..], true)
+        .map_err(|_| general_error("Invalid BLS 12-381 G2 public key"))?;
+    if pk.is_zero() {
+        return Err(general_error("Invalid BLS 12-381 G2 public key"));
+    }
+    Ok(pk)
+}
+
+fn hash_public_key(public_key: &DeterministicPublicKey) -> G1 {
+    <G1 as HashToCurve<ExpandMsgXmd<sha2::Sha256>>>::hash_to_curve(
+        public_key.to_bytes_compressed_form(),
+        POP_DST,
+    )
+}
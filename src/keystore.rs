@@ -0,0 +1,381 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Password encrypted BLS 12-381 key pairs in the EIP-2335 keystore format.
+//!
+//! The password derives a 32 byte key with PBKDF2-HMAC-SHA256 or scrypt. The first half
+//! of it encrypts the secret key with AES-128-CTR, and the checksum is the SHA-256 hash
+//! of the second half followed by the ciphertext, so a wrong password is detected before
+//! the secret key is used. `pubkey` is the compressed G1 or G2 public key. Passwords must
+//! be NFKD normalized by the caller, as the JavaScript wrappers do, and control
+//! characters are removed here as EIP-2335 specifies.
+
+use crate::bls12381::BlsKeyPair;
+use crate::cose::check_key_pair;
+use crate::utils::{general_error, set_panic_hook};
+
+use aes::Aes128;
+use bbs::prelude::*;
+use ctr::cipher::{KeyIvInit, StreamCipher};
+use hmac::Hmac;
+use pairing_plus::bls12_381::{G1, G2};
+use rand::{thread_rng, RngCore};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::convert::TryInto;
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroizing;
+
+const KEYSTORE_VERSION: u32 = 4;
+pub(crate) const KDF_PBKDF2: &str = "pbkdf2";
+const KDF_SCRYPT: &str = "scrypt";
+const PBKDF2_PRF: &str = "hmac-sha256";
+const CHECKSUM_SHA256: &str = "sha256";
+const CIPHER_AES_128_CTR: &str = "aes-128-ctr";
+const DERIVED_KEY_SIZE: usize = 32;
+const AES_128_KEY_SIZE: usize = 16;
+const AES_BLOCK_SIZE: usize = 16;
+const SALT_SIZE: usize = 32;
+/// The cost EIP-2335 uses for both functions, the PBKDF2 iterations or the scrypt `n`
+pub(crate) const DEFAULT_COST: u32 = 262_144;
+const SCRYPT_R: u32 = 8;
+const SCRYPT_P: u32 = 1;
+/// Bounds on the work of imported keystores
+const MAX_PBKDF2_ITERATIONS: u32 = 1 << 24;
+const MAX_SCRYPT_MEMORY: u64 = 1 << 28;
+const MAX_SCRYPT_P: u32 = 16;
+
+wasm_impl!(
+    KeystoreExportRequest,
+    keyPair: BlsKeyPair,
+    password: String,
+    kdf: Option<String>,
+    cost: Option<u32>,
+    description: Option<String>
+);
+
+wasm_impl!(KeystoreImportRequest, keystore: String, password: String);
+
+/// An EIP-2335 keystore
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Keystore {
+    pub crypto: KeystoreCrypto,
+    #[serde(default)]
+    pub description: String,
+    pub pubkey: String,
+    #[serde(default)]
+    pub path: String,
+    pub uuid: String,
+    pub version: u32,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct KeystoreCrypto {
+    pub kdf: KeystoreModule,
+    pub checksum: KeystoreModule,
+    pub cipher: KeystoreModule,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct KeystoreModule {
+    pub function: String,
+    pub params: Value,
+    pub message: String,
+}
+
+#[derive(Deserialize)]
+struct Pbkdf2Params {
+    dklen: usize,
+    c: u32,
+    prf: String,
+    salt: String,
+}
+
+#[derive(Deserialize)]
+struct ScryptParams {
+    dklen: usize,
+    n: u32,
+    r: u32,
+    p: u32,
+    salt: String,
+}
+
+#[derive(Deserialize)]
+struct CipherParams {
+    iv: String,
+}
+
+impl Keystore {
+    /// Encrypt the secret key of a key pair with the `pbkdf2` or `scrypt` function, where
+    /// `cost` is the PBKDF2 iterations or the scrypt `n`
+    pub fn encrypt(
+        key_pair: &BlsKeyPair,
+        password: &str,
+        kdf: &str,
+        cost: u32,
+    ) -> Result<Self, BBSError> {
+        let (public_key, secret_key) = match (&key_pair.publicKey, &key_pair.secretKey) {
+            (Some(pk), Some(sk)) => (pk, sk),
+            _ => {
+                return Err(general_error(
+                    "A keystore requires the public and secret keys",
+                ))
+            }
+        };
+        match public_key.len() {
+            G1_COMPRESSED_SIZE => check_key_pair::<G1>(
+                public_key,
+                Some(&secret_key.to_bytes_compressed_form()),
+                G1_COMPRESSED_SIZE,
+            )?,
+            G2_COMPRESSED_SIZE => check_key_pair::<G2>(
+                public_key,
+                Some(&secret_key.to_bytes_compressed_form()),
+                G2_COMPRESSED_SIZE,
+            )?,
+            _ => return Err(general_error("Invalid BLS 12-381 public key")),
+        };
+
+        let mut rng = thread_rng();
+        let mut salt = [0u8; SALT_SIZE];
+        rng.fill_bytes(&mut salt);
+        let mut iv = [0u8; AES_BLOCK_SIZE];
+        rng.fill_bytes(&mut iv);
+        let params = match kdf {
+            KDF_PBKDF2 => json!({
+                "dklen": DERIVED_KEY_SIZE,
+                "c": cost,
+                "prf": PBKDF2_PRF,
+                "salt": hex::encode(salt),
+            }),
+            KDF_SCRYPT => json!({
+                "dklen": DERIVED_KEY_SIZE,
+                "n": cost,
+                "r": SCRYPT_R,
+                "p": SCRYPT_P,
+                "salt": hex::encode(salt),
+            }),
+            _ => return Err(general_error("Unsupported keystore KDF")),
+        };
+        let kdf = KeystoreModule {
+            function: kdf.to_string(),
+            params,
+            message: String::new(),
+        };
+        let derived_key = derive_key(&kdf, password)?;
+
+        let mut ciphertext = Zeroizing::new(secret_key.to_bytes_compressed_form().to_vec());
+        aes_128_ctr(&derived_key, &iv, &mut ciphertext);
+        Ok(Keystore {
+            crypto: KeystoreCrypto {
+                kdf,
+                checksum: KeystoreModule {
+                    function: CHECKSUM_SHA256.to_string(),
+                    params: json!({}),
+                    message: hex::encode(checksum(&derived_key, &ciphertext)),
+                },
+                cipher: KeystoreModule {
+                    function: CIPHER_AES_128_CTR.to_string(),
+                    params: json!({ "iv": hex::encode(iv) }),
+                    message: hex::encode(&ciphertext[..]),
+                },
+            },
+            description: String::new(),
+            pubkey: hex::encode(public_key),
+            path: String::new(),
+            uuid: uuid(&mut rng),
+            version: KEYSTORE_VERSION,
+        })
+    }
+
+    /// Decrypt the key pair, checking the password and that the secret key belongs to
+    /// the public key
+    pub fn decrypt(&self, password: &str) -> Result<BlsKeyPair, BBSError> {
+        if self.version != KEYSTORE_VERSION {
+            return Err(general_error("Unsupported keystore version"));
+        }
+        let crypto = &self.crypto;
+        if crypto.checksum.function != CHECKSUM_SHA256
+            || crypto.cipher.function != CIPHER_AES_128_CTR
+        {
+            return Err(general_error("Unsupported keystore checksum or cipher"));
+        }
+        let cipher_params: CipherParams = params(&crypto.cipher)?;
+        let iv: [u8; AES_BLOCK_SIZE] = decode_hex(&cipher_params.iv)?
+            .as_slice()
+            .try_into()
+            .map_err(|_| general_error("Invalid keystore IV"))?;
+        let mut secret_key = Zeroizing::new(decode_hex(&crypto.cipher.message)?);
+        let public_key = decode_hex(&self.pubkey)?;
+
+        let derived_key = derive_key(&crypto.kdf, password)?;
+        let expected = decode_hex(&crypto.checksum.message)?;
+        let actual = checksum(&derived_key, &secret_key);
+        // Compare without an early exit
+        if expected.len() != actual.len()
+            || expected
+                .iter()
+                .zip(actual.iter())
+                .fold(0u8, |d, (e, a)| d | (e ^ a))
+                != 0
+        {
+            return Err(general_error("Invalid keystore password"));
+        }
+        aes_128_ctr(&derived_key, &iv, &mut secret_key);
+
+        let secret_key = match public_key.len() {
+            G1_COMPRESSED_SIZE => {
+                check_key_pair::<G1>(&public_key, Some(&secret_key[..]), G1_COMPRESSED_SIZE)?
+            }
+            G2_COMPRESSED_SIZE => {
+                check_key_pair::<G2>(&public_key, Some(&secret_key[..]), G2_COMPRESSED_SIZE)?
+            }
+            _ => return Err(general_error("Invalid BLS 12-381 public key")),
+        };
+        Ok(BlsKeyPair {
+            publicKey: Some(public_key),
+            secretKey: secret_key,
+        })
+    }
+}
+
+/// Encrypts the secret key of a BLS 12-381 key pair with a password, producing an
+/// EIP-2335 keystore as JSON
+#[wasm_bindgen(js_name = exportEncryptedKey)]
+pub async fn export_encrypted_key(request: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let request: KeystoreExportRequest = request.try_into()?;
+    let mut keystore = map_err!(Keystore::encrypt(
+        &request.keyPair,
+        &request.password,
+        request.kdf.as_deref().unwrap_or(KDF_PBKDF2),
+        request.cost.unwrap_or(DEFAULT_COST),
+    ))?;
+    keystore.description = request.description.unwrap_or_default();
+    Ok(JsValue::from_str(
+        &serde_json::to_string(&keystore).unwrap(),
+    ))
+}
+
+/// Decrypts a BLS 12-381 key pair from an EIP-2335 keystore
+#[wasm_bindgen(js_name = importEncryptedKey)]
+pub async fn import_encrypted_key(request: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let request: KeystoreImportRequest = request.try_into()?;
+    let keystore: Keystore = map_err!(serde_json::from_str(&request.keystore))?;
+    let key_pair = map_err!(keystore.decrypt(&request.password))?;
+    Ok(serde_wasm_bindgen::to_value(&key_pair).unwrap())
+}
+
+fn derive_key(
+    kdf: &KeystoreModule,
+    password: &str,
+) -> Result<Zeroizing<[u8; DERIVED_KEY_SIZE]>, BBSError> {
+    // EIP-2335 removes the C0 and C1 control codes and delete
+    let password: Zeroizing<String> = Zeroizing::new(
+        password
+            .chars()
+            .filter(|c| !matches!(*c as u32, 0x00..=0x1f | 0x7f..=0x9f))
+            .collect(),
+    );
+    let mut derived_key = Zeroizing::new([0u8; DERIVED_KEY_SIZE]);
+    match kdf.function.as_str() {
+        KDF_PBKDF2 => {
+            let params: Pbkdf2Params = params(kdf)?;
+            if params.dklen != DERIVED_KEY_SIZE
+                || params.prf != PBKDF2_PRF
+                || params.c == 0
+                || params.c > MAX_PBKDF2_ITERATIONS
+            {
+                return Err(general_error("Unsupported keystore KDF parameters"));
+            }
+            let salt = decode_hex(&params.salt)?;
+            pbkdf2::pbkdf2::<Hmac<Sha256>>(
+                password.as_bytes(),
+                &salt,
+                params.c as usize,
+                &mut derived_key[..],
+            );
+        }
+        KDF_SCRYPT => {
+            let params: ScryptParams = params(kdf)?;
+            if params.dklen != DERIVED_KEY_SIZE
+                || params.n < 2
+                || !params.n.is_power_of_two()
+                || params.r == 0
+                || params.p == 0
+                || params.p > MAX_SCRYPT_P
+                || 128 * params.n as u64 * params.r as u64 > MAX_SCRYPT_MEMORY
+            {
+                return Err(general_error("Unsupported keystore KDF parameters"));
+            }
+            let salt = decode_hex(&params.salt)?;
+            let scrypt_params = scrypt::Params::new(
+                params.n.trailing_zeros() as u8,
+                params.r,
+                params.p,
+                DERIVED_KEY_SIZE,
+            )
+            .map_err(|_| general_error("Unsupported keystore KDF parameters"))?;
+            scrypt::scrypt(
+                password.as_bytes(),
+                &salt,
+                &scrypt_params,
+                &mut derived_key[..],
+            )
+            .map_err(|_| general_error("Unsupported keystore KDF parameters"))?;
+        }
+        _ => return Err(general_error("Unsupported keystore KDF")),
+    }
+    Ok(derived_key)
+}
+
+/// Encrypt or decrypt in place with the first half of the derived key
+fn aes_128_ctr(derived_key: &[u8; DERIVED_KEY_SIZE], iv: &[u8; AES_BLOCK_SIZE], data: &mut [u8]) {
+    let mut cipher =
+        ctr::Ctr128BE::<Aes128>::new(derived_key[..AES_128_KEY_SIZE].into(), iv.into());
+    cipher.apply_keystream(data);
+}
+
+fn checksum(derived_key: &[u8; DERIVED_KEY_SIZE], ciphertext: &[u8]) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    hasher.input(&derived_key[AES_128_KEY_SIZE..]);
+    hasher.input(ciphertext);
+    hasher.result().to_vec()
+}
+
+fn params<'a, T: Deserialize<'a>>(module: &'a KeystoreModule) -> Result<T, BBSError> {
+    T::deserialize(&module.params).map_err(|_| general_error("Invalid keystore parameters"))
+}
+
+/// A random version 4 UUID
+fn uuid<R: RngCore>(rng: &mut R) -> String {
+    let mut bytes = [0u8; 16];
+    rng.fill_bytes(&mut bytes);
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex = hex::encode(bytes);
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+fn decode_hex(value: &str) -> Result<Vec<u8>, BBSError> {
+    hex::decode(value).map_err(|_| general_error("The value is not hex encoded"))
+}
@@ -14,6 +14,7 @@
 #[macro_use]
 extern crate arrayref;
 
+use crate::verifiable_encryption::ElGamalCiphertext;
 use bbs::prelude::*;
 use serde::{
     de::{Error as DError, Visitor},
@@ -24,16 +25,65 @@ use wasm_bindgen::prelude::*;
 
 #[macro_use]
 mod macros;
+pub mod accumulator;
+pub mod bbs_bls_signature_2020;
 pub mod bbs_plus;
 pub mod bls12381;
+mod cbor;
+pub mod cose;
+pub mod designated_verifier;
+pub mod holder_binding;
+mod json_ld;
+pub mod json_web_proof;
+pub mod jwk;
+pub mod key_handle;
+pub mod key_possession;
+pub mod keystore;
+pub mod linear_relation;
+mod linked_proof;
+pub mod message_commitment;
+pub mod multikey;
+pub mod numeric_message;
+pub mod predicate;
+pub mod pseudonym;
+mod range_proof;
+pub mod rate_limit;
+mod rdf_canonize;
+pub mod set_membership;
+mod statements;
+pub mod status_list;
+pub mod threshold;
 mod utils;
+pub mod verifiable_encryption;
+
+/// Starts the pool of web workers backing the shared-memory threads of `signBatch`,
+/// only available when built with the `atomics` target feature
+#[cfg(all(target_arch = "wasm32", target_feature = "atomics"))]
+pub use wasm_bindgen_rayon::init_thread_pool;
 
 wasm_impl!(BbsVerifyResponse, verified: bool, error: Option<String>);
 
+wasm_impl!(
+    BbsVerifyProofResponse,
+    verified: bool,
+    error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pseudonym: Option<Commitment>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ciphertext: Option<Vec<ElGamalCiphertext>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    commitments: Option<Vec<Commitment>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tag: Option<Commitment>
+);
+
 #[derive(Debug)]
 pub struct PoKOfSignatureProofWrapper {
     pub bit_vector: Vec<u8>,
     pub proof: PoKOfSignatureProof,
+    /// The proofs of the statements about the hidden messages, appended after the
+    /// signature proof
+    pub statements: Vec<u8>,
 }
 
 impl PoKOfSignatureProofWrapper {
@@ -44,7 +94,11 @@ impl PoKOfSignatureProofWrapper {
     ) -> Self {
         let mut bit_vector = (message_count as u16).to_be_bytes().to_vec();
         bit_vector.append(&mut revealed_to_bitvector(message_count, revealed));
-        Self { bit_vector, proof }
+        Self {
+            bit_vector,
+            proof,
+            statements: Vec::new(),
+        }
     }
 
     pub fn unwrap(self) -> (BTreeSet<usize>, PoKOfSignatureProof) {
@@ -54,6 +108,7 @@ impl PoKOfSignatureProofWrapper {
     pub fn to_bytes(&self) -> Vec<u8> {
         let mut data = self.bit_vector.to_vec();
         data.append(&mut self.proof.to_bytes_compressed_form());
+        data.extend_from_slice(&self.statements);
         data
     }
 }
@@ -69,9 +124,18 @@ impl TryFrom<&[u8]> for PoKOfSignatureProofWrapper {
             return Err(JsValue::FALSE);
         }
         let proof = map_err!(PoKOfSignatureProof::try_from(&value[offset..]))?;
+        // The statements follow the compressed form of the signature proof, which
+        // is the only form `createProof` outputs
+        let proof_bytes = proof.to_bytes_compressed_form();
+        let statements = if value[offset..].starts_with(&proof_bytes) {
+            value[offset + proof_bytes.len()..].to_vec()
+        } else {
+            Vec::new()
+        };
         Ok(Self {
             bit_vector: value[..offset].to_vec(),
             proof,
+            statements,
         })
     }
 }
@@ -113,8 +177,30 @@ impl<'a> Deserialize<'a> for PoKOfSignatureProofWrapper {
 }
 
 pub mod prelude {
+    pub use crate::accumulator::*;
+    pub use crate::bbs_bls_signature_2020::*;
     pub use crate::bbs_plus::*;
     pub use crate::bls12381::*;
+    pub use crate::cose::*;
+    pub use crate::designated_verifier::*;
+    pub use crate::holder_binding::*;
+    pub use crate::json_web_proof::*;
+    pub use crate::jwk::*;
+    pub use crate::key_handle::*;
+    pub use crate::key_possession::*;
+    pub use crate::keystore::*;
+    pub use crate::linear_relation::*;
+    pub use crate::message_commitment::*;
+    pub use crate::multikey::*;
+    pub use crate::numeric_message::*;
+    pub use crate::predicate::*;
+    pub use crate::pseudonym::*;
+    pub use crate::range_proof::{BitProof, RangeProof};
+    pub use crate::rate_limit::*;
+    pub use crate::set_membership::*;
+    pub use crate::status_list::*;
+    pub use crate::threshold::*;
+    pub use crate::verifiable_encryption::*;
 }
 
 /// Expects `revealed` to be sorted
@@ -0,0 +1,466 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Linear relations between hidden messages of one or more signatures.
+//!
+//! A relation `a_1 * m_1 + ... + a_n * m_n = b` holds over the message scalars, where
+//! each `m_i` is a hidden message of any of the signatures. All the signature proofs
+//! share one challenge `c`, and the blindings `r_i` of the hidden messages are chosen
+//! so `a_1 * r_1 + ... + a_n * r_n = 0` for every relation. The responses are
+//! `r_i - c * m_i`, so the verifier checks `a_1 * s_1 + ... + a_n * s_n = -c * b`.
+//!
+//! A relation can instead compare the sum with `b`, in which case the signature proofs
+//! are linked to a range proof that `d` is in `[0, 2^64)` for `d = sum - b - 1` for `>`,
+//! `sum - b` for `>=`, `b - sum - 1` for `<` and `b - sum` for `<=`. The blinding of `d`
+//! is derived from the blindings of the hidden messages, so the verifier derives its
+//! response from their responses.
+//!
+//! Text messages are hashed to scalars before signing, so relations between them are
+//! limited to equalities such as `m_1 - m_2 = 0`. Numeric messages are signed as the
+//! scalars of their integer values, so arithmetic relations and comparisons between
+//! them hold over the integers as long as the sums do not wrap around the group order.
+
+use crate::linked_proof;
+use crate::numeric_message::{integer_to_fr, message_scalar};
+use crate::range_proof::{RangeProof, RangeProver, RANGE_BITS};
+use crate::utils::{general_error, set_panic_hook, verify_response};
+use crate::PoKOfSignatureProofWrapper;
+
+use bbs::prelude::*;
+use ff_zeroize::Field;
+use pairing_plus::bls12_381::Fr;
+use rand::thread_rng;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    convert::TryInto,
+};
+use wasm_bindgen::prelude::*;
+
+wasm_impl!(
+    #[allow(non_snake_case)]
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    LinearRelationTerm,
+    credential: usize,
+    messageIndex: usize,
+    coefficient: i64
+);
+
+wasm_impl!(
+    #[allow(non_snake_case)]
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    LinearRelation,
+    terms: Vec<LinearRelationTerm>,
+    constant: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    comparison: Option<String>
+);
+
+wasm_impl!(
+    #[allow(non_snake_case)]
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    LinearRelationCredential,
+    signature: Signature,
+    publicKey: PublicKey,
+    messages: Vec<Vec<u8>>,
+    revealed: Vec<usize>
+);
+
+wasm_impl!(
+    LinearRelationCreateProofRequest,
+    credentials: Vec<LinearRelationCredential>,
+    nonce: Vec<u8>,
+    relations: Vec<LinearRelation>
+);
+
+wasm_impl!(
+    LinearRelationProof,
+    proofs: Vec<PoKOfSignatureProofWrapper>,
+    inequalities: Vec<RangeProof>
+);
+
+/// A comparison of a relation as the value `d = sign * sum - offset` in `[0, 2^64)`
+struct Inequality {
+    sign: Fr,
+    offset: Fr,
+}
+
+impl Inequality {
+    /// `None` for an equality
+    fn new(relation: &LinearRelation) -> Result<Option<Self>, BBSError> {
+        let (negate, strict) = match relation.comparison.as_deref() {
+            None | Some("=") => return Ok(None),
+            Some(">") => (false, true),
+            Some(">=") => (false, false),
+            Some("<") => (true, true),
+            Some("<=") => (true, false),
+            Some(_) => return Err(general_error("Invalid relation comparison")),
+        };
+        let mut sign = Fr::one();
+        let mut offset = integer_to_fr(relation.constant);
+        if negate {
+            sign.negate();
+            offset.negate();
+        }
+        if strict {
+            offset.add_assign(&Fr::one());
+        }
+        Ok(Some(Self { sign, offset }))
+    }
+
+    /// `sign * row . values`
+    fn combine(&self, row: &[Fr], values: &[Fr]) -> Fr {
+        let mut sum = dot(row, values);
+        sum.mul_assign(&self.sign);
+        sum
+    }
+}
+
+wasm_impl!(
+    #[allow(non_snake_case)]
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    LinearRelationVerifyCredential,
+    publicKey: PublicKey,
+    messages: Vec<Vec<u8>>
+);
+
+wasm_impl!(
+    LinearRelationVerifyProofRequest,
+    proof: LinearRelationProof,
+    credentials: Vec<LinearRelationVerifyCredential>,
+    nonce: Vec<u8>,
+    relations: Vec<LinearRelation>
+);
+
+/// Creates proofs of knowledge of one or more signatures that also prove the linear
+/// relations in the request between their hidden messages
+#[wasm_bindgen(js_name = createLinearRelationProof)]
+pub async fn create_linear_relation_proof(request: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let request: LinearRelationCreateProofRequest = request.try_into()?;
+    let mut revealed = Vec::new();
+    let mut messages = Vec::new();
+    for credential in &request.credentials {
+        let message_count = credential.publicKey.message_count();
+        if credential.revealed.iter().any(|r| *r >= message_count) {
+            return Err(JsValue::from("revealed value is out of bounds"));
+        }
+        if credential.messages.len() != message_count {
+            return Err(JsValue::from("messages do not match the public key"));
+        }
+        revealed.push(
+            credential
+                .revealed
+                .iter()
+                .copied()
+                .collect::<BTreeSet<usize>>(),
+        );
+        messages.push(
+            credential
+                .messages
+                .iter()
+                .map(message_scalar)
+                .collect::<Vec<SignatureMessage>>(),
+        );
+    }
+    let (variables, matrix, constants) = map_err!(relation_matrix(
+        &request.relations,
+        &revealed,
+        &request
+            .credentials
+            .iter()
+            .map(|c| c.publicKey.message_count())
+            .collect::<Vec<usize>>(),
+    ))?;
+    let inequalities = map_err!(request
+        .relations
+        .iter()
+        .map(Inequality::new)
+        .collect::<Result<Vec<Option<Inequality>>, BBSError>>())?;
+    let values: Vec<Fr> = variables
+        .iter()
+        .map(|(credential, index)| *messages[*credential][*index].as_ref())
+        .collect();
+    let mut equalities = Vec::new();
+    for ((row, constant), inequality) in matrix.iter().zip(constants.iter()).zip(&inequalities) {
+        if inequality.is_none() {
+            if &dot(row, &values) != constant {
+                return Err(JsValue::from(
+                    "The hidden messages do not satisfy a relation",
+                ));
+            }
+            equalities.push(row.clone());
+        }
+    }
+
+    let blindings = kernel_vector(equalities, variables.len());
+    let mut range_provers = Vec::new();
+    let mut range_bytes = Vec::new();
+    for (row, inequality) in matrix.iter().zip(&inequalities) {
+        if let Some(inequality) = inequality {
+            let mut value = inequality.combine(row, &values);
+            value.sub_assign(&inequality.offset);
+            let prover = RangeProver::new(&value, &inequality.combine(row, &blindings), RANGE_BITS)
+                .ok_or_else(|| JsValue::from("The hidden messages do not satisfy a relation"))?;
+            prover.append_bytes(&mut range_bytes);
+            range_provers.push(prover);
+        }
+    }
+    let mut linked = vec![BTreeMap::new(); request.credentials.len()];
+    for ((credential, index), blinding) in variables.iter().zip(blindings) {
+        linked[*credential].insert(*index, ProofNonce::from(blinding));
+    }
+    let mut poks = Vec::new();
+    let mut bytes = Vec::new();
+    for (i, credential) in request.credentials.iter().enumerate() {
+        let pok = map_err!(linked_proof::init_pok(
+            &credential.signature,
+            &credential.publicKey,
+            &messages[i],
+            &revealed[i],
+            &linked[i],
+        ))?;
+        bytes.extend_from_slice(&pok.to_bytes());
+        poks.push(pok);
+    }
+    let mut linked_bytes = relation_bytes(&request.relations);
+    linked_bytes.extend_from_slice(&range_bytes);
+    let challenge = linked_proof::challenge(bytes, &linked_bytes, &request.nonce);
+
+    let mut proofs = Vec::new();
+    for ((pok, credential), revealed) in poks
+        .into_iter()
+        .zip(request.credentials.iter())
+        .zip(revealed.iter())
+    {
+        let proof = map_err!(pok.gen_proof(&challenge))?;
+        proofs.push(PoKOfSignatureProofWrapper::new(
+            credential.publicKey.message_count(),
+            revealed,
+            proof,
+        ));
+    }
+    let inequalities = range_provers.iter().map(|p| p.prove(&challenge)).collect();
+    Ok(serde_wasm_bindgen::to_value(&LinearRelationProof {
+        proofs,
+        inequalities,
+    })
+    .unwrap())
+}
+
+/// Verifies proofs of knowledge of one or more signatures and the linear relations
+/// in the request between their hidden messages
+#[wasm_bindgen(js_name = verifyLinearRelationProof)]
+pub async fn verify_linear_relation_proof(request: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    Ok(verify_response(request, check_linear_relation_proof))
+}
+
+fn check_linear_relation_proof(
+    request: LinearRelationVerifyProofRequest,
+) -> Result<bool, BBSError> {
+    if request.credentials.len() != request.proof.proofs.len() {
+        return Ok(false);
+    }
+    let mut revealed = Vec::new();
+    let mut proofs = Vec::new();
+    let mut bytes = Vec::new();
+    for (wrapper, credential) in request
+        .proof
+        .proofs
+        .into_iter()
+        .zip(request.credentials.iter())
+    {
+        let (r, proof) = wrapper.unwrap();
+        bytes.extend_from_slice(&proof.get_bytes_for_challenge(r.clone(), &credential.publicKey));
+        revealed.push(r);
+        proofs.push(proof);
+    }
+    let (variables, matrix, constants) = relation_matrix(
+        &request.relations,
+        &revealed,
+        &request
+            .credentials
+            .iter()
+            .map(|c| c.publicKey.message_count())
+            .collect::<Vec<usize>>(),
+    )?;
+    let inequalities = request
+        .relations
+        .iter()
+        .map(Inequality::new)
+        .collect::<Result<Vec<Option<Inequality>>, BBSError>>()?;
+    if inequalities.iter().flatten().count() != request.proof.inequalities.len() {
+        return Ok(false);
+    }
+    let mut linked_bytes = relation_bytes(&request.relations);
+    for range_proof in &request.proof.inequalities {
+        range_proof.append_bytes(&mut linked_bytes);
+    }
+    let challenge = linked_proof::challenge(bytes, &linked_bytes, &request.nonce);
+
+    let responses = variables
+        .iter()
+        .map(|(credential, index)| {
+            linked_proof::hidden_response(&proofs[*credential], &revealed[*credential], *index)
+        })
+        .collect::<Result<Vec<SignatureMessage>, BBSError>>()?;
+    let responses: Vec<Fr> = responses.iter().map(|r| *r.as_ref()).collect();
+    let mut range_proofs = request.proof.inequalities.iter();
+    for ((row, constant), inequality) in matrix.iter().zip(constants.iter()).zip(&inequalities) {
+        match inequality {
+            Some(inequality) => {
+                // The response of d is sign * (a_1 * s_1 + ... + a_n * s_n) + c * offset
+                let mut response = inequality.offset;
+                response.mul_assign(challenge.as_ref());
+                response.add_assign(&inequality.combine(row, &responses));
+                let range_proof = range_proofs.next().unwrap();
+                if !range_proof.verify(RANGE_BITS, &SignatureMessage::from(response), &challenge) {
+                    return Ok(false);
+                }
+            }
+            None => {
+                // a_1 * s_1 + ... + a_n * s_n + c * b = 0
+                let mut sum = *constant;
+                sum.mul_assign(challenge.as_ref());
+                sum.add_assign(&dot(row, &responses));
+                if !sum.is_zero() {
+                    return Ok(false);
+                }
+            }
+        }
+    }
+    for ((proof, credential), revealed) in proofs
+        .iter()
+        .zip(request.credentials.iter())
+        .zip(revealed.iter())
+    {
+        let revealed_messages = linked_proof::revealed_messages(revealed, &credential.messages);
+        if !linked_proof::verify_pok(proof, &credential.publicKey, &revealed_messages, &challenge)?
+        {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// The hidden messages used by the relations as `(credential, index)`, and the
+/// coefficients and constant of each relation over them
+#[allow(clippy::type_complexity)]
+fn relation_matrix(
+    relations: &[LinearRelation],
+    revealed: &[BTreeSet<usize>],
+    message_counts: &[usize],
+) -> Result<(Vec<(usize, usize)>, Vec<Vec<Fr>>, Vec<Fr>), BBSError> {
+    let mut columns = BTreeMap::new();
+    for term in relations.iter().flat_map(|r| r.terms.iter()) {
+        if term.credential >= message_counts.len()
+            || term.messageIndex >= message_counts[term.credential]
+        {
+            return Err(general_error("A relation term is out of bounds"));
+        }
+        if revealed[term.credential].contains(&term.messageIndex) {
+            return Err(general_error("A message in a relation cannot be revealed"));
+        }
+        columns.insert((term.credential, term.messageIndex), 0);
+    }
+    for (i, column) in columns.values_mut().enumerate() {
+        *column = i;
+    }
+    let mut matrix = Vec::new();
+    let mut constants = Vec::new();
+    for relation in relations {
+        let mut row = vec![Fr::zero(); columns.len()];
+        for term in &relation.terms {
+            row[columns[&(term.credential, term.messageIndex)]]
+                .add_assign(&integer_to_fr(term.coefficient));
+        }
+        matrix.push(row);
+        constants.push(integer_to_fr(relation.constant));
+    }
+    Ok((columns.into_keys().collect(), matrix, constants))
+}
+
+/// A random vector `r` with `matrix * r = 0`, found by reducing the matrix to row
+/// echelon form and solving for the pivot entries from random free entries
+fn kernel_vector(mut matrix: Vec<Vec<Fr>>, width: usize) -> Vec<Fr> {
+    let mut pivots = Vec::new();
+    let mut row = 0;
+    for column in 0..width {
+        let pivot = match (row..matrix.len()).find(|r| !matrix[*r][column].is_zero()) {
+            Some(p) => p,
+            None => continue,
+        };
+        matrix.swap(row, pivot);
+        let inverse = matrix[row][column].inverse().unwrap();
+        for entry in matrix[row].iter_mut() {
+            entry.mul_assign(&inverse);
+        }
+        let pivot_row = matrix[row].clone();
+        for (other, other_row) in matrix.iter_mut().enumerate() {
+            if other != row && !other_row[column].is_zero() {
+                let factor = other_row[column];
+                for (entry, pivot_entry) in other_row.iter_mut().zip(pivot_row.iter()) {
+                    let mut product = *pivot_entry;
+                    product.mul_assign(&factor);
+                    entry.sub_assign(&product);
+                }
+            }
+        }
+        pivots.push(column);
+        row += 1;
+    }
+
+    let mut rng = thread_rng();
+    let mut vector: Vec<Fr> = (0..width).map(|_| Fr::random(&mut rng)).collect();
+    for (row, column) in pivots.iter().enumerate() {
+        let mut value = Fr::zero();
+        for (j, (entry, v)) in matrix[row].iter().zip(vector.iter()).enumerate() {
+            if j != *column {
+                let mut product = *entry;
+                product.mul_assign(v);
+                value.sub_assign(&product);
+            }
+        }
+        vector[*column] = value;
+    }
+    vector
+}
+
+/// `a_1 * v_1 + ... + a_n * v_n`
+fn dot(row: &[Fr], values: &[Fr]) -> Fr {
+    let mut sum = Fr::zero();
+    for (a, v) in row.iter().zip(values.iter()) {
+        let mut term = *v;
+        term.mul_assign(a);
+        sum.add_assign(&term);
+    }
+    sum
+}
+
+fn relation_bytes(relations: &[LinearRelation]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for relation in relations {
+        bytes.extend_from_slice(&(relation.terms.len() as u64).to_be_bytes());
+        for term in &relation.terms {
+            bytes.extend_from_slice(&(term.credential as u64).to_be_bytes());
+            bytes.extend_from_slice(&(term.messageIndex as u64).to_be_bytes());
+            bytes.extend_from_slice(&term.coefficient.to_be_bytes());
+        }
+        bytes.extend_from_slice(&relation.constant.to_be_bytes());
+        let comparison = relation.comparison.as_deref().unwrap_or("=");
+        bytes.extend_from_slice(&(comparison.len() as u64).to_be_bytes());
+        bytes.extend_from_slice(comparison.as_bytes());
+    }
+    bytes
+}
@@ -0,0 +1,319 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Helpers for proofs of knowledge of a signature that are linked to other
+//! sigma protocols over the hidden messages.
+//!
+//! A hidden message shared with another protocol is blinded with a blinding
+//! chosen by the prover, so its response in the signature proof is
+//! `blinding - c * m`. The other protocol commits with the same blinding and the
+//! verifier checks it with the response taken from the signature proof, which
+//! proves both protocols use the same message. The commitments of the other
+//! protocols are hashed into the challenge between the signature proof and the nonce.
+
+use crate::numeric_message::message_scalar;
+use crate::utils::zeroize_proof_messages;
+
+use bbs::prelude::*;
+use pairing_plus::{bls12_381::G1, serdes::SerDes, CurveProjective};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Create the proof of knowledge of a signature where the hidden messages in
+/// `linked` are blinded with the given blindings
+pub(crate) fn init_pok(
+    signature: &Signature,
+    pk: &PublicKey,
+    messages: &[SignatureMessage],
+    revealed: &BTreeSet<usize>,
+    linked: &BTreeMap<usize, ProofNonce>,
+) -> Result<PoKOfSignature, BBSError> {
+    let mut proof_messages: Vec<ProofMessage> = messages
+        .iter()
+        .enumerate()
+        .map(|(i, m)| {
+            if revealed.contains(&i) {
+                ProofMessage::Revealed(*m)
+            } else if let Some(blinding) = linked.get(&i) {
+                ProofMessage::Hidden(HiddenMessage::ExternalBlinding(*m, *blinding))
+            } else {
+                ProofMessage::Hidden(HiddenMessage::ProofSpecificBlinding(*m))
+            }
+        })
+        .collect();
+    let pok = PoKOfSignature::init(signature, pk, proof_messages.as_slice());
+    zeroize_proof_messages(&mut proof_messages);
+    pok
+}
+
+/// Hash the signature proof bytes, the linked protocol bytes and the nonce into the
+/// challenge, treating an empty nonce like `createProof` and `verifyProof` do
+pub(crate) fn challenge(mut bytes: Vec<u8>, linked: &[u8], nonce: &[u8]) -> ProofChallenge {
+    bytes.extend_from_slice(linked);
+    if nonce.is_empty() {
+        bytes.extend_from_slice(&[0u8; FR_COMPRESSED_SIZE]);
+    } else {
+        bytes.extend_from_slice(
+            ProofNonce::hash(nonce)
+                .to_bytes_uncompressed_form()
+                .as_ref(),
+        );
+    }
+    ProofChallenge::hash(&bytes)
+}
+
+/// Verify the signature proof against a challenge computed with `challenge`
+pub(crate) fn verify_pok(
+    proof: &PoKOfSignatureProof,
+    pk: &PublicKey,
+    revealed: &BTreeMap<usize, SignatureMessage>,
+    challenge: &ProofChallenge,
+) -> Result<bool, BBSError> {
+    Ok(proof.verify(pk, revealed, challenge)?.is_valid())
+}
+
+/// The response for the hidden message at `index` in a signature proof
+pub(crate) fn hidden_response(
+    proof: &PoKOfSignatureProof,
+    revealed: &BTreeSet<usize>,
+    index: usize,
+) -> Result<SignatureMessage, BBSError> {
+    if revealed.contains(&index) {
+        return Err(BBSErrorKind::GeneralError {
+            msg: format!("Message {} is revealed", index),
+        }
+        .into());
+    }
+    proof.get_resp_for_message(index - revealed.range(..index).count())
+}
+
+/// Match the revealed messages to their indices like `verifyProof` does
+pub(crate) fn revealed_messages(
+    revealed: &BTreeSet<usize>,
+    messages: &[Vec<u8>],
+) -> BTreeMap<usize, SignatureMessage> {
+    revealed
+        .iter()
+        .zip(messages.iter())
+        .map(|(i, m)| (*i, message_scalar(m)))
+        .collect()
+}
+
+/// The generators `g` and `h` of the Pedersen commitments of a protocol, hashed from
+/// its domain separation tag
+pub(crate) fn generators(dst: &[u8]) -> [G1; 2] {
+    let hash = |suffix: u8| {
+        let mut data = dst.to_vec();
+        data.push(suffix);
+        *GeneratorG1::hash(data).as_ref()
+    };
+    [hash(b'G'), hash(b'H')]
+}
+
+/// Compute `bases[0]^scalars[0] * ... * bases[n]^scalars[n]`
+pub(crate) fn multi_exp(bases: &[G1], scalars: &[SignatureMessage]) -> G1 {
+    let mut builder = CommitmentBuilder::new();
+    for (b, s) in bases.iter().zip(scalars.iter()) {
+        builder.add(GeneratorG1::from(*b), *s);
+    }
+    *builder.finalize().as_ref()
+}
+
+/// Compute the commitment of a sigma protocol from its responses as
+/// `bases^responses * statement^challenge`
+pub(crate) fn recompute_commitment(
+    bases: &[G1],
+    responses: &[SignatureMessage],
+    statement: &G1,
+    challenge: &ProofChallenge,
+) -> G1 {
+    let mut result = multi_exp(bases, responses);
+    let mut s = *statement;
+    s.mul_assign(*challenge.as_ref());
+    result.add_assign(&s);
+    result
+}
+
+/// The response `blinding - c * secret`
+pub(crate) fn response(
+    blinding: &ProofNonce,
+    challenge: &ProofChallenge,
+    secret: &SignatureMessage,
+) -> SignatureMessage {
+    use ff_zeroize::Field;
+
+    let mut c = *challenge.as_ref();
+    c.mul_assign(secret.as_ref());
+    let mut s = *blinding.as_ref();
+    s.sub_assign(&c);
+    SignatureMessage::from(s)
+}
+
+/// A Pedersen commitment `K = g^m * h^s` to a hidden message `m` of a signature
+/// proof, with the commitment `T = g^b * h^b_s` proving `m` is the message blinded
+/// with `b` in the signature proof
+pub(crate) struct MessageCommitment {
+    pub(crate) commitment: G1,
+    pub(crate) proof: G1,
+    pub(crate) blinding: SignatureMessage,
+    proof_blinding: ProofNonce,
+}
+
+impl MessageCommitment {
+    pub(crate) fn new(
+        bases: &[G1; 2],
+        message: &SignatureMessage,
+        message_blinding: &ProofNonce,
+    ) -> Self {
+        Self::with_blinding(bases, message, message_blinding, SignatureMessage::random())
+    }
+
+    /// A commitment with the blinding `s` chosen by the caller
+    pub(crate) fn with_blinding(
+        bases: &[G1; 2],
+        message: &SignatureMessage,
+        message_blinding: &ProofNonce,
+        blinding: SignatureMessage,
+    ) -> Self {
+        let proof_blinding = ProofNonce::random();
+        Self {
+            commitment: multi_exp(bases, &[*message, blinding]),
+            proof: multi_exp(
+                bases,
+                &[
+                    SignatureMessage::from(*message_blinding.as_ref()),
+                    SignatureMessage::from(*proof_blinding.as_ref()),
+                ],
+            ),
+            blinding,
+            proof_blinding,
+        }
+    }
+
+    /// The response for the blinding `s` of the commitment
+    pub(crate) fn response(&self, challenge: &ProofChallenge) -> SignatureMessage {
+        response(&self.proof_blinding, challenge, &self.blinding)
+    }
+}
+
+/// Check a `MessageCommitment` with the response for the message taken from the
+/// signature proof
+pub(crate) fn verify_message_commitment(
+    bases: &[G1; 2],
+    commitment: &G1,
+    proof: &G1,
+    message_response: &SignatureMessage,
+    blinding_response: &SignatureMessage,
+    challenge: &ProofChallenge,
+) -> bool {
+    &recompute_commitment(
+        bases,
+        &[*message_response, *blinding_response],
+        commitment,
+        challenge,
+    ) == proof
+}
+
+/// `K * g^-v`, a commitment to `m - v` for the commitment `K` to `m`
+pub(crate) fn offset_commitment(commitment: &G1, g: &G1, value: &SignatureMessage) -> G1 {
+    let mut base = *g;
+    base.mul_assign(*value.as_ref());
+    base.negate();
+    base.add_assign(commitment);
+    base
+}
+
+/// Proof that the message `m` of a `MessageCommitment` `K = g^m * h^s` is not `v`.
+/// `K * g^-v` commits to `m - v`, which is not zero when `g = (K * g^-v)^w * h^t`
+/// for `w = 1 / (m - v)` and `t = -s * w`
+pub(crate) struct NotEqualCommitment {
+    pub(crate) commitment: G1,
+    inverse: SignatureMessage,
+    inverse_blinding: ProofNonce,
+    product: SignatureMessage,
+    product_blinding: ProofNonce,
+}
+
+impl NotEqualCommitment {
+    /// `None` when the message equals `value`
+    pub(crate) fn new(
+        bases: &[G1; 2],
+        message_commitment: &MessageCommitment,
+        message: &SignatureMessage,
+        value: &SignatureMessage,
+    ) -> Option<Self> {
+        use ff_zeroize::Field;
+
+        let [g, h] = bases;
+        let mut inverse = *message.as_ref();
+        inverse.sub_assign(value.as_ref());
+        let inverse = inverse.inverse()?;
+        let mut product = *message_commitment.blinding.as_ref();
+        product.negate();
+        product.mul_assign(&inverse);
+        let inverse_blinding = ProofNonce::random();
+        let product_blinding = ProofNonce::random();
+        Some(Self {
+            commitment: multi_exp(
+                &[
+                    offset_commitment(&message_commitment.commitment, g, value),
+                    *h,
+                ],
+                &[
+                    SignatureMessage::from(*inverse_blinding.as_ref()),
+                    SignatureMessage::from(*product_blinding.as_ref()),
+                ],
+            ),
+            inverse: SignatureMessage::from(inverse),
+            inverse_blinding,
+            product: SignatureMessage::from(product),
+            product_blinding,
+        })
+    }
+
+    /// The responses for `w` and `t`
+    pub(crate) fn responses(
+        &self,
+        challenge: &ProofChallenge,
+    ) -> (SignatureMessage, SignatureMessage) {
+        (
+            response(&self.inverse_blinding, challenge, &self.inverse),
+            response(&self.product_blinding, challenge, &self.product),
+        )
+    }
+}
+
+/// Check a `NotEqualCommitment` for the commitment `K` and `value`
+pub(crate) fn verify_not_equal(
+    bases: &[G1; 2],
+    commitment: &G1,
+    value: &SignatureMessage,
+    proof: &G1,
+    inverse_response: &SignatureMessage,
+    product_response: &SignatureMessage,
+    challenge: &ProofChallenge,
+) -> bool {
+    let [g, h] = bases;
+    &recompute_commitment(
+        &[offset_commitment(commitment, g, value), *h],
+        &[*inverse_response, *product_response],
+        g,
+        challenge,
+    ) == proof
+}
+
+/// Append the uncompressed form of the points to `bytes`
+pub(crate) fn append_points(bytes: &mut Vec<u8>, points: &[G1]) {
+    for p in points {
+        p.serialize(bytes, false).unwrap();
+    }
+}
@@ -15,11 +15,12 @@ macro_rules! wasm_impl {
     (
     $(#[$meta:meta])+
     $name:ident,
-    $($field:ident:$type:ident),+
+    $($(#[$fmeta:meta])* $field:ident:$type:ident),+
     ) => {
         $(#[$meta])*
         pub struct $name {
             $(
+                $(#[$fmeta])*
                 pub $field: $type,
             )*
         }
@@ -29,11 +30,12 @@ macro_rules! wasm_impl {
 
     (
      $name:ident,
-     $($field:ident:$type:ident),+) => {
+     $($(#[$fmeta:meta])* $field:ident:$type:ident),+) => {
         #[allow(non_snake_case)]
         #[derive(Debug, Deserialize, Serialize)]
         pub struct $name {
             $(
+                $(#[$fmeta])*
                 pub $field: $type,
             )*
         }
@@ -44,10 +46,11 @@ macro_rules! wasm_impl {
     (
      $(#[$meta:meta])+
      $name:ident,
-     $($field:ident:$type:ty),*) => {
+     $($(#[$fmeta:meta])* $field:ident:$type:ty),*) => {
         $(#[$meta])*
         pub struct $name {
             $(
+                $(#[$fmeta])*
                 pub $field: $type,
             )*
         }
@@ -56,11 +59,12 @@ macro_rules! wasm_impl {
     };
     (
      $name:ident,
-     $($field:ident:$type:ty),*) => {
+     $($(#[$fmeta:meta])* $field:ident:$type:ty),*) => {
         #[allow(non_snake_case)]
         #[derive(Debug, Deserialize, Serialize)]
         pub struct $name {
             $(
+                $(#[$fmeta])*
                 pub $field: $type,
             )*
         }
@@ -0,0 +1,194 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Commit-and-prove over hidden messages.
+//!
+//! The `committed` option of `createProof` outputs Pedersen commitments `g^m * h^s` to
+//! chosen hidden messages with blinding factors `s` chosen by the holder, proving each
+//! commits to the signed message. The holder keeps the blinding factors to open the
+//! commitments in other proof systems, and the `committed` option of `verifyProof`
+//! checks the commitments for the same indices and outputs them.
+
+use crate::linked_proof;
+use crate::statements::{
+    write_point, write_scalar, ProverStatement, StatementReader, VerifierStatement,
+};
+use crate::utils::set_panic_hook;
+use crate::BbsVerifyProofResponse;
+
+use bbs::prelude::*;
+use pairing_plus::{bls12_381::G1, CurveProjective};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use wasm_bindgen::prelude::*;
+
+const GENERATOR_DST: &[u8] = b"BBS_MESSAGE_COMMITMENT_GENERATOR_";
+
+wasm_impl!(
+    #[allow(non_snake_case)]
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    MessageCommitmentOptions,
+    messageIndex: usize,
+    blindingFactor: SignatureBlinding
+);
+
+/// Generates a blinding factor for a message commitment, which the holder keeps to
+/// open the commitment
+#[wasm_bindgen(js_name = generateBlindingFactor)]
+pub async fn generate_blinding_factor() -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    Ok(serde_wasm_bindgen::to_value(&SignatureBlinding::random()).unwrap())
+}
+
+/// The generators `g` and `h` of the message commitments
+#[wasm_bindgen(js_name = messageCommitmentGenerators)]
+pub async fn message_commitment_generators() -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let generators: Vec<Commitment> = linked_proof::generators(GENERATOR_DST)
+        .iter()
+        .map(|g| Commitment::from(*g))
+        .collect();
+    Ok(serde_wasm_bindgen::to_value(&generators).unwrap())
+}
+
+/// Proves the commitments commit to the hidden messages
+pub(crate) struct MessageCommitmentProver<'a> {
+    options: &'a [MessageCommitmentOptions],
+    commitments: Vec<linked_proof::MessageCommitment>,
+}
+
+impl<'a> MessageCommitmentProver<'a> {
+    pub(crate) fn new(options: &'a [MessageCommitmentOptions]) -> Self {
+        Self {
+            options,
+            commitments: Vec::new(),
+        }
+    }
+}
+
+impl ProverStatement for MessageCommitmentProver<'_> {
+    fn linked(&self) -> Vec<usize> {
+        self.options.iter().map(|o| o.messageIndex).collect()
+    }
+
+    fn commit(
+        &mut self,
+        messages: &[SignatureMessage],
+        blindings: &BTreeMap<usize, ProofNonce>,
+        bytes: &mut Vec<u8>,
+    ) -> Result<(), BBSError> {
+        let bases = linked_proof::generators(GENERATOR_DST);
+        self.commitments = self
+            .options
+            .iter()
+            .map(|o| {
+                linked_proof::MessageCommitment::with_blinding(
+                    &bases,
+                    &messages[o.messageIndex],
+                    &blindings[&o.messageIndex],
+                    SignatureMessage::from(*o.blindingFactor.as_ref()),
+                )
+            })
+            .collect();
+        let points: Vec<[G1; 2]> = self
+            .commitments
+            .iter()
+            .map(|c| [c.commitment, c.proof])
+            .collect();
+        bytes.append(&mut commitment_bytes(&self.linked(), &points));
+        Ok(())
+    }
+
+    fn prove(&self, challenge: &ProofChallenge, proof: &mut Vec<u8>) {
+        for commitment in &self.commitments {
+            write_point(proof, &commitment.commitment);
+            write_point(proof, &commitment.proof);
+            write_scalar(proof, &commitment.response(challenge));
+        }
+    }
+}
+
+/// Checks the commitments commit to the hidden messages, and outputs them
+pub(crate) struct MessageCommitmentVerifier<'a> {
+    committed: &'a [usize],
+    commitments: Vec<([G1; 2], SignatureMessage)>,
+}
+
+impl<'a> MessageCommitmentVerifier<'a> {
+    pub(crate) fn new(committed: &'a [usize]) -> Self {
+        Self {
+            committed,
+            commitments: Vec::new(),
+        }
+    }
+}
+
+impl VerifierStatement for MessageCommitmentVerifier<'_> {
+    fn linked(&self) -> Vec<usize> {
+        self.committed.to_vec()
+    }
+
+    fn read(&mut self, reader: &mut StatementReader, bytes: &mut Vec<u8>) -> Result<(), BBSError> {
+        self.commitments.clear();
+        for _ in self.committed {
+            let points = [reader.point()?, reader.point()?];
+            self.commitments.push((points, reader.scalar()?));
+        }
+        let points: Vec<[G1; 2]> = self.commitments.iter().map(|c| c.0).collect();
+        bytes.append(&mut commitment_bytes(self.committed, &points));
+        Ok(())
+    }
+
+    fn verify(
+        &self,
+        responses: &BTreeMap<usize, SignatureMessage>,
+        challenge: &ProofChallenge,
+    ) -> bool {
+        let bases = linked_proof::generators(GENERATOR_DST);
+        self.committed.iter().zip(self.commitments.iter()).all(
+            |(i, ([commitment, proof], blinding_response))| {
+                !commitment.is_zero()
+                    && linked_proof::verify_message_commitment(
+                        &bases,
+                        commitment,
+                        proof,
+                        &responses[i],
+                        blinding_response,
+                        challenge,
+                    )
+            },
+        )
+    }
+
+    fn output(&self, response: &mut BbsVerifyProofResponse) {
+        response.commitments = Some(
+            self.commitments
+                .iter()
+                .map(|c| Commitment::from(c.0[0]))
+                .collect(),
+        );
+    }
+}
+
+/// The committed indices are hashed into the challenge with the commitments, so the
+/// commitments are bound to the messages they were created for
+fn commitment_bytes(committed: &[usize], commitments: &[[G1; 2]]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    let [g, h] = linked_proof::generators(GENERATOR_DST);
+    linked_proof::append_points(&mut bytes, &[g, h]);
+    for (i, points) in committed.iter().zip(commitments.iter()) {
+        bytes.extend_from_slice(&(*i as u32).to_be_bytes());
+        linked_proof::append_points(&mut bytes, points);
+    }
+    bytes
+}
@@ -0,0 +1,276 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Multikey and `did:key` encodings of BLS 12-381 public keys.
+//!
+//! A multikey is the multicodec `bls12_381-g1-pub` (0xea) or `bls12_381-g2-pub` (0xeb)
+//! varint followed by the compressed public key, multibase encoded as base58btc with the
+//! `z` prefix. The `did:key` identifier of a public key is `did:key:` followed by its
+//! multikey.
+
+use crate::utils::{general_error, set_panic_hook};
+
+use bbs::prelude::*;
+use pairing_plus::{
+    bls12_381::{G1, G2},
+    serdes::SerDes,
+};
+use serde::{
+    de::{Error as DError, SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize,
+};
+use std::convert::TryFrom;
+use wasm_bindgen::prelude::*;
+
+/// The `bls12_381-g1-pub` multicodec as an unsigned varint
+const BLS12381_G1_PUB: [u8; 2] = [0xea, 0x01];
+/// The `bls12_381-g2-pub` multicodec as an unsigned varint
+const BLS12381_G2_PUB: [u8; 2] = [0xeb, 0x01];
+const MULTIBASE_BASE58BTC: char = 'z';
+const DID_KEY_PREFIX: &str = "did:key:";
+const BASE58_ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+/// Longer than any multikey, so decoding cannot be made quadratic on large input
+const MAX_MULTIKEY_LENGTH: usize = 256;
+
+wasm_impl!(
+    /// A `did:key` document with a single `Multikey` verification method
+    #[allow(non_snake_case)]
+    #[derive(Debug, Deserialize, Serialize)]
+    DidKeyDocument,
+    #[serde(rename = "@context")]
+    context: Vec<String>,
+    id: String,
+    verificationMethod: Vec<DidKeyVerificationMethod>,
+    authentication: Vec<String>,
+    assertionMethod: Vec<String>,
+    capabilityDelegation: Vec<String>,
+    capabilityInvocation: Vec<String>
+);
+
+wasm_impl!(
+    DidKeyVerificationMethod,
+    id: String,
+    #[serde(rename = "type")]
+    kind: String,
+    controller: String,
+    publicKeyMultibase: String
+);
+
+/// Encode a compressed G1 or G2 public key as a multikey
+pub fn encode_multikey(public_key: &[u8]) -> Result<String, BBSError> {
+    let codec = match public_key.len() {
+        G1_COMPRESSED_SIZE => {
+            G1::deserialize(&mut &public_key[..], true)
+                .map_err(|_| general_error("Invalid BLS 12-381 public key"))?;
+            BLS12381_G1_PUB
+        }
+        G2_COMPRESSED_SIZE => {
+            G2::deserialize(&mut &public_key[..], true)
+                .map_err(|_| general_error("Invalid BLS 12-381 public key"))?;
+            BLS12381_G2_PUB
+        }
+        _ => return Err(general_error("Invalid BLS 12-381 public key")),
+    };
+    let mut bytes = codec.to_vec();
+    bytes.extend_from_slice(public_key);
+    Ok(format!("{}{}", MULTIBASE_BASE58BTC, base58_encode(&bytes)))
+}
+
+/// Decode the compressed G1 or G2 public key of a multikey or a `did:key` identifier
+pub fn decode_multikey(multikey: &str) -> Result<Vec<u8>, BBSError> {
+    let multikey = multikey.strip_prefix(DID_KEY_PREFIX).unwrap_or(multikey);
+    let multikey = multikey.split('#').next().unwrap_or_default();
+    if multikey.len() > MAX_MULTIKEY_LENGTH {
+        return Err(general_error("Invalid multikey"));
+    }
+    let encoded = match multikey.strip_prefix(MULTIBASE_BASE58BTC) {
+        Some(encoded) => encoded,
+        None => return Err(general_error("Expected a base58btc multibase value")),
+    };
+    let bytes = base58_decode(encoded)?;
+    if bytes.len() < 2 {
+        return Err(general_error("Invalid multikey"));
+    }
+    let (codec, public_key) = bytes.split_at(2);
+    let valid = match <[u8; 2]>::try_from(codec).unwrap() {
+        BLS12381_G1_PUB => {
+            public_key.len() == G1_COMPRESSED_SIZE
+                && G1::deserialize(&mut &public_key[..], true).is_ok()
+        }
+        BLS12381_G2_PUB => {
+            public_key.len() == G2_COMPRESSED_SIZE
+                && G2::deserialize(&mut &public_key[..], true).is_ok()
+        }
+        _ => return Err(general_error("Unsupported multikey codec")),
+    };
+    if !valid {
+        return Err(general_error("Invalid BLS 12-381 public key"));
+    }
+    Ok(public_key.to_vec())
+}
+
+impl DidKeyDocument {
+    /// The `did:key` document of a compressed G1 or G2 public key
+    pub fn new(public_key: &[u8]) -> Result<Self, BBSError> {
+        let multikey = encode_multikey(public_key)?;
+        let id = format!("{}{}", DID_KEY_PREFIX, multikey);
+        let method = format!("{}#{}", id, multikey);
+        Ok(Self {
+            context: vec![
+                "https://www.w3.org/ns/did/v1".to_string(),
+                "https://w3id.org/security/multikey/v1".to_string(),
+            ],
+            verificationMethod: vec![DidKeyVerificationMethod {
+                id: method.clone(),
+                kind: "Multikey".to_string(),
+                controller: id.clone(),
+                publicKeyMultibase: multikey,
+            }],
+            authentication: vec![method.clone()],
+            assertionMethod: vec![method.clone()],
+            capabilityDelegation: vec![method.clone()],
+            capabilityInvocation: vec![method],
+            id,
+        })
+    }
+}
+
+/// Encodes a BLS 12-381 G1 or G2 public key as a multikey
+#[wasm_bindgen(js_name = encodeMultikey)]
+pub async fn encode_multikey_js(public_key: Vec<u8>) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    Ok(JsValue::from_str(&map_err!(encode_multikey(&public_key))?))
+}
+
+/// Decodes the BLS 12-381 G1 or G2 public key of a multikey or a `did:key` identifier
+#[wasm_bindgen(js_name = decodeMultikey)]
+pub async fn decode_multikey_js(multikey: String) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let public_key = map_err!(decode_multikey(&multikey))?;
+    Ok(js_sys::Uint8Array::from(public_key.as_slice()).into())
+}
+
+/// Creates the `did:key` document of a BLS 12-381 G1 or G2 public key given as bytes,
+/// a multikey or a `did:key` identifier
+#[wasm_bindgen(js_name = createDidKeyDocument)]
+pub async fn create_did_key_document(public_key: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let public_key: PublicKeyBytes = map_err!(serde_wasm_bindgen::from_value(public_key))?;
+    let document = map_err!(DidKeyDocument::new(&public_key.0))?;
+    Ok(serde_wasm_bindgen::to_value(&document).unwrap())
+}
+
+/// Deserializes a G2 public key from its bytes, a multikey or a `did:key` identifier
+pub(crate) fn deserialize_public_key<'de, D>(
+    deserializer: D,
+) -> Result<DeterministicPublicKey, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let public_key = PublicKeyBytes::deserialize(deserializer)?.0;
+    if public_key.len() != G2_COMPRESSED_SIZE {
+        return Err(DError::custom("Expected a BLS 12-381 G2 public key"));
+    }
+    DeterministicPublicKey::try_from(public_key.as_slice())
+        .map_err(|_| DError::custom("Invalid BLS 12-381 G2 public key"))
+}
+
+/// Public key bytes, given directly or as a multikey or `did:key` identifier
+struct PublicKeyBytes(Vec<u8>);
+
+impl<'de> Deserialize<'de> for PublicKeyBytes {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct PublicKeyVisitor;
+
+        impl<'de> Visitor<'de> for PublicKeyVisitor {
+            type Value = PublicKeyBytes;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("public key bytes, a multikey or a did:key identifier")
+            }
+
+            fn visit_bytes<E: DError>(self, value: &[u8]) -> Result<PublicKeyBytes, E> {
+                Ok(PublicKeyBytes(value.to_vec()))
+            }
+
+            fn visit_str<E: DError>(self, value: &str) -> Result<PublicKeyBytes, E> {
+                decode_multikey(value)
+                    .map(PublicKeyBytes)
+                    .map_err(|e| E::custom(format!("{:?}", e)))
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<PublicKeyBytes, A::Error> {
+                let mut bytes = Vec::new();
+                while let Some(b) = seq.next_element()? {
+                    bytes.push(b);
+                }
+                Ok(PublicKeyBytes(bytes))
+            }
+        }
+
+        deserializer.deserialize_any(PublicKeyVisitor)
+    }
+}
+
+fn base58_encode(bytes: &[u8]) -> String {
+    let zeros = bytes.iter().take_while(|b| **b == 0).count();
+    // Little endian base 58 digits of the number
+    let mut digits: Vec<u8> = Vec::with_capacity(bytes.len() * 138 / 100 + 1);
+    for b in &bytes[zeros..] {
+        let mut carry = *b as u32;
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+    let mut encoded = "1".repeat(zeros);
+    encoded.extend(
+        digits
+            .iter()
+            .rev()
+            .map(|d| BASE58_ALPHABET[*d as usize] as char),
+    );
+    encoded
+}
+
+fn base58_decode(encoded: &str) -> Result<Vec<u8>, BBSError> {
+    let zeros = encoded.bytes().take_while(|c| *c == b'1').count();
+    // Little endian base 256 digits of the number
+    let mut bytes: Vec<u8> = Vec::with_capacity(encoded.len());
+    for c in encoded.bytes().skip(zeros) {
+        let mut carry = match BASE58_ALPHABET.iter().position(|a| *a == c) {
+            Some(value) => value as u32,
+            None => return Err(general_error("The value is not base58btc encoded")),
+        };
+        for byte in bytes.iter_mut() {
+            carry += (*byte as u32) * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+    bytes.resize(bytes.len() + zeros, 0);
+    bytes.reverse();
+    Ok(bytes)
+}
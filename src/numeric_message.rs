@@ -0,0 +1,131 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Numeric messages, signed as the scalar of their value rather than hashed.
+//!
+//! A numeric message is `NUMERIC_MESSAGE_PREFIX || x` where `x` is the canonical 32
+//! byte big endian scalar of a signed 64 bit integer, negative integers being `r - |x|`
+//! for the group order `r`. The prefix is not valid UTF-8, so text messages are never
+//! numeric messages, and any other message is hashed. Arithmetic over the scalars of
+//! numeric messages, such as linear relations and comparisons, is arithmetic over their
+//! integer values.
+
+use crate::utils::set_panic_hook;
+
+use bbs::prelude::*;
+use ff_zeroize::{Field, PrimeField};
+use pairing_plus::bls12_381::{Fr, FrRepr};
+use std::convert::TryFrom;
+use wasm_bindgen::{prelude::*, JsCast};
+
+/// The prefix of a numeric message
+pub const NUMERIC_MESSAGE_PREFIX: &[u8] = b"\xffBBS_NUMERIC_MESSAGE";
+
+/// Encodes an integer given as a number, a bigint or a decimal string as a numeric
+/// message
+#[wasm_bindgen(js_name = encodeNumericMessage)]
+pub async fn encode_numeric_message(value: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let value = integer_value(&value)?;
+    Ok(js_sys::Uint8Array::from(numeric_message(value).as_slice()).into())
+}
+
+/// Decodes the integer of a numeric message as a bigint
+#[wasm_bindgen(js_name = decodeNumericMessage)]
+pub async fn decode_numeric_message(message: Vec<u8>) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    match numeric_value(&message) {
+        Some(value) => Ok(js_sys::BigInt::from(value).into()),
+        None => Err(JsValue::from("Not a numeric message")),
+    }
+}
+
+/// The numeric message of `value`
+pub fn numeric_message(value: i64) -> Vec<u8> {
+    let mut message = NUMERIC_MESSAGE_PREFIX.to_vec();
+    message.extend_from_slice(
+        &SignatureMessage::from(integer_to_fr(value)).to_bytes_compressed_form(),
+    );
+    message
+}
+
+/// The integer of a numeric message, or `None` for any other message
+pub fn numeric_value(message: &[u8]) -> Option<i64> {
+    let scalar = message.strip_prefix(NUMERIC_MESSAGE_PREFIX)?;
+    if scalar.len() != FR_COMPRESSED_SIZE {
+        return None;
+    }
+    let mut value = *SignatureMessage::try_from(scalar).ok()?.as_ref();
+    if let Some(value) = small_integer(&value) {
+        return i64::try_from(value).ok();
+    }
+    value.negate();
+    let magnitude = small_integer(&value)?;
+    if magnitude > i64::MIN.unsigned_abs() {
+        return None;
+    }
+    Some(-(magnitude as i128) as i64)
+}
+
+/// The scalar a message is signed as, its value for a numeric message and its hash
+/// otherwise
+pub(crate) fn message_scalar<T: AsRef<[u8]>>(message: T) -> SignatureMessage {
+    let message = message.as_ref();
+    match numeric_value(message) {
+        Some(value) => SignatureMessage::from(integer_to_fr(value)),
+        None => SignatureMessage::hash(message),
+    }
+}
+
+/// The scalar of an integer, `r - |value|` for a negative value
+pub(crate) fn integer_to_fr(value: i64) -> Fr {
+    let mut result = Fr::from_repr(FrRepr::from(value.unsigned_abs())).unwrap();
+    if value < 0 {
+        result.negate();
+    }
+    result
+}
+
+/// A scalar below `2^64` as an integer
+pub(crate) fn small_integer(value: &Fr) -> Option<u64> {
+    let repr = value.into_repr();
+    if repr.0[1..].iter().all(|limb| *limb == 0) {
+        Some(repr.0[0])
+    } else {
+        None
+    }
+}
+
+/// A JavaScript safe integer, a bigint or a canonical decimal string as an integer
+fn integer_value(value: &JsValue) -> Result<i64, JsValue> {
+    const MAX_SAFE_INTEGER: f64 = 9007199254740991.0;
+
+    if let Some(number) = value.as_f64() {
+        if number.fract() != 0.0 || number.abs() > MAX_SAFE_INTEGER {
+            return Err(JsValue::from("The number is not a safe integer"));
+        }
+        return Ok(number as i64);
+    }
+    let decimal = if let Some(bigint) = value.dyn_ref::<js_sys::BigInt>() {
+        String::from(bigint.to_string(10)?)
+    } else if let Some(decimal) = value.as_string() {
+        decimal
+    } else {
+        return Err(JsValue::from("The value is not an integer"));
+    };
+    match decimal.parse::<i64>() {
+        // Only the canonical form, without a sign for positive values or leading zeros
+        Ok(integer) if integer.to_string() == decimal => Ok(integer),
+        _ => Err(JsValue::from("The value is not a 64 bit integer")),
+    }
+}
@@ -0,0 +1,186 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Predicates over hidden messages.
+//!
+//! Besides revealing or hiding each message, the `notEqual` option of `createProof` asks
+//! for the hidden message at an index to not equal a public value. Each predicate
+//! commits to the hidden message and proves the commitment holds the signed message and
+//! that the committed message minus `H(value)` is invertible, so it is not zero. The
+//! `notEqual` option of `verifyProof` checks the same predicates in the same order.
+
+use crate::linked_proof;
+use crate::numeric_message::message_scalar;
+use crate::statements::{
+    write_point, write_scalar, ProverStatement, StatementReader, VerifierStatement,
+};
+use crate::utils::general_error;
+
+use bbs::prelude::*;
+use pairing_plus::bls12_381::G1;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use wasm_bindgen::prelude::*;
+
+const GENERATOR_DST: &[u8] = b"BBS_PREDICATE_GENERATOR_";
+
+wasm_impl!(
+    #[allow(non_snake_case)]
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    NotEqualPredicate,
+    messageIndex: usize,
+    value: Vec<u8>
+);
+
+/// Proves the hidden messages do not equal the public values
+pub(crate) struct NotEqualProver<'a> {
+    predicates: &'a [NotEqualPredicate],
+    proofs: Vec<(
+        linked_proof::MessageCommitment,
+        linked_proof::NotEqualCommitment,
+    )>,
+}
+
+impl<'a> NotEqualProver<'a> {
+    pub(crate) fn new(predicates: &'a [NotEqualPredicate]) -> Self {
+        Self {
+            predicates,
+            proofs: Vec::new(),
+        }
+    }
+}
+
+impl ProverStatement for NotEqualProver<'_> {
+    fn linked(&self) -> Vec<usize> {
+        self.predicates.iter().map(|p| p.messageIndex).collect()
+    }
+
+    fn commit(
+        &mut self,
+        messages: &[SignatureMessage],
+        blindings: &BTreeMap<usize, ProofNonce>,
+        bytes: &mut Vec<u8>,
+    ) -> Result<(), BBSError> {
+        let bases = linked_proof::generators(GENERATOR_DST);
+        self.proofs.clear();
+        for p in self.predicates {
+            let m = &messages[p.messageIndex];
+            let commitment =
+                linked_proof::MessageCommitment::new(&bases, m, &blindings[&p.messageIndex]);
+            let inequality = linked_proof::NotEqualCommitment::new(
+                &bases,
+                &commitment,
+                m,
+                &message_scalar(&p.value),
+            )
+            .ok_or_else(|| general_error("A hidden message equals its notEqual value"))?;
+            self.proofs.push((commitment, inequality));
+        }
+        let points: Vec<[G1; 3]> = self
+            .proofs
+            .iter()
+            .map(|(c, i)| [c.commitment, c.proof, i.commitment])
+            .collect();
+        bytes.append(&mut predicate_bytes(self.predicates, &points));
+        Ok(())
+    }
+
+    fn prove(&self, challenge: &ProofChallenge, proof: &mut Vec<u8>) {
+        for (commitment, inequality) in &self.proofs {
+            let (inverse_response, product_response) = inequality.responses(challenge);
+            write_point(proof, &commitment.commitment);
+            write_point(proof, &commitment.proof);
+            write_point(proof, &inequality.commitment);
+            write_scalar(proof, &commitment.response(challenge));
+            write_scalar(proof, &inverse_response);
+            write_scalar(proof, &product_response);
+        }
+    }
+}
+
+/// Checks the hidden messages do not equal the public values
+pub(crate) struct NotEqualVerifier<'a> {
+    predicates: &'a [NotEqualPredicate],
+    proofs: Vec<([G1; 3], [SignatureMessage; 3])>,
+}
+
+impl<'a> NotEqualVerifier<'a> {
+    pub(crate) fn new(predicates: &'a [NotEqualPredicate]) -> Self {
+        Self {
+            predicates,
+            proofs: Vec::new(),
+        }
+    }
+}
+
+impl VerifierStatement for NotEqualVerifier<'_> {
+    fn linked(&self) -> Vec<usize> {
+        self.predicates.iter().map(|p| p.messageIndex).collect()
+    }
+
+    fn read(&mut self, reader: &mut StatementReader, bytes: &mut Vec<u8>) -> Result<(), BBSError> {
+        self.proofs.clear();
+        for _ in self.predicates {
+            let points = [reader.point()?, reader.point()?, reader.point()?];
+            let responses = [reader.scalar()?, reader.scalar()?, reader.scalar()?];
+            self.proofs.push((points, responses));
+        }
+        let points: Vec<[G1; 3]> = self.proofs.iter().map(|p| p.0).collect();
+        bytes.append(&mut predicate_bytes(self.predicates, &points));
+        Ok(())
+    }
+
+    fn verify(
+        &self,
+        responses: &BTreeMap<usize, SignatureMessage>,
+        challenge: &ProofChallenge,
+    ) -> bool {
+        let bases = linked_proof::generators(GENERATOR_DST);
+        self.predicates.iter().zip(self.proofs.iter()).all(
+            |(p, ([commitment, proof, inequality], [blinding, inverse, product]))| {
+                linked_proof::verify_message_commitment(
+                    &bases,
+                    commitment,
+                    proof,
+                    &responses[&p.messageIndex],
+                    blinding,
+                    challenge,
+                ) && linked_proof::verify_not_equal(
+                    &bases,
+                    commitment,
+                    &message_scalar(&p.value),
+                    inequality,
+                    inverse,
+                    product,
+                    challenge,
+                )
+            },
+        )
+    }
+}
+
+/// The public values are hashed into the challenge with their message indices and
+/// commitments, so the proof is bound to the predicates it was created for
+fn predicate_bytes(predicates: &[NotEqualPredicate], not_equal: &[[G1; 3]]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for p in predicates {
+        bytes.extend_from_slice(&(p.messageIndex as u32).to_be_bytes());
+        bytes.extend_from_slice(&message_scalar(&p.value).to_bytes_compressed_form());
+    }
+    let [g, h] = linked_proof::generators(GENERATOR_DST);
+    linked_proof::append_points(&mut bytes, &[g, h]);
+    for points in not_equal {
+        linked_proof::append_points(&mut bytes, points);
+    }
+    bytes
+}
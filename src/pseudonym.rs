@@ -0,0 +1,138 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Per-verifier pseudonyms.
+//!
+//! The pseudonym of a holder for a scope is `nym = H(scope)^m_k` where `m_k` is a
+//! hidden signed message such as a link secret. It is stable for a scope but
+//! unlinkable across scopes. The `pseudonym` option of `createProof` proves it uses the
+//! signed value, and the `pseudonym` option of `verifyProof` checks it and outputs it.
+
+use crate::linked_proof;
+use crate::statements::{write_point, ProverStatement, StatementReader, VerifierStatement};
+use crate::BbsVerifyProofResponse;
+
+use bbs::prelude::*;
+use pairing_plus::{bls12_381::G1, CurveProjective};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use wasm_bindgen::prelude::*;
+
+const SCOPE_GENERATOR_DST: &[u8] = b"BBS_PSEUDONYM_SCOPE_GENERATOR_";
+
+wasm_impl!(PseudonymOptions, scope: Vec<u8>, secretIndex: usize);
+
+/// Proves the pseudonym for the scope is derived from the hidden secret
+pub(crate) struct PseudonymProver<'a> {
+    options: &'a PseudonymOptions,
+    pseudonym: G1,
+    commitment: G1,
+}
+
+impl<'a> PseudonymProver<'a> {
+    pub(crate) fn new(options: &'a PseudonymOptions) -> Self {
+        Self {
+            options,
+            pseudonym: G1::zero(),
+            commitment: G1::zero(),
+        }
+    }
+}
+
+impl ProverStatement for PseudonymProver<'_> {
+    fn linked(&self) -> Vec<usize> {
+        vec![self.options.secretIndex]
+    }
+
+    fn commit(
+        &mut self,
+        messages: &[SignatureMessage],
+        blindings: &BTreeMap<usize, ProofNonce>,
+        bytes: &mut Vec<u8>,
+    ) -> Result<(), BBSError> {
+        let generator = scope_generator(&self.options.scope);
+        let pseudonym =
+            linked_proof::multi_exp(&[generator], &[messages[self.options.secretIndex]]);
+        let commitment = linked_proof::multi_exp(
+            &[generator],
+            &[SignatureMessage::from(
+                *blindings[&self.options.secretIndex].as_ref(),
+            )],
+        );
+        linked_proof::append_points(bytes, &[generator, pseudonym, commitment]);
+        self.pseudonym = pseudonym;
+        self.commitment = commitment;
+        Ok(())
+    }
+
+    fn prove(&self, _challenge: &ProofChallenge, proof: &mut Vec<u8>) {
+        write_point(proof, &self.pseudonym);
+        write_point(proof, &self.commitment);
+    }
+}
+
+/// Checks the pseudonym for the scope is derived from the hidden secret, and outputs it
+pub(crate) struct PseudonymVerifier<'a> {
+    options: &'a PseudonymOptions,
+    generator: G1,
+    pseudonym: G1,
+    commitment: G1,
+}
+
+impl<'a> PseudonymVerifier<'a> {
+    pub(crate) fn new(options: &'a PseudonymOptions) -> Self {
+        Self {
+            options,
+            generator: scope_generator(&options.scope),
+            pseudonym: G1::zero(),
+            commitment: G1::zero(),
+        }
+    }
+}
+
+impl VerifierStatement for PseudonymVerifier<'_> {
+    fn linked(&self) -> Vec<usize> {
+        vec![self.options.secretIndex]
+    }
+
+    fn read(&mut self, reader: &mut StatementReader, bytes: &mut Vec<u8>) -> Result<(), BBSError> {
+        self.pseudonym = reader.point()?;
+        self.commitment = reader.point()?;
+        linked_proof::append_points(bytes, &[self.generator, self.pseudonym, self.commitment]);
+        Ok(())
+    }
+
+    fn verify(
+        &self,
+        responses: &BTreeMap<usize, SignatureMessage>,
+        challenge: &ProofChallenge,
+    ) -> bool {
+        !self.pseudonym.is_zero()
+            && linked_proof::recompute_commitment(
+                &[self.generator],
+                &[responses[&self.options.secretIndex]],
+                &self.pseudonym,
+                challenge,
+            ) == self.commitment
+    }
+
+    fn output(&self, response: &mut BbsVerifyProofResponse) {
+        response.pseudonym = Some(Commitment::from(self.pseudonym));
+    }
+}
+
+fn scope_generator(scope: &[u8]) -> G1 {
+    let mut data = SCOPE_GENERATOR_DST.to_vec();
+    data.extend_from_slice(scope);
+    *GeneratorG1::hash(data).as_ref()
+}
@@ -0,0 +1,296 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Range proofs for values derived from hidden messages.
+//!
+//! A value `d` in `[0, 2^n)` is committed to bit by bit as `C_t = g^b_t * h^s_t`, and
+//! each `C_t` is proven to commit to 0 or 1 with an OR of the proofs of knowledge of
+//! `log_h(C_t)` and `log_h(C_t / g)`. Then `D = prod(C_t^(2^t)) = g^d * h^s` for
+//! `s = sum(2^t * s_t)`, and a proof of knowledge of `d` and `s` in `D`, where the
+//! blinding of `d` is derived from the blindings of the hidden messages in the signature
+//! proofs, proves `d` is the value computed from the hidden messages.
+
+use crate::linked_proof;
+use crate::numeric_message::small_integer;
+use crate::statements::{write_point, write_scalar, StatementReader};
+
+use bbs::prelude::*;
+use ff_zeroize::Field;
+use pairing_plus::{
+    bls12_381::{Fr, G1},
+    CurveProjective,
+};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+/// The number of bits of the values in the range proofs of comparisons
+pub(crate) const RANGE_BITS: usize = 64;
+const GENERATOR_DST: &[u8] = b"BBS_RANGE_PROOF_GENERATOR_";
+
+wasm_impl!(
+    /// The proof that a commitment `C = g^b * h^s` commits to a bit `b`, the challenge
+    /// of the one branch is the proof challenge minus `zeroChallenge`
+    #[allow(non_snake_case)]
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    BitProof,
+    commitment: Commitment,
+    zeroCommitment: Commitment,
+    oneCommitment: Commitment,
+    zeroChallenge: SignatureMessage,
+    zeroResponse: SignatureMessage,
+    oneResponse: SignatureMessage
+);
+
+wasm_impl!(
+    /// The proof that a value is in `[0, 2^n)` for `n` bit proofs
+    #[allow(non_snake_case)]
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    RangeProof,
+    bits: Vec<BitProof>,
+    commitment: Commitment,
+    blindingResponse: SignatureMessage
+);
+
+impl RangeProof {
+    /// Append the commitments to hash into the challenge
+    pub(crate) fn append_bytes(&self, bytes: &mut Vec<u8>) {
+        for bit in &self.bits {
+            linked_proof::append_points(
+                bytes,
+                &[
+                    *bit.commitment.as_ref(),
+                    *bit.zeroCommitment.as_ref(),
+                    *bit.oneCommitment.as_ref(),
+                ],
+            );
+        }
+        linked_proof::append_points(bytes, &[*self.commitment.as_ref()]);
+    }
+
+    /// Append the proof to a statement proof
+    pub(crate) fn write(&self, proof: &mut Vec<u8>) {
+        for bit in &self.bits {
+            write_point(proof, bit.commitment.as_ref());
+            write_point(proof, bit.zeroCommitment.as_ref());
+            write_point(proof, bit.oneCommitment.as_ref());
+            write_scalar(proof, &bit.zeroChallenge);
+            write_scalar(proof, &bit.zeroResponse);
+            write_scalar(proof, &bit.oneResponse);
+        }
+        write_point(proof, self.commitment.as_ref());
+        write_scalar(proof, &self.blindingResponse);
+    }
+
+    /// Read a proof of a value of `bit_count` bits written with `write`
+    pub(crate) fn read(reader: &mut StatementReader, bit_count: usize) -> Result<Self, BBSError> {
+        let mut bits = Vec::with_capacity(bit_count);
+        for _ in 0..bit_count {
+            bits.push(BitProof {
+                commitment: Commitment::from(reader.point()?),
+                zeroCommitment: Commitment::from(reader.point()?),
+                oneCommitment: Commitment::from(reader.point()?),
+                zeroChallenge: reader.scalar()?,
+                zeroResponse: reader.scalar()?,
+                oneResponse: reader.scalar()?,
+            });
+        }
+        Ok(Self {
+            bits,
+            commitment: Commitment::from(reader.point()?),
+            blindingResponse: reader.scalar()?,
+        })
+    }
+
+    /// Check the proof of a value of `bit_count` bits for the response `value_response`
+    /// of the value, which the caller derives from the responses of the hidden messages
+    pub(crate) fn verify(
+        &self,
+        bit_count: usize,
+        value_response: &SignatureMessage,
+        challenge: &ProofChallenge,
+    ) -> bool {
+        if self.bits.len() != bit_count {
+            return false;
+        }
+        let [g, h] = linked_proof::generators(GENERATOR_DST);
+        let mut value_commitment = G1::zero();
+        for bit in self.bits.iter().rev() {
+            value_commitment.double();
+            value_commitment.add_assign(bit.commitment.as_ref());
+
+            // C and C / g for the bit 0 and 1 branches
+            let zero = *bit.commitment.as_ref();
+            let mut one = zero;
+            one.sub_assign(&g);
+            let mut one_challenge = *challenge.as_ref();
+            one_challenge.sub_assign(bit.zeroChallenge.as_ref());
+            if linked_proof::multi_exp(&[h, zero], &[bit.zeroResponse, bit.zeroChallenge])
+                != *bit.zeroCommitment.as_ref()
+                || linked_proof::multi_exp(
+                    &[h, one],
+                    &[bit.oneResponse, SignatureMessage::from(one_challenge)],
+                ) != *bit.oneCommitment.as_ref()
+            {
+                return false;
+            }
+        }
+        linked_proof::recompute_commitment(
+            &[g, h],
+            &[*value_response, self.blindingResponse],
+            &value_commitment,
+            challenge,
+        ) == *self.commitment.as_ref()
+    }
+}
+
+struct BitProver {
+    commitment: G1,
+    blinding: SignatureMessage,
+    bit: bool,
+    nonce: ProofNonce,
+    simulated_challenge: SignatureMessage,
+    simulated_response: SignatureMessage,
+    commitments: [G1; 2],
+}
+
+/// Proves a value computed from hidden messages is in `[0, 2^n)`
+pub(crate) struct RangeProver {
+    bits: Vec<BitProver>,
+    blinding: SignatureMessage,
+    blinding_nonce: ProofNonce,
+    commitment: G1,
+}
+
+impl RangeProver {
+    /// `None` when the value is not in the range of `bit_count` bits, at most 64.
+    /// `value_blinding` is the blinding of the value derived from the blindings of the
+    /// hidden messages
+    pub(crate) fn new(value: &Fr, value_blinding: &Fr, bit_count: usize) -> Option<Self> {
+        let value = small_integer(value)?;
+        if bit_count < RANGE_BITS && value >> bit_count != 0 {
+            return None;
+        }
+        let [g, h] = linked_proof::generators(GENERATOR_DST);
+        let mut blinding = Fr::zero();
+        let mut power = Fr::one();
+        let mut bits = Vec::with_capacity(bit_count);
+        for t in 0..bit_count {
+            let bit = (value >> t) & 1 == 1;
+            let bit_blinding = SignatureMessage::random();
+            let mut term = *bit_blinding.as_ref();
+            term.mul_assign(&power);
+            blinding.add_assign(&term);
+            power.double();
+
+            let mut commitment = linked_proof::multi_exp(&[h], &[bit_blinding]);
+            if bit {
+                commitment.add_assign(&g);
+            }
+            let mut one = commitment;
+            one.sub_assign(&g);
+            // The branch of the bit is proven with the nonce and the other is simulated
+            let nonce = ProofNonce::random();
+            let simulated_challenge = SignatureMessage::random();
+            let simulated_response = SignatureMessage::random();
+            let real = linked_proof::multi_exp(&[h], &[SignatureMessage::from(*nonce.as_ref())]);
+            let simulated = linked_proof::multi_exp(
+                &[h, if bit { commitment } else { one }],
+                &[simulated_response, simulated_challenge],
+            );
+            bits.push(BitProver {
+                commitment,
+                blinding: bit_blinding,
+                bit,
+                nonce,
+                simulated_challenge,
+                simulated_response,
+                commitments: if bit {
+                    [simulated, real]
+                } else {
+                    [real, simulated]
+                },
+            });
+        }
+        let blinding_nonce = ProofNonce::random();
+        let commitment = linked_proof::multi_exp(
+            &[g, h],
+            &[
+                SignatureMessage::from(*value_blinding),
+                SignatureMessage::from(*blinding_nonce.as_ref()),
+            ],
+        );
+        Some(Self {
+            bits,
+            blinding: SignatureMessage::from(blinding),
+            blinding_nonce,
+            commitment,
+        })
+    }
+
+    /// Append the commitments to hash into the challenge
+    pub(crate) fn append_bytes(&self, bytes: &mut Vec<u8>) {
+        for bit in &self.bits {
+            linked_proof::append_points(
+                bytes,
+                &[bit.commitment, bit.commitments[0], bit.commitments[1]],
+            );
+        }
+        linked_proof::append_points(bytes, &[self.commitment]);
+    }
+
+    pub(crate) fn prove(&self, challenge: &ProofChallenge) -> RangeProof {
+        let bits = self
+            .bits
+            .iter()
+            .map(|bit| {
+                let mut real_challenge = *challenge.as_ref();
+                real_challenge.sub_assign(bit.simulated_challenge.as_ref());
+                let real_response = linked_proof::response(
+                    &bit.nonce,
+                    &ProofChallenge::from(real_challenge),
+                    &bit.blinding,
+                );
+                let (zero_challenge, zero_response, one_response) = if bit.bit {
+                    (
+                        bit.simulated_challenge,
+                        bit.simulated_response,
+                        real_response,
+                    )
+                } else {
+                    (
+                        SignatureMessage::from(real_challenge),
+                        real_response,
+                        bit.simulated_response,
+                    )
+                };
+                BitProof {
+                    commitment: Commitment::from(bit.commitment),
+                    zeroCommitment: Commitment::from(bit.commitments[0]),
+                    oneCommitment: Commitment::from(bit.commitments[1]),
+                    zeroChallenge: zero_challenge,
+                    zeroResponse: zero_response,
+                    oneResponse: one_response,
+                }
+            })
+            .collect();
+        RangeProof {
+            bits,
+            commitment: Commitment::from(self.commitment),
+            blindingResponse: linked_proof::response(
+                &self.blinding_nonce,
+                challenge,
+                &self.blinding,
+            ),
+        }
+    }
+}
@@ -0,0 +1,331 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Rate-limited presentations with epoch bound one-show tags.
+//!
+//! For a hidden signed secret `s`, the tag of counter `j` in an epoch is
+//! `T = H(epoch, j)^s`. The `rateLimit` option of `createProof` commits to the secret
+//! as `K = g^s * h^r` and proves with an OR-composition that `K = g^s * h^r` and
+//! `T = H(epoch, j)^s` for one `j < limit`, without revealing `j`. A holder has `limit`
+//! distinct tags per epoch, so a tag seen twice in an epoch is a reuse, while tags of
+//! different epochs are unlinkable. The `rateLimit` option of `verifyProof` checks the
+//! proof and outputs the tag, which the verifier must check has not been seen before
+//! in the epoch.
+
+use crate::linked_proof;
+use crate::statements::{
+    write_point, write_scalar, ProverStatement, StatementReader, VerifierStatement,
+};
+use crate::utils::general_error;
+use crate::BbsVerifyProofResponse;
+
+use bbs::prelude::*;
+use ff_zeroize::Field;
+use pairing_plus::{
+    bls12_381::{Fr, G1},
+    CurveProjective,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use wasm_bindgen::prelude::*;
+
+const GENERATOR_DST: &[u8] = b"BBS_RATE_LIMIT_GENERATOR_";
+const TAG_GENERATOR_DST: &[u8] = b"BBS_RATE_LIMIT_TAG_GENERATOR_";
+
+wasm_impl!(
+    RateLimitProofOptions,
+    secretIndex: usize,
+    epoch: Vec<u8>,
+    counter: usize,
+    limit: usize
+);
+
+wasm_impl!(
+    RateLimitVerifyOptions,
+    secretIndex: usize,
+    epoch: Vec<u8>,
+    limit: usize
+);
+
+/// One branch of the OR-composition, with the commitments for `K` and the tag, the
+/// branch challenge and the responses for the secret and the blinding of `K`
+type RateLimitBranch = ([G1; 2], ProofChallenge, [SignatureMessage; 2]);
+
+/// Proves the tag for the counter in the epoch is derived from the hidden secret,
+/// and that the counter is below the limit
+pub(crate) struct RateLimitProver<'a> {
+    options: &'a RateLimitProofOptions,
+    secret: SignatureMessage,
+    tag: G1,
+    commitment: Option<linked_proof::MessageCommitment>,
+    secret_blinding: ProofNonce,
+    blinding_blinding: ProofNonce,
+    branches: Vec<RateLimitBranch>,
+}
+
+impl<'a> RateLimitProver<'a> {
+    pub(crate) fn new(options: &'a RateLimitProofOptions) -> Self {
+        Self {
+            options,
+            secret: SignatureMessage::default(),
+            tag: G1::zero(),
+            commitment: None,
+            secret_blinding: ProofNonce::random(),
+            blinding_blinding: ProofNonce::random(),
+            branches: Vec::new(),
+        }
+    }
+}
+
+impl ProverStatement for RateLimitProver<'_> {
+    fn linked(&self) -> Vec<usize> {
+        vec![self.options.secretIndex]
+    }
+
+    fn commit(
+        &mut self,
+        messages: &[SignatureMessage],
+        blindings: &BTreeMap<usize, ProofNonce>,
+        bytes: &mut Vec<u8>,
+    ) -> Result<(), BBSError> {
+        let counter = self.options.counter;
+        if counter >= self.options.limit {
+            return Err(general_error("counter must be less than limit"));
+        }
+        let s = messages[self.options.secretIndex];
+        let bases = linked_proof::generators(GENERATOR_DST);
+        let commitment =
+            linked_proof::MessageCommitment::new(&bases, &s, &blindings[&self.options.secretIndex]);
+        let tag_generators = tag_generators(&self.options.epoch, self.options.limit);
+        let tag = linked_proof::multi_exp(&[tag_generators[counter]], &[s]);
+
+        // The branches other than the counter are simulated from a random challenge and responses
+        self.branches.clear();
+        for (i, tag_generator) in tag_generators.iter().enumerate() {
+            if i == counter {
+                let secret_blinding = SignatureMessage::from(*self.secret_blinding.as_ref());
+                let blinding_blinding = SignatureMessage::from(*self.blinding_blinding.as_ref());
+                self.branches.push((
+                    [
+                        linked_proof::multi_exp(&bases, &[secret_blinding, blinding_blinding]),
+                        linked_proof::multi_exp(&[*tag_generator], &[secret_blinding]),
+                    ],
+                    ProofChallenge::default(),
+                    [SignatureMessage::default(); 2],
+                ));
+            } else {
+                let c = ProofChallenge::random();
+                let responses = [SignatureMessage::random(), SignatureMessage::random()];
+                self.branches.push((
+                    [
+                        linked_proof::recompute_commitment(
+                            &bases,
+                            &responses,
+                            &commitment.commitment,
+                            &c,
+                        ),
+                        linked_proof::recompute_commitment(
+                            &[*tag_generator],
+                            &responses[..1],
+                            &tag,
+                            &c,
+                        ),
+                    ],
+                    c,
+                    responses,
+                ));
+            }
+        }
+
+        bytes.append(&mut rate_limit_bytes(
+            &tag_generators,
+            &tag,
+            &commitment.commitment,
+            &commitment.proof,
+            &self.branches,
+        ));
+        self.secret = s;
+        self.tag = tag;
+        self.commitment = Some(commitment);
+        Ok(())
+    }
+
+    fn prove(&self, challenge: &ProofChallenge, proof: &mut Vec<u8>) {
+        let commitment = self.commitment.as_ref().unwrap();
+        let counter = self.options.counter;
+        let mut counter_challenge = *challenge.as_ref();
+        for (i, b) in self.branches.iter().enumerate() {
+            if i != counter {
+                counter_challenge.sub_assign(b.1.as_ref());
+            }
+        }
+        let counter_challenge = ProofChallenge::from(counter_challenge);
+
+        write_point(proof, &self.tag);
+        write_point(proof, &commitment.commitment);
+        write_point(proof, &commitment.proof);
+        write_scalar(proof, &commitment.response(challenge));
+        for (i, (commitments, c, responses)) in self.branches.iter().enumerate() {
+            let (c, responses) = if i == counter {
+                (
+                    counter_challenge,
+                    [
+                        linked_proof::response(
+                            &self.secret_blinding,
+                            &counter_challenge,
+                            &self.secret,
+                        ),
+                        linked_proof::response(
+                            &self.blinding_blinding,
+                            &counter_challenge,
+                            &commitment.blinding,
+                        ),
+                    ],
+                )
+            } else {
+                (*c, *responses)
+            };
+            write_point(proof, &commitments[0]);
+            write_point(proof, &commitments[1]);
+            write_scalar(proof, &SignatureMessage::from(*c.as_ref()));
+            write_scalar(proof, &responses[0]);
+            write_scalar(proof, &responses[1]);
+        }
+    }
+}
+
+/// Checks the tag is derived from the hidden secret for a counter below the limit in
+/// the epoch, and outputs it
+pub(crate) struct RateLimitVerifier<'a> {
+    options: &'a RateLimitVerifyOptions,
+    tag_generators: Vec<G1>,
+    tag: G1,
+    commitment: [G1; 2],
+    blinding_response: SignatureMessage,
+    branches: Vec<RateLimitBranch>,
+}
+
+impl<'a> RateLimitVerifier<'a> {
+    pub(crate) fn new(options: &'a RateLimitVerifyOptions) -> Self {
+        Self {
+            options,
+            tag_generators: tag_generators(&options.epoch, options.limit),
+            tag: G1::zero(),
+            commitment: [G1::zero(); 2],
+            blinding_response: SignatureMessage::default(),
+            branches: Vec::new(),
+        }
+    }
+}
+
+impl VerifierStatement for RateLimitVerifier<'_> {
+    fn linked(&self) -> Vec<usize> {
+        vec![self.options.secretIndex]
+    }
+
+    fn read(&mut self, reader: &mut StatementReader, bytes: &mut Vec<u8>) -> Result<(), BBSError> {
+        self.tag = reader.point()?;
+        self.commitment = [reader.point()?, reader.point()?];
+        self.blinding_response = reader.scalar()?;
+        self.branches.clear();
+        for _ in &self.tag_generators {
+            let commitments = [reader.point()?, reader.point()?];
+            let c = ProofChallenge::from(*reader.scalar()?.as_ref());
+            let responses = [reader.scalar()?, reader.scalar()?];
+            self.branches.push((commitments, c, responses));
+        }
+        bytes.append(&mut rate_limit_bytes(
+            &self.tag_generators,
+            &self.tag,
+            &self.commitment[0],
+            &self.commitment[1],
+            &self.branches,
+        ));
+        Ok(())
+    }
+
+    fn verify(
+        &self,
+        responses: &BTreeMap<usize, SignatureMessage>,
+        challenge: &ProofChallenge,
+    ) -> bool {
+        if self.options.limit == 0 || self.tag.is_zero() {
+            return false;
+        }
+        let bases = linked_proof::generators(GENERATOR_DST);
+        let [commitment, commitment_proof] = &self.commitment;
+        if !linked_proof::verify_message_commitment(
+            &bases,
+            commitment,
+            commitment_proof,
+            &responses[&self.options.secretIndex],
+            &self.blinding_response,
+            challenge,
+        ) {
+            return false;
+        }
+        let mut challenge_sum = Fr::zero();
+        for (tag_generator, (commitments, c, branch_responses)) in
+            self.tag_generators.iter().zip(self.branches.iter())
+        {
+            if linked_proof::recompute_commitment(&bases, branch_responses, commitment, c)
+                != commitments[0]
+                || linked_proof::recompute_commitment(
+                    &[*tag_generator],
+                    &branch_responses[..1],
+                    &self.tag,
+                    c,
+                ) != commitments[1]
+            {
+                return false;
+            }
+            challenge_sum.add_assign(c.as_ref());
+        }
+        &challenge_sum == challenge.as_ref()
+    }
+
+    fn output(&self, response: &mut BbsVerifyProofResponse) {
+        response.tag = Some(Commitment::from(self.tag));
+    }
+}
+
+/// `H(epoch, j)` for every counter `j < limit`
+fn tag_generators(epoch: &[u8], limit: usize) -> Vec<G1> {
+    (0..limit)
+        .map(|j| {
+            let mut data = TAG_GENERATOR_DST.to_vec();
+            data.extend_from_slice(&(epoch.len() as u64).to_be_bytes());
+            data.extend_from_slice(epoch);
+            data.extend_from_slice(&(j as u64).to_be_bytes());
+            *GeneratorG1::hash(data).as_ref()
+        })
+        .collect()
+}
+
+fn rate_limit_bytes(
+    tag_generators: &[G1],
+    tag: &G1,
+    commitment: &G1,
+    commitment_proof: &G1,
+    branches: &[RateLimitBranch],
+) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    let [g, h] = linked_proof::generators(GENERATOR_DST);
+    linked_proof::append_points(&mut bytes, &[g, h]);
+    linked_proof::append_points(&mut bytes, tag_generators);
+    linked_proof::append_points(&mut bytes, &[*tag, *commitment, *commitment_proof]);
+    for b in branches {
+        linked_proof::append_points(&mut bytes, &b.0);
+    }
+    bytes
+}
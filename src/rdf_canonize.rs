@@ -0,0 +1,435 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! RDF Dataset Canonicalization (RDFC-1.0) of N-Quads.
+//!
+//! Blank nodes are labelled `c14n0`, `c14n1`, ... from the hashes of the quads they
+//! appear in, so isomorphic datasets serialize to the same canonical N-Quads.
+
+use crate::utils::general_error;
+
+use bbs::prelude::*;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+const SKOLEM_PREFIX: &str = "urn:bnid:";
+/// Bound on the Hash N-Degree Quads calls so poisoned datasets fail instead of hanging
+const MAX_N_DEGREE_CALLS: usize = 4096;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Term {
+    Iri(String),
+    BlankNode(String),
+    Literal {
+        value: String,
+        datatype: Option<String>,
+        language: Option<String>,
+    },
+    DefaultGraph,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Quad {
+    pub(crate) subject: Term,
+    pub(crate) predicate: Term,
+    pub(crate) object: Term,
+    pub(crate) graph: Term,
+}
+
+impl Quad {
+    fn terms(&self) -> [(&Term, &str); 3] {
+        [
+            (&self.subject, "s"),
+            (&self.object, "o"),
+            (&self.graph, "g"),
+        ]
+    }
+
+    /// Replace the blank node labels with `f(label)`
+    pub(crate) fn relabel<F: FnMut(&str) -> String>(&self, mut f: F) -> Quad {
+        let mut relabel = |t: &Term| match t {
+            Term::BlankNode(b) => Term::BlankNode(f(b)),
+            t => t.clone(),
+        };
+        Quad {
+            subject: relabel(&self.subject),
+            predicate: self.predicate.clone(),
+            object: relabel(&self.object),
+            graph: relabel(&self.graph),
+        }
+    }
+
+    /// Replace the blank nodes with `<prefix + label>` IRIs
+    pub(crate) fn skolemize(&self, prefix: &str) -> Quad {
+        let skolemize = |t: &Term| match t {
+            Term::BlankNode(b) => Term::Iri(format!("{}{}", prefix, b)),
+            t => t.clone(),
+        };
+        Quad {
+            subject: skolemize(&self.subject),
+            predicate: self.predicate.clone(),
+            object: skolemize(&self.object),
+            graph: skolemize(&self.graph),
+        }
+    }
+
+    /// Replace the `<urn:bnid:label>` IRIs of skolemized blank nodes with the blank nodes
+    pub(crate) fn deskolemize(&self) -> Quad {
+        Quad {
+            subject: deskolemize(self.subject.clone()),
+            predicate: self.predicate.clone(),
+            object: deskolemize(self.object.clone()),
+            graph: deskolemize(self.graph.clone()),
+        }
+    }
+
+    /// The canonical N-Quads line, including the trailing newline
+    pub(crate) fn to_nquad(&self) -> String {
+        let mut s = String::new();
+        for t in &[&self.subject, &self.predicate, &self.object, &self.graph] {
+            if **t == Term::DefaultGraph {
+                continue;
+            }
+            write_term(&mut s, t);
+            s.push(' ');
+        }
+        s.push_str(".\n");
+        s
+    }
+}
+
+/// Keeps the order in which identifiers were issued
+#[derive(Clone, Debug)]
+pub(crate) struct IdentifierIssuer {
+    prefix: &'static str,
+    issued: Vec<(String, String)>,
+    index: HashMap<String, usize>,
+}
+
+impl IdentifierIssuer {
+    fn new(prefix: &'static str) -> Self {
+        Self {
+            prefix,
+            issued: Vec::new(),
+            index: HashMap::new(),
+        }
+    }
+
+    fn issue(&mut self, existing: &str) -> String {
+        if let Some(id) = self.get(existing) {
+            return id.to_string();
+        }
+        let id = format!("{}{}", self.prefix, self.issued.len());
+        self.index.insert(existing.to_string(), self.issued.len());
+        self.issued.push((existing.to_string(), id.clone()));
+        id
+    }
+
+    pub(crate) fn get(&self, existing: &str) -> Option<&str> {
+        self.index.get(existing).map(|i| self.issued[*i].1.as_str())
+    }
+
+    /// The `(existing, issued)` identifiers in the order they were issued
+    pub(crate) fn issued(&self) -> &[(String, String)] {
+        &self.issued
+    }
+}
+
+/// Canonicalize a dataset, returning the quads relabelled with the canonical
+/// identifiers in code point order and the issuer mapping the input labels to them
+pub(crate) fn canonicalize(quads: &[Quad]) -> Result<(Vec<Quad>, IdentifierIssuer), BBSError> {
+    let mut state = State {
+        blank_nodes: BTreeMap::new(),
+        canonical: IdentifierIssuer::new("c14n"),
+        calls: 0,
+    };
+    for (i, quad) in quads.iter().enumerate() {
+        for (term, _) in quad.terms().iter() {
+            if let Term::BlankNode(b) = term {
+                let entry = state.blank_nodes.entry(b.clone()).or_default();
+                if entry.last() != Some(&i) {
+                    entry.push(i);
+                }
+            }
+        }
+    }
+
+    let mut hash_to_blank_nodes: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for b in state.blank_nodes.keys() {
+        hash_to_blank_nodes
+            .entry(state.hash_first_degree(quads, b))
+            .or_default()
+            .push(b.clone());
+    }
+
+    let mut non_unique = Vec::new();
+    for (_, blank_nodes) in hash_to_blank_nodes {
+        if blank_nodes.len() == 1 {
+            state.canonical.issue(&blank_nodes[0]);
+        } else {
+            non_unique.push(blank_nodes);
+        }
+    }
+
+    for blank_nodes in non_unique {
+        let mut hash_paths = Vec::new();
+        for b in &blank_nodes {
+            if state.canonical.get(b).is_some() {
+                continue;
+            }
+            let mut issuer = IdentifierIssuer::new("b");
+            issuer.issue(b);
+            hash_paths.push(state.hash_n_degree(quads, b, issuer)?);
+        }
+        hash_paths.sort_by(|a, b| a.0.cmp(&b.0));
+        for (_, issuer) in hash_paths {
+            for (existing, _) in issuer.issued() {
+                state.canonical.issue(existing);
+            }
+        }
+    }
+
+    let canonical = state.canonical;
+    let mut result: Vec<Quad> = quads
+        .iter()
+        .map(|q| q.relabel(|b| canonical.get(b).unwrap().to_string()))
+        .collect();
+    sort_quads(&mut result);
+    Ok((result, canonical))
+}
+
+/// Sort quads in the code point order of their N-Quads
+pub(crate) fn sort_quads(quads: &mut [Quad]) {
+    quads.sort_by_cached_key(|q| q.to_nquad());
+}
+
+struct State {
+    blank_nodes: BTreeMap<String, Vec<usize>>,
+    canonical: IdentifierIssuer,
+    calls: usize,
+}
+
+impl State {
+    fn hash_first_degree(&self, quads: &[Quad], reference: &str) -> String {
+        let mut nquads: Vec<String> = self.blank_nodes[reference]
+            .iter()
+            .map(|i| {
+                quads[*i]
+                    .relabel(|b| if b == reference { "a" } else { "z" }.to_string())
+                    .to_nquad()
+            })
+            .collect();
+        nquads.sort();
+        hash(&nquads.concat())
+    }
+
+    fn hash_related(
+        &self,
+        quads: &[Quad],
+        related: &str,
+        quad: &Quad,
+        issuer: &IdentifierIssuer,
+        position: &str,
+    ) -> String {
+        let mut input = position.to_string();
+        if position != "g" {
+            write_term(&mut input, &quad.predicate);
+        }
+        if let Some(id) = self.canonical.get(related).or_else(|| issuer.get(related)) {
+            input.push_str("_:");
+            input.push_str(id);
+        } else {
+            input.push_str(&self.hash_first_degree(quads, related));
+        }
+        hash(&input)
+    }
+
+    fn hash_n_degree(
+        &mut self,
+        quads: &[Quad],
+        identifier: &str,
+        mut issuer: IdentifierIssuer,
+    ) -> Result<(String, IdentifierIssuer), BBSError> {
+        self.calls += 1;
+        if self.calls > MAX_N_DEGREE_CALLS {
+            return Err(general_error("Maximum canonicalization work exceeded"));
+        }
+
+        let mut related_hashes: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        for i in &self.blank_nodes[identifier] {
+            let quad = &quads[*i];
+            for (term, position) in quad.terms().iter() {
+                if let Term::BlankNode(related) = term {
+                    if related != identifier {
+                        let h = self.hash_related(quads, related, quad, &issuer, position);
+                        related_hashes.entry(h).or_default().insert(related.clone());
+                    }
+                }
+            }
+        }
+
+        let mut data_to_hash = String::new();
+        for (related_hash, blank_nodes) in related_hashes {
+            data_to_hash.push_str(&related_hash);
+            let blank_nodes: Vec<String> = blank_nodes.into_iter().collect();
+            let mut chosen_path = String::new();
+            let mut chosen_issuer = None;
+            let mut permutation: Vec<usize> = (0..blank_nodes.len()).collect();
+            loop {
+                if let Some((path, issuer_copy)) =
+                    self.permutation_path(quads, &permutation, &blank_nodes, &issuer, &chosen_path)?
+                {
+                    if chosen_issuer.is_none() || path < chosen_path {
+                        chosen_path = path;
+                        chosen_issuer = Some(issuer_copy);
+                    }
+                }
+                if !next_permutation(&mut permutation) {
+                    break;
+                }
+            }
+            data_to_hash.push_str(&chosen_path);
+            issuer = chosen_issuer.unwrap();
+        }
+        Ok((hash(&data_to_hash), issuer))
+    }
+
+    /// The path of a permutation of related blank nodes, or `None` when it cannot
+    /// be shorter than `chosen_path`
+    fn permutation_path(
+        &mut self,
+        quads: &[Quad],
+        permutation: &[usize],
+        blank_nodes: &[String],
+        issuer: &IdentifierIssuer,
+        chosen_path: &str,
+    ) -> Result<Option<(String, IdentifierIssuer)>, BBSError> {
+        let worse = |path: &str| {
+            !chosen_path.is_empty() && path.len() >= chosen_path.len() && path > chosen_path
+        };
+        let mut issuer_copy = issuer.clone();
+        let mut path = String::new();
+        let mut recursion_list = Vec::new();
+        for related in permutation.iter().map(|i| &blank_nodes[*i]) {
+            if let Some(id) = self.canonical.get(related) {
+                path.push_str("_:");
+                path.push_str(id);
+            } else {
+                if issuer_copy.get(related).is_none() {
+                    recursion_list.push(related);
+                }
+                path.push_str("_:");
+                path.push_str(&issuer_copy.issue(related));
+            }
+            if worse(&path) {
+                return Ok(None);
+            }
+        }
+        for related in recursion_list {
+            let (result_hash, result_issuer) =
+                self.hash_n_degree(quads, related, issuer_copy.clone())?;
+            path.push_str("_:");
+            path.push_str(&issuer_copy.issue(related));
+            path.push('<');
+            path.push_str(&result_hash);
+            path.push('>');
+            issuer_copy = result_issuer;
+            if worse(&path) {
+                return Ok(None);
+            }
+        }
+        Ok(Some((path, issuer_copy)))
+    }
+}
+
+fn deskolemize(term: Term) -> Term {
+    match term {
+        Term::Iri(iri) if iri.starts_with(SKOLEM_PREFIX) => {
+            let label = &iri[SKOLEM_PREFIX.len()..];
+            Term::BlankNode(label.strip_prefix("_:").unwrap_or(label).to_string())
+        }
+        t => t,
+    }
+}
+
+fn write_term(s: &mut String, term: &Term) {
+    match term {
+        Term::Iri(iri) => {
+            s.push('<');
+            s.push_str(iri);
+            s.push('>');
+        }
+        Term::BlankNode(b) => {
+            s.push_str("_:");
+            s.push_str(b);
+        }
+        Term::Literal {
+            value,
+            datatype,
+            language,
+        } => {
+            s.push('"');
+            for c in value.chars() {
+                match c {
+                    '\u{8}' => s.push_str("\\b"),
+                    '\t' => s.push_str("\\t"),
+                    '\n' => s.push_str("\\n"),
+                    '\u{c}' => s.push_str("\\f"),
+                    '\r' => s.push_str("\\r"),
+                    '"' => s.push_str("\\\""),
+                    '\\' => s.push_str("\\\\"),
+                    '\u{0}'..='\u{1f}' | '\u{7f}' => s.push_str(&format!("\\u{:04X}", c as u32)),
+                    c => s.push(c),
+                }
+            }
+            s.push('"');
+            if let Some(language) = language {
+                s.push('@');
+                s.push_str(language);
+            } else if let Some(datatype) = datatype {
+                s.push_str("^^<");
+                s.push_str(datatype);
+                s.push('>');
+            }
+        }
+        Term::DefaultGraph => {}
+    }
+}
+
+/// The next permutation in lexicographic order, `false` after the last one
+fn next_permutation(p: &mut [usize]) -> bool {
+    if p.len() < 2 {
+        return false;
+    }
+    let mut i = p.len() - 1;
+    while i > 0 && p[i - 1] >= p[i] {
+        i -= 1;
+    }
+    if i == 0 {
+        return false;
+    }
+    let mut j = p.len() - 1;
+    while p[j] <= p[i - 1] {
+        j -= 1;
+    }
+    p.swap(i - 1, j);
+    p[i..].reverse();
+    true
+}
+
+pub(crate) fn hash(input: &str) -> String {
+    Sha256::digest(input.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
@@ -0,0 +1,255 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Set membership of a hidden message.
+//!
+//! The `setMembership` option of `createProof` commits to the hidden message `m` as
+//! `K = g^m * h^s` and proves with an OR-composition that `K * g^-H(v)` is a power of
+//! `h` for one of the public values `v`. Every value has its own branch with its own
+//! challenge, the branch challenges sum to the challenge of the proof, and all but the
+//! true branch are simulated.
+
+use crate::linked_proof;
+use crate::numeric_message::message_scalar;
+use crate::statements::{
+    write_point, write_scalar, ProverStatement, StatementReader, VerifierStatement,
+};
+use crate::utils::general_error;
+
+use bbs::prelude::*;
+use ff_zeroize::Field;
+use pairing_plus::{
+    bls12_381::{Fr, G1},
+    CurveProjective,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use wasm_bindgen::prelude::*;
+
+const GENERATOR_DST: &[u8] = b"BBS_SET_MEMBERSHIP_GENERATOR_";
+
+wasm_impl!(
+    #[allow(non_snake_case)]
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    SetMembershipOptions,
+    messageIndex: usize,
+    set: Vec<Vec<u8>>
+);
+
+/// Proves the hidden message is one of the values in the set
+pub(crate) struct SetMembershipProver<'a> {
+    options: &'a SetMembershipOptions,
+    set: Vec<SignatureMessage>,
+    member: usize,
+    commitment: Option<linked_proof::MessageCommitment>,
+    member_blinding: ProofNonce,
+    branches: Vec<(G1, ProofChallenge, SignatureMessage)>,
+}
+
+impl<'a> SetMembershipProver<'a> {
+    pub(crate) fn new(options: &'a SetMembershipOptions) -> Self {
+        Self {
+            options,
+            set: options.set.iter().map(message_scalar).collect(),
+            member: 0,
+            commitment: None,
+            member_blinding: ProofNonce::random(),
+            branches: Vec::new(),
+        }
+    }
+}
+
+impl ProverStatement for SetMembershipProver<'_> {
+    fn linked(&self) -> Vec<usize> {
+        vec![self.options.messageIndex]
+    }
+
+    fn commit(
+        &mut self,
+        messages: &[SignatureMessage],
+        blindings: &BTreeMap<usize, ProofNonce>,
+        bytes: &mut Vec<u8>,
+    ) -> Result<(), BBSError> {
+        let m = &messages[self.options.messageIndex];
+        self.member = self
+            .set
+            .iter()
+            .position(|v| v == m)
+            .ok_or_else(|| general_error("The hidden message is not in the set"))?;
+        let bases = linked_proof::generators(GENERATOR_DST);
+        let [g, h] = bases;
+        let commitment =
+            linked_proof::MessageCommitment::new(&bases, m, &blindings[&self.options.messageIndex]);
+
+        // The branches other than the member are simulated from a random challenge and response
+        self.branches.clear();
+        for (i, v) in self.set.iter().enumerate() {
+            if i == self.member {
+                let t = linked_proof::multi_exp(
+                    &[h],
+                    &[SignatureMessage::from(*self.member_blinding.as_ref())],
+                );
+                self.branches
+                    .push((t, ProofChallenge::default(), SignatureMessage::default()));
+            } else {
+                let statement = linked_proof::offset_commitment(&commitment.commitment, &g, v);
+                let c = ProofChallenge::random();
+                let z = SignatureMessage::random();
+                let t = linked_proof::recompute_commitment(&[h], &[z], &statement, &c);
+                self.branches.push((t, c, z));
+            }
+        }
+
+        let branch_commitments: Vec<G1> = self.branches.iter().map(|b| b.0).collect();
+        bytes.append(&mut set_membership_bytes(
+            self.options.messageIndex,
+            &self.set,
+            &commitment.commitment,
+            &commitment.proof,
+            &branch_commitments,
+        ));
+        self.commitment = Some(commitment);
+        Ok(())
+    }
+
+    fn prove(&self, challenge: &ProofChallenge, proof: &mut Vec<u8>) {
+        let commitment = self.commitment.as_ref().unwrap();
+        let mut member_challenge = *challenge.as_ref();
+        for (i, b) in self.branches.iter().enumerate() {
+            if i != self.member {
+                member_challenge.sub_assign(b.1.as_ref());
+            }
+        }
+        let member_challenge = ProofChallenge::from(member_challenge);
+
+        write_point(proof, &commitment.commitment);
+        write_point(proof, &commitment.proof);
+        write_scalar(proof, &commitment.response(challenge));
+        for (i, (t, c, z)) in self.branches.iter().enumerate() {
+            let (c, z) = if i == self.member {
+                (
+                    member_challenge,
+                    linked_proof::response(
+                        &self.member_blinding,
+                        &member_challenge,
+                        &commitment.blinding,
+                    ),
+                )
+            } else {
+                (*c, *z)
+            };
+            write_point(proof, t);
+            write_scalar(proof, &SignatureMessage::from(*c.as_ref()));
+            write_scalar(proof, &z);
+        }
+    }
+}
+
+/// Checks the hidden message is one of the values in the set
+pub(crate) struct SetMembershipVerifier<'a> {
+    options: &'a SetMembershipOptions,
+    set: Vec<SignatureMessage>,
+    commitment: [G1; 2],
+    blinding_response: SignatureMessage,
+    branches: Vec<(G1, ProofChallenge, SignatureMessage)>,
+}
+
+impl<'a> SetMembershipVerifier<'a> {
+    pub(crate) fn new(options: &'a SetMembershipOptions) -> Self {
+        Self {
+            options,
+            set: options.set.iter().map(message_scalar).collect(),
+            commitment: [G1::zero(); 2],
+            blinding_response: SignatureMessage::default(),
+            branches: Vec::new(),
+        }
+    }
+}
+
+impl VerifierStatement for SetMembershipVerifier<'_> {
+    fn linked(&self) -> Vec<usize> {
+        vec![self.options.messageIndex]
+    }
+
+    fn read(&mut self, reader: &mut StatementReader, bytes: &mut Vec<u8>) -> Result<(), BBSError> {
+        self.commitment = [reader.point()?, reader.point()?];
+        self.blinding_response = reader.scalar()?;
+        self.branches.clear();
+        for _ in &self.set {
+            let t = reader.point()?;
+            let c = ProofChallenge::from(*reader.scalar()?.as_ref());
+            self.branches.push((t, c, reader.scalar()?));
+        }
+        let branch_commitments: Vec<G1> = self.branches.iter().map(|b| b.0).collect();
+        bytes.append(&mut set_membership_bytes(
+            self.options.messageIndex,
+            &self.set,
+            &self.commitment[0],
+            &self.commitment[1],
+            &branch_commitments,
+        ));
+        Ok(())
+    }
+
+    fn verify(
+        &self,
+        responses: &BTreeMap<usize, SignatureMessage>,
+        challenge: &ProofChallenge,
+    ) -> bool {
+        if self.set.is_empty() {
+            return false;
+        }
+        let bases = linked_proof::generators(GENERATOR_DST);
+        let [g, h] = bases;
+        let [commitment, commitment_proof] = &self.commitment;
+        if !linked_proof::verify_message_commitment(
+            &bases,
+            commitment,
+            commitment_proof,
+            &responses[&self.options.messageIndex],
+            &self.blinding_response,
+            challenge,
+        ) {
+            return false;
+        }
+        let mut challenge_sum = Fr::zero();
+        for (v, (t, c, z)) in self.set.iter().zip(self.branches.iter()) {
+            let statement = linked_proof::offset_commitment(commitment, &g, v);
+            if &linked_proof::recompute_commitment(&[h], &[*z], &statement, c) != t {
+                return false;
+            }
+            challenge_sum.add_assign(c.as_ref());
+        }
+        &challenge_sum == challenge.as_ref()
+    }
+}
+
+/// The message index and the public set are hashed into the challenge with the
+/// commitments, so the proof is bound to the set it was created for
+fn set_membership_bytes(
+    index: usize,
+    set: &[SignatureMessage],
+    commitment: &G1,
+    commitment_proof: &G1,
+    branches: &[G1],
+) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(&(index as u32).to_be_bytes());
+    for v in set {
+        bytes.extend_from_slice(&v.to_bytes_compressed_form());
+    }
+    let [g, h] = linked_proof::generators(GENERATOR_DST);
+    linked_proof::append_points(&mut bytes, &[g, h, *commitment, *commitment_proof]);
+    linked_proof::append_points(&mut bytes, branches);
+    bytes
+}
@@ -0,0 +1,453 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Statements about the hidden messages that `createProof` proves along with the
+//! signature and `verifyProof` checks.
+//!
+//! Each statement is linked to the proof of knowledge of the signature as described in
+//! `linked_proof`, with one blinding per hidden message shared by all the statements
+//! about it. The statements are hashed into the challenge and their proofs are appended
+//! after the signature proof in the order of the fields of `ProofStatements`, so a proof
+//! without statements is the proof `createProof` has always created.
+
+use crate::accumulator::{
+    AccumulatorProofOptions, AccumulatorProver, AccumulatorVerifier, AccumulatorVerifyOptions,
+};
+use crate::designated_verifier::{
+    DesignatedVerifierOptions, DesignatedVerifierProver, DesignatedVerifierVerifier,
+};
+use crate::holder_binding::{
+    HolderBindingProofOptions, HolderBindingProver, HolderBindingVerifier,
+    HolderBindingVerifyOptions,
+};
+use crate::linked_proof;
+use crate::message_commitment::{
+    MessageCommitmentOptions, MessageCommitmentProver, MessageCommitmentVerifier,
+};
+use crate::numeric_message::message_scalar;
+use crate::predicate::{NotEqualPredicate, NotEqualProver, NotEqualVerifier};
+use crate::pseudonym::{PseudonymOptions, PseudonymProver, PseudonymVerifier};
+use crate::rate_limit::{
+    RateLimitProofOptions, RateLimitProver, RateLimitVerifier, RateLimitVerifyOptions,
+};
+use crate::set_membership::{SetMembershipOptions, SetMembershipProver, SetMembershipVerifier};
+use crate::status_list::{
+    StatusListProofOptions, StatusListProver, StatusListVerifier, StatusListVerifyOptions,
+};
+use crate::utils::{general_error, zeroize_copy};
+use crate::verifiable_encryption::{
+    VerifiableEncryptionOptions, VerifiableEncryptionProver, VerifiableEncryptionVerifier,
+};
+use crate::{BbsVerifyProofResponse, PoKOfSignatureProofWrapper};
+
+use bbs::prelude::*;
+use pairing_plus::{
+    bls12_381::{G1, G2},
+    serdes::SerDes,
+};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    convert::TryFrom,
+};
+
+/// The prover side of a statement
+pub(crate) trait ProverStatement {
+    /// The indices of the hidden messages the statement is about
+    fn linked(&self) -> Vec<usize>;
+
+    /// Commit to the statement with the blindings of the linked messages, appending
+    /// the bytes to hash into the challenge
+    fn commit(
+        &mut self,
+        messages: &[SignatureMessage],
+        blindings: &BTreeMap<usize, ProofNonce>,
+        bytes: &mut Vec<u8>,
+    ) -> Result<(), BBSError>;
+
+    /// Append the proof of the statement for the challenge
+    fn prove(&self, challenge: &ProofChallenge, proof: &mut Vec<u8>);
+}
+
+/// The verifier side of a statement
+pub(crate) trait VerifierStatement {
+    /// The indices of the hidden messages the statement is about
+    fn linked(&self) -> Vec<usize>;
+
+    /// Read the proof of the statement, appending the bytes to hash into the challenge
+    fn read(&mut self, reader: &mut StatementReader, bytes: &mut Vec<u8>) -> Result<(), BBSError>;
+
+    /// Check the proof with the responses of the linked messages in the signature proof
+    fn verify(
+        &self,
+        responses: &BTreeMap<usize, SignatureMessage>,
+        challenge: &ProofChallenge,
+    ) -> bool;
+
+    /// Add the outputs of a verified statement to the response
+    fn output(&self, _response: &mut BbsVerifyProofResponse) {}
+}
+
+/// Reads the proofs of the statements appended after a signature proof
+pub(crate) struct StatementReader<'a>(&'a [u8]);
+
+impl<'a> StatementReader<'a> {
+    fn take(&mut self, length: usize) -> Result<&'a [u8], BBSError> {
+        if self.0.len() < length {
+            return Err(general_error("Invalid statement proof"));
+        }
+        let (bytes, rest) = self.0.split_at(length);
+        self.0 = rest;
+        Ok(bytes)
+    }
+
+    /// Read a compressed point, checking it is in the G1 subgroup
+    pub(crate) fn point(&mut self) -> Result<G1, BBSError> {
+        let mut bytes = self.take(G1_COMPRESSED_SIZE)?;
+        Ok(G1::deserialize(&mut bytes, true)?)
+    }
+
+    /// Read a compressed G2 point, checking it is in the G2 subgroup
+    pub(crate) fn g2_point(&mut self) -> Result<G2, BBSError> {
+        let mut bytes = self.take(G2_COMPRESSED_SIZE)?;
+        Ok(G2::deserialize(&mut bytes, true)?)
+    }
+
+    /// Read a scalar
+    pub(crate) fn scalar(&mut self) -> Result<SignatureMessage, BBSError> {
+        SignatureMessage::try_from(self.take(FR_COMPRESSED_SIZE)?)
+    }
+
+    /// Read bytes written with `write_bytes`
+    pub(crate) fn bytes(&mut self) -> Result<&'a [u8], BBSError> {
+        let length = u32::from_be_bytes(*array_ref![self.take(4)?, 0, 4]);
+        self.take(length as usize)
+    }
+}
+
+/// Append the compressed form of a point to a statement proof
+pub(crate) fn write_point(proof: &mut Vec<u8>, point: &G1) {
+    point.serialize(proof, true).unwrap();
+}
+
+/// Append a scalar to a statement proof
+pub(crate) fn write_scalar(proof: &mut Vec<u8>, scalar: &SignatureMessage) {
+    proof.extend_from_slice(&scalar.to_bytes_compressed_form());
+}
+
+/// Append bytes to a statement proof, prefixed with their length
+pub(crate) fn write_bytes(proof: &mut Vec<u8>, bytes: &[u8]) {
+    proof.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+    proof.extend_from_slice(bytes);
+}
+
+/// The statements of a `createProof` or `blsCreateProof` request
+pub(crate) struct ProofStatements<'a> {
+    pub(crate) holder_binding: Option<&'a HolderBindingProofOptions>,
+    pub(crate) pseudonym: Option<&'a PseudonymOptions>,
+    pub(crate) accumulator: Option<&'a AccumulatorProofOptions>,
+    pub(crate) status_list: Option<&'a StatusListProofOptions>,
+    pub(crate) verifiable_encryption: Option<&'a VerifiableEncryptionOptions>,
+    pub(crate) committed: Option<&'a [MessageCommitmentOptions]>,
+    pub(crate) not_equal: Option<&'a [NotEqualPredicate]>,
+    pub(crate) set_membership: Option<&'a SetMembershipOptions>,
+    pub(crate) rate_limit: Option<&'a RateLimitProofOptions>,
+    pub(crate) designated_verifier: Option<&'a DesignatedVerifierOptions>,
+}
+
+impl<'a> ProofStatements<'a> {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.holder_binding.is_none()
+            && self.pseudonym.is_none()
+            && self.accumulator.is_none()
+            && self.status_list.is_none()
+            && self.verifiable_encryption.is_none()
+            && self.committed.is_none()
+            && self.not_equal.is_none()
+            && self.set_membership.is_none()
+            && self.rate_limit.is_none()
+            && self.designated_verifier.is_none()
+    }
+
+    /// The number of signed messages for the messages of a request, which leave out
+    /// the link secret of the holder binding
+    pub(crate) fn message_count(&self, messages: usize) -> usize {
+        messages + self.holder_binding.map_or(0, |_| 1)
+    }
+
+    /// Create the proof of knowledge of the signature that also proves the statements
+    pub(crate) fn create_proof(
+        &self,
+        signature: &Signature,
+        pk: &PublicKey,
+        messages: &[Vec<u8>],
+        revealed: &[usize],
+        nonce: &[u8],
+    ) -> Result<PoKOfSignatureProofWrapper, BBSError> {
+        let mut messages: Vec<SignatureMessage> = messages.iter().map(message_scalar).collect();
+        if let Some(options) = self.holder_binding {
+            if options.linkSecretIndex > messages.len() {
+                return Err(general_error("linkSecretIndex is out of bounds"));
+            }
+            messages.insert(options.linkSecretIndex, message_scalar(&options.linkSecret));
+        }
+
+        let mut statements: Vec<Box<dyn ProverStatement + 'a>> = Vec::new();
+        if let Some(options) = self.holder_binding {
+            statements.push(Box::new(HolderBindingProver::new(options)));
+        }
+        if let Some(options) = self.pseudonym {
+            statements.push(Box::new(PseudonymProver::new(options)));
+        }
+        if let Some(options) = self.accumulator {
+            statements.push(Box::new(AccumulatorProver::new(options)));
+        }
+        if let Some(options) = self.status_list {
+            statements.push(Box::new(StatusListProver::new(options)));
+        }
+        if let Some(options) = self.verifiable_encryption {
+            statements.push(Box::new(VerifiableEncryptionProver::new(options)));
+        }
+        if let Some(options) = self.committed {
+            statements.push(Box::new(MessageCommitmentProver::new(options)));
+        }
+        if let Some(predicates) = self.not_equal {
+            statements.push(Box::new(NotEqualProver::new(predicates)));
+        }
+        if let Some(options) = self.set_membership {
+            statements.push(Box::new(SetMembershipProver::new(options)));
+        }
+        if let Some(options) = self.rate_limit {
+            statements.push(Box::new(RateLimitProver::new(options)));
+        }
+        let proof = prove(
+            signature,
+            pk,
+            &messages,
+            revealed,
+            nonce,
+            &mut statements,
+            self.designated_verifier,
+        );
+        messages.iter_mut().for_each(zeroize_copy);
+        proof
+    }
+}
+
+/// The statements of a `verifyProof` or `blsVerifyProof` request
+pub(crate) struct VerifyStatements<'a> {
+    pub(crate) holder_binding: Option<&'a HolderBindingVerifyOptions>,
+    pub(crate) pseudonym: Option<&'a PseudonymOptions>,
+    pub(crate) accumulator: Option<&'a AccumulatorVerifyOptions>,
+    pub(crate) status_list: Option<&'a StatusListVerifyOptions>,
+    pub(crate) verifiable_encryption: Option<&'a VerifiableEncryptionOptions>,
+    pub(crate) committed: Option<&'a [usize]>,
+    pub(crate) not_equal: Option<&'a [NotEqualPredicate]>,
+    pub(crate) set_membership: Option<&'a SetMembershipOptions>,
+    pub(crate) rate_limit: Option<&'a RateLimitVerifyOptions>,
+    pub(crate) designated_verifier: Option<&'a DesignatedVerifierOptions>,
+}
+
+impl<'a> VerifyStatements<'a> {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.holder_binding.is_none()
+            && self.pseudonym.is_none()
+            && self.accumulator.is_none()
+            && self.status_list.is_none()
+            && self.verifiable_encryption.is_none()
+            && self.committed.is_none()
+            && self.not_equal.is_none()
+            && self.set_membership.is_none()
+            && self.rate_limit.is_none()
+            && self.designated_verifier.is_none()
+    }
+
+    /// Verify the proof of knowledge of the signature and the proofs of the statements
+    pub(crate) fn verify_proof(
+        &self,
+        proof: PoKOfSignatureProofWrapper,
+        pk: &PublicKey,
+        messages: &[Vec<u8>],
+        nonce: &[u8],
+    ) -> BbsVerifyProofResponse {
+        let mut statements: Vec<Box<dyn VerifierStatement + 'a>> = Vec::new();
+        if let Some(options) = self.holder_binding {
+            statements.push(Box::new(HolderBindingVerifier::new(options)));
+        }
+        if let Some(options) = self.pseudonym {
+            statements.push(Box::new(PseudonymVerifier::new(options)));
+        }
+        if let Some(options) = self.accumulator {
+            statements.push(Box::new(AccumulatorVerifier::new(options)));
+        }
+        if let Some(options) = self.status_list {
+            statements.push(Box::new(StatusListVerifier::new(options)));
+        }
+        if let Some(options) = self.verifiable_encryption {
+            statements.push(Box::new(VerifiableEncryptionVerifier::new(options)));
+        }
+        if let Some(committed) = self.committed {
+            statements.push(Box::new(MessageCommitmentVerifier::new(committed)));
+        }
+        if let Some(predicates) = self.not_equal {
+            statements.push(Box::new(NotEqualVerifier::new(predicates)));
+        }
+        if let Some(options) = self.set_membership {
+            statements.push(Box::new(SetMembershipVerifier::new(options)));
+        }
+        if let Some(options) = self.rate_limit {
+            statements.push(Box::new(RateLimitVerifier::new(options)));
+        }
+        let mut response = BbsVerifyProofResponse {
+            verified: false,
+            error: None,
+            pseudonym: None,
+            ciphertext: None,
+            commitments: None,
+            tag: None,
+        };
+        match verify(
+            proof,
+            pk,
+            messages,
+            nonce,
+            &mut statements,
+            self.designated_verifier,
+        ) {
+            Ok(verified) => response.verified = verified,
+            Err(e) => response.error = Some(format!("{:?}", e)),
+        }
+        if response.verified {
+            statements.iter().for_each(|s| s.output(&mut response));
+        }
+        response
+    }
+}
+
+fn prove(
+    signature: &Signature,
+    pk: &PublicKey,
+    messages: &[SignatureMessage],
+    revealed: &[usize],
+    nonce: &[u8],
+    statements: &mut [Box<dyn ProverStatement + '_>],
+    designated_verifier: Option<&DesignatedVerifierOptions>,
+) -> Result<PoKOfSignatureProofWrapper, BBSError> {
+    let message_count = pk.message_count();
+    if messages.len() != message_count {
+        return Err(general_error("messages do not match the public key"));
+    }
+    if revealed.iter().any(|r| *r >= message_count) {
+        return Err(general_error("revealed value is out of bounds"));
+    }
+    let revealed: BTreeSet<usize> = revealed.iter().copied().collect();
+    let mut blindings = BTreeMap::new();
+    for i in statements.iter().flat_map(|s| s.linked()) {
+        if i >= message_count || revealed.contains(&i) {
+            return Err(general_error(&format!(
+                "Message {} of a statement must be a hidden message",
+                i
+            )));
+        }
+        blindings.entry(i).or_insert_with(ProofNonce::random);
+    }
+
+    let pok = linked_proof::init_pok(signature, pk, messages, &revealed, &blindings)?;
+    let mut bytes = Vec::new();
+    for statement in statements.iter_mut() {
+        statement.commit(messages, &blindings, &mut bytes)?;
+    }
+    // A designated verifier proof proves the signature and the statements with the
+    // challenge left after simulating the key branch
+    let designated_verifier = designated_verifier
+        .map(DesignatedVerifierProver::new)
+        .transpose()?;
+    if let Some(designated_verifier) = &designated_verifier {
+        designated_verifier.commit(&mut bytes);
+    }
+    let mut challenge = linked_proof::challenge(pok.to_bytes(), &bytes, nonce);
+    if let Some(designated_verifier) = &designated_verifier {
+        challenge = designated_verifier.signature_challenge(&challenge);
+    }
+    let proof = pok.gen_proof(&challenge)?;
+
+    let mut wrapper = PoKOfSignatureProofWrapper::new(message_count, &revealed, proof);
+    for statement in statements.iter() {
+        statement.prove(&challenge, &mut wrapper.statements);
+    }
+    if let Some(designated_verifier) = &designated_verifier {
+        designated_verifier.prove(&mut wrapper.statements);
+    }
+    Ok(wrapper)
+}
+
+fn verify(
+    mut proof: PoKOfSignatureProofWrapper,
+    pk: &PublicKey,
+    messages: &[Vec<u8>],
+    nonce: &[u8],
+    statements: &mut [Box<dyn VerifierStatement + '_>],
+    designated_verifier: Option<&DesignatedVerifierOptions>,
+) -> Result<bool, BBSError> {
+    let statement_proofs = std::mem::take(&mut proof.statements);
+    let (revealed, proof) = proof.unwrap();
+    if messages.len() != revealed.len() {
+        return Err(general_error(&format!(
+            "Given messages count ({}) is different from revealed messages count ({}) for this proof",
+            messages.len(),
+            revealed.len()
+        )));
+    }
+
+    let mut reader = StatementReader(&statement_proofs);
+    let mut bytes = Vec::new();
+    for statement in statements.iter_mut() {
+        statement.read(&mut reader, &mut bytes)?;
+    }
+    let mut designated_verifier = designated_verifier.map(DesignatedVerifierVerifier::new);
+    if let Some(designated_verifier) = designated_verifier.as_mut() {
+        designated_verifier.read(&mut reader, &mut bytes)?;
+    }
+    if !reader.0.is_empty() {
+        return Err(general_error(
+            "The proof has statements that were not requested",
+        ));
+    }
+    let mut challenge = linked_proof::challenge(
+        proof.get_bytes_for_challenge(revealed.clone(), pk),
+        &bytes,
+        nonce,
+    );
+    if let Some(designated_verifier) = &designated_verifier {
+        if !designated_verifier.verify() {
+            return Ok(false);
+        }
+        challenge = designated_verifier.signature_challenge(&challenge);
+    }
+
+    let mut responses = BTreeMap::new();
+    for i in statements.iter().flat_map(|s| s.linked()) {
+        if i >= pk.message_count() {
+            return Err(general_error(&format!("Message {} is out of bounds", i)));
+        }
+        responses.insert(i, linked_proof::hidden_response(&proof, &revealed, i)?);
+    }
+    if !statements.iter().all(|s| s.verify(&responses, &challenge)) {
+        return Ok(false);
+    }
+    linked_proof::verify_pok(
+        &proof,
+        pk,
+        &linked_proof::revealed_messages(&revealed, messages),
+        &challenge,
+    )
+}
@@ -0,0 +1,322 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Status checks against a W3C Bitstring Status List for a hidden status list index.
+//!
+//! The publisher of a list of `N` bits signs every gap between consecutive set bits,
+//! with `-1` and `N` as the outer bounds, as the messages `(D, a, b)` with a BLS 12-381
+//! G2 key, where `D` is the hash of the decoded list and `a < b` are the bounds of the
+//! gap. The credential signs its status list index `x` as a numeric message, and the
+//! `statusList` option of `createProof` proves knowledge of the signature of a gap of
+//! the list with `D` revealed and `a` and `b` hidden, and that `x - a - 1` and
+//! `b - x - 1` are in `[0, 2^64)` with range proofs linked to both signature proofs.
+//! So `x` is unset and within the list, and the proof has the same size whatever the
+//! number of set bits.
+
+use crate::linked_proof;
+use crate::numeric_message::{integer_to_fr, small_integer};
+use crate::range_proof::{RangeProof, RangeProver, RANGE_BITS};
+use crate::statements::{write_bytes, ProverStatement, StatementReader, VerifierStatement};
+use crate::utils::{general_error, set_panic_hook};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use bbs::prelude::*;
+use ff_zeroize::Field;
+use flate2::read::GzDecoder;
+use pairing_plus::bls12_381::Fr;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    convert::{TryFrom, TryInto},
+    io::Read,
+};
+use wasm_bindgen::prelude::*;
+
+const MULTIBASE_BASE64URL: char = 'u';
+const DIGEST_DST: &[u8] = b"BBS_STATUS_LIST_";
+/// The largest decoded status list, of 16 Mi entries
+const MAX_LIST_BYTES: usize = 2 * 1024 * 1024;
+/// The digest of the list and the bounds of a gap
+const GAP_MESSAGE_COUNT: usize = 3;
+
+wasm_impl!(
+    StatusListSignRequest,
+    secretKey: SecretKey,
+    encodedList: String
+);
+
+wasm_impl!(
+    /// The signature of the unset indices between `start` and `end`, both excluded
+    #[allow(non_snake_case)]
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    StatusListGap,
+    start: i64,
+    end: i64,
+    signature: Signature
+);
+
+wasm_impl!(
+    StatusListProofOptions,
+    statusMessageIndex: usize,
+    encodedList: String,
+    publicKey: DeterministicPublicKey,
+    gap: StatusListGap
+);
+
+wasm_impl!(
+    StatusListVerifyOptions,
+    statusMessageIndex: usize,
+    encodedList: String,
+    publicKey: DeterministicPublicKey
+);
+
+/// Signs the gaps between the set bits of the encoded status list with a BLS 12-381 G2
+/// key pair secret key, for holders to prove their index is in one of them
+#[wasm_bindgen(js_name = signStatusList)]
+pub async fn sign_status_list(request: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let request: StatusListSignRequest = request.try_into()?;
+    let list = map_err!(decode_status_list(&request.encodedList))?;
+    let (dpk, sk) =
+        DeterministicPublicKey::new(Some(KeyGenOption::FromSecretKey(request.secretKey.clone())));
+    let pk = dpk.to_public_key(GAP_MESSAGE_COUNT)?;
+    let digest = list_digest(&list);
+
+    let bits = list.len() as i64 * 8;
+    let mut bounds = vec![-1];
+    bounds.extend((0..bits).filter(|i| is_set(&list, *i as usize)));
+    bounds.push(bits);
+    let mut gaps = Vec::new();
+    for bound in bounds.windows(2).filter(|b| b[1] - b[0] > 1) {
+        let messages = gap_messages(&digest, bound[0], bound[1]);
+        gaps.push(StatusListGap {
+            start: bound[0],
+            end: bound[1],
+            signature: map_err!(Signature::new(&messages, &sk, &pk))?,
+        });
+    }
+    Ok(serde_wasm_bindgen::to_value(&gaps).unwrap())
+}
+
+/// Proves the hidden status list index is in a signed gap of the status list
+pub(crate) struct StatusListProver<'a> {
+    options: &'a StatusListProofOptions,
+    gap: Option<PoKOfSignature>,
+    ranges: Vec<RangeProver>,
+}
+
+impl<'a> StatusListProver<'a> {
+    pub(crate) fn new(options: &'a StatusListProofOptions) -> Self {
+        Self {
+            options,
+            gap: None,
+            ranges: Vec::new(),
+        }
+    }
+}
+
+impl ProverStatement for StatusListProver<'_> {
+    fn linked(&self) -> Vec<usize> {
+        vec![self.options.statusMessageIndex]
+    }
+
+    fn commit(
+        &mut self,
+        messages: &[SignatureMessage],
+        blindings: &BTreeMap<usize, ProofNonce>,
+        bytes: &mut Vec<u8>,
+    ) -> Result<(), BBSError> {
+        let index = self.options.statusMessageIndex;
+        let gap = &self.options.gap;
+        let x = small_integer(messages[index].as_ref())
+            .ok_or_else(|| general_error("The status list index is not a numeric message"))?;
+        if gap.start as i128 >= x as i128 || x as i128 >= gap.end as i128 {
+            return Err(general_error(
+                "The status list index is not in the signed gap",
+            ));
+        }
+
+        let list = decode_status_list(&self.options.encodedList)?;
+        let pk = self.options.publicKey.to_public_key(GAP_MESSAGE_COUNT)?;
+        let gap_messages = gap_messages(&list_digest(&list), gap.start, gap.end);
+        if !gap.signature.verify(&gap_messages, &pk)? {
+            return Err(general_error("The gap is not signed for the status list"));
+        }
+        let [start_blinding, end_blinding] = [ProofNonce::random(), ProofNonce::random()];
+        let mut linked = BTreeMap::new();
+        linked.insert(1, start_blinding);
+        linked.insert(2, end_blinding);
+        let revealed: BTreeSet<usize> = [0].into_iter().collect();
+        let pok = linked_proof::init_pok(&gap.signature, &pk, &gap_messages, &revealed, &linked)?;
+        bytes.extend_from_slice(&pok.to_bytes());
+
+        // x - a - 1 blinded with b_x - b_a and b - x - 1 blinded with b_b - b_x
+        let x_blinding = blindings[&index].as_ref();
+        let differences = [
+            (
+                &messages[index],
+                &gap_messages[1],
+                x_blinding,
+                start_blinding.as_ref(),
+            ),
+            (
+                &gap_messages[2],
+                &messages[index],
+                end_blinding.as_ref(),
+                x_blinding,
+            ),
+        ];
+        self.ranges.clear();
+        for (minuend, subtrahend, minuend_blinding, subtrahend_blinding) in differences {
+            let mut value = *minuend.as_ref();
+            value.sub_assign(subtrahend.as_ref());
+            value.sub_assign(&Fr::one());
+            let mut blinding = *minuend_blinding;
+            blinding.sub_assign(subtrahend_blinding);
+            let range = RangeProver::new(&value, &blinding, RANGE_BITS)
+                .ok_or_else(|| general_error("The status list index is not in the signed gap"))?;
+            range.append_bytes(bytes);
+            self.ranges.push(range);
+        }
+        self.gap = Some(pok);
+        Ok(())
+    }
+
+    fn prove(&self, challenge: &ProofChallenge, proof: &mut Vec<u8>) {
+        // The proof of the gap signature was initialised with matching secrets in
+        // `commit`, so it cannot fail
+        let gap = self.gap.clone().unwrap().gen_proof(challenge).unwrap();
+        write_bytes(proof, &gap.to_bytes_compressed_form());
+        for range in &self.ranges {
+            range.prove(challenge).write(proof);
+        }
+    }
+}
+
+/// Checks the hidden status list index is in a signed gap of the status list
+pub(crate) struct StatusListVerifier<'a> {
+    options: &'a StatusListVerifyOptions,
+    public_key: Option<PublicKey>,
+    digest: SignatureMessage,
+    gap: Option<PoKOfSignatureProof>,
+    ranges: Vec<RangeProof>,
+}
+
+impl<'a> StatusListVerifier<'a> {
+    pub(crate) fn new(options: &'a StatusListVerifyOptions) -> Self {
+        Self {
+            options,
+            public_key: None,
+            digest: SignatureMessage::default(),
+            gap: None,
+            ranges: Vec::new(),
+        }
+    }
+}
+
+impl VerifierStatement for StatusListVerifier<'_> {
+    fn linked(&self) -> Vec<usize> {
+        vec![self.options.statusMessageIndex]
+    }
+
+    fn read(&mut self, reader: &mut StatementReader, bytes: &mut Vec<u8>) -> Result<(), BBSError> {
+        let list = decode_status_list(&self.options.encodedList)?;
+        let pk = self.options.publicKey.to_public_key(GAP_MESSAGE_COUNT)?;
+        let gap = PoKOfSignatureProof::try_from(reader.bytes()?)?;
+        bytes.extend_from_slice(&gap.get_bytes_for_challenge([0].into_iter().collect(), &pk));
+        self.ranges = vec![
+            RangeProof::read(reader, RANGE_BITS)?,
+            RangeProof::read(reader, RANGE_BITS)?,
+        ];
+        self.ranges.iter().for_each(|r| r.append_bytes(bytes));
+        self.digest = list_digest(&list);
+        self.public_key = Some(pk);
+        self.gap = Some(gap);
+        Ok(())
+    }
+
+    fn verify(
+        &self,
+        responses: &BTreeMap<usize, SignatureMessage>,
+        challenge: &ProofChallenge,
+    ) -> bool {
+        let (pk, gap) = match (&self.public_key, &self.gap) {
+            (Some(pk), Some(gap)) => (pk, gap),
+            _ => return false,
+        };
+        let revealed: BTreeSet<usize> = [0].into_iter().collect();
+        let (start, end) = match (
+            linked_proof::hidden_response(gap, &revealed, 1),
+            linked_proof::hidden_response(gap, &revealed, 2),
+        ) {
+            (Ok(start), Ok(end)) => (start, end),
+            _ => return false,
+        };
+        // The responses of x - a - 1 and b - x - 1 are s_x - s_a + c and s_b - s_x + c
+        let x = &responses[&self.options.statusMessageIndex];
+        let differences = [(x, &start), (&end, x)];
+        let in_gap =
+            differences
+                .iter()
+                .zip(self.ranges.iter())
+                .all(|((minuend, subtrahend), range)| {
+                    let mut response = *minuend.as_ref();
+                    response.sub_assign(subtrahend.as_ref());
+                    response.add_assign(challenge.as_ref());
+                    range.verify(RANGE_BITS, &SignatureMessage::from(response), challenge)
+                });
+        let mut revealed_messages = BTreeMap::new();
+        revealed_messages.insert(0, self.digest);
+        in_gap && linked_proof::verify_pok(gap, pk, &revealed_messages, challenge).unwrap_or(false)
+    }
+}
+
+/// Decode the multibase base64url encoded, GZIP compressed bitstring of a status list
+fn decode_status_list(encoded_list: &str) -> Result<Vec<u8>, BBSError> {
+    let encoded_list = encoded_list
+        .strip_prefix(MULTIBASE_BASE64URL)
+        .unwrap_or(encoded_list);
+    let compressed = URL_SAFE_NO_PAD
+        .decode(encoded_list)
+        .map_err(|_| general_error("The encoded list is not base64url encoded"))?;
+    let mut list = Vec::new();
+    GzDecoder::new(compressed.as_slice())
+        .take(MAX_LIST_BYTES as u64 + 1)
+        .read_to_end(&mut list)
+        .map_err(|_| general_error("The encoded list is not GZIP compressed"))?;
+    if list.len() > MAX_LIST_BYTES {
+        return Err(general_error("The encoded list is too large"));
+    }
+    Ok(list)
+}
+
+/// The first index is the left-most bit of the bitstring
+fn is_set(list: &[u8], index: usize) -> bool {
+    list[index / 8] & (0x80 >> (index % 8)) != 0
+}
+
+/// The hash `D` of the decoded list
+fn list_digest(list: &[u8]) -> SignatureMessage {
+    let mut bytes = DIGEST_DST.to_vec();
+    bytes.extend_from_slice(list);
+    SignatureMessage::hash(bytes)
+}
+
+/// The signed messages `(D, a, b)` of a gap
+fn gap_messages(digest: &SignatureMessage, start: i64, end: i64) -> Vec<SignatureMessage> {
+    vec![
+        *digest,
+        SignatureMessage::from(integer_to_fr(start)),
+        SignatureMessage::from(integer_to_fr(end)),
+    ]
+}
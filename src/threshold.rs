@@ -0,0 +1,729 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! t-of-n threshold issuance of BBS+ signatures.
+//!
+//! The issuer secret key `x` is Shamir shared between `n` parties with a
+//! Joint-Feldman distributed key generation, no party ever learns `x`.
+//!
+//! Signing is split into an offline presigning ceremony and an online signing step.
+//! Presigning jointly samples shares of random `a`, `e` and `s`, computes shares of
+//! `delta = a(x + e)` and `alpha = as` and opens `delta`, `e` and `s`. As the products
+//! are computed on degree 2(t-1) sharings, presigning needs at least 2t - 1 parties
+//! and assumes the parties follow the protocol. Afterwards any t parties can sign,
+//! each publishing `(g1 * h_1^m_1 ... h_L^m_L)^a_i * h_0^alpha_i` and the combiner
+//! computes `A = (B^a)^(1/delta) = B^(1/(x + e))`, a normal BBS+ signature.
+//!
+//! A presignature must only ever be used for one signature.
+
+use crate::numeric_message::message_scalar;
+use crate::utils::{general_error, secret_key_to_fr, set_panic_hook};
+
+use bbs::prelude::*;
+use ff_zeroize::{Field, PrimeField};
+use pairing_plus::{
+    bls12_381::{Fr, FrRepr, G1, G2},
+    serdes::SerDes,
+    CurveProjective,
+};
+use rand::thread_rng;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeSet, convert::TryInto};
+use wasm_bindgen::prelude::*;
+
+wasm_impl!(
+    ThresholdKeyGenRound1Request,
+    threshold: usize,
+    participants: usize,
+    index: usize
+);
+
+wasm_impl!(
+    /// The dealing of a party in the key generation ceremony.
+    /// `shares[j - 1]` must be sent privately to party `j`,
+    /// `commitments` are broadcast to all parties
+    #[allow(non_snake_case)]
+    #[derive(Debug, Deserialize, Serialize)]
+    ThresholdKeyDealing,
+    index: usize,
+    commitments: Vec<GeneratorG2>,
+    shares: Vec<SecretKey>
+);
+
+wasm_impl!(
+    ThresholdReceivedKeyDealing,
+    from: usize,
+    commitments: Vec<GeneratorG2>,
+    share: SecretKey
+);
+
+wasm_impl!(
+    ThresholdKeyGenRound2Request,
+    threshold: usize,
+    participants: usize,
+    index: usize,
+    dealings: Vec<ThresholdReceivedKeyDealing>
+);
+
+wasm_impl!(
+    ThresholdKeyShare,
+    index: usize,
+    threshold: usize,
+    participants: usize,
+    secretKeyShare: SecretKey,
+    publicKey: DeterministicPublicKey
+);
+
+wasm_impl!(
+    ThresholdPresignRound1Request,
+    threshold: usize,
+    index: usize,
+    parties: Vec<usize>
+);
+
+wasm_impl!(
+    #[allow(non_snake_case)]
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    ThresholdPresignShare, a: SecretKey, e: SecretKey, s: SecretKey);
+
+wasm_impl!(
+    /// The dealing of a party in the presigning ceremony.
+    /// `shares[k]` must be sent privately to party `parties[k]`
+    #[allow(non_snake_case)]
+    #[derive(Debug, Deserialize, Serialize)]
+    ThresholdPresignDealing,
+    index: usize,
+    shares: Vec<ThresholdPresignShare>
+);
+
+wasm_impl!(
+    ThresholdPresignRound2Request,
+    threshold: usize,
+    parties: Vec<usize>,
+    keyShare: ThresholdKeyShare,
+    shares: Vec<ThresholdPresignShare>
+);
+
+wasm_impl!(
+    ThresholdPresignRound2State,
+    index: usize,
+    a: SecretKey,
+    e: SecretKey,
+    s: SecretKey
+);
+
+wasm_impl!(
+    #[allow(non_snake_case)]
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    ThresholdPresignReshare, delta: SecretKey, alpha: SecretKey);
+
+wasm_impl!(
+    ThresholdPresignRound2Response,
+    state: ThresholdPresignRound2State,
+    reshares: Vec<ThresholdPresignReshare>
+);
+
+wasm_impl!(
+    ThresholdPresignRound3Request,
+    threshold: usize,
+    parties: Vec<usize>,
+    state: ThresholdPresignRound2State,
+    reshares: Vec<ThresholdPresignReshare>
+);
+
+wasm_impl!(
+    ThresholdPresignRound3State,
+    index: usize,
+    a: SecretKey,
+    alpha: SecretKey
+);
+
+wasm_impl!(
+    #[allow(non_snake_case)]
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    ThresholdPresignOpening,
+    index: usize,
+    delta: SignatureMessage,
+    e: SignatureMessage,
+    s: SignatureMessage
+);
+
+wasm_impl!(
+    ThresholdPresignRound3Response,
+    state: ThresholdPresignRound3State,
+    opening: ThresholdPresignOpening
+);
+
+wasm_impl!(
+    ThresholdPresignFinalizeRequest,
+    threshold: usize,
+    state: ThresholdPresignRound3State,
+    openings: Vec<ThresholdPresignOpening>
+);
+
+wasm_impl!(
+    #[allow(non_snake_case)]
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    ThresholdPresignature,
+    index: usize,
+    a: SecretKey,
+    alpha: SecretKey,
+    delta: SignatureMessage,
+    e: SignatureMessage,
+    s: SignatureMessage
+);
+
+wasm_impl!(
+    ThresholdPartialSignRequest,
+    presignature: ThresholdPresignature,
+    publicKey: DeterministicPublicKey,
+    messages: Vec<Vec<u8>>
+);
+
+wasm_impl!(
+    #[allow(non_snake_case)]
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    ThresholdPartialSignature,
+    index: usize,
+    partial: Commitment,
+    delta: SignatureMessage,
+    e: SignatureMessage,
+    s: SignatureMessage
+);
+
+wasm_impl!(
+    ThresholdCombineRequest,
+    threshold: usize,
+    publicKey: DeterministicPublicKey,
+    messages: Vec<Vec<u8>>,
+    partialSignatures: Vec<ThresholdPartialSignature>
+);
+
+/// Starts the distributed key generation by dealing shares of a random polynomial
+/// to every participant along with Feldman commitments to its coefficients
+#[wasm_bindgen(js_name = thresholdKeyGenRound1)]
+pub async fn threshold_key_gen_round_1(request: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let request: ThresholdKeyGenRound1Request = request.try_into()?;
+    let dealing = deal_key_shares(request.threshold, request.participants, request.index)?;
+    Ok(serde_wasm_bindgen::to_value(&dealing).unwrap())
+}
+
+/// Completes the distributed key generation by verifying the received shares
+/// and combining them into this party's secret key share
+#[wasm_bindgen(js_name = thresholdKeyGenRound2)]
+pub async fn threshold_key_gen_round_2(request: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let request: ThresholdKeyGenRound2Request = request.try_into()?;
+    let key_share = combine_key_dealings(
+        request.threshold,
+        request.participants,
+        request.index,
+        &request.dealings,
+    )?;
+    Ok(serde_wasm_bindgen::to_value(&key_share).unwrap())
+}
+
+/// Starts presigning by dealing shares of random `a`, `e` and `s` to the presigning parties
+#[wasm_bindgen(js_name = thresholdPresignRound1)]
+pub async fn threshold_presign_round_1(request: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let request: ThresholdPresignRound1Request = request.try_into()?;
+    let dealing = deal_presign_shares(request.threshold, request.index, &request.parties)?;
+    Ok(serde_wasm_bindgen::to_value(&dealing).unwrap())
+}
+
+/// Combines the received presigning shares and reshares the products `a(x + e)` and `as`
+#[wasm_bindgen(js_name = thresholdPresignRound2)]
+pub async fn threshold_presign_round_2(request: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let request: ThresholdPresignRound2Request = request.try_into()?;
+    let response = reshare_presign_products(
+        request.threshold,
+        &request.parties,
+        &request.keyShare,
+        &request.shares,
+    )?;
+    Ok(serde_wasm_bindgen::to_value(&response).unwrap())
+}
+
+/// Reduces the received reshares to this party's shares of `delta` and `alpha`,
+/// returning the opening of `delta`, `e` and `s` to broadcast to the other parties
+#[wasm_bindgen(js_name = thresholdPresignRound3)]
+pub async fn threshold_presign_round_3(request: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let request: ThresholdPresignRound3Request = request.try_into()?;
+    let response = reduce_presign_reshares(
+        request.threshold,
+        &request.parties,
+        &request.state,
+        &request.reshares,
+    )?;
+    Ok(serde_wasm_bindgen::to_value(&response).unwrap())
+}
+
+/// Opens `delta`, `e` and `s` from the broadcast openings producing this party's presignature
+#[wasm_bindgen(js_name = thresholdPresignFinalize)]
+pub async fn threshold_presign_finalize(request: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let request: ThresholdPresignFinalizeRequest = request.try_into()?;
+    let presignature = finalize_presignature(request.threshold, &request.state, &request.openings)?;
+    Ok(serde_wasm_bindgen::to_value(&presignature).unwrap())
+}
+
+/// Produces this party's partial signature over a set of messages from a presignature
+#[wasm_bindgen(js_name = thresholdPartialSign)]
+pub async fn threshold_partial_sign(request: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let request: ThresholdPartialSignRequest = request.try_into()?;
+    let pk = request.publicKey.to_public_key(request.messages.len())?;
+    let messages: Vec<SignatureMessage> = request.messages.iter().map(message_scalar).collect();
+    let partial = partial_sign(&request.presignature, &messages, &pk)?;
+    Ok(serde_wasm_bindgen::to_value(&partial).unwrap())
+}
+
+/// Combines at least `threshold` partial signatures into a BBS+ signature
+/// that verifies with `blsVerify` against the shared public key
+#[wasm_bindgen(js_name = thresholdCombineSignatures)]
+pub async fn threshold_combine_signatures(request: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let request: ThresholdCombineRequest = request.try_into()?;
+    let pk = request.publicKey.to_public_key(request.messages.len())?;
+    let messages: Vec<SignatureMessage> = request.messages.iter().map(message_scalar).collect();
+    let signature = combine_partial_signatures(request.threshold, &request.partialSignatures)?;
+    match signature.verify(messages.as_slice(), &pk) {
+        Ok(true) => Ok(serde_wasm_bindgen::to_value(&signature).unwrap()),
+        Ok(false) => Err(JsValue::from("Combined signature is invalid")),
+        Err(e) => Err(JsValue::from(&format!("{:?}", e))),
+    }
+}
+
+/// Deal shares of a random secret to `participants` parties, any `threshold` of which can recover it
+pub fn deal_key_shares(
+    threshold: usize,
+    participants: usize,
+    index: usize,
+) -> Result<ThresholdKeyDealing, BBSError> {
+    check_threshold(threshold, participants)?;
+    check_index(index, participants)?;
+    let polynomial = random_polynomial(Fr::random(&mut thread_rng()), threshold);
+    let commitments = polynomial
+        .iter()
+        .map(|c| {
+            let mut p = G2::one();
+            p.mul_assign(*c);
+            GeneratorG2::from(p)
+        })
+        .collect();
+    let shares = (1..=participants)
+        .map(|j| SecretKey::from(evaluate(&polynomial, j)))
+        .collect();
+    Ok(ThresholdKeyDealing {
+        index,
+        commitments,
+        shares,
+    })
+}
+
+/// Verify the shares dealt to party `index` and combine them into its secret key share
+pub fn combine_key_dealings(
+    threshold: usize,
+    participants: usize,
+    index: usize,
+    dealings: &[ThresholdReceivedKeyDealing],
+) -> Result<ThresholdKeyShare, BBSError> {
+    check_threshold(threshold, participants)?;
+    check_index(index, participants)?;
+    let dealers: BTreeSet<usize> = dealings.iter().map(|d| d.from).collect();
+    if dealers.len() != participants || dealings.len() != participants {
+        return Err(general_error(&format!(
+            "Expected a dealing from each of the {} participants",
+            participants
+        )));
+    }
+
+    let mut secret = Fr::zero();
+    let mut public_key = G2::zero();
+    for dealing in dealings {
+        check_index(dealing.from, participants)?;
+        if dealing.commitments.len() != threshold {
+            return Err(general_error(&format!(
+                "Dealing from party {} has {} commitments but expected {}",
+                dealing.from,
+                dealing.commitments.len(),
+                threshold
+            )));
+        }
+        let share = secret_key_to_fr(&dealing.share)?;
+        let mut expected = G2::one();
+        expected.mul_assign(share);
+        if expected != evaluate_commitments(&dealing.commitments, index) {
+            return Err(general_error(&format!(
+                "Share dealt by party {} does not match its commitments",
+                dealing.from
+            )));
+        }
+        secret.add_assign(&share);
+        public_key.add_assign(dealing.commitments[0].as_ref());
+    }
+
+    Ok(ThresholdKeyShare {
+        index,
+        threshold,
+        participants,
+        secretKeyShare: SecretKey::from(secret),
+        publicKey: DeterministicPublicKey::from(public_key),
+    })
+}
+
+/// Deal shares of random `a`, `e` and `s` to each of the presigning `parties`
+pub fn deal_presign_shares(
+    threshold: usize,
+    index: usize,
+    parties: &[usize],
+) -> Result<ThresholdPresignDealing, BBSError> {
+    check_presign_parties(threshold, index, parties)?;
+    let mut rng = thread_rng();
+    let a = random_polynomial(Fr::random(&mut rng), threshold);
+    let e = random_polynomial(Fr::random(&mut rng), threshold);
+    let s = random_polynomial(Fr::random(&mut rng), threshold);
+    let shares = parties
+        .iter()
+        .map(|j| ThresholdPresignShare {
+            a: SecretKey::from(evaluate(&a, *j)),
+            e: SecretKey::from(evaluate(&e, *j)),
+            s: SecretKey::from(evaluate(&s, *j)),
+        })
+        .collect();
+    Ok(ThresholdPresignDealing { index, shares })
+}
+
+/// Sum the received shares of `a`, `e` and `s` then reshare this party's
+/// degree 2(t-1) shares of `a(x + e)` and `as` to each of the presigning `parties`
+pub fn reshare_presign_products(
+    threshold: usize,
+    parties: &[usize],
+    key_share: &ThresholdKeyShare,
+    shares: &[ThresholdPresignShare],
+) -> Result<ThresholdPresignRound2Response, BBSError> {
+    check_presign_parties(threshold, key_share.index, parties)?;
+    if shares.len() != parties.len() {
+        return Err(general_error(&format!(
+            "Expected {} presign shares but received {}",
+            parties.len(),
+            shares.len()
+        )));
+    }
+    let mut a = Fr::zero();
+    let mut e = Fr::zero();
+    let mut s = Fr::zero();
+    for share in shares {
+        a.add_assign(&secret_key_to_fr(&share.a)?);
+        e.add_assign(&secret_key_to_fr(&share.e)?);
+        s.add_assign(&secret_key_to_fr(&share.s)?);
+    }
+
+    // a(x + e)
+    let mut delta = secret_key_to_fr(&key_share.secretKeyShare)?;
+    delta.add_assign(&e);
+    delta.mul_assign(&a);
+    // as
+    let mut alpha = a;
+    alpha.mul_assign(&s);
+
+    let delta_polynomial = random_polynomial(delta, threshold);
+    let alpha_polynomial = random_polynomial(alpha, threshold);
+    let reshares = parties
+        .iter()
+        .map(|j| ThresholdPresignReshare {
+            delta: SecretKey::from(evaluate(&delta_polynomial, *j)),
+            alpha: SecretKey::from(evaluate(&alpha_polynomial, *j)),
+        })
+        .collect();
+
+    Ok(ThresholdPresignRound2Response {
+        state: ThresholdPresignRound2State {
+            index: key_share.index,
+            a: SecretKey::from(a),
+            e: SecretKey::from(e),
+            s: SecretKey::from(s),
+        },
+        reshares,
+    })
+}
+
+/// Interpolate the received reshares into this party's degree t-1 shares of `delta` and `alpha`
+pub fn reduce_presign_reshares(
+    threshold: usize,
+    parties: &[usize],
+    state: &ThresholdPresignRound2State,
+    reshares: &[ThresholdPresignReshare],
+) -> Result<ThresholdPresignRound3Response, BBSError> {
+    check_presign_parties(threshold, state.index, parties)?;
+    if reshares.len() != parties.len() {
+        return Err(general_error(&format!(
+            "Expected {} reshares but received {}",
+            parties.len(),
+            reshares.len()
+        )));
+    }
+    let mut delta = Fr::zero();
+    let mut alpha = Fr::zero();
+    for (j, reshare) in parties.iter().zip(reshares.iter()) {
+        let lambda = lagrange_coefficient(parties, *j);
+        let mut d = secret_key_to_fr(&reshare.delta)?;
+        d.mul_assign(&lambda);
+        delta.add_assign(&d);
+        let mut al = secret_key_to_fr(&reshare.alpha)?;
+        al.mul_assign(&lambda);
+        alpha.add_assign(&al);
+    }
+
+    Ok(ThresholdPresignRound3Response {
+        state: ThresholdPresignRound3State {
+            index: state.index,
+            a: state.a.clone(),
+            alpha: SecretKey::from(alpha),
+        },
+        opening: ThresholdPresignOpening {
+            index: state.index,
+            delta: SignatureMessage::from(delta),
+            e: SignatureMessage::from(secret_key_to_fr(&state.e)?),
+            s: SignatureMessage::from(secret_key_to_fr(&state.s)?),
+        },
+    })
+}
+
+/// Open `delta`, `e` and `s` from at least `threshold` openings
+pub fn finalize_presignature(
+    threshold: usize,
+    state: &ThresholdPresignRound3State,
+    openings: &[ThresholdPresignOpening],
+) -> Result<ThresholdPresignature, BBSError> {
+    let indices = distinct_indices(threshold, openings.iter().map(|o| o.index))?;
+    let delta = interpolate(&indices, openings.iter().map(|o| *o.delta.as_ref()));
+    if delta.is_zero() {
+        return Err(general_error("Presigning produced a zero delta"));
+    }
+    Ok(ThresholdPresignature {
+        index: state.index,
+        a: state.a.clone(),
+        alpha: state.alpha.clone(),
+        delta: SignatureMessage::from(delta),
+        e: SignatureMessage::from(interpolate(
+            &indices,
+            openings.iter().map(|o| *o.e.as_ref()),
+        )),
+        s: SignatureMessage::from(interpolate(
+            &indices,
+            openings.iter().map(|o| *o.s.as_ref()),
+        )),
+    })
+}
+
+/// Compute `(g1 * h_1^m_1 ... h_L^m_L)^a_i * h_0^alpha_i` for this party's presignature
+pub fn partial_sign(
+    presignature: &ThresholdPresignature,
+    messages: &[SignatureMessage],
+    pk: &PublicKey,
+) -> Result<ThresholdPartialSignature, BBSError> {
+    if messages.len() != pk.message_count() {
+        return Err(BBSErrorKind::PublicKeyGeneratorMessageCountMismatch(
+            pk.message_count(),
+            messages.len(),
+        )
+        .into());
+    }
+    let a = secret_key_to_fr(&presignature.a)?;
+    let alpha = secret_key_to_fr(&presignature.alpha)?;
+    let mut builder = CommitmentBuilder::new();
+    builder.add(GeneratorG1::from(G1::one()), SignatureMessage::from(a));
+    builder.add(pk.h0, SignatureMessage::from(alpha));
+    for (h, m) in pk.h.iter().zip(messages.iter()) {
+        let mut exp = *m.as_ref();
+        exp.mul_assign(&a);
+        builder.add(h, SignatureMessage::from(exp));
+    }
+    Ok(ThresholdPartialSignature {
+        index: presignature.index,
+        partial: builder.finalize(),
+        delta: presignature.delta,
+        e: presignature.e,
+        s: presignature.s,
+    })
+}
+
+/// Combine at least `threshold` partial signatures from the same presigning ceremony
+pub fn combine_partial_signatures(
+    threshold: usize,
+    partials: &[ThresholdPartialSignature],
+) -> Result<Signature, BBSError> {
+    let indices = distinct_indices(threshold, partials.iter().map(|p| p.index))?;
+    let first = &partials[0];
+    if partials
+        .iter()
+        .any(|p| p.delta != first.delta || p.e != first.e || p.s != first.s)
+    {
+        return Err(general_error(
+            "Partial signatures come from different presignatures",
+        ));
+    }
+    let delta_inverse = first
+        .delta
+        .as_ref()
+        .inverse()
+        .ok_or_else(|| general_error("Presignature has a zero delta"))?;
+
+    let mut a = G1::zero();
+    for (i, partial) in indices.iter().zip(partials.iter()) {
+        let mut p: G1 = *partial.partial.as_ref();
+        p.mul_assign(lagrange_coefficient(&indices, *i));
+        a.add_assign(&p);
+    }
+    a.mul_assign(delta_inverse);
+
+    let mut bytes = Vec::with_capacity(SIGNATURE_COMPRESSED_SIZE);
+    a.serialize(&mut bytes, true)?;
+    bytes.extend_from_slice(&first.e.to_bytes_compressed_form());
+    bytes.extend_from_slice(&first.s.to_bytes_compressed_form());
+    Signature::try_from(bytes.as_slice())
+}
+
+fn check_threshold(threshold: usize, participants: usize) -> Result<(), BBSError> {
+    if threshold == 0 || threshold > participants {
+        return Err(general_error(&format!(
+            "Threshold must be between 1 and {} but was {}",
+            participants, threshold
+        )));
+    }
+    Ok(())
+}
+
+fn check_index(index: usize, participants: usize) -> Result<(), BBSError> {
+    if index == 0 || index > participants {
+        return Err(general_error(&format!(
+            "Party index must be between 1 and {} but was {}",
+            participants, index
+        )));
+    }
+    Ok(())
+}
+
+fn check_presign_parties(
+    threshold: usize,
+    index: usize,
+    parties: &[usize],
+) -> Result<(), BBSError> {
+    let distinct: BTreeSet<usize> = parties.iter().copied().collect();
+    if distinct.len() != parties.len() || distinct.contains(&0) {
+        return Err(general_error(
+            "Presigning parties must be distinct non-zero indices",
+        ));
+    }
+    if threshold == 0 || parties.len() < 2 * threshold - 1 {
+        return Err(general_error(&format!(
+            "Presigning requires at least {} parties but {} were given",
+            2 * threshold.max(1) - 1,
+            parties.len()
+        )));
+    }
+    if !distinct.contains(&index) {
+        return Err(general_error(&format!(
+            "Party {} is not one of the presigning parties",
+            index
+        )));
+    }
+    Ok(())
+}
+
+fn distinct_indices<I: Iterator<Item = usize>>(
+    threshold: usize,
+    indices: I,
+) -> Result<Vec<usize>, BBSError> {
+    let indices: Vec<usize> = indices.collect();
+    let distinct: BTreeSet<usize> = indices.iter().copied().collect();
+    if distinct.len() != indices.len() || distinct.contains(&0) {
+        return Err(general_error("Party indices must be distinct and non-zero"));
+    }
+    if threshold == 0 || indices.len() < threshold {
+        return Err(general_error(&format!(
+            "At least {} shares are required but {} were given",
+            threshold,
+            indices.len()
+        )));
+    }
+    Ok(indices)
+}
+
+fn fr_from_index(i: usize) -> Fr {
+    Fr::from_repr(FrRepr::from(i as u64)).unwrap()
+}
+
+/// A polynomial of degree `threshold - 1` with `secret` as the constant term
+fn random_polynomial(secret: Fr, threshold: usize) -> Vec<Fr> {
+    let mut rng = thread_rng();
+    let mut polynomial = Vec::with_capacity(threshold);
+    polynomial.push(secret);
+    for _ in 1..threshold {
+        polynomial.push(Fr::random(&mut rng));
+    }
+    polynomial
+}
+
+fn evaluate(polynomial: &[Fr], x: usize) -> Fr {
+    let x = fr_from_index(x);
+    let mut result = Fr::zero();
+    for c in polynomial.iter().rev() {
+        result.mul_assign(&x);
+        result.add_assign(c);
+    }
+    result
+}
+
+/// Evaluate the Feldman commitments in the exponent at `x`
+fn evaluate_commitments(commitments: &[GeneratorG2], x: usize) -> G2 {
+    let x = fr_from_index(x);
+    let mut result = G2::zero();
+    for c in commitments.iter().rev() {
+        result.mul_assign(x);
+        result.add_assign(c.as_ref());
+    }
+    result
+}
+
+/// The Lagrange coefficient at zero for party `i` amongst `indices`
+fn lagrange_coefficient(indices: &[usize], i: usize) -> Fr {
+    let xi = fr_from_index(i);
+    let mut numerator = Fr::one();
+    let mut denominator = Fr::one();
+    for j in indices.iter().filter(|j| **j != i) {
+        let xj = fr_from_index(*j);
+        numerator.mul_assign(&xj);
+        let mut d = xj;
+        d.sub_assign(&xi);
+        denominator.mul_assign(&d);
+    }
+    // indices are distinct so the denominator is never zero
+    numerator.mul_assign(&denominator.inverse().unwrap());
+    numerator
+}
+
+fn interpolate<I: Iterator<Item = Fr>>(indices: &[usize], values: I) -> Fr {
+    let mut result = Fr::zero();
+    for (i, v) in indices.iter().zip(values) {
+        let mut term = v;
+        term.mul_assign(&lagrange_coefficient(indices, *i));
+        result.add_assign(&term);
+    }
+    result
+}
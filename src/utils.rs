@@ -11,6 +11,16 @@
  * limitations under the License.
  */
 
+use crate::BbsVerifyResponse;
+
+use bbs::prelude::*;
+use ff_zeroize::Field;
+use pairing_plus::{
+    bls12_381::{Bls12, Fq12, Fr, G1, G2},
+    serdes::SerDes,
+    CurveAffine, CurveProjective, Engine,
+};
+use std::{convert::TryFrom, io::Cursor, sync::atomic};
 use wasm_bindgen::prelude::*;
 
 pub fn set_panic_hook() {
@@ -26,6 +36,72 @@ pub fn set_panic_hook() {
     }
 }
 
+/// A `GeneralError` with the message `msg`
+pub(crate) fn general_error(msg: &str) -> BBSError {
+    BBSErrorKind::GeneralError {
+        msg: msg.to_string(),
+    }
+    .into()
+}
+
+/// Parses a verify request and checks it with `verify`, reporting a request that cannot be
+/// parsed or a failed check as an unverified `BbsVerifyResponse` with its error
+pub(crate) fn verify_response<R, E>(
+    request: JsValue,
+    verify: impl FnOnce(R) -> Result<bool, E>,
+) -> JsValue
+where
+    R: TryFrom<JsValue, Error = JsValue>,
+    E: std::fmt::Debug,
+{
+    let response = match R::try_from(request)
+        .map_err(|e| format!("{:?}", e))
+        .and_then(|r| verify(r).map_err(|e| format!("{:?}", e)))
+    {
+        Ok(verified) => BbsVerifyResponse {
+            verified,
+            error: None,
+        },
+        Err(e) => BbsVerifyResponse {
+            verified: false,
+            error: Some(e),
+        },
+    };
+    serde_wasm_bindgen::to_value(&response).unwrap()
+}
+
+/// Overwrites a secret that does not implement `Zeroize`, such as a `SignatureBlinding`
+/// or a `SignatureMessage`, with its zero value. The write is volatile so that it is not
+/// removed as a dead store when the value is about to be dropped.
+pub(crate) fn zeroize_copy<T: Copy + Default>(value: &mut T) {
+    // SAFETY: `value` is a valid, aligned and exclusive reference, and as `T` is `Copy`
+    // it has no drop glue that overwriting it would skip
+    unsafe { std::ptr::write_volatile(value, T::default()) };
+    atomic::compiler_fence(atomic::Ordering::SeqCst);
+}
+
+/// Overwrites the messages and external blindings of proof messages, which are not `Copy`
+pub(crate) fn zeroize_proof_messages(messages: &mut [ProofMessage]) {
+    for message in messages.iter_mut() {
+        match message {
+            ProofMessage::Revealed(m)
+            | ProofMessage::Hidden(HiddenMessage::ProofSpecificBlinding(m)) => zeroize_copy(m),
+            ProofMessage::Hidden(HiddenMessage::ExternalBlinding(m, blinding)) => {
+                zeroize_copy(m);
+                zeroize_copy(blinding);
+            }
+        }
+    }
+}
+
+/// The scalar of a secret key, which the `bbs` crate keeps private
+pub(crate) fn secret_key_to_fr(sk: &SecretKey) -> Result<Fr, BBSError> {
+    Ok(Fr::deserialize(
+        &mut Cursor::new(sk.to_bytes_compressed_form()),
+        true,
+    )?)
+}
+
 #[wasm_bindgen]
 extern "C" {
     // Use `js_namespace` here to bind `console.log(..)` instead of just
@@ -41,3 +117,17 @@ extern "C" {
     #[wasm_bindgen(js_namespace = console, js_name = log)]
     pub fn log_many(a: &str, b: &str);
 }
+
+/// Whether the product of the pairings `e(a, b)` of the pairs is one, with a single final
+/// exponentiation
+pub(crate) fn pairing_product_is_one(pairs: &[(G1, G2)]) -> bool {
+    let prepared: Vec<_> = pairs
+        .iter()
+        .map(|(a, b)| (a.into_affine().prepare(), b.into_affine().prepare()))
+        .collect();
+    let refs: Vec<_> = prepared.iter().map(|(a, b)| (a, b)).collect();
+    match Bls12::final_exponentiation(&Bls12::miller_loop(&refs)) {
+        Some(product) => product == Fq12::one(),
+        None => false,
+    }
+}
@@ -0,0 +1,476 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Verifiable encryption of a hidden message to an auditor.
+//!
+//! The auditor key pair is a BLS 12-381 G1 key pair `(x, Y = g^x)`. The scalar `m` of
+//! the hidden message is split into sixteen 16 bit chunks with
+//! `m = sum(2^(16i) * m_i)`, and each chunk is encrypted with ElGamal as
+//! `(g^r_i, Y^r_i * g^m_i)`. The `verifiableEncryption` option of `createProof` proves
+//! knowledge of the `r_i` and `m_i`, with the blinding of `m` in the signature proof
+//! split across the chunks so the responses of the chunks add up to the response of
+//! `m`, and proves each `m_i` is in `[0, 2^16)` with a range proof. The auditor
+//! decrypts each `g^m_i` and solves its discrete logarithm to recover `m`, which is the
+//! value of a numeric message and the hash of any other message.
+
+use crate::linked_proof;
+use crate::numeric_message::{
+    message_scalar, numeric_message, numeric_value, NUMERIC_MESSAGE_PREFIX,
+};
+use crate::range_proof::{RangeProof, RangeProver};
+use crate::statements::{
+    write_point, write_scalar, ProverStatement, StatementReader, VerifierStatement,
+};
+use crate::utils::{general_error, secret_key_to_fr, set_panic_hook};
+use crate::BbsVerifyProofResponse;
+
+use bbs::prelude::*;
+use ff_zeroize::{Field, PrimeField};
+use pairing_plus::{
+    bls12_381::{Fr, FrRepr, G1},
+    serdes::SerDes,
+    CurveProjective,
+};
+use serde::{
+    de::{Error as DError, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    convert::{TryFrom, TryInto},
+};
+use wasm_bindgen::prelude::*;
+
+/// The number of bits of a chunk
+const CHUNK_BITS: usize = 16;
+/// The number of chunks of a scalar
+const CHUNK_COUNT: usize = 16;
+/// The number of baby steps to solve the discrete logarithm of a chunk
+const BABY_STEPS: u64 = 1 << (CHUNK_BITS / 2);
+
+/// The public key `Y = g^x` of an auditor, a BLS 12-381 G1 public key
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AuditorPublicKey(G1);
+
+impl AuditorPublicKey {
+    pub fn to_bytes_compressed_form(&self) -> Vec<u8> {
+        compressed(&self.0)
+    }
+}
+
+impl AsRef<G1> for AuditorPublicKey {
+    fn as_ref(&self) -> &G1 {
+        &self.0
+    }
+}
+
+impl TryFrom<&[u8]> for AuditorPublicKey {
+    type Error = BBSError;
+
+    /// Checks the key is a compressed point of the G1 subgroup other than the identity
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        if value.len() != G1_COMPRESSED_SIZE {
+            return Err(general_error("Invalid auditor public key"));
+        }
+        let key = G1::deserialize(&mut &value[..], true)?;
+        if key.is_zero() {
+            return Err(general_error("Invalid auditor public key"));
+        }
+        Ok(Self(key))
+    }
+}
+
+impl Serialize for AuditorPublicKey {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_bytes(&self.to_bytes_compressed_form())
+    }
+}
+
+impl<'a> Deserialize<'a> for AuditorPublicKey {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'a>,
+    {
+        struct DeserializeVisitor;
+
+        impl<'a> Visitor<'a> for DeserializeVisitor {
+            type Value = AuditorPublicKey;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("expected byte array")
+            }
+
+            fn visit_bytes<E>(self, value: &[u8]) -> Result<AuditorPublicKey, E>
+            where
+                E: DError,
+            {
+                AuditorPublicKey::try_from(value)
+                    .map_err(|_| DError::invalid_value(serde::de::Unexpected::Bytes(value), &self))
+            }
+        }
+
+        deserializer.deserialize_bytes(DeserializeVisitor)
+    }
+}
+
+wasm_impl!(
+    VerifiableEncryptionOptions,
+    messageIndex: usize,
+    auditorPublicKey: AuditorPublicKey
+);
+
+wasm_impl!(
+    /// The ElGamal encryption of a chunk of a message
+    #[allow(non_snake_case)]
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    ElGamalCiphertext,
+    ephemeralKey: Commitment,
+    maskedMessage: Commitment
+);
+
+wasm_impl!(
+    VerifiableEncryptionDecryptRequest,
+    secretKey: SecretKey,
+    ciphertext: Vec<ElGamalCiphertext>,
+    candidates: Option<Vec<Vec<u8>>>
+);
+
+wasm_impl!(
+    VerifiableEncryptionDecryptResponse,
+    message: SignatureMessage,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    numericMessage: Option<Vec<u8>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    candidateIndex: Option<usize>
+);
+
+/// Decrypts the scalar of an encrypted message with the auditor secret key. It is
+/// returned as a numeric message when it is the value of one, and the index of the
+/// candidate values it matches is returned if any
+#[wasm_bindgen(js_name = decryptVerifiableEncryption)]
+pub async fn decrypt_verifiable_encryption(request: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let request: VerifiableEncryptionDecryptRequest = request.try_into()?;
+    if request.ciphertext.len() != CHUNK_COUNT {
+        return Err(JsValue::from("Invalid ciphertext"));
+    }
+    let x = map_err!(secret_key_to_fr(&request.secretKey))?;
+
+    let chunk_weight = chunk_weight();
+    let mut weight = Fr::one();
+    let mut message = Fr::zero();
+    for ciphertext in &request.ciphertext {
+        // g^m_i = Y^r_i * g^m_i / (g^r_i)^x
+        let mut shared_secret = *ciphertext.ephemeralKey.as_ref();
+        shared_secret.mul_assign(x);
+        let mut chunk = *ciphertext.maskedMessage.as_ref();
+        chunk.sub_assign(&shared_secret);
+        let chunk = discrete_log(&chunk)
+            .ok_or_else(|| JsValue::from("The ciphertext does not decrypt to a message"))?;
+        let mut term = Fr::from_repr(FrRepr::from(chunk)).unwrap();
+        term.mul_assign(&weight);
+        message.add_assign(&term);
+        weight.mul_assign(&chunk_weight);
+    }
+    let message = SignatureMessage::from(message);
+
+    let numeric = numeric_message_of(&message);
+    let candidate_index = request
+        .candidates
+        .as_ref()
+        .and_then(|c| c.iter().position(|c| message_scalar(c) == message));
+    Ok(
+        serde_wasm_bindgen::to_value(&VerifiableEncryptionDecryptResponse {
+            message,
+            numericMessage: numeric,
+            candidateIndex: candidate_index,
+        })
+        .unwrap(),
+    )
+}
+
+struct ChunkProver {
+    chunk: SignatureMessage,
+    chunk_blinding: ProofNonce,
+    randomness: SignatureMessage,
+    randomness_blinding: ProofNonce,
+    ciphertext: [G1; 2],
+    commitments: [G1; 2],
+    range: RangeProver,
+}
+
+/// Proves the ciphertext encrypts the hidden message to the auditor
+pub(crate) struct VerifiableEncryptionProver<'a> {
+    options: &'a VerifiableEncryptionOptions,
+    chunks: Vec<ChunkProver>,
+}
+
+impl<'a> VerifiableEncryptionProver<'a> {
+    pub(crate) fn new(options: &'a VerifiableEncryptionOptions) -> Self {
+        Self {
+            options,
+            chunks: Vec::new(),
+        }
+    }
+}
+
+impl ProverStatement for VerifiableEncryptionProver<'_> {
+    fn linked(&self) -> Vec<usize> {
+        vec![self.options.messageIndex]
+    }
+
+    fn commit(
+        &mut self,
+        messages: &[SignatureMessage],
+        blindings: &BTreeMap<usize, ProofNonce>,
+        bytes: &mut Vec<u8>,
+    ) -> Result<(), BBSError> {
+        let index = self.options.messageIndex;
+        let g = G1::one();
+        let y = *self.options.auditorPublicKey.as_ref();
+        linked_proof::append_points(bytes, &[g, y]);
+
+        self.chunks.clear();
+        let chunks = message_chunks(messages[index].as_ref());
+        let chunk_blindings = chunk_blindings(blindings[&index].as_ref());
+        for (chunk, chunk_blinding) in chunks.into_iter().zip(chunk_blindings) {
+            // (g^r, Y^r * g^m_i) with the commitments (g^b_r, Y^b_r * g^b_i)
+            let randomness = SignatureMessage::random();
+            let randomness_blinding = ProofNonce::random();
+            let ciphertext = [
+                linked_proof::multi_exp(&[g], &[randomness]),
+                linked_proof::multi_exp(&[y, g], &[randomness, chunk]),
+            ];
+            let b_r = SignatureMessage::from(*randomness_blinding.as_ref());
+            let commitments = [
+                linked_proof::multi_exp(&[g], &[b_r]),
+                linked_proof::multi_exp(&[y, g], &[b_r, SignatureMessage::from(chunk_blinding)]),
+            ];
+            let range = RangeProver::new(chunk.as_ref(), &chunk_blinding, CHUNK_BITS)
+                .ok_or_else(|| general_error("Invalid message chunk"))?;
+            linked_proof::append_points(
+                bytes,
+                &[ciphertext[0], ciphertext[1], commitments[0], commitments[1]],
+            );
+            range.append_bytes(bytes);
+            self.chunks.push(ChunkProver {
+                chunk,
+                chunk_blinding: ProofNonce::from(chunk_blinding),
+                randomness,
+                randomness_blinding,
+                ciphertext,
+                commitments,
+                range,
+            });
+        }
+        Ok(())
+    }
+
+    fn prove(&self, challenge: &ProofChallenge, proof: &mut Vec<u8>) {
+        for chunk in &self.chunks {
+            write_point(proof, &chunk.ciphertext[0]);
+            write_point(proof, &chunk.ciphertext[1]);
+            write_point(proof, &chunk.commitments[0]);
+            write_point(proof, &chunk.commitments[1]);
+            write_scalar(
+                proof,
+                &linked_proof::response(&chunk.randomness_blinding, challenge, &chunk.randomness),
+            );
+            write_scalar(
+                proof,
+                &linked_proof::response(&chunk.chunk_blinding, challenge, &chunk.chunk),
+            );
+            chunk.range.prove(challenge).write(proof);
+        }
+    }
+}
+
+struct ChunkProof {
+    ciphertext: [G1; 2],
+    commitments: [G1; 2],
+    randomness_response: SignatureMessage,
+    chunk_response: SignatureMessage,
+    range: RangeProof,
+}
+
+/// Checks the ciphertext encrypts the hidden message to the auditor, and outputs it
+pub(crate) struct VerifiableEncryptionVerifier<'a> {
+    options: &'a VerifiableEncryptionOptions,
+    chunks: Vec<ChunkProof>,
+}
+
+impl<'a> VerifiableEncryptionVerifier<'a> {
+    pub(crate) fn new(options: &'a VerifiableEncryptionOptions) -> Self {
+        Self {
+            options,
+            chunks: Vec::new(),
+        }
+    }
+}
+
+impl VerifierStatement for VerifiableEncryptionVerifier<'_> {
+    fn linked(&self) -> Vec<usize> {
+        vec![self.options.messageIndex]
+    }
+
+    fn read(&mut self, reader: &mut StatementReader, bytes: &mut Vec<u8>) -> Result<(), BBSError> {
+        linked_proof::append_points(bytes, &[G1::one(), *self.options.auditorPublicKey.as_ref()]);
+        self.chunks.clear();
+        for _ in 0..CHUNK_COUNT {
+            let chunk = ChunkProof {
+                ciphertext: [reader.point()?, reader.point()?],
+                commitments: [reader.point()?, reader.point()?],
+                randomness_response: reader.scalar()?,
+                chunk_response: reader.scalar()?,
+                range: RangeProof::read(reader, CHUNK_BITS)?,
+            };
+            linked_proof::append_points(
+                bytes,
+                &[
+                    chunk.ciphertext[0],
+                    chunk.ciphertext[1],
+                    chunk.commitments[0],
+                    chunk.commitments[1],
+                ],
+            );
+            chunk.range.append_bytes(bytes);
+            self.chunks.push(chunk);
+        }
+        Ok(())
+    }
+
+    fn verify(
+        &self,
+        responses: &BTreeMap<usize, SignatureMessage>,
+        challenge: &ProofChallenge,
+    ) -> bool {
+        let g = G1::one();
+        let y = *self.options.auditorPublicKey.as_ref();
+        let chunk_weight = chunk_weight();
+        let mut weight = Fr::one();
+        let mut message_response = Fr::zero();
+        for chunk in &self.chunks {
+            let mut term = *chunk.chunk_response.as_ref();
+            term.mul_assign(&weight);
+            message_response.add_assign(&term);
+            weight.mul_assign(&chunk_weight);
+
+            if linked_proof::recompute_commitment(
+                &[g],
+                &[chunk.randomness_response],
+                &chunk.ciphertext[0],
+                challenge,
+            ) != chunk.commitments[0]
+                || linked_proof::recompute_commitment(
+                    &[y, g],
+                    &[chunk.randomness_response, chunk.chunk_response],
+                    &chunk.ciphertext[1],
+                    challenge,
+                ) != chunk.commitments[1]
+                || !chunk
+                    .range
+                    .verify(CHUNK_BITS, &chunk.chunk_response, challenge)
+            {
+                return false;
+            }
+        }
+        // The responses of the chunks add up to the response of the message
+        SignatureMessage::from(message_response) == responses[&self.options.messageIndex]
+    }
+
+    fn output(&self, response: &mut BbsVerifyProofResponse) {
+        response.ciphertext = Some(
+            self.chunks
+                .iter()
+                .map(|c| ElGamalCiphertext {
+                    ephemeralKey: Commitment::from(c.ciphertext[0]),
+                    maskedMessage: Commitment::from(c.ciphertext[1]),
+                })
+                .collect(),
+        );
+    }
+}
+
+/// `2^16`, the weight of a chunk relative to the previous one
+fn chunk_weight() -> Fr {
+    Fr::from_repr(FrRepr::from(1u64 << CHUNK_BITS)).unwrap()
+}
+
+/// The chunks `m_i` of `m = sum(2^(16i) * m_i)`
+fn message_chunks(message: &Fr) -> Vec<SignatureMessage> {
+    let limbs = message.into_repr().0;
+    (0..CHUNK_COUNT)
+        .map(|i| {
+            let bits = i * CHUNK_BITS;
+            let chunk = (limbs[bits / 64] >> (bits % 64)) & ((1 << CHUNK_BITS) - 1);
+            SignatureMessage::from(Fr::from_repr(FrRepr::from(chunk)).unwrap())
+        })
+        .collect()
+}
+
+/// Random blindings `b_i` of the chunks with `b = sum(2^(16i) * b_i)` for the blinding
+/// `b` of the message
+fn chunk_blindings(blinding: &Fr) -> Vec<Fr> {
+    let chunk_weight = chunk_weight();
+    let mut weight = Fr::one();
+    let mut rest = *blinding;
+    let mut blindings = Vec::with_capacity(CHUNK_COUNT);
+    for _ in 0..CHUNK_COUNT - 1 {
+        let chunk_blinding = *ProofNonce::random().as_ref();
+        let mut term = chunk_blinding;
+        term.mul_assign(&weight);
+        rest.sub_assign(&term);
+        blindings.push(chunk_blinding);
+        weight.mul_assign(&chunk_weight);
+    }
+    rest.mul_assign(&weight.inverse().unwrap());
+    blindings.push(rest);
+    blindings
+}
+
+/// The discrete logarithm of `g^m` for `m` in `[0, 2^16)` with baby steps `g^j` and
+/// giant steps `g^(-256k)`
+fn discrete_log(point: &G1) -> Option<u64> {
+    let mut baby_steps = HashMap::new();
+    let mut step = G1::zero();
+    for j in 0..BABY_STEPS {
+        baby_steps.insert(compressed(&step), j);
+        step.add_assign(&G1::one());
+    }
+    // step is g^256
+    step.negate();
+    let mut current = *point;
+    for k in 0..BABY_STEPS {
+        if let Some(j) = baby_steps.get(&compressed(&current)) {
+            return Some(k * BABY_STEPS + j);
+        }
+        current.add_assign(&step);
+    }
+    None
+}
+
+fn compressed(point: &G1) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(G1_COMPRESSED_SIZE);
+    point.serialize(&mut bytes, true).unwrap();
+    bytes
+}
+
+/// The numeric message of a scalar that is the value of one
+fn numeric_message_of(message: &SignatureMessage) -> Option<Vec<u8>> {
+    let mut candidate = NUMERIC_MESSAGE_PREFIX.to_vec();
+    candidate.extend_from_slice(&message.to_bytes_compressed_form());
+    numeric_value(&candidate).map(numeric_message)
+}
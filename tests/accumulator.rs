@@ -0,0 +1,259 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Test suite for the Web and headless browsers.
+
+#![cfg(target_arch = "wasm32")]
+extern crate wasm_bindgen_test;
+use bbs::prelude::*;
+use std::convert::{TryFrom, TryInto};
+use wasm::prelude::*;
+use wasm::{BbsVerifyResponse, PoKOfSignatureProofWrapper};
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+async fn update(
+    sk: &SecretKey,
+    accumulator: Commitment,
+    additions: &[&[u8]],
+    removals: &[&[u8]],
+) -> AccumulatorUpdateResponse {
+    let request = AccumulatorUpdateRequest {
+        secretKey: sk.clone(),
+        accumulator,
+        additions: additions.iter().map(|a| a.to_vec()).collect(),
+        removals: removals.iter().map(|r| r.to_vec()).collect(),
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    accumulator_update(js_value)
+        .await
+        .unwrap()
+        .try_into()
+        .unwrap()
+}
+
+async fn verify_witness(
+    dpk: &DeterministicPublicKey,
+    accumulator: Commitment,
+    element: &[u8],
+    witness: Commitment,
+) -> bool {
+    let request = AccumulatorVerifyWitnessRequest {
+        publicKey: dpk.clone(),
+        accumulator,
+        element: element.to_vec(),
+        witness,
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    accumulator_verify_witness(js_value)
+        .await
+        .unwrap()
+        .is_truthy()
+}
+
+async fn update_witness(
+    element: &[u8],
+    witness: Commitment,
+    updates: &[&AccumulatorBatchUpdate],
+) -> Result<Commitment, JsValue> {
+    let request = AccumulatorUpdateWitnessRequest {
+        element: element.to_vec(),
+        witness,
+        updates: updates.iter().map(|u| (*u).clone()).collect(),
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    accumulator_update_witness(js_value)
+        .await
+        .map(|w| serde_wasm_bindgen::from_value(w).unwrap())
+}
+
+async fn verify(
+    proof: &PoKOfSignatureProofWrapper,
+    pk: &PublicKey,
+    messages: &[Vec<u8>],
+    accumulator: Option<AccumulatorVerifyOptions>,
+) -> bool {
+    let request = VerifyProofContext {
+        proof: PoKOfSignatureProofWrapper::try_from(proof.to_bytes().as_slice()).unwrap(),
+        publicKey: pk.clone(),
+        messages: messages[..1].to_vec(),
+        nonce: b"nonce".to_vec(),
+        holderBinding: None,
+        pseudonym: None,
+        accumulator,
+        statusList: None,
+        verifiableEncryption: None,
+        committed: None,
+        notEqual: None,
+        setMembership: None,
+        rateLimit: None,
+        designatedVerifier: None,
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    let result = bbs_verify_proof(js_value).await.unwrap();
+    let result: BbsVerifyResponse = serde_wasm_bindgen::from_value(result).unwrap();
+    result.verified
+}
+
+async fn prove_and_verify(
+    signature: &Signature,
+    pk: &PublicKey,
+    messages: &[Vec<u8>],
+    accumulator: Commitment,
+    witness: Commitment,
+    dpk: &DeterministicPublicKey,
+) -> bool {
+    let request = CreateProofRequest {
+        signature: signature.clone(),
+        publicKey: pk.clone(),
+        messages: messages.to_vec(),
+        revealed: vec![0],
+        nonce: b"nonce".to_vec(),
+        holderBinding: None,
+        pseudonym: None,
+        accumulator: Some(AccumulatorProofOptions {
+            revocationIndex: 1,
+            accumulator,
+            witness,
+        }),
+        statusList: None,
+        verifiableEncryption: None,
+        committed: None,
+        notEqual: None,
+        setMembership: None,
+        rateLimit: None,
+        designatedVerifier: None,
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    let proof: PoKOfSignatureProofWrapper =
+        serde_wasm_bindgen::from_value(bbs_create_proof(js_value).await.unwrap()).unwrap();
+
+    let options = AccumulatorVerifyOptions {
+        revocationIndex: 1,
+        accumulator,
+        publicKey: dpk.clone(),
+    };
+    let verified = verify(&proof, pk, messages, Some(options)).await;
+    // The membership proof is part of the proof, which does not verify without it
+    assert!(!verify(&proof, pk, messages, None).await);
+    verified
+}
+
+#[allow(non_snake_case)]
+#[wasm_bindgen_test]
+pub async fn accumulator_tests() {
+    let (dpk, accumulator_sk) = DeterministicPublicKey::new(None);
+    let accumulator: Commitment =
+        serde_wasm_bindgen::from_value(accumulator_initialize().await.unwrap()).unwrap();
+    let added = update(&accumulator_sk, accumulator, &[b"id1", b"id2", b"id3"], &[]).await;
+    assert_eq!(added.update.additions.len(), 3);
+    assert_eq!(added.update.additionPolynomial.len(), 3);
+    assert_eq!(added.witnesses.len(), 3);
+    let witness = added.witnesses[0];
+    assert!(verify_witness(&dpk, added.accumulator, b"id1", witness).await);
+    assert!(!verify_witness(&dpk, added.accumulator, b"id2", witness).await);
+
+    let request = AccumulatorWitnessRequest {
+        secretKey: accumulator_sk.clone(),
+        accumulator: added.accumulator,
+        element: b"id1".to_vec(),
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    let issued: Commitment =
+        serde_wasm_bindgen::from_value(accumulator_membership_witness(js_value).await.unwrap())
+            .unwrap();
+    assert_eq!(issued, witness);
+
+    let (pk, sk) = generate(2).unwrap();
+    let messages = vec![b"Message1".to_vec(), b"id1".to_vec()];
+    let hashed: Vec<SignatureMessage> = messages.iter().map(SignatureMessage::hash).collect();
+    let signature = Signature::new(hashed.as_slice(), &sk, &pk).unwrap();
+    assert!(prove_and_verify(&signature, &pk, &messages, added.accumulator, witness, &dpk).await);
+
+    // Witnesses are updated from the published batch updates, one or several at once
+    let batch = update(
+        &accumulator_sk,
+        added.accumulator,
+        &[b"id4", b"id5", b"id6"],
+        &[b"id2", b"id3"],
+    )
+    .await;
+    assert!(!verify_witness(&dpk, batch.accumulator, b"id1", witness).await);
+    let batch_witness = update_witness(b"id1", witness, &[&batch.update])
+        .await
+        .unwrap();
+    assert!(verify_witness(&dpk, batch.accumulator, b"id1", batch_witness).await);
+    assert_eq!(
+        update_witness(b"id4", batch.witnesses[0], &[])
+            .await
+            .unwrap(),
+        batch.witnesses[0]
+    );
+    let updated = update(&accumulator_sk, batch.accumulator, &[b"id7"], &[b"id5"]).await;
+    let new_witness = update_witness(b"id1", witness, &[&batch.update, &updated.update])
+        .await
+        .unwrap();
+    assert!(verify_witness(&dpk, updated.accumulator, b"id1", new_witness).await);
+    let other_witness = update_witness(b"id4", batch.witnesses[0], &[&updated.update])
+        .await
+        .unwrap();
+    assert!(verify_witness(&dpk, updated.accumulator, b"id4", other_witness).await);
+    assert!(
+        prove_and_verify(
+            &signature,
+            &pk,
+            &messages,
+            updated.accumulator,
+            new_witness,
+            &dpk
+        )
+        .await
+    );
+    assert!(
+        !prove_and_verify(
+            &signature,
+            &pk,
+            &messages,
+            updated.accumulator,
+            witness,
+            &dpk
+        )
+        .await
+    );
+
+    // A revoked id can no longer update its witness or prove membership
+    let revoked = update(&accumulator_sk, updated.accumulator, &[], &[b"id1"]).await;
+    assert!(update_witness(b"id1", new_witness, &[&revoked.update])
+        .await
+        .is_err());
+    assert!(update_witness(
+        b"id1",
+        witness,
+        &[&batch.update, &updated.update, &revoked.update]
+    )
+    .await
+    .is_err());
+    assert!(
+        !prove_and_verify(
+            &signature,
+            &pk,
+            &messages,
+            revoked.accumulator,
+            new_witness,
+            &dpk
+        )
+        .await
+    );
+}
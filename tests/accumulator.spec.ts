@@ -0,0 +1,162 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+import {
+  generateBls12381G2KeyPair,
+  bls12381toBbs,
+  sign,
+  createProof,
+  verifyProof,
+  accumulatorInitialize,
+  accumulatorUpdate,
+  accumulatorMembershipWitness,
+  accumulatorVerifyWitness,
+  accumulatorUpdateWitness,
+} from "../lib";
+import { stringToBytes } from "./utilities";
+
+describe("accumulator", () => {
+  it("should add revocation ids and issue their witnesses", async () => {
+    const accumulatorKeyPair = await generateBls12381G2KeyPair();
+    const initial = await accumulatorInitialize();
+    expect(initial).toBeInstanceOf(Uint8Array);
+
+    const added = await accumulatorUpdate({
+      secretKey: accumulatorKeyPair.secretKey,
+      accumulator: initial,
+      additions: [stringToBytes("id1"), stringToBytes("id2")],
+      removals: [],
+    });
+    expect(added.witnesses).toHaveLength(2);
+    expect(added.update.additions).toHaveLength(2);
+    expect(added.update.additionPolynomial).toHaveLength(2);
+
+    const witness = await accumulatorMembershipWitness({
+      secretKey: accumulatorKeyPair.secretKey,
+      accumulator: added.accumulator,
+      element: stringToBytes("id1"),
+    });
+    expect(witness).toEqual(added.witnesses[0]);
+    expect(
+      await accumulatorVerifyWitness({
+        publicKey: accumulatorKeyPair.publicKey,
+        accumulator: added.accumulator,
+        element: stringToBytes("id1"),
+        witness,
+      })
+    ).toBe(true);
+    expect(
+      await accumulatorVerifyWitness({
+        publicKey: accumulatorKeyPair.publicKey,
+        accumulator: added.accumulator,
+        element: stringToBytes("id2"),
+        witness,
+      })
+    ).toBe(false);
+  });
+
+  it("should update witnesses from the published batch updates", async () => {
+    const accumulatorKeyPair = await generateBls12381G2KeyPair();
+    const added = await accumulatorUpdate({
+      secretKey: accumulatorKeyPair.secretKey,
+      accumulator: await accumulatorInitialize(),
+      additions: [stringToBytes("id1"), stringToBytes("id2")],
+      removals: [],
+    });
+    const updated = await accumulatorUpdate({
+      secretKey: accumulatorKeyPair.secretKey,
+      accumulator: added.accumulator,
+      additions: [stringToBytes("id3")],
+      removals: [stringToBytes("id2")],
+    });
+    const witness = await accumulatorUpdateWitness({
+      element: stringToBytes("id1"),
+      witness: added.witnesses[0],
+      updates: [updated.update],
+    });
+    expect(
+      await accumulatorVerifyWitness({
+        publicKey: accumulatorKeyPair.publicKey,
+        accumulator: updated.accumulator,
+        element: stringToBytes("id1"),
+        witness,
+      })
+    ).toBe(true);
+
+    // A removed id can no longer update its witness
+    await expect(
+      accumulatorUpdateWitness({
+        element: stringToBytes("id2"),
+        witness: added.witnesses[1],
+        updates: [updated.update],
+      })
+    ).rejects.toThrowError();
+  });
+
+  it("should prove the hidden revocation id is in the accumulator", async () => {
+    const accumulatorKeyPair = await generateBls12381G2KeyPair();
+    const added = await accumulatorUpdate({
+      secretKey: accumulatorKeyPair.secretKey,
+      accumulator: await accumulatorInitialize(),
+      additions: [stringToBytes("id1"), stringToBytes("id2")],
+      removals: [],
+    });
+    const keyPair = await bls12381toBbs({
+      keyPair: await generateBls12381G2KeyPair(),
+      messageCount: 2,
+    });
+    const messages = [stringToBytes("Message1"), stringToBytes("id1")];
+    const signature = await sign({ keyPair, messages });
+    const nonce = stringToBytes("nonce");
+
+    const proof = await createProof({
+      signature,
+      publicKey: keyPair.publicKey,
+      messages,
+      revealed: [0],
+      nonce,
+      accumulator: {
+        revocationIndex: 1,
+        accumulator: added.accumulator,
+        witness: added.witnesses[0],
+      },
+    });
+    const verifyRequest = {
+      proof,
+      publicKey: keyPair.publicKey,
+      messages: [messages[0]],
+      nonce,
+      accumulator: {
+        revocationIndex: 1,
+        accumulator: added.accumulator,
+        publicKey: accumulatorKeyPair.publicKey,
+      },
+    };
+    expect((await verifyProof(verifyRequest)).verified).toBeTruthy();
+
+    const revoked = await accumulatorUpdate({
+      secretKey: accumulatorKeyPair.secretKey,
+      accumulator: added.accumulator,
+      additions: [],
+      removals: [stringToBytes("id1")],
+    });
+    const result = await verifyProof({
+      ...verifyRequest,
+      accumulator: {
+        ...verifyRequest.accumulator,
+        accumulator: revoked.accumulator,
+      },
+    });
+    expect(result.verified).toBeFalsy();
+  });
+});
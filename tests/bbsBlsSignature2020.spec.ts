@@ -0,0 +1,166 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+import {
+  generateBls12381G2KeyPair,
+  signBbsBlsSignature2020,
+  verifyBbsBlsSignature2020,
+  deriveBbsBlsSignatureProof2020,
+  verifyBbsBlsSignatureProof2020,
+  BlsKeyPair,
+  JsonLdDocument,
+} from "../lib";
+
+const personContext = "https://example.org/person/v1";
+
+const contexts = {
+  [personContext]: {
+    "@context": {
+      name: "https://schema.org/name",
+      birthDate: {
+        "@id": "https://schema.org/birthDate",
+        "@type": "http://www.w3.org/2001/XMLSchema#date",
+      },
+    },
+  },
+};
+
+const context = [
+  "https://www.w3.org/2018/credentials/v1",
+  "https://w3id.org/security/bbs/v1",
+  personContext,
+];
+
+const document = {
+  "@context": context,
+  id: "urn:uuid:credential",
+  type: ["VerifiableCredential"],
+  issuer: "did:example:issuer",
+  issuanceDate: "2020-04-25T00:00:00Z",
+  credentialSubject: {
+    name: "Alice",
+    birthDate: "1990-01-01",
+  },
+};
+
+const revealDocument = {
+  "@context": context,
+  type: "VerifiableCredential",
+  credentialSubject: {
+    "@explicit": true,
+    name: {},
+  },
+};
+
+const tamper = (signed: JsonLdDocument): JsonLdDocument => ({
+  ...signed,
+  credentialSubject: {
+    ...(signed.credentialSubject as JsonLdDocument),
+    name: "Bob",
+  },
+});
+
+describe("bbsBlsSignature2020", () => {
+  let keyPair: BlsKeyPair;
+  let signed: JsonLdDocument;
+
+  beforeAll(async () => {
+    keyPair = await generateBls12381G2KeyPair();
+    signed = (
+      await signBbsBlsSignature2020({
+        keyPair,
+        document,
+        proof: {
+          verificationMethod: "did:example:issuer#key-1",
+          proofPurpose: "assertionMethod",
+          created: "2020-04-25T00:00:00Z",
+        },
+        contexts,
+      })
+    ).document;
+  });
+
+  it("should sign and verify a document", async () => {
+    expect((signed.proof as JsonLdDocument).type).toEqual(
+      "BbsBlsSignature2020"
+    );
+    const verify = async (doc: JsonLdDocument): Promise<boolean> =>
+      (
+        await verifyBbsBlsSignature2020({
+          publicKey: keyPair.publicKey,
+          document: doc,
+          contexts,
+        })
+      ).verified;
+    expect(await verify(signed)).toBeTruthy();
+    expect(await verify(tamper(signed))).toBeFalsy();
+  });
+
+  it("should derive a proof revealing the framed statements", async () => {
+    const derived = (
+      await deriveBbsBlsSignatureProof2020({
+        publicKey: keyPair.publicKey,
+        document: signed,
+        revealDocument,
+        contexts,
+      })
+    ).document;
+    expect(derived.credentialSubject).toEqual({
+      id: "urn:bnid:_:c14n0",
+      name: "Alice",
+    });
+    const proof = derived.proof as JsonLdDocument;
+    expect(proof.type).toEqual("BbsBlsSignatureProof2020");
+    expect(Buffer.from(proof.nonce as string, "base64").length).toEqual(50);
+
+    const verify = async (doc: JsonLdDocument): Promise<boolean> =>
+      (
+        await verifyBbsBlsSignatureProof2020({
+          publicKey: keyPair.publicKey,
+          document: doc,
+          contexts,
+        })
+      ).verified;
+    expect(await verify(derived)).toBeTruthy();
+    expect(await verify(tamper(derived))).toBeFalsy();
+    expect(
+      await verify({
+        ...derived,
+        proof: {
+          ...proof,
+          nonce: Buffer.from("another nonce").toString("base64"),
+        },
+      })
+    ).toBeFalsy();
+  });
+
+  it("should keep a given nonce", async () => {
+    const nonce = Buffer.from("verifier nonce").toString("base64");
+    const derived = await deriveBbsBlsSignatureProof2020({
+      publicKey: keyPair.publicKey,
+      document: signed,
+      revealDocument,
+      nonce,
+      contexts,
+    });
+    expect((derived.document.proof as JsonLdDocument).nonce).toEqual(nonce);
+  });
+
+  it("should not resolve contexts that are not given", async () => {
+    const result = await verifyBbsBlsSignature2020({
+      publicKey: keyPair.publicKey,
+      document: signed,
+    });
+    expect(result.verified).toBeFalsy();
+  });
+});
@@ -0,0 +1,289 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Test suite for the Web and headless browsers.
+
+#![cfg(target_arch = "wasm32")]
+extern crate wasm_bindgen_test;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use bbs::prelude::*;
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use std::convert::{TryFrom, TryInto};
+use wasm::prelude::*;
+use wasm::{BbsVerifyResponse, PoKOfSignatureProofWrapper};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const PERSON_CONTEXT: &str = "https://example.org/person/v1";
+
+fn object(value: Value) -> Map<String, Value> {
+    match value {
+        Value::Object(o) => o,
+        _ => panic!("not an object"),
+    }
+}
+
+fn contexts() -> Option<BTreeMap<String, Value>> {
+    let mut contexts = BTreeMap::new();
+    contexts.insert(
+        PERSON_CONTEXT.to_string(),
+        json!({
+            "@context": {
+                "name": "https://schema.org/name",
+                "birthDate": {
+                    "@id": "https://schema.org/birthDate",
+                    "@type": "http://www.w3.org/2001/XMLSchema#date"
+                }
+            }
+        }),
+    );
+    Some(contexts)
+}
+
+fn context() -> Value {
+    json!([
+        "https://www.w3.org/2018/credentials/v1",
+        "https://w3id.org/security/bbs/v1",
+        PERSON_CONTEXT
+    ])
+}
+
+fn document() -> Map<String, Value> {
+    object(json!({
+        "@context": context(),
+        "id": "urn:uuid:credential",
+        "type": ["VerifiableCredential"],
+        "issuer": "did:example:issuer",
+        "issuanceDate": "2020-04-25T00:00:00Z",
+        "credentialSubject": {
+            "name": "Alice",
+            "birthDate": "1990-01-01"
+        }
+    }))
+}
+
+fn reveal_document() -> Map<String, Value> {
+    object(json!({
+        "@context": context(),
+        "type": "VerifiableCredential",
+        "credentialSubject": {
+            "@explicit": true,
+            "name": {}
+        }
+    }))
+}
+
+fn tamper(document: &Map<String, Value>) -> Map<String, Value> {
+    let mut document = document.clone();
+    document["credentialSubject"]["name"] = json!("Bob");
+    document
+}
+
+async fn verify_signature(public_key: &[u8], document: Map<String, Value>) -> bool {
+    let request = BbsBlsSignature2020VerifyRequest {
+        publicKey: DeterministicPublicKey::try_from(public_key).unwrap(),
+        document,
+        contexts: contexts(),
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    let result: BbsVerifyResponse = verify_bbs_bls_signature_2020(js_value)
+        .await
+        .unwrap()
+        .try_into()
+        .unwrap();
+    result.verified
+}
+
+async fn verify_proof(public_key: &[u8], document: Map<String, Value>) -> bool {
+    let request = BbsBlsSignatureProof2020VerifyRequest {
+        publicKey: DeterministicPublicKey::try_from(public_key).unwrap(),
+        document,
+        contexts: contexts(),
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    let result: BbsVerifyResponse = verify_bbs_bls_signature_proof_2020(js_value)
+        .await
+        .unwrap()
+        .try_into()
+        .unwrap();
+    result.verified
+}
+
+#[allow(non_snake_case)]
+#[wasm_bindgen_test]
+pub async fn bbs_bls_signature_2020_tests() {
+    let key_pair: BlsKeyPair = bls_generate_g2_key(None).await.unwrap().try_into().unwrap();
+    let public_key = key_pair.publicKey.clone().unwrap();
+
+    let request = BbsBlsSignature2020SignRequest {
+        keyPair: key_pair,
+        document: document(),
+        proof: object(json!({
+            "verificationMethod": "did:example:issuer#key-1",
+            "proofPurpose": "assertionMethod",
+            "created": "2020-04-25T00:00:00Z"
+        })),
+        contexts: contexts(),
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    let signed: BbsBlsSignature2020Response = sign_bbs_bls_signature_2020(js_value)
+        .await
+        .unwrap()
+        .try_into()
+        .unwrap();
+    let signed = signed.document;
+    assert_eq!(signed["proof"]["type"], "BbsBlsSignature2020");
+    assert!(verify_signature(&public_key, signed.clone()).await);
+    assert!(!verify_signature(&public_key, tamper(&signed)).await);
+
+    // The proof value is a blsSign signature over the canonical statements
+    let proof_value = signed["proof"]["proofValue"].as_str().unwrap();
+    let messages: Vec<Vec<u8>> = [
+        "_:c14n0 <http://purl.org/dc/terms/created> \"2020-04-25T00:00:00Z\"^^<http://www.w3.org/2001/XMLSchema#dateTime> .",
+        "_:c14n0 <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://w3id.org/security#BbsBlsSignature2020> .",
+        "_:c14n0 <https://w3id.org/security#proofPurpose> <https://w3id.org/security#assertionMethod> .",
+        "_:c14n0 <https://w3id.org/security#verificationMethod> <did:example:issuer#key-1> .",
+        "<urn:uuid:credential> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://www.w3.org/2018/credentials#VerifiableCredential> .",
+        "<urn:uuid:credential> <https://www.w3.org/2018/credentials#credentialSubject> _:c14n0 .",
+        "<urn:uuid:credential> <https://www.w3.org/2018/credentials#issuanceDate> \"2020-04-25T00:00:00Z\"^^<http://www.w3.org/2001/XMLSchema#dateTime> .",
+        "<urn:uuid:credential> <https://www.w3.org/2018/credentials#issuer> <did:example:issuer> .",
+        "_:c14n0 <https://schema.org/birthDate> \"1990-01-01\"^^<http://www.w3.org/2001/XMLSchema#date> .",
+        "_:c14n0 <https://schema.org/name> \"Alice\" .",
+    ]
+    .iter()
+    .map(|s| s.as_bytes().to_vec())
+    .collect();
+    let request = BlsBbsVerifyRequest {
+        publicKey: DeterministicPublicKey::try_from(public_key.as_slice()).unwrap(),
+        signature: Signature::try_from(STANDARD.decode(proof_value).unwrap()).unwrap(),
+        messages,
+    };
+    let result: BbsVerifyResponse = bls_verify(serde_wasm_bindgen::to_value(&request).unwrap())
+        .await
+        .unwrap()
+        .try_into()
+        .unwrap();
+    assert!(result.verified);
+
+    let derive = |reveal_document: Map<String, Value>, nonce: Option<String>| {
+        serde_wasm_bindgen::to_value(&BbsBlsSignatureProof2020DeriveRequest {
+            publicKey: DeterministicPublicKey::try_from(public_key.as_slice()).unwrap(),
+            document: signed.clone(),
+            revealDocument: reveal_document,
+            nonce,
+            contexts: contexts(),
+        })
+        .unwrap()
+    };
+    let derived: BbsBlsSignatureProof2020DeriveResponse =
+        derive_bbs_bls_signature_proof_2020(derive(reveal_document(), None))
+            .await
+            .unwrap()
+            .try_into()
+            .unwrap();
+    let derived = derived.document;
+
+    // The frame reveals the subject name with the skolemized label of the subject
+    assert_eq!(
+        derived["credentialSubject"],
+        json!({ "id": "urn:bnid:_:c14n0", "name": "Alice" })
+    );
+    assert_eq!(derived["issuer"], "did:example:issuer");
+    let proof = &derived["proof"];
+    assert_eq!(proof["type"], "BbsBlsSignatureProof2020");
+    assert_eq!(proof["verificationMethod"], "did:example:issuer#key-1");
+    let nonce = STANDARD.decode(proof["nonce"].as_str().unwrap()).unwrap();
+    assert_eq!(nonce.len(), 50);
+    assert!(verify_proof(&public_key, derived.clone()).await);
+
+    // The proof value is a blsCreateProof proof over the statements
+    let request = BlsVerifyProofContext {
+        proof: PoKOfSignatureProofWrapper::try_from(
+            STANDARD
+                .decode(proof["proofValue"].as_str().unwrap())
+                .unwrap()
+                .as_slice(),
+        )
+        .unwrap(),
+        publicKey: DeterministicPublicKey::try_from(public_key.as_slice()).unwrap(),
+        messages: [
+            "_:c14n0 <http://purl.org/dc/terms/created> \"2020-04-25T00:00:00Z\"^^<http://www.w3.org/2001/XMLSchema#dateTime> .",
+            "_:c14n0 <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://w3id.org/security#BbsBlsSignature2020> .",
+            "_:c14n0 <https://w3id.org/security#proofPurpose> <https://w3id.org/security#assertionMethod> .",
+            "_:c14n0 <https://w3id.org/security#verificationMethod> <did:example:issuer#key-1> .",
+            "<urn:uuid:credential> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://www.w3.org/2018/credentials#VerifiableCredential> .",
+            "<urn:uuid:credential> <https://www.w3.org/2018/credentials#credentialSubject> _:c14n0 .",
+            "<urn:uuid:credential> <https://www.w3.org/2018/credentials#issuanceDate> \"2020-04-25T00:00:00Z\"^^<http://www.w3.org/2001/XMLSchema#dateTime> .",
+            "<urn:uuid:credential> <https://www.w3.org/2018/credentials#issuer> <did:example:issuer> .",
+            "_:c14n0 <https://schema.org/name> \"Alice\" .",
+        ]
+        .iter()
+        .map(|s| s.as_bytes().to_vec())
+        .collect(),
+        nonce,
+        holderBinding: None,
+        pseudonym: None,
+        accumulator: None,
+        statusList: None,
+        verifiableEncryption: None,
+        committed: None,
+        notEqual: None,
+        setMembership: None,
+        rateLimit: None,
+        designatedVerifier: None,
+    };
+    let result: BbsVerifyResponse =
+        bls_verify_proof(serde_wasm_bindgen::to_value(&request).unwrap())
+            .await
+            .unwrap()
+            .try_into()
+            .unwrap();
+    assert!(result.verified);
+
+    assert!(!verify_proof(&public_key, tamper(&derived)).await);
+    let mut other_nonce = derived.clone();
+    other_nonce["proof"]["nonce"] = json!(STANDARD.encode(b"another nonce"));
+    assert!(!verify_proof(&public_key, other_nonce).await);
+
+    // A given nonce is kept, and a frame matching nothing reveals only the proof
+    let nonce = STANDARD.encode(b"verifier nonce");
+    let mut reveal_nothing = reveal_document();
+    reveal_nothing.insert("id".to_string(), json!("urn:uuid:other"));
+    let derived: BbsBlsSignatureProof2020DeriveResponse =
+        derive_bbs_bls_signature_proof_2020(derive(reveal_nothing, Some(nonce.clone())))
+            .await
+            .unwrap()
+            .try_into()
+            .unwrap();
+    let derived = derived.document;
+    assert_eq!(derived["proof"]["nonce"], json!(nonce));
+    assert!(derived.get("credentialSubject").is_none());
+    assert!(verify_proof(&public_key, derived).await);
+
+    // Documents using a context that is neither bundled nor given are rejected
+    let request = BbsBlsSignature2020VerifyRequest {
+        publicKey: DeterministicPublicKey::try_from(public_key.as_slice()).unwrap(),
+        document: signed,
+        contexts: None,
+    };
+    let result: BbsVerifyResponse =
+        verify_bbs_bls_signature_2020(serde_wasm_bindgen::to_value(&request).unwrap())
+            .await
+            .unwrap()
+            .try_into()
+            .unwrap();
+    assert!(!result.verified);
+    assert!(result.error.unwrap().contains(PERSON_CONTEXT));
+}
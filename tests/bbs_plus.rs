@@ -69,6 +69,49 @@ pub async fn bbs_sign_tests() {
     assert!(sig_res.is_ok());
 }
 
+#[allow(non_snake_case)]
+#[wasm_bindgen_test]
+pub async fn bbs_sign_batch_tests() {
+    let (pk, sk) = generate(2).unwrap();
+    let messages = vec![
+        vec![b"Message1".to_vec(), b"Message2".to_vec()],
+        vec![b"Message3".to_vec(), b"Message4".to_vec()],
+        vec![b"Message5".to_vec(), b"Message6".to_vec()],
+    ];
+    let request = BbsSignBatchRequest {
+        keyPair: BbsKeyPair {
+            publicKey: pk.clone(),
+            secretKey: Some(sk.clone()),
+            messageCount: 2,
+        },
+        messages: messages.clone(),
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    let s_res = bbs_sign_batch(js_value).await;
+    assert!(s_res.is_ok());
+    let signatures: Vec<Signature> = serde_wasm_bindgen::from_value(s_res.unwrap()).unwrap();
+    assert_eq!(signatures.len(), messages.len());
+    for (signature, messages) in signatures.iter().zip(messages.iter()) {
+        let messages: Vec<SignatureMessage> = messages.iter().map(SignatureMessage::hash).collect();
+        assert!(signature.verify(messages.as_slice(), &pk).unwrap());
+    }
+
+    let request = BbsSignBatchRequest {
+        keyPair: BbsKeyPair {
+            publicKey: pk,
+            secretKey: Some(sk),
+            messageCount: 2,
+        },
+        messages: vec![
+            vec![b"Message1".to_vec(), b"Message2".to_vec()],
+            vec![b"Message3".to_vec()],
+        ],
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    let s_res = bbs_sign_batch(js_value).await;
+    assert!(s_res.is_err());
+}
+
 #[allow(non_snake_case)]
 #[wasm_bindgen_test]
 pub async fn bbs_verify_tests() {
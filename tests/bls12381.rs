@@ -16,7 +16,9 @@
 #![cfg(target_arch = "wasm32")]
 extern crate wasm_bindgen_test;
 use bbs::prelude::*;
+use std::{collections::BTreeSet, convert::TryInto};
 use wasm::prelude::*;
+use wasm::BbsVerifyResponse;
 // use wasm::log;
 use wasm_bindgen_test::*;
 
@@ -129,3 +131,88 @@ async fn bls_generate_key_test() {
     assert_eq!(publicKey.length(), 96);
     assert_eq!(secretKey.length(), 32);
 }
+
+#[wasm_bindgen_test]
+async fn bls_generate_key_unique_test() {
+    let mut secret_keys = BTreeSet::new();
+    for _ in 0..16 {
+        let key: BlsKeyPair = bls_generate_g1_key(None).await.unwrap().try_into().unwrap();
+        secret_keys.insert(key.secretKey.unwrap().to_bytes_compressed_form());
+    }
+    assert_eq!(secret_keys.len(), 16);
+
+    let first: BlsKeyPair = bls_generate_g2_key(None).await.unwrap().try_into().unwrap();
+    let second: BlsKeyPair = bls_generate_g2_key(None).await.unwrap().try_into().unwrap();
+    assert_ne!(first.publicKey, second.publicKey);
+}
+
+#[allow(non_snake_case)]
+#[wasm_bindgen_test]
+async fn bls_blind_sign_test() {
+    let (dpk, sk) = DeterministicPublicKey::new(None);
+    let nonce = b"dummy nonce".to_vec();
+    let messages = vec![
+        b"Message1".to_vec(),
+        b"Message2".to_vec(),
+        b"Message3".to_vec(),
+    ];
+
+    let request = BlsBlindSignatureContextRequest {
+        publicKey: dpk.clone(),
+        messageCount: 3,
+        messages: vec![messages[0].clone()],
+        blinded: vec![0],
+        nonce: nonce.clone(),
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    let result = bls_blind_signature_commitment(js_value).await;
+    assert!(result.is_ok());
+    let context: BlindSignatureContextResponse = result.unwrap().try_into().unwrap();
+
+    let mut blinded = BTreeSet::new();
+    blinded.insert(0);
+    for (nonce, expected) in [(nonce.clone(), true), (b"bad nonce".to_vec(), false)] {
+        let request = BlsBlindSignatureVerifyContextRequest {
+            commitment: context.commitment.clone(),
+            proofOfHiddenMessages: context.proofOfHiddenMessages.clone(),
+            challengeHash: context.challengeHash.clone(),
+            publicKey: dpk.clone(),
+            messageCount: 3,
+            blinded: blinded.clone(),
+            nonce,
+        };
+        let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+        let res = bls_verify_blind_signature_proof(js_value).await;
+        assert!(res.is_ok());
+        assert_eq!(res.unwrap().is_truthy(), expected);
+    }
+
+    let request = BlsBlindSignContextRequest {
+        commitment: context.commitment.clone(),
+        publicKey: dpk.clone(),
+        messageCount: 3,
+        secretKey: sk,
+        messages: messages[1..].to_vec(),
+        known: vec![1, 2],
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    let blind_signature = bls_blind_sign(js_value).await;
+    assert!(blind_signature.is_ok());
+
+    let request = UnblindSignatureRequest {
+        signature: serde_wasm_bindgen::from_value(blind_signature.unwrap()).unwrap(),
+        blindingFactor: context.blindingFactor.clone(),
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    let signature = bbs_get_unblinded_signature(js_value).await.unwrap();
+
+    let request = BlsBbsVerifyRequest {
+        publicKey: dpk,
+        signature: serde_wasm_bindgen::from_value(signature).unwrap(),
+        messages,
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    let result = bls_verify(js_value).await.unwrap();
+    let result: BbsVerifyResponse = serde_wasm_bindgen::from_value(result).unwrap();
+    assert!(result.verified);
+}
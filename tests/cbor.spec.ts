@@ -0,0 +1,83 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+import {
+  generateBls12381G1KeyPair,
+  generateBls12381G2KeyPair,
+  bls12381toBbs,
+  sign,
+  createProof,
+  blsKeyPairToCoseKey,
+  blsKeyPairFromCoseKey,
+  bbsKeyPairToCbor,
+  bbsKeyPairFromCbor,
+  bbsPublicKeyToCbor,
+  bbsPublicKeyFromCbor,
+  signatureToCbor,
+  signatureFromCbor,
+  proofToCbor,
+  proofFromCbor,
+} from "../lib";
+import { stringToBytes } from "./utilities";
+
+describe("cbor", () => {
+  it("should round trip BLS key pairs through COSE_Key", async () => {
+    for (const keyPair of [
+      await generateBls12381G1KeyPair(),
+      await generateBls12381G2KeyPair(),
+    ]) {
+      const coseKey = await blsKeyPairToCoseKey(keyPair);
+      expect(coseKey).toBeInstanceOf(Uint8Array);
+      const decoded = await blsKeyPairFromCoseKey(coseKey);
+      expect(decoded.publicKey).toEqual(keyPair.publicKey);
+      expect(decoded.secretKey).toEqual(keyPair.secretKey);
+    }
+  });
+
+  it("should round trip BBS keys, signatures and proofs through CBOR", async () => {
+    const messages = [stringToBytes("Message1"), stringToBytes("Message2")];
+    const keyPair = await bls12381toBbs({
+      keyPair: await generateBls12381G2KeyPair(),
+      messageCount: 2,
+    });
+    const decodedKeyPair = await bbsKeyPairFromCbor(
+      await bbsKeyPairToCbor(keyPair)
+    );
+    expect(decodedKeyPair.publicKey).toEqual(keyPair.publicKey);
+    expect(decodedKeyPair.secretKey).toEqual(keyPair.secretKey);
+    expect(decodedKeyPair.messageCount).toEqual(2);
+    expect(
+      await bbsPublicKeyFromCbor(await bbsPublicKeyToCbor(keyPair.publicKey))
+    ).toEqual(keyPair.publicKey);
+
+    const signature = await sign({ keyPair, messages });
+    expect(await signatureFromCbor(await signatureToCbor(signature))).toEqual(
+      signature
+    );
+
+    const proof = await createProof({
+      signature,
+      publicKey: keyPair.publicKey,
+      messages,
+      revealed: [0],
+      nonce: stringToBytes("nonce"),
+    });
+    expect(await proofFromCbor(await proofToCbor(proof))).toEqual(proof);
+  });
+
+  it("should throw error when decoding malformed CBOR", async () => {
+    await expect(
+      signatureFromCbor(new Uint8Array([0xa1, 0x01]))
+    ).rejects.toThrowError();
+  });
+});
@@ -0,0 +1,201 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Test suite for the Web and headless browsers.
+
+#![cfg(target_arch = "wasm32")]
+extern crate wasm_bindgen_test;
+use bbs::prelude::*;
+use std::convert::TryInto;
+use wasm::prelude::*;
+use wasm::{BbsVerifyResponse, PoKOfSignatureProofWrapper};
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+fn to_bytes(value: JsValue) -> Vec<u8> {
+    js_sys::Uint8Array::new(&value).to_vec()
+}
+
+#[wasm_bindgen_test]
+pub async fn bls_key_pair_cose_key_tests() {
+    for key_pair in [
+        bls_generate_g2_key(None).await.unwrap(),
+        bls_generate_g1_key(None).await.unwrap(),
+    ] {
+        let key_pair: BlsKeyPair = key_pair.try_into().unwrap();
+        let cose_key = key_pair.to_cbor().unwrap();
+        let decoded = BlsKeyPair::from_cbor(&cose_key).unwrap();
+        assert_eq!(decoded.publicKey, key_pair.publicKey);
+        assert_eq!(decoded.secretKey, key_pair.secretKey);
+
+        // The map starts with kty OKP and crv -1
+        assert_eq!(&cose_key[..4], &[0xa4, 0x01, 0x01, 0x20]);
+
+        let js_value = serde_wasm_bindgen::to_value(&key_pair).unwrap();
+        let cose_key = to_bytes(bls_key_pair_to_cose_key(js_value).await.unwrap());
+        let decoded: BlsKeyPair = bls_key_pair_from_cose_key(cose_key)
+            .await
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(decoded.publicKey, key_pair.publicKey);
+
+        let public_only = BlsKeyPair {
+            publicKey: key_pair.publicKey.clone(),
+            secretKey: None,
+        };
+        let decoded = BlsKeyPair::from_cbor(&public_only.to_cbor().unwrap()).unwrap();
+        assert!(decoded.secretKey.is_none());
+    }
+
+    // A secret key of another key pair is rejected
+    let key_pair: BlsKeyPair = bls_generate_g2_key(None).await.unwrap().try_into().unwrap();
+    let other: BlsKeyPair = bls_generate_g2_key(None).await.unwrap().try_into().unwrap();
+    let mismatched = BlsKeyPair {
+        publicKey: key_pair.publicKey.clone(),
+        secretKey: other.secretKey,
+    };
+    assert!(BlsKeyPair::from_cbor(&mismatched.to_cbor().unwrap()).is_err());
+
+    // A public key that is not in the compressed form is rejected
+    let mut cose_key = key_pair.to_cbor().unwrap();
+    cose_key[8] ^= 0x80;
+    assert!(BlsKeyPair::from_cbor(&cose_key).is_err());
+    assert!(BlsKeyPair::from_cbor(&[0xa0]).is_err());
+    assert!(bls_key_pair_from_cose_key(vec![0xff]).await.is_err());
+}
+
+#[allow(non_snake_case)]
+#[wasm_bindgen_test]
+pub async fn bbs_cbor_tests() {
+    let key_pair: BlsKeyPair = bls_generate_g2_key(None).await.unwrap().try_into().unwrap();
+    let request = Bls12381ToBbsRequest {
+        keyPair: BlsKeyPair {
+            publicKey: key_pair.publicKey.clone(),
+            secretKey: key_pair.secretKey.clone(),
+        },
+        messageCount: 3,
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    let bbs_key_pair = bls_to_bbs_key(js_value).await.unwrap();
+    let cbor = to_bytes(bbs_key_pair_to_cbor(bbs_key_pair).await.unwrap());
+    let bbs_key_pair: BbsKeyPair = bbs_key_pair_from_cbor(cbor)
+        .await
+        .unwrap()
+        .try_into()
+        .unwrap();
+    assert_eq!(bbs_key_pair.messageCount, 3);
+    assert_eq!(bbs_key_pair.secretKey, key_pair.secretKey);
+
+    let public_key = bbs_key_pair.publicKey.to_cbor().unwrap();
+    assert_eq!(
+        PublicKey::from_cbor(&public_key)
+            .unwrap()
+            .to_bytes_compressed_form(),
+        bbs_key_pair.publicKey.to_bytes_compressed_form()
+    );
+    let js_value = serde_wasm_bindgen::to_value(&bbs_key_pair.publicKey).unwrap();
+    assert_eq!(
+        to_bytes(bbs_public_key_to_cbor(js_value).await.unwrap()),
+        public_key
+    );
+    assert!(bbs_public_key_from_cbor(public_key).await.is_ok());
+
+    // A message count that does not match the public key is rejected
+    let mut mismatched = bbs_key_pair.to_cbor().unwrap();
+    let last = mismatched.len() - 1;
+    mismatched[last] = 0x04;
+    assert!(BbsKeyPair::from_cbor(&mismatched).is_err());
+
+    // Signatures and proofs still verify after a round trip
+    let messages = vec![b"one".to_vec(), b"two".to_vec(), b"three".to_vec()];
+    let request = BlsBbsSignRequest {
+        keyPair: key_pair,
+        messages: messages.clone(),
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    let signature = bls_sign(js_value).await.unwrap();
+    let cbor = to_bytes(signature_to_cbor(signature).await.unwrap());
+    assert_eq!(cbor.len(), SIGNATURE_COMPRESSED_SIZE + 2);
+    let signature: Signature =
+        serde_wasm_bindgen::from_value(signature_from_cbor(cbor).await.unwrap()).unwrap();
+    assert!(signature_from_cbor(vec![0x41, 0x00]).await.is_err());
+
+    let public_key = request.keyPair.publicKey.unwrap();
+    let request = BlsCreateProofRequest {
+        signature,
+        publicKey: DeterministicPublicKey::from(*arrayref::array_ref![
+            public_key,
+            0,
+            G2_COMPRESSED_SIZE
+        ]),
+        messages: messages.clone(),
+        revealed: vec![0, 2],
+        nonce: b"nonce".to_vec(),
+        holderBinding: None,
+        pseudonym: None,
+        accumulator: None,
+        statusList: None,
+        verifiableEncryption: None,
+        committed: None,
+        notEqual: None,
+        setMembership: None,
+        rateLimit: None,
+        designatedVerifier: None,
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    let proof = bls_create_proof(js_value).await.unwrap();
+    let cbor = to_bytes(proof_to_cbor(proof).await.unwrap());
+    let proof: PoKOfSignatureProofWrapper =
+        serde_wasm_bindgen::from_value(proof_from_cbor(cbor.clone()).await.unwrap()).unwrap();
+    assert_eq!(
+        PoKOfSignatureProofWrapper::from_cbor(&cbor)
+            .unwrap()
+            .to_cbor()
+            .unwrap(),
+        cbor
+    );
+
+    let request = BlsVerifyProofContext {
+        proof,
+        publicKey: request.publicKey,
+        messages: vec![messages[0].clone(), messages[2].clone()],
+        nonce: b"nonce".to_vec(),
+        holderBinding: None,
+        pseudonym: None,
+        accumulator: None,
+        statusList: None,
+        verifiableEncryption: None,
+        committed: None,
+        notEqual: None,
+        setMembership: None,
+        rateLimit: None,
+        designatedVerifier: None,
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    let result: BbsVerifyResponse = bls_verify_proof(js_value)
+        .await
+        .unwrap()
+        .try_into()
+        .unwrap();
+    assert!(result.verified);
+
+    // A revealed index beyond the message count is rejected
+    let proof = PoKOfSignatureProofWrapper::from_cbor(&cbor).unwrap();
+    let mut tampered = cbor.clone();
+    tampered[3] = 0x03;
+    assert!(PoKOfSignatureProofWrapper::from_cbor(&tampered).is_err());
+    assert!(proof.to_cbor().is_ok());
+}
@@ -0,0 +1,78 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+import {
+  generateBls12381G2KeyPair,
+  bls12381toBbs,
+  sign,
+  createProof,
+  verifyProof,
+  BbsKeyPair,
+} from "../lib";
+import { stringToBytes } from "./utilities";
+
+describe("designatedVerifier", () => {
+  const messages = [
+    stringToBytes("Message1"),
+    stringToBytes("Message2"),
+    stringToBytes("Message3"),
+  ];
+  const pseudonym = { scope: stringToBytes("verifier1"), secretIndex: 1 };
+  let keyPair: BbsKeyPair;
+  let signature: Uint8Array;
+  let verifierPublicKey: Uint8Array;
+
+  beforeAll(async () => {
+    keyPair = await bls12381toBbs({
+      keyPair: await generateBls12381G2KeyPair(),
+      messageCount: 3,
+    });
+    signature = await sign({ keyPair, messages });
+    verifierPublicKey = (await generateBls12381G2KeyPair()).publicKey;
+  });
+
+  const verify = async (
+    proof: Uint8Array,
+    publicKey?: Uint8Array
+  ): Promise<boolean> =>
+    (
+      await verifyProof({
+        proof,
+        publicKey: keyPair.publicKey,
+        messages: [messages[0]],
+        nonce: stringToBytes("nonce"),
+        pseudonym,
+        designatedVerifier: publicKey
+          ? { verifierPublicKey: publicKey }
+          : undefined,
+      })
+    ).verified;
+
+  it("should create and verify a proof for the designated verifier", async () => {
+    const proof = await createProof({
+      signature,
+      publicKey: keyPair.publicKey,
+      messages,
+      revealed: [0],
+      nonce: stringToBytes("nonce"),
+      pseudonym,
+      designatedVerifier: { verifierPublicKey },
+    });
+    expect(proof).toBeInstanceOf(Uint8Array);
+    expect(await verify(proof, verifierPublicKey)).toBeTruthy();
+
+    const otherVerifier = await generateBls12381G2KeyPair();
+    expect(await verify(proof, otherVerifier.publicKey)).toBeFalsy();
+    expect(await verify(proof)).toBeFalsy();
+  });
+});
@@ -0,0 +1,123 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Test suite for the Web and headless browsers.
+
+#![cfg(target_arch = "wasm32")]
+extern crate wasm_bindgen_test;
+use bbs::prelude::*;
+use std::convert::{TryFrom, TryInto};
+use wasm::prelude::*;
+use wasm::{BbsVerifyProofResponse, PoKOfSignatureProofWrapper};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+async fn verifier_key() -> DeterministicPublicKey {
+    let key: BlsKeyPair = bls_generate_g2_key(None).await.unwrap().try_into().unwrap();
+    DeterministicPublicKey::try_from(key.publicKey.unwrap().as_slice()).unwrap()
+}
+
+fn scope() -> PseudonymOptions {
+    PseudonymOptions {
+        scope: b"verifier1".to_vec(),
+        secretIndex: 1,
+    }
+}
+
+async fn verify(
+    proof: &PoKOfSignatureProofWrapper,
+    pk: &PublicKey,
+    verifier_public_key: Option<&DeterministicPublicKey>,
+    pseudonym: Option<PseudonymOptions>,
+) -> BbsVerifyProofResponse {
+    let request = VerifyProofContext {
+        proof: PoKOfSignatureProofWrapper::try_from(proof.to_bytes().as_slice()).unwrap(),
+        publicKey: pk.clone(),
+        messages: vec![b"Message1".to_vec()],
+        nonce: b"nonce".to_vec(),
+        holderBinding: None,
+        pseudonym,
+        accumulator: None,
+        statusList: None,
+        verifiableEncryption: None,
+        committed: None,
+        notEqual: None,
+        setMembership: None,
+        rateLimit: None,
+        designatedVerifier: verifier_public_key.map(|k| DesignatedVerifierOptions {
+            verifierPublicKey: k.clone(),
+        }),
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    serde_wasm_bindgen::from_value(bbs_verify_proof(js_value).await.unwrap()).unwrap()
+}
+
+#[allow(non_snake_case)]
+#[wasm_bindgen_test]
+pub async fn designated_verifier_tests() {
+    let (pk, sk) = generate(3).unwrap();
+    let messages = vec![
+        b"Message1".to_vec(),
+        b"Message2".to_vec(),
+        b"Message3".to_vec(),
+    ];
+    let hashed: Vec<SignatureMessage> = messages.iter().map(SignatureMessage::hash).collect();
+    let signature = Signature::new(hashed.as_slice(), &sk, &pk).unwrap();
+    let verifier_public_key = verifier_key().await;
+
+    let request = CreateProofRequest {
+        signature,
+        publicKey: pk.clone(),
+        messages,
+        revealed: vec![0],
+        nonce: b"nonce".to_vec(),
+        holderBinding: None,
+        pseudonym: Some(scope()),
+        accumulator: None,
+        statusList: None,
+        verifiableEncryption: None,
+        committed: None,
+        notEqual: None,
+        setMembership: None,
+        rateLimit: None,
+        designatedVerifier: Some(DesignatedVerifierOptions {
+            verifierPublicKey: verifier_public_key.clone(),
+        }),
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    let proof: PoKOfSignatureProofWrapper =
+        serde_wasm_bindgen::from_value(bbs_create_proof(js_value).await.unwrap()).unwrap();
+    let result = verify(&proof, &pk, Some(&verifier_public_key), Some(scope())).await;
+    assert!(result.verified);
+    // The other statements of the proof are proven in the signature branch
+    assert!(result.pseudonym.is_some());
+
+    // The proof is bound to the designated verifier
+    assert!(
+        !verify(&proof, &pk, Some(&verifier_key().await), Some(scope()))
+            .await
+            .verified
+    );
+    assert!(!verify(&proof, &pk, None, Some(scope())).await.verified);
+
+    // The challenges of both branches must sum to the challenge of the proof
+    let mut tampered = PoKOfSignatureProofWrapper::try_from(proof.to_bytes().as_slice()).unwrap();
+    let length = tampered.statements.len();
+    tampered.statements[length - FR_COMPRESSED_SIZE * 2 + 1] ^= 1;
+    assert!(
+        !verify(&tampered, &pk, Some(&verifier_public_key), Some(scope()))
+            .await
+            .verified
+    );
+}
@@ -0,0 +1,132 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+import {
+  generateBls12381G2KeyPair,
+  bls12381toBbs,
+  sign,
+  createProof,
+  verifyProof,
+  generateLinkSecret,
+  createLinkSecretCommitment,
+  linkSecretBlindSignCommitment,
+  BbsCreateProofRequest,
+  BbsVerifyProofRequest,
+} from "../lib";
+import { stringToBytes } from "./utilities";
+
+describe("holderBinding", () => {
+  it("should create a blind sign commitment to the link secret", async () => {
+    const keyPair = await bls12381toBbs({
+      keyPair: await generateBls12381G2KeyPair(),
+      messageCount: 3,
+    });
+    const linkSecret = await generateLinkSecret();
+    expect(linkSecret).toBeInstanceOf(Uint8Array);
+    expect(linkSecret.length).toEqual(32);
+
+    const context = await linkSecretBlindSignCommitment({
+      publicKey: keyPair.publicKey,
+      linkSecret,
+      linkSecretIndex: 0,
+      nonce: stringToBytes("issuance nonce"),
+    });
+    expect(context.commitment).toBeInstanceOf(Uint8Array);
+    expect(context.proofOfHiddenMessages).toBeInstanceOf(Uint8Array);
+    expect(context.challengeHash).toBeInstanceOf(Uint8Array);
+    expect(context.blindingFactor).toBeInstanceOf(Uint8Array);
+  });
+
+  it("should prove the hidden link secret matches the commitment", async () => {
+    const keyPair = await bls12381toBbs({
+      keyPair: await generateBls12381G2KeyPair(),
+      messageCount: 3,
+    });
+    const linkSecret = await generateLinkSecret();
+    const messages = [stringToBytes("Message2"), stringToBytes("Message3")];
+    const signature = await sign({
+      keyPair,
+      messages: [linkSecret, ...messages],
+    });
+    const registered = await createLinkSecretCommitment({ linkSecret });
+    const nonce = stringToBytes("presentation nonce");
+
+    const request: BbsCreateProofRequest = {
+      signature,
+      publicKey: keyPair.publicKey,
+      messages,
+      revealed: [2],
+      nonce,
+      holderBinding: {
+        linkSecret,
+        linkSecretIndex: 0,
+        commitment: registered.commitment,
+        blindingFactor: registered.blindingFactor,
+      },
+    };
+    const proof = await createProof(request);
+    expect(proof).toBeInstanceOf(Uint8Array);
+
+    const verifyRequest: BbsVerifyProofRequest = {
+      proof,
+      publicKey: keyPair.publicKey,
+      messages: [messages[1]],
+      nonce,
+      holderBinding: {
+        linkSecretIndex: 0,
+        commitment: registered.commitment,
+      },
+    };
+    expect((await verifyProof(verifyRequest)).verified).toBeTruthy();
+
+    const other = await createLinkSecretCommitment({ linkSecret });
+    const otherResult = await verifyProof({
+      ...verifyRequest,
+      holderBinding: { linkSecretIndex: 0, commitment: other.commitment },
+    });
+    expect(otherResult.verified).toBeFalsy();
+    const unbound = await verifyProof({
+      ...verifyRequest,
+      holderBinding: undefined,
+    });
+    expect(unbound.verified).toBeFalsy();
+  });
+
+  it("should throw error when revealing the link secret", async () => {
+    const keyPair = await bls12381toBbs({
+      keyPair: await generateBls12381G2KeyPair(),
+      messageCount: 2,
+    });
+    const linkSecret = await generateLinkSecret();
+    const messages = [stringToBytes("Message2")];
+    const signature = await sign({
+      keyPair,
+      messages: [linkSecret, ...messages],
+    });
+    const registered = await createLinkSecretCommitment({ linkSecret });
+    const request: BbsCreateProofRequest = {
+      signature,
+      publicKey: keyPair.publicKey,
+      messages,
+      revealed: [0],
+      nonce: stringToBytes("nonce"),
+      holderBinding: {
+        linkSecret,
+        linkSecretIndex: 0,
+        commitment: registered.commitment,
+        blindingFactor: registered.blindingFactor,
+      },
+    };
+    await expect(createProof(request)).rejects.toThrowError();
+  });
+});
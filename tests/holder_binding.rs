@@ -0,0 +1,208 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Test suite for the Web and headless browsers.
+
+#![cfg(target_arch = "wasm32")]
+extern crate wasm_bindgen_test;
+use bbs::prelude::*;
+use std::convert::{TryFrom, TryInto};
+use wasm::prelude::*;
+use wasm::{BbsVerifyResponse, PoKOfSignatureProofWrapper};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+async fn link_secret_commitment(link_secret: &[u8]) -> LinkSecretCommitment {
+    let request = LinkSecretCommitmentRequest {
+        linkSecret: link_secret.to_vec(),
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    create_link_secret_commitment(js_value)
+        .await
+        .unwrap()
+        .try_into()
+        .unwrap()
+}
+
+async fn verify(
+    proof: &PoKOfSignatureProofWrapper,
+    pk: &PublicKey,
+    messages: &[Vec<u8>],
+    nonce: &[u8],
+    commitment: Commitment,
+) -> bool {
+    let request = VerifyProofContext {
+        proof: PoKOfSignatureProofWrapper::try_from(proof.to_bytes().as_slice()).unwrap(),
+        publicKey: pk.clone(),
+        messages: messages.to_vec(),
+        nonce: nonce.to_vec(),
+        holderBinding: Some(HolderBindingVerifyOptions {
+            linkSecretIndex: 0,
+            commitment,
+        }),
+        pseudonym: None,
+        accumulator: None,
+        statusList: None,
+        verifiableEncryption: None,
+        committed: None,
+        notEqual: None,
+        setMembership: None,
+        rateLimit: None,
+        designatedVerifier: None,
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    let result = bbs_verify_proof(js_value).await.unwrap();
+    let result: BbsVerifyResponse = serde_wasm_bindgen::from_value(result).unwrap();
+    result.verified
+}
+
+#[allow(non_snake_case)]
+#[wasm_bindgen_test]
+pub async fn holder_binding_tests() {
+    let (pk, sk) = generate(3).unwrap();
+    let link_secret = js_sys::Uint8Array::from(generate_link_secret().await.unwrap()).to_vec();
+    assert_eq!(link_secret.len(), 32);
+    let messages = vec![b"Message2".to_vec(), b"Message3".to_vec()];
+
+    // Issuance of a credential with the link secret blindly signed at index 0
+    let request = LinkSecretBlindSignCommitmentRequest {
+        publicKey: pk.clone(),
+        linkSecret: link_secret.clone(),
+        linkSecretIndex: 0,
+        nonce: b"issuance nonce".to_vec(),
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    let context: BlindSignatureContextResponse = link_secret_blind_sign_commitment(js_value)
+        .await
+        .unwrap()
+        .try_into()
+        .unwrap();
+    let request = BlindSignContextRequest {
+        commitment: context.commitment,
+        publicKey: pk.clone(),
+        secretKey: sk,
+        messages: messages.clone(),
+        known: vec![1, 2],
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    let blind_signature = bbs_blind_sign(js_value).await.unwrap();
+    let request = UnblindSignatureRequest {
+        signature: serde_wasm_bindgen::from_value(blind_signature).unwrap(),
+        blindingFactor: context.blindingFactor,
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    let signature: Signature =
+        serde_wasm_bindgen::from_value(bbs_get_unblinded_signature(js_value).await.unwrap())
+            .unwrap();
+
+    let registered = link_secret_commitment(&link_secret).await;
+    let nonce = b"presentation nonce".to_vec();
+    let request = CreateProofRequest {
+        signature: signature.clone(),
+        publicKey: pk.clone(),
+        messages: messages.clone(),
+        revealed: vec![2],
+        nonce: nonce.clone(),
+        holderBinding: Some(HolderBindingProofOptions {
+            linkSecret: link_secret.clone(),
+            linkSecretIndex: 0,
+            commitment: registered.commitment,
+            blindingFactor: registered.blindingFactor.clone(),
+        }),
+        pseudonym: None,
+        accumulator: None,
+        statusList: None,
+        verifiableEncryption: None,
+        committed: None,
+        notEqual: None,
+        setMembership: None,
+        rateLimit: None,
+        designatedVerifier: None,
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    let proof: PoKOfSignatureProofWrapper =
+        serde_wasm_bindgen::from_value(bbs_create_proof(js_value).await.unwrap()).unwrap();
+    assert!(!proof.statements.is_empty());
+    let decoded = PoKOfSignatureProofWrapper::from_cbor(&proof.to_cbor().unwrap()).unwrap();
+    assert_eq!(decoded.to_bytes(), proof.to_bytes());
+
+    let revealed = vec![messages[1].clone()];
+    assert!(verify(&proof, &pk, &revealed, &nonce, registered.commitment).await);
+    assert!(!verify(&proof, &pk, &revealed, b"bad nonce", registered.commitment).await);
+    assert!(
+        !verify(
+            &proof,
+            &pk,
+            &[b"Message1".to_vec()],
+            &nonce,
+            registered.commitment
+        )
+        .await
+    );
+
+    // A commitment to another link secret, or to the same link secret with
+    // another blinding factor than the holder knows, does not verify
+    let other = link_secret_commitment(b"another link secret").await;
+    assert!(!verify(&proof, &pk, &revealed, &nonce, other.commitment).await);
+    let rerandomised = link_secret_commitment(&link_secret).await;
+    assert!(!verify(&proof, &pk, &revealed, &nonce, rerandomised.commitment).await);
+
+    // The binding is part of the proof, which does not verify without it
+    let request = VerifyProofContext {
+        proof: PoKOfSignatureProofWrapper::try_from(proof.to_bytes().as_slice()).unwrap(),
+        publicKey: pk.clone(),
+        messages: revealed.clone(),
+        nonce: nonce.clone(),
+        holderBinding: None,
+        pseudonym: None,
+        accumulator: None,
+        statusList: None,
+        verifiableEncryption: None,
+        committed: None,
+        notEqual: None,
+        setMembership: None,
+        rateLimit: None,
+        designatedVerifier: None,
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    let result: BbsVerifyResponse =
+        serde_wasm_bindgen::from_value(bbs_verify_proof(js_value).await.unwrap()).unwrap();
+    assert!(!result.verified);
+
+    // The link secret cannot be revealed
+    let request = CreateProofRequest {
+        signature,
+        publicKey: pk.clone(),
+        messages,
+        revealed: vec![0],
+        nonce,
+        holderBinding: Some(HolderBindingProofOptions {
+            linkSecret: link_secret,
+            linkSecretIndex: 0,
+            commitment: registered.commitment,
+            blindingFactor: registered.blindingFactor,
+        }),
+        pseudonym: None,
+        accumulator: None,
+        statusList: None,
+        verifiableEncryption: None,
+        committed: None,
+        notEqual: None,
+        setMembership: None,
+        rateLimit: None,
+        designatedVerifier: None,
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    assert!(bbs_create_proof(js_value).await.is_err());
+}
@@ -0,0 +1,92 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+import {
+  generateBls12381G2KeyPair,
+  createIssuedJwp,
+  verifyIssuedJwp,
+  createPresentedJwp,
+  verifyPresentedJwp,
+  parseJwp,
+  BlsKeyPair,
+  JwpResponse,
+} from "../lib";
+import { stringToBytes } from "./utilities";
+
+describe("jsonWebProof", () => {
+  const payloads = [
+    stringToBytes('"Doe"'),
+    stringToBytes('"Jay"'),
+    new Uint8Array(0),
+    stringToBytes("42"),
+  ];
+  let keyPair: BlsKeyPair;
+  let issued: JwpResponse;
+
+  beforeAll(async () => {
+    keyPair = await generateBls12381G2KeyPair();
+    issued = await createIssuedJwp({
+      keyPair,
+      header: { iss: "https://issuer.example" },
+      payloads,
+    });
+  });
+
+  it("should issue a JWP in both serializations", async () => {
+    expect(issued.compact.split(".")).toHaveLength(3);
+    for (const jwp of [issued.compact, issued.json]) {
+      expect(
+        (await verifyIssuedJwp({ publicKey: keyPair.publicKey, jwp })).verified
+      ).toBeTruthy();
+      const parsed = await parseJwp({ jwp });
+      expect(parsed.presentationHeader).toBeUndefined();
+      expect(JSON.parse(parsed.issuerHeader).iss).toEqual(
+        "https://issuer.example"
+      );
+      expect(parsed.payloads).toEqual(payloads);
+    }
+  });
+
+  it("should present a JWP disclosing some payloads", async () => {
+    const presented = await createPresentedJwp({
+      publicKey: keyPair.publicKey,
+      jwp: issued.compact,
+      header: { nonce: "b2OaPrgQ9nEOXVBR", aud: "https://verifier.example" },
+      disclosed: [0, 3],
+    });
+    expect(presented.compact.split(".")).toHaveLength(4);
+    for (const jwp of [presented.compact, presented.json]) {
+      expect(
+        (await verifyPresentedJwp({ publicKey: keyPair.publicKey, jwp }))
+          .verified
+      ).toBeTruthy();
+    }
+
+    const parsed = await parseJwp({ jwp: presented.compact });
+    expect(JSON.parse(parsed.presentationHeader as string).aud).toEqual(
+      "https://verifier.example"
+    );
+    expect(parsed.payloads).toEqual([payloads[0], null, null, payloads[3]]);
+    expect(parsed.proof).toBeInstanceOf(Uint8Array);
+
+    const otherKeyPair = await generateBls12381G2KeyPair();
+    expect(
+      (
+        await verifyPresentedJwp({
+          publicKey: otherKeyPair.publicKey,
+          jwp: presented.compact,
+        })
+      ).verified
+    ).toBeFalsy();
+  });
+});
@@ -0,0 +1,237 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Test suite for the Web and headless browsers.
+
+#![cfg(target_arch = "wasm32")]
+extern crate wasm_bindgen_test;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use bbs::prelude::*;
+use serde_json::{json, Map, Value};
+use std::convert::{TryFrom, TryInto};
+use wasm::prelude::*;
+use wasm::BbsVerifyResponse;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+fn header(value: Value) -> Map<String, Value> {
+    match value {
+        Value::Object(map) => map,
+        _ => unreachable!(),
+    }
+}
+
+async fn verify_issued(public_key: &[u8], jwp: &str) -> BbsVerifyResponse {
+    let request = JwpVerifyRequest {
+        publicKey: DeterministicPublicKey::try_from(public_key).unwrap(),
+        jwp: jwp.to_string(),
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    verify_issued_jwp(js_value)
+        .await
+        .unwrap()
+        .try_into()
+        .unwrap()
+}
+
+async fn verify_presented(public_key: &[u8], jwp: &str) -> BbsVerifyResponse {
+    let request = JwpVerifyRequest {
+        publicKey: DeterministicPublicKey::try_from(public_key).unwrap(),
+        jwp: jwp.to_string(),
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    verify_presented_jwp(js_value)
+        .await
+        .unwrap()
+        .try_into()
+        .unwrap()
+}
+
+async fn present(public_key: &[u8], jwp: &str, disclosed: Vec<usize>) -> JwpResponse {
+    let request = JwpPresentRequest {
+        publicKey: DeterministicPublicKey::try_from(public_key).unwrap(),
+        jwp: jwp.to_string(),
+        header: header(json!({ "nonce": "b2OaPrgQ9nEOXVBR", "aud": "https://verifier.example" })),
+        disclosed,
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    create_presented_jwp(js_value)
+        .await
+        .unwrap()
+        .try_into()
+        .unwrap()
+}
+
+#[allow(non_snake_case)]
+#[wasm_bindgen_test]
+pub async fn json_web_proof_tests() {
+    let key_pair: BlsKeyPair = bls_generate_g2_key(None).await.unwrap().try_into().unwrap();
+    let public_key = key_pair.publicKey.clone().unwrap();
+    let request = JwpIssueRequest {
+        keyPair: key_pair,
+        header: header(json!({ "iss": "https://issuer.example", "alg": "BBS" })),
+        payloads: vec![
+            b"\"Doe\"".to_vec(),
+            b"\"Jay\"".to_vec(),
+            Vec::new(),
+            b"42".to_vec(),
+        ],
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    let issued: JwpResponse = create_issued_jwp(js_value)
+        .await
+        .unwrap()
+        .try_into()
+        .unwrap();
+    assert_eq!(issued.compact.split('.').count(), 3);
+    assert!(issued.compact.contains("~_~"));
+
+    // Both serializations verify and carry the same content
+    assert!(verify_issued(&public_key, &issued.compact).await.verified);
+    assert!(verify_issued(&public_key, &issued.json).await.verified);
+    let compact: JwpParseResponse = parse_jwp(
+        serde_wasm_bindgen::to_value(&JwpParseRequest {
+            jwp: issued.compact.clone(),
+        })
+        .unwrap(),
+    )
+    .await
+    .unwrap()
+    .try_into()
+    .unwrap();
+    let json: JwpParseResponse = parse_jwp(
+        serde_wasm_bindgen::to_value(&JwpParseRequest {
+            jwp: issued.json.clone(),
+        })
+        .unwrap(),
+    )
+    .await
+    .unwrap()
+    .try_into()
+    .unwrap();
+    assert_eq!(compact.issuerHeader, json.issuerHeader);
+    assert_eq!(compact.payloads, json.payloads);
+    assert_eq!(compact.proof, json.proof);
+    assert!(compact.presentationHeader.is_none());
+    assert_eq!(compact.payloads[2], Some(Vec::new()));
+    let issuer_header: Value = serde_json::from_str(&compact.issuerHeader).unwrap();
+    assert_eq!(issuer_header["alg"], "mattr.global/bbs-plus");
+    assert_eq!(issuer_header["iss"], "https://issuer.example");
+
+    // A changed payload or another key fails
+    let parts: Vec<&str> = issued.compact.split('.').collect();
+    let payloads: Vec<&str> = parts[1].split('~').collect();
+    let tampered = format!(
+        "{}.{}~{}.{}",
+        parts[0],
+        URL_SAFE_NO_PAD.encode(b"\"Roe\""),
+        payloads[1..].join("~"),
+        parts[2]
+    );
+    assert!(!verify_issued(&public_key, &tampered).await.verified);
+    let other: BlsKeyPair = bls_generate_g2_key(None).await.unwrap().try_into().unwrap();
+    assert!(
+        !verify_issued(&other.publicKey.clone().unwrap(), &issued.compact)
+            .await
+            .verified
+    );
+
+    // A presentation discloses the chosen payloads only
+    let presented = present(&public_key, &issued.json, vec![1, 2]).await;
+    assert_eq!(presented.compact.split('.').count(), 4);
+    assert!(
+        verify_presented(&public_key, &presented.compact)
+            .await
+            .verified
+    );
+    assert!(
+        verify_presented(&public_key, &presented.json)
+            .await
+            .verified
+    );
+    let parsed: JwpParseResponse = parse_jwp(
+        serde_wasm_bindgen::to_value(&JwpParseRequest {
+            jwp: presented.compact.clone(),
+        })
+        .unwrap(),
+    )
+    .await
+    .unwrap()
+    .try_into()
+    .unwrap();
+    assert_eq!(
+        parsed.payloads,
+        vec![None, Some(b"\"Jay\"".to_vec()), Some(Vec::new()), None]
+    );
+    let presentation_header: Value =
+        serde_json::from_str(&parsed.presentationHeader.unwrap()).unwrap();
+    assert_eq!(presentation_header["nonce"], "b2OaPrgQ9nEOXVBR");
+
+    // The forms are not interchangeable
+    assert!(
+        !verify_issued(&public_key, &presented.compact)
+            .await
+            .verified
+    );
+    assert!(
+        !verify_presented(&public_key, &issued.compact)
+            .await
+            .verified
+    );
+
+    // Replacing the presentation header or disclosing another payload fails
+    let parts: Vec<&str> = presented.compact.split('.').collect();
+    let replay = format!(
+        "{}.{}.{}.{}",
+        issued.compact.split('.').next().unwrap(),
+        parts[1],
+        parts[2],
+        parts[3]
+    );
+    assert!(!verify_presented(&public_key, &replay).await.verified);
+    let payloads: Vec<&str> = parts[2].split('~').collect();
+    let disclosed = format!(
+        "{}.{}.{}~{}.{}",
+        parts[0],
+        parts[1],
+        issued
+            .compact
+            .split('.')
+            .nth(1)
+            .unwrap()
+            .split('~')
+            .next()
+            .unwrap(),
+        payloads[1..].join("~"),
+        parts[3]
+    );
+    assert!(!verify_presented(&public_key, &disclosed).await.verified);
+
+    // Disclosing nothing still proves the issuer header
+    let presented = present(&public_key, &issued.compact, Vec::new()).await;
+    assert!(
+        verify_presented(&public_key, &presented.compact)
+            .await
+            .verified
+    );
+
+    let request = JwpPresentRequest {
+        publicKey: DeterministicPublicKey::try_from(public_key.as_slice()).unwrap(),
+        jwp: issued.compact.clone(),
+        header: Map::new(),
+        disclosed: vec![4],
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    assert!(create_presented_jwp(js_value).await.is_err());
+}
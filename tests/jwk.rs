@@ -0,0 +1,153 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Test suite for the Web and headless browsers.
+
+#![cfg(target_arch = "wasm32")]
+extern crate wasm_bindgen_test;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use std::convert::TryInto;
+use wasm::prelude::*;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+async fn export(key_pair: &BlsKeyPair, kty: Option<&str>) -> Jwk {
+    let request = JwkExportRequest {
+        keyPair: BlsKeyPair {
+            publicKey: key_pair.publicKey.clone(),
+            secretKey: key_pair.secretKey.clone(),
+        },
+        kty: kty.map(str::to_string),
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    export_jwk(js_value).await.unwrap().try_into().unwrap()
+}
+
+async fn import(jwk: &Jwk) -> Result<BlsKeyPair, ()> {
+    let js_value = serde_wasm_bindgen::to_value(jwk).unwrap();
+    match import_jwk(js_value).await {
+        Ok(key_pair) => Ok(key_pair.try_into().unwrap()),
+        Err(_) => Err(()),
+    }
+}
+
+#[wasm_bindgen_test]
+pub async fn jwk_round_trip_tests() {
+    for (key_pair, crv, size) in [
+        (bls_generate_g2_key(None).await.unwrap(), "BLS12381_G2", 96),
+        (bls_generate_g1_key(None).await.unwrap(), "BLS12381_G1", 48),
+    ] {
+        let key_pair: BlsKeyPair = key_pair.try_into().unwrap();
+
+        let jwk = export(&key_pair, None).await;
+        assert_eq!(jwk.kty, "OKP");
+        assert_eq!(jwk.crv, crv);
+        assert_eq!(URL_SAFE_NO_PAD.decode(&jwk.x).unwrap().len(), size);
+        assert!(jwk.y.is_none());
+        assert_eq!(
+            URL_SAFE_NO_PAD
+                .decode(jwk.d.as_ref().unwrap())
+                .unwrap()
+                .len(),
+            32
+        );
+        let imported = import(&jwk).await.unwrap();
+        assert_eq!(imported.publicKey, key_pair.publicKey);
+        assert_eq!(imported.secretKey, key_pair.secretKey);
+
+        let jwk = export(&key_pair, Some("EC")).await;
+        assert_eq!(jwk.kty, "EC");
+        assert_eq!(URL_SAFE_NO_PAD.decode(&jwk.x).unwrap().len(), size);
+        assert_eq!(
+            URL_SAFE_NO_PAD
+                .decode(jwk.y.as_ref().unwrap())
+                .unwrap()
+                .len(),
+            size
+        );
+        let imported = import(&jwk).await.unwrap();
+        assert_eq!(imported.publicKey, key_pair.publicKey);
+        assert_eq!(imported.secretKey, key_pair.secretKey);
+
+        // Public keys export without `d`
+        let public_only = BlsKeyPair {
+            publicKey: key_pair.publicKey.clone(),
+            secretKey: None,
+        };
+        let jwk = export(&public_only, None).await;
+        assert!(jwk.d.is_none());
+        assert!(import(&jwk).await.unwrap().secretKey.is_none());
+    }
+}
+
+#[wasm_bindgen_test]
+pub async fn jwk_validation_tests() {
+    let key_pair: BlsKeyPair = bls_generate_g2_key(None).await.unwrap().try_into().unwrap();
+    let other: BlsKeyPair = bls_generate_g2_key(None).await.unwrap().try_into().unwrap();
+    let jwk = export(&key_pair, Some("EC")).await;
+
+    // A secret key of another key pair
+    let mismatched = Jwk {
+        d: export(&other, None).await.d,
+        ..export(&key_pair, None).await
+    };
+    assert!(import(&mismatched).await.is_err());
+
+    // A truncated public key or secret key
+    let x = URL_SAFE_NO_PAD.decode(&jwk.x).unwrap();
+    let truncated = Jwk {
+        x: URL_SAFE_NO_PAD.encode(&x[..95]),
+        ..export(&key_pair, Some("EC")).await
+    };
+    assert!(import(&truncated).await.is_err());
+    let d = URL_SAFE_NO_PAD.decode(jwk.d.as_ref().unwrap()).unwrap();
+    let truncated = Jwk {
+        d: Some(URL_SAFE_NO_PAD.encode(&d[..31])),
+        ..export(&key_pair, None).await
+    };
+    assert!(import(&truncated).await.is_err());
+
+    // A point that is not on the curve
+    let mut y = URL_SAFE_NO_PAD.decode(jwk.y.as_ref().unwrap()).unwrap();
+    y[95] ^= 1;
+    let off_curve = Jwk {
+        y: Some(URL_SAFE_NO_PAD.encode(&y)),
+        d: None,
+        ..export(&key_pair, Some("EC")).await
+    };
+    assert!(import(&off_curve).await.is_err());
+
+    // A G2 key labelled as G1, and unsupported key types and curves
+    let wrong_curve = Jwk {
+        crv: "BLS12381_G1".to_string(),
+        ..export(&key_pair, None).await
+    };
+    assert!(import(&wrong_curve).await.is_err());
+    let wrong_kty = Jwk {
+        kty: "OKP".to_string(),
+        ..export(&key_pair, Some("EC")).await
+    };
+    assert!(import(&wrong_kty).await.is_err());
+    let unsupported = Jwk {
+        crv: "P-256".to_string(),
+        ..export(&key_pair, None).await
+    };
+    assert!(import(&unsupported).await.is_err());
+    let request = JwkExportRequest {
+        keyPair: key_pair,
+        kty: Some("RSA".to_string()),
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    assert!(export_jwk(js_value).await.is_err());
+}
@@ -0,0 +1,56 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+import {
+  generateBls12381G1KeyPair,
+  generateBls12381G2KeyPair,
+  exportJwk,
+  importJwk,
+} from "../lib";
+
+describe("jwk", () => {
+  it("should round trip BLS key pairs through a JWK", async () => {
+    for (const keyPair of [
+      await generateBls12381G1KeyPair(),
+      await generateBls12381G2KeyPair(),
+    ]) {
+      for (const kty of ["OKP", "EC"] as const) {
+        const jwk = await exportJwk({ keyPair, kty });
+        expect(jwk.kty).toEqual(kty);
+        expect(jwk.d).toBeDefined();
+        const imported = await importJwk(jwk);
+        expect(imported.publicKey).toEqual(keyPair.publicKey);
+        expect(imported.secretKey).toEqual(keyPair.secretKey);
+      }
+    }
+  });
+
+  it("should export the curve and no secret key for a public key", async () => {
+    const keyPair = await generateBls12381G2KeyPair();
+    const jwk = await exportJwk({ keyPair: { publicKey: keyPair.publicKey } });
+    expect(jwk.kty).toEqual("OKP");
+    expect(jwk.crv).toEqual("BLS12381_G2");
+    expect(jwk.d).toBeUndefined();
+    const imported = await importJwk(jwk);
+    expect(imported.publicKey).toEqual(keyPair.publicKey);
+    expect(imported.secretKey).toBeUndefined();
+  });
+
+  it("should throw error when importing an invalid JWK", async () => {
+    const keyPair = await generateBls12381G2KeyPair();
+    const jwk = await exportJwk({ keyPair });
+    await expect(
+      importJwk({ ...jwk, crv: "BLS12381_G1" })
+    ).rejects.toThrowError();
+  });
+});
@@ -0,0 +1,95 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+import {
+  generateBls12381G1KeyHandle,
+  generateBls12381G2KeyHandle,
+  generateBls12381G2KeyPair,
+  importKeyHandle,
+  importEncryptedKeyHandle,
+  importEncryptedKey,
+  bls12381toBbs,
+  blsVerify,
+  verify,
+  verifyKeyPossessionProof,
+} from "../lib";
+import { stringToBytes } from "./utilities";
+
+describe("keyHandle", () => {
+  const messages = [stringToBytes("Message1"), stringToBytes("Message2")];
+
+  it("should sign with a generated G2 key handle", async () => {
+    const handle = await generateBls12381G2KeyHandle();
+    expect(handle.publicKey).toBeInstanceOf(Uint8Array);
+    expect(handle.publicKey).toHaveLength(96);
+    const signature = await handle.sign({ messages });
+    expect(
+      (await blsVerify({ publicKey: handle.publicKey, signature, messages }))
+        .verified
+    ).toBeTruthy();
+    expect(
+      (
+        await verifyKeyPossessionProof({
+          publicKey: handle.publicKey,
+          proof: await handle.createKeyPossessionProof(),
+        })
+      ).verified
+    ).toBeTruthy();
+    handle.free();
+  });
+
+  it("should sign like the imported key pair", async () => {
+    const keyPair = await generateBls12381G2KeyPair();
+    const handle = await importKeyHandle(keyPair);
+    expect(handle.publicKey).toEqual(keyPair.publicKey);
+    const signature = await handle.sign({ messages });
+    const bbsKeyPair = await bls12381toBbs({ keyPair, messageCount: 2 });
+    expect(
+      (
+        await verify({
+          publicKey: bbsKeyPair.publicKey,
+          signature,
+          messages,
+        })
+      ).verified
+    ).toBeTruthy();
+    handle.free();
+  });
+
+  it("should round trip a key handle through an encrypted keystore", async () => {
+    const handle = await generateBls12381G2KeyHandle();
+    const keystore = await handle.exportEncryptedKey({
+      password: "correct horse",
+      cost: 16,
+    });
+    const imported = await importEncryptedKeyHandle({
+      keystore,
+      password: "correct horse",
+    });
+    expect(imported.publicKey).toEqual(handle.publicKey);
+    const keyPair = await importEncryptedKey({
+      keystore,
+      password: "correct horse",
+    });
+    expect(keyPair.publicKey).toEqual(handle.publicKey);
+    handle.free();
+    imported.free();
+  });
+
+  it("should throw error when signing with a G1 key handle", async () => {
+    const handle = await generateBls12381G1KeyHandle();
+    expect(handle.publicKey).toHaveLength(48);
+    await expect(handle.sign({ messages })).rejects.toThrowError();
+    handle.free();
+  });
+});
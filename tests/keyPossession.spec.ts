@@ -0,0 +1,55 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+import {
+  generateBls12381G1KeyPair,
+  generateBls12381G2KeyPair,
+  createKeyPossessionProof,
+  verifyKeyPossessionProof,
+  encodeMultikey,
+} from "../lib";
+
+describe("keyPossession", () => {
+  it("should create and verify a proof of possession", async () => {
+    const keyPair = await generateBls12381G2KeyPair();
+    const proof = await createKeyPossessionProof({ keyPair });
+    expect(proof).toBeInstanceOf(Uint8Array);
+    expect(proof).toHaveLength(48);
+
+    const multikey = await encodeMultikey(keyPair.publicKey);
+    for (const publicKey of [
+      keyPair.publicKey,
+      multikey,
+      `did:key:${multikey}`,
+    ]) {
+      expect(
+        (await verifyKeyPossessionProof({ publicKey, proof })).verified
+      ).toBeTruthy();
+    }
+
+    const otherKeyPair = await generateBls12381G2KeyPair();
+    expect(
+      (
+        await verifyKeyPossessionProof({
+          publicKey: otherKeyPair.publicKey,
+          proof,
+        })
+      ).verified
+    ).toBeFalsy();
+  });
+
+  it("should throw error for a G1 key pair", async () => {
+    const keyPair = await generateBls12381G1KeyPair();
+    await expect(createKeyPossessionProof({ keyPair })).rejects.toThrowError();
+  });
+});
@@ -0,0 +1,179 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Test suite for the Web and headless browsers.
+
+#![cfg(target_arch = "wasm32")]
+extern crate wasm_bindgen_test;
+use bbs::prelude::*;
+use std::convert::{TryFrom, TryInto};
+use wasm::prelude::*;
+use wasm::BbsVerifyResponse;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+async fn verify(public_key: &[u8], signature: JsValue, messages: Vec<Vec<u8>>) -> bool {
+    let request = BlsBbsVerifyRequest {
+        publicKey: DeterministicPublicKey::try_from(public_key).unwrap(),
+        signature: serde_wasm_bindgen::from_value(signature).unwrap(),
+        messages,
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    let result = bls_verify(js_value).await.unwrap();
+    let result: BbsVerifyResponse = serde_wasm_bindgen::from_value(result).unwrap();
+    result.verified
+}
+
+fn sign_request(messages: &[Vec<u8>]) -> JsValue {
+    serde_wasm_bindgen::to_value(&KeyHandleSignRequest {
+        messages: messages.to_vec(),
+    })
+    .unwrap()
+}
+
+#[wasm_bindgen_test]
+pub async fn key_handle_sign_tests() {
+    let messages = vec![b"Jane".to_vec(), b"Doe".to_vec(), b"1990-01-01".to_vec()];
+
+    let handle = KeyHandle::generate_g2(None);
+    assert_eq!(handle.public_key().len(), 96);
+    let signature = handle.sign(sign_request(&messages)).unwrap();
+    assert!(verify(&handle.public_key(), signature, messages.clone()).await);
+
+    // A seeded handle has the key pair of the same seed
+    let seed = vec![7u8; 32];
+    let key_pair: BlsKeyPair = bls_generate_g2_key(Some(seed.clone()))
+        .await
+        .unwrap()
+        .try_into()
+        .unwrap();
+    let handle = KeyHandle::generate_g2(Some(seed));
+    assert_eq!(Some(handle.public_key()), key_pair.publicKey);
+
+    // Imported handles sign for the imported public key
+    let key_pair: BlsKeyPair = bls_generate_g2_key(None).await.unwrap().try_into().unwrap();
+    let handle =
+        KeyHandle::from_key_pair(serde_wasm_bindgen::to_value(&key_pair).unwrap()).unwrap();
+    assert_eq!(Some(handle.public_key()), key_pair.publicKey);
+    let signature = handle.sign(sign_request(&messages)).unwrap();
+    assert!(
+        verify(
+            key_pair.publicKey.as_ref().unwrap(),
+            signature,
+            messages.clone()
+        )
+        .await
+    );
+    let only_secret = BlsKeyPair {
+        publicKey: None,
+        secretKey: key_pair.secretKey.clone(),
+    };
+    let handle =
+        KeyHandle::from_key_pair(serde_wasm_bindgen::to_value(&only_secret).unwrap()).unwrap();
+    assert_eq!(Some(handle.public_key()), key_pair.publicKey);
+
+    // The secret key must belong to the public key and is required
+    let other: BlsKeyPair = bls_generate_g2_key(None).await.unwrap().try_into().unwrap();
+    let mismatched = BlsKeyPair {
+        publicKey: other.publicKey.clone(),
+        secretKey: key_pair.secretKey.clone(),
+    };
+    assert!(KeyHandle::from_key_pair(serde_wasm_bindgen::to_value(&mismatched).unwrap()).is_err());
+    let only_public = BlsKeyPair {
+        publicKey: other.publicKey,
+        secretKey: None,
+    };
+    assert!(KeyHandle::from_key_pair(serde_wasm_bindgen::to_value(&only_public).unwrap()).is_err());
+
+    // BBS signatures need a G2 key
+    let handle = KeyHandle::generate_g1(None);
+    assert_eq!(handle.public_key().len(), 48);
+    assert!(handle.sign(sign_request(&messages)).is_err());
+}
+
+#[wasm_bindgen_test]
+pub async fn key_handle_blind_sign_tests() {
+    let handle = KeyHandle::generate_g2(None);
+    let dpk = DeterministicPublicKey::try_from(handle.public_key().as_slice()).unwrap();
+    let messages = vec![b"link secret".to_vec(), b"Jane".to_vec(), b"Doe".to_vec()];
+    let nonce = b"nonce".to_vec();
+
+    let request = BlsBlindSignatureContextRequest {
+        publicKey: dpk,
+        messageCount: 3,
+        messages: vec![messages[0].clone()],
+        blinded: vec![0],
+        nonce,
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    let context: BlindSignatureContextResponse = bls_blind_signature_commitment(js_value)
+        .await
+        .unwrap()
+        .try_into()
+        .unwrap();
+
+    let request = KeyHandleBlindSignRequest {
+        commitment: context.commitment.clone(),
+        messageCount: 3,
+        messages: messages[1..].to_vec(),
+        known: vec![1, 2],
+    };
+    let blind_signature = handle
+        .blind_sign(serde_wasm_bindgen::to_value(&request).unwrap())
+        .unwrap();
+
+    let request = UnblindSignatureRequest {
+        signature: serde_wasm_bindgen::from_value(blind_signature).unwrap(),
+        blindingFactor: context.blindingFactor.clone(),
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    let signature = bbs_get_unblinded_signature(js_value).await.unwrap();
+    assert!(verify(&handle.public_key(), signature, messages).await);
+}
+
+#[wasm_bindgen_test]
+pub async fn key_handle_encrypted_key_tests() {
+    let handle = KeyHandle::generate_g2(None);
+    let request = KeyHandleExportRequest {
+        password: "correct horse".to_string(),
+        kdf: Some("scrypt".to_string()),
+        cost: Some(16),
+        description: None,
+    };
+    let keystore = handle
+        .export_encrypted_key(serde_wasm_bindgen::to_value(&request).unwrap())
+        .unwrap()
+        .as_string()
+        .unwrap();
+
+    let request = KeystoreImportRequest {
+        keystore: keystore.clone(),
+        password: "correct horse".to_string(),
+    };
+    let imported =
+        KeyHandle::from_encrypted_key(serde_wasm_bindgen::to_value(&request).unwrap()).unwrap();
+    assert_eq!(imported.public_key(), handle.public_key());
+    let messages = vec![b"message".to_vec()];
+    let signature = imported.sign(sign_request(&messages)).unwrap();
+    assert!(verify(&handle.public_key(), signature, messages).await);
+
+    let request = KeystoreImportRequest {
+        keystore,
+        password: "wrong horse".to_string(),
+    };
+    assert!(
+        KeyHandle::from_encrypted_key(serde_wasm_bindgen::to_value(&request).unwrap()).is_err()
+    );
+}
@@ -0,0 +1,93 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Test suite for the Web and headless browsers.
+
+#![cfg(target_arch = "wasm32")]
+extern crate wasm_bindgen_test;
+use bbs::prelude::*;
+use std::convert::{TryFrom, TryInto};
+use wasm::prelude::*;
+use wasm::BbsVerifyResponse;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+async fn prove(key_pair: &BlsKeyPair) -> Result<Vec<u8>, JsValue> {
+    let request = KeyPossessionProofRequest {
+        keyPair: BlsKeyPair {
+            publicKey: key_pair.publicKey.clone(),
+            secretKey: key_pair.secretKey.clone(),
+        },
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    let proof = create_key_possession_proof(js_value).await?;
+    Ok(serde_wasm_bindgen::from_value(proof).unwrap())
+}
+
+async fn verify(public_key: &[u8], proof: Vec<u8>) -> bool {
+    let request = KeyPossessionVerifyRequest {
+        publicKey: DeterministicPublicKey::try_from(public_key).unwrap(),
+        proof,
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    let result = verify_key_possession_proof(js_value).await.unwrap();
+    let result: BbsVerifyResponse = serde_wasm_bindgen::from_value(result).unwrap();
+    result.verified
+}
+
+#[wasm_bindgen_test]
+pub async fn key_possession_proof_tests() {
+    let key_pair: BlsKeyPair = bls_generate_g2_key(None).await.unwrap().try_into().unwrap();
+    let public_key = key_pair.publicKey.clone().unwrap();
+    let proof = prove(&key_pair).await.unwrap();
+    assert_eq!(proof.len(), 48);
+    assert!(verify(&public_key, proof.clone()).await);
+
+    // The proof is bound to the public key
+    let other: BlsKeyPair = bls_generate_g2_key(None).await.unwrap().try_into().unwrap();
+    let other_public_key = other.publicKey.clone().unwrap();
+    assert!(!verify(&other_public_key, proof.clone()).await);
+    let mut tampered = proof.clone();
+    tampered[47] ^= 1;
+    assert!(!verify(&public_key, tampered).await);
+    assert!(!verify(&public_key, proof[..47].to_vec()).await);
+
+    // A proof cannot be created for someone else's public key
+    let mismatched = BlsKeyPair {
+        publicKey: other.publicKey,
+        secretKey: key_pair.secretKey.clone(),
+    };
+    assert!(prove(&mismatched).await.is_err());
+
+    // G1 keys and key pairs without a secret key are rejected
+    let g1_key_pair: BlsKeyPair = bls_generate_g1_key(None).await.unwrap().try_into().unwrap();
+    assert!(prove(&g1_key_pair).await.is_err());
+    let only_public = BlsKeyPair {
+        publicKey: Some(public_key),
+        secretKey: None,
+    };
+    assert!(prove(&only_public).await.is_err());
+}
+
+#[wasm_bindgen_test]
+pub async fn key_handle_possession_proof_tests() {
+    let handle = KeyHandle::generate_g2(None);
+    let proof: Vec<u8> =
+        serde_wasm_bindgen::from_value(handle.create_key_possession_proof().unwrap()).unwrap();
+    assert!(verify(&handle.public_key(), proof).await);
+
+    let handle = KeyHandle::generate_g1(None);
+    assert!(handle.create_key_possession_proof().is_err());
+}
@@ -0,0 +1,162 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Test suite for the Web and headless browsers.
+
+#![cfg(target_arch = "wasm32")]
+extern crate wasm_bindgen_test;
+use std::convert::TryInto;
+use wasm::prelude::*;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const PASSWORD: &str = "testpassword\u{1F511}";
+const SECRET_KEY: &str = "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f";
+const PUBLIC_KEY: &str = "9612d7a727c9d0a22e185a1c768478dfe919cada9266988cb32359c11f2b7b27f4ae4040902382ae2910c15e2b420d07";
+
+/// The EIP-2335 test vector secret key encrypted with a cost of 16
+fn keystore(kdf: &str, params: &str, checksum: &str, ciphertext: &str) -> String {
+    format!(
+        r#"{{"crypto":{{"kdf":{{"function":"{}","params":{},"message":""}},"checksum":{{"function":"sha256","params":{{}},"message":"{}"}},"cipher":{{"function":"aes-128-ctr","params":{{"iv":"264daa3f303d7259501c93d997d84fe6"}},"message":"{}"}}}},"description":"","pubkey":"{}","path":"m/12381/60/0/0","uuid":"64625def-3331-4eea-ab6f-782f3ed16a83","version":4}}"#,
+        kdf, params, checksum, ciphertext, PUBLIC_KEY
+    )
+}
+
+fn pbkdf2_keystore() -> String {
+    keystore(
+        "pbkdf2",
+        r#"{"dklen":32,"c":16,"prf":"hmac-sha256","salt":"d4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3"}"#,
+        "28751e1411954ff1e93a3dcf128c0a44b20a99159ac8aad39ca34cb9a3cfd13a",
+        "88885f5836629ae359f7954334cfa3a1cdd6cbdf549825f18c05d027c2874cc9",
+    )
+}
+
+fn scrypt_keystore() -> String {
+    keystore(
+        "scrypt",
+        r#"{"dklen":32,"n":16,"r":8,"p":1,"salt":"d4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3"}"#,
+        "01fdeadd92f9333bcb830fb2c6de4c9f906c91cb84b77435d886722fa4c418f2",
+        "32da0474576fbb8f22eb7935f0b32e48d142e94431f0e93bf56f55961ce99520",
+    )
+}
+
+async fn export(key_pair: &BlsKeyPair, password: &str, kdf: &str) -> String {
+    let request = KeystoreExportRequest {
+        keyPair: BlsKeyPair {
+            publicKey: key_pair.publicKey.clone(),
+            secretKey: key_pair.secretKey.clone(),
+        },
+        password: password.to_string(),
+        kdf: Some(kdf.to_string()),
+        cost: Some(16),
+        description: Some("test key".to_string()),
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    export_encrypted_key(js_value)
+        .await
+        .unwrap()
+        .as_string()
+        .unwrap()
+}
+
+async fn import(keystore: &str, password: &str) -> Result<BlsKeyPair, ()> {
+    let request = KeystoreImportRequest {
+        keystore: keystore.to_string(),
+        password: password.to_string(),
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    match import_encrypted_key(js_value).await {
+        Ok(key_pair) => Ok(key_pair.try_into().unwrap()),
+        Err(_) => Err(()),
+    }
+}
+
+#[wasm_bindgen_test]
+pub async fn keystore_known_answer_tests() {
+    for keystore in [pbkdf2_keystore(), scrypt_keystore()] {
+        let key_pair = import(&keystore, PASSWORD).await.unwrap();
+        assert_eq!(
+            hex::encode(key_pair.secretKey.unwrap().to_bytes_compressed_form()),
+            SECRET_KEY
+        );
+        assert_eq!(hex::encode(key_pair.publicKey.unwrap()), PUBLIC_KEY);
+
+        // Control characters are removed from the password
+        assert!(import(&keystore, "test\u{7f}password\u{1F511}\n")
+            .await
+            .is_ok());
+        assert!(import(&keystore, "testpassword").await.is_err());
+    }
+
+    // A modified ciphertext fails the checksum
+    let tampered = pbkdf2_keystore().replace("88885f58", "88885f59");
+    assert!(import(&tampered, PASSWORD).await.is_err());
+    // A different public key fails the key pair check
+    let key_pair: BlsKeyPair = bls_generate_g1_key(None).await.unwrap().try_into().unwrap();
+    let tampered = pbkdf2_keystore().replace(
+        PUBLIC_KEY,
+        &hex::encode(key_pair.publicKey.as_ref().unwrap()),
+    );
+    assert!(import(&tampered, PASSWORD).await.is_err());
+}
+
+#[wasm_bindgen_test]
+pub async fn keystore_round_trip_tests() {
+    for key_pair in [
+        bls_generate_g2_key(None).await.unwrap(),
+        bls_generate_g1_key(None).await.unwrap(),
+    ] {
+        let key_pair: BlsKeyPair = key_pair.try_into().unwrap();
+        for kdf in ["pbkdf2", "scrypt"] {
+            let keystore = export(&key_pair, "correct horse", kdf).await;
+            let parsed: Keystore = serde_json::from_str(&keystore).unwrap();
+            assert_eq!(parsed.version, 4);
+            assert_eq!(parsed.description, "test key");
+            assert_eq!(parsed.crypto.kdf.function, kdf);
+            assert_eq!(
+                parsed.pubkey,
+                hex::encode(key_pair.publicKey.as_ref().unwrap())
+            );
+
+            let imported = import(&keystore, "correct horse").await.unwrap();
+            assert_eq!(imported.publicKey, key_pair.publicKey);
+            assert_eq!(imported.secretKey, key_pair.secretKey);
+            assert!(import(&keystore, "wrong horse").await.is_err());
+        }
+    }
+
+    // The secret key is required and the KDF must be supported
+    let key_pair: BlsKeyPair = bls_generate_g2_key(None).await.unwrap().try_into().unwrap();
+    let request = KeystoreExportRequest {
+        keyPair: BlsKeyPair {
+            publicKey: key_pair.publicKey.clone(),
+            secretKey: None,
+        },
+        password: "correct horse".to_string(),
+        kdf: None,
+        cost: Some(16),
+        description: None,
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    assert!(export_encrypted_key(js_value).await.is_err());
+    let request = KeystoreExportRequest {
+        keyPair: key_pair,
+        password: "correct horse".to_string(),
+        kdf: Some("argon2".to_string()),
+        cost: Some(16),
+        description: None,
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    assert!(export_encrypted_key(js_value).await.is_err());
+}
@@ -0,0 +1,62 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+import {
+  generateBls12381G1KeyPair,
+  generateBls12381G2KeyPair,
+  exportEncryptedKey,
+  importEncryptedKey,
+} from "../lib";
+
+describe("keystore", () => {
+  it("should round trip BLS key pairs through an encrypted keystore", async () => {
+    for (const keyPair of [
+      await generateBls12381G1KeyPair(),
+      await generateBls12381G2KeyPair(),
+    ]) {
+      for (const kdf of ["pbkdf2", "scrypt"] as const) {
+        const keystore = await exportEncryptedKey({
+          keyPair,
+          password: "correct horse",
+          kdf,
+          cost: 16,
+          description: "test key",
+        });
+        const parsed = JSON.parse(keystore);
+        expect(parsed.version).toEqual(4);
+        expect(parsed.crypto.kdf.function).toEqual(kdf);
+        expect(parsed.pubkey).toEqual(
+          Buffer.from(keyPair.publicKey).toString("hex")
+        );
+
+        const imported = await importEncryptedKey({
+          keystore,
+          password: "correct horse",
+        });
+        expect(imported.publicKey).toEqual(keyPair.publicKey);
+        expect(imported.secretKey).toEqual(keyPair.secretKey);
+      }
+    }
+  });
+
+  it("should throw error for a wrong password", async () => {
+    const keystore = await exportEncryptedKey({
+      keyPair: await generateBls12381G2KeyPair(),
+      password: "correct horse",
+      cost: 16,
+    });
+    await expect(
+      importEncryptedKey({ keystore, password: "wrong horse" })
+    ).rejects.toThrowError();
+  });
+});
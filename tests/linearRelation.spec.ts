@@ -0,0 +1,140 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+import {
+  generateBls12381G2KeyPair,
+  bls12381toBbs,
+  sign,
+  encodeNumericMessage,
+  createLinearRelationProof,
+  verifyLinearRelationProof,
+  LinearRelation,
+  LinearRelationCredential,
+  LinearRelationProof,
+} from "../lib";
+import { stringToBytes } from "./utilities";
+
+const credential = async (
+  messages: Uint8Array[],
+  revealed: number[]
+): Promise<LinearRelationCredential> => {
+  const keyPair = await bls12381toBbs({
+    keyPair: await generateBls12381G2KeyPair(),
+    messageCount: messages.length,
+  });
+  return {
+    signature: await sign({ keyPair, messages }),
+    publicKey: keyPair.publicKey,
+    messages,
+    revealed,
+  };
+};
+
+const prove = (
+  credentials: LinearRelationCredential[],
+  relations: LinearRelation[]
+): Promise<LinearRelationProof> =>
+  createLinearRelationProof({
+    credentials,
+    nonce: stringToBytes("nonce"),
+    relations,
+  });
+
+const verify = async (
+  proof: LinearRelationProof,
+  credentials: LinearRelationCredential[],
+  relations: LinearRelation[]
+): Promise<boolean> =>
+  (
+    await verifyLinearRelationProof({
+      proof,
+      credentials: credentials.map((item) => ({
+        publicKey: item.publicKey,
+        messages: item.revealed.map((i) => item.messages[i]),
+      })),
+      nonce: stringToBytes("nonce"),
+      relations,
+    })
+  ).verified;
+
+describe("linearRelation", () => {
+  it("should prove hidden messages are equal across credentials", async () => {
+    const credentials = [
+      await credential(
+        [stringToBytes("Alice"), stringToBytes("did:example:alice")],
+        [0]
+      ),
+      await credential(
+        [stringToBytes("did:example:alice"), stringToBytes("class C")],
+        [1]
+      ),
+    ];
+    const relations: LinearRelation[] = [
+      {
+        terms: [
+          { credential: 0, messageIndex: 1, coefficient: 1 },
+          { credential: 1, messageIndex: 0, coefficient: -1 },
+        ],
+        constant: 0,
+      },
+    ];
+    const proof = await prove(credentials, relations);
+    expect(proof.proofs).toHaveLength(2);
+    expect(await verify(proof, credentials, relations)).toBeTruthy();
+    expect(await verify(proof, credentials, [])).toBeFalsy();
+  });
+
+  it("should prove comparisons between numeric messages", async () => {
+    const credentials = [
+      await credential(
+        [
+          stringToBytes("did:example:alice"),
+          await encodeNumericMessage(85000),
+        ],
+        []
+      ),
+      await credential(
+        [
+          stringToBytes("did:example:alice"),
+          await encodeNumericMessage(24000),
+        ],
+        []
+      ),
+    ];
+    const incomeExceedsRent = (
+      comparison: ">" | "<",
+      constant: number
+    ): LinearRelation => ({
+      terms: [
+        { credential: 0, messageIndex: 1, coefficient: 1 },
+        { credential: 1, messageIndex: 1, coefficient: -3 },
+      ],
+      constant,
+      comparison,
+    });
+    const relations = [incomeExceedsRent(">", 0)];
+    const proof = await prove(credentials, relations);
+    expect(proof.inequalities).toHaveLength(1);
+    expect(await verify(proof, credentials, relations)).toBeTruthy();
+    expect(
+      await verify(proof, credentials, [incomeExceedsRent(">", 1)])
+    ).toBeFalsy();
+
+    await expect(
+      prove(credentials, [incomeExceedsRent("<", 0)])
+    ).rejects.toThrowError();
+    await expect(
+      prove(credentials, [incomeExceedsRent(">", 13000)])
+    ).rejects.toThrowError();
+  });
+});
@@ -0,0 +1,236 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Test suite for the Web and headless browsers.
+
+#![cfg(target_arch = "wasm32")]
+extern crate wasm_bindgen_test;
+use bbs::prelude::*;
+use std::convert::TryInto;
+use wasm::prelude::*;
+use wasm::BbsVerifyResponse;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+fn credential(messages: &[&str], revealed: Vec<usize>) -> LinearRelationCredential {
+    let (pk, sk) = generate(messages.len()).unwrap();
+    let messages: Vec<Vec<u8>> = messages.iter().map(|m| m.as_bytes().to_vec()).collect();
+    let hashed: Vec<SignatureMessage> = messages.iter().map(SignatureMessage::hash).collect();
+    LinearRelationCredential {
+        signature: Signature::new(hashed.as_slice(), &sk, &pk).unwrap(),
+        publicKey: pk,
+        messages,
+        revealed,
+    }
+}
+
+/// A credential signed with `sign`, which signs numeric messages as their values
+async fn numeric_credential(
+    messages: Vec<Vec<u8>>,
+    revealed: Vec<usize>,
+) -> LinearRelationCredential {
+    let (pk, sk) = generate(messages.len()).unwrap();
+    let request = BbsSignRequest {
+        keyPair: BbsKeyPair {
+            publicKey: pk.clone(),
+            secretKey: Some(sk),
+            messageCount: messages.len(),
+        },
+        messages: messages.clone(),
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    LinearRelationCredential {
+        signature: serde_wasm_bindgen::from_value(bbs_sign(js_value).await.unwrap()).unwrap(),
+        publicKey: pk,
+        messages,
+        revealed,
+    }
+}
+
+fn comparison(terms: &[(usize, usize, i64)], comparison: &str, constant: i64) -> LinearRelation {
+    LinearRelation {
+        comparison: Some(comparison.to_string()),
+        ..relation(terms, constant)
+    }
+}
+
+fn relation(terms: &[(usize, usize, i64)], constant: i64) -> LinearRelation {
+    LinearRelation {
+        terms: terms
+            .iter()
+            .map(|(credential, index, coefficient)| LinearRelationTerm {
+                credential: *credential,
+                messageIndex: *index,
+                coefficient: *coefficient,
+            })
+            .collect(),
+        constant,
+        comparison: None,
+    }
+}
+
+async fn prove(
+    credentials: &[LinearRelationCredential],
+    relations: &[LinearRelation],
+) -> Result<JsValue, JsValue> {
+    let request = LinearRelationCreateProofRequest {
+        credentials: credentials.to_vec(),
+        nonce: b"nonce".to_vec(),
+        relations: relations.to_vec(),
+    };
+    create_linear_relation_proof(serde_wasm_bindgen::to_value(&request).unwrap()).await
+}
+
+async fn verify(
+    proof: &LinearRelationProof,
+    credentials: &[LinearRelationCredential],
+    relations: &[LinearRelation],
+) -> bool {
+    let request = LinearRelationVerifyProofRequest {
+        proof: serde_wasm_bindgen::from_value(serde_wasm_bindgen::to_value(proof).unwrap())
+            .unwrap(),
+        credentials: credentials
+            .iter()
+            .map(|c| LinearRelationVerifyCredential {
+                publicKey: c.publicKey.clone(),
+                messages: c.revealed.iter().map(|i| c.messages[*i].clone()).collect(),
+            })
+            .collect(),
+        nonce: b"nonce".to_vec(),
+        relations: relations.to_vec(),
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    let result = verify_linear_relation_proof(js_value).await.unwrap();
+    let result: BbsVerifyResponse = serde_wasm_bindgen::from_value(result).unwrap();
+    result.verified
+}
+
+#[allow(non_snake_case)]
+#[wasm_bindgen_test]
+pub async fn linear_relation_tests() {
+    let credentials = vec![
+        credential(&["Alice", "did:example:alice", "1990-01-01"], vec![0]),
+        credential(
+            &["did:example:alice", "class C", "did:example:alice"],
+            vec![1],
+        ),
+    ];
+
+    // The subject of both credentials is the same, and appears twice in the second
+    let relations = vec![
+        relation(&[(0, 1, 1), (1, 0, -1)], 0),
+        relation(&[(1, 0, 2), (1, 2, -2)], 0),
+        relation(&[(0, 1, 3), (1, 0, -1), (1, 2, -2)], 0),
+    ];
+    let proof: LinearRelationProof = prove(&credentials, &relations)
+        .await
+        .unwrap()
+        .try_into()
+        .unwrap();
+    assert_eq!(proof.proofs.len(), 2);
+    assert!(verify(&proof, &credentials, &relations).await);
+
+    // The relations must match the ones the proof was created for
+    assert!(!verify(&proof, &credentials, &relations[..2]).await);
+    let changed = vec![
+        relation(&[(0, 1, 1), (1, 0, -1)], 0),
+        relation(&[(1, 0, 2), (1, 2, -2)], 0),
+        relation(&[(0, 1, 3), (1, 0, -1), (1, 2, -2)], 1),
+    ];
+    assert!(!verify(&proof, &credentials, &changed).await);
+    assert!(!verify(&proof, &credentials[..1], &relations).await);
+
+    // A relation the hidden messages do not satisfy cannot be proven
+    assert!(
+        prove(&credentials, &[relation(&[(0, 1, 1), (0, 2, -1)], 0)])
+            .await
+            .is_err()
+    );
+    // Nor one over a revealed message
+    assert!(
+        prove(&credentials, &[relation(&[(0, 0, 1), (1, 0, -1)], 0)])
+            .await
+            .is_err()
+    );
+}
+
+#[allow(non_snake_case)]
+#[wasm_bindgen_test]
+pub async fn numeric_linear_relation_tests() {
+    // netIncome = gross - tax within a payslip, and the income of the payslip is
+    // greater than the rent of a lease
+    let payslip = numeric_credential(
+        vec![
+            b"did:example:alice".to_vec(),
+            numeric_message(85_000),
+            numeric_message(-1_500),
+            numeric_message(60_000),
+            numeric_message(25_000),
+        ],
+        vec![],
+    )
+    .await;
+    let lease = numeric_credential(
+        vec![b"did:example:alice".to_vec(), numeric_message(24_000)],
+        vec![],
+    )
+    .await;
+    let credentials = vec![payslip, lease];
+    let relations = vec![
+        relation(&[(0, 3, 1), (0, 1, -1), (0, 4, 1)], 0),
+        relation(&[(0, 0, 1), (1, 0, -1)], 0),
+        comparison(&[(0, 3, 1), (1, 1, -1)], ">", 0),
+        comparison(&[(0, 3, 1)], ">=", 60_000),
+        comparison(&[(0, 2, 1)], "<", 0),
+        comparison(&[(0, 4, 2), (1, 1, -1)], "<=", 26_000),
+    ];
+    let proof: LinearRelationProof = prove(&credentials, &relations)
+        .await
+        .unwrap()
+        .try_into()
+        .unwrap();
+    assert_eq!(proof.inequalities.len(), 4);
+    assert!(verify(&proof, &credentials, &relations).await);
+
+    // The comparisons and their constants must match
+    let mut changed = relations.clone();
+    changed[2].comparison = Some(">=".to_string());
+    assert!(!verify(&proof, &credentials, &changed).await);
+    let mut changed = relations.clone();
+    changed[3].constant = 59_999;
+    assert!(!verify(&proof, &credentials, &changed).await);
+    let mut changed = relations.clone();
+    changed[4].comparison = None;
+    assert!(!verify(&proof, &credentials, &changed).await);
+
+    // A comparison the hidden messages do not satisfy cannot be proven
+    for relation in [
+        comparison(&[(0, 3, 1), (1, 1, -1)], "<", 0),
+        comparison(&[(0, 3, 1)], ">", 60_000),
+        comparison(&[(0, 2, 1)], ">=", 0),
+        comparison(&[(1, 1, 1)], "<=", 23_999),
+    ] {
+        assert!(prove(&credentials, &[relation]).await.is_err());
+    }
+    assert!(prove(&credentials, &[comparison(&[(1, 1, 1)], "!=", 0)])
+        .await
+        .is_err());
+    // Nor can an arithmetic relation the values do not satisfy
+    assert!(
+        prove(&credentials, &[relation(&[(0, 3, 1), (0, 1, -1)], 0)])
+            .await
+            .is_err()
+    );
+}
@@ -0,0 +1,91 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+import {
+  generateBls12381G2KeyPair,
+  bls12381toBbs,
+  sign,
+  createProof,
+  verifyProof,
+  generateBlindingFactor,
+  messageCommitmentGenerators,
+  BbsCreateProofRequest,
+  BbsVerifyProofRequest,
+} from "../lib";
+import { stringToBytes } from "./utilities";
+
+describe("messageCommitment", () => {
+  it("should return the commitment generators", async () => {
+    const generators = await messageCommitmentGenerators();
+    expect(generators).toHaveLength(2);
+    generators.forEach((generator) => {
+      expect(generator).toBeInstanceOf(Uint8Array);
+      expect(generator.length).toEqual(48);
+    });
+  });
+
+  it("should output commitments to the committed hidden messages", async () => {
+    const keyPair = await bls12381toBbs({
+      keyPair: await generateBls12381G2KeyPair(),
+      messageCount: 3,
+    });
+    const messages = [
+      stringToBytes("Message1"),
+      stringToBytes("Message2"),
+      stringToBytes("Message3"),
+    ];
+    const signature = await sign({ keyPair, messages });
+    const blindingFactors = [
+      await generateBlindingFactor(),
+      await generateBlindingFactor(),
+    ];
+    expect(blindingFactors[0]).toBeInstanceOf(Uint8Array);
+    expect(blindingFactors[0]).not.toEqual(blindingFactors[1]);
+
+    const request: BbsCreateProofRequest = {
+      signature,
+      publicKey: keyPair.publicKey,
+      messages,
+      revealed: [0],
+      nonce: stringToBytes("nonce"),
+      committed: [
+        { messageIndex: 1, blindingFactor: blindingFactors[0] },
+        { messageIndex: 2, blindingFactor: blindingFactors[1] },
+      ],
+    };
+    const proof = await createProof(request);
+    const verifyRequest: BbsVerifyProofRequest = {
+      proof,
+      publicKey: keyPair.publicKey,
+      messages: [messages[0]],
+      nonce: stringToBytes("nonce"),
+      committed: [1, 2],
+    };
+    const result = await verifyProof(verifyRequest);
+    expect(result.verified).toBeTruthy();
+    expect(result.commitments).toHaveLength(2);
+    result.commitments!.forEach((commitment) =>
+      expect(commitment).toBeInstanceOf(Uint8Array)
+    );
+
+    // The commitments are bound to the committed indices
+    const swapped = await verifyProof({ ...verifyRequest, committed: [2, 1] });
+    expect(swapped.verified).toBeFalsy();
+    expect(swapped.commitments).toBeUndefined();
+    const missing = await verifyProof({
+      ...verifyRequest,
+      committed: undefined,
+    });
+    expect(missing.verified).toBeFalsy();
+  });
+});
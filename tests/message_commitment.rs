@@ -0,0 +1,134 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Test suite for the Web and headless browsers.
+
+#![cfg(target_arch = "wasm32")]
+extern crate wasm_bindgen_test;
+use bbs::prelude::*;
+use pairing_plus::{bls12_381::G1, CurveProjective};
+use std::convert::TryFrom;
+use wasm::prelude::*;
+use wasm::{BbsVerifyProofResponse, PoKOfSignatureProofWrapper};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+async fn verify(
+    proof: &PoKOfSignatureProofWrapper,
+    pk: &PublicKey,
+    committed: Option<Vec<usize>>,
+) -> BbsVerifyProofResponse {
+    let request = VerifyProofContext {
+        proof: PoKOfSignatureProofWrapper::try_from(proof.to_bytes().as_slice()).unwrap(),
+        publicKey: pk.clone(),
+        messages: vec![b"Message1".to_vec()],
+        nonce: b"nonce".to_vec(),
+        holderBinding: None,
+        pseudonym: None,
+        accumulator: None,
+        statusList: None,
+        verifiableEncryption: None,
+        committed,
+        notEqual: None,
+        setMembership: None,
+        rateLimit: None,
+        designatedVerifier: None,
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    serde_wasm_bindgen::from_value(bbs_verify_proof(js_value).await.unwrap()).unwrap()
+}
+
+#[allow(non_snake_case)]
+#[wasm_bindgen_test]
+pub async fn message_commitment_tests() {
+    let (pk, sk) = generate(3).unwrap();
+    let messages = vec![
+        b"Message1".to_vec(),
+        b"Message2".to_vec(),
+        b"Message3".to_vec(),
+    ];
+    let hashed: Vec<SignatureMessage> = messages.iter().map(SignatureMessage::hash).collect();
+    let signature = Signature::new(hashed.as_slice(), &sk, &pk).unwrap();
+
+    let mut blinding_factors: Vec<SignatureBlinding> = Vec::new();
+    for _ in 0..2 {
+        let blinding_factor = generate_blinding_factor().await.unwrap();
+        blinding_factors.push(serde_wasm_bindgen::from_value(blinding_factor).unwrap());
+    }
+    let request = CreateProofRequest {
+        signature,
+        publicKey: pk.clone(),
+        messages,
+        revealed: vec![0],
+        nonce: b"nonce".to_vec(),
+        holderBinding: None,
+        pseudonym: None,
+        accumulator: None,
+        statusList: None,
+        verifiableEncryption: None,
+        committed: Some(
+            blinding_factors
+                .iter()
+                .enumerate()
+                .map(|(i, b)| MessageCommitmentOptions {
+                    messageIndex: i + 1,
+                    blindingFactor: b.clone(),
+                })
+                .collect(),
+        ),
+        notEqual: None,
+        setMembership: None,
+        rateLimit: None,
+        designatedVerifier: None,
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    let proof: PoKOfSignatureProofWrapper =
+        serde_wasm_bindgen::from_value(bbs_create_proof(js_value).await.unwrap()).unwrap();
+    let result = verify(&proof, &pk, Some(vec![1, 2])).await;
+    assert!(result.verified);
+    let commitments = result.commitments.unwrap();
+    assert_eq!(commitments.len(), 2);
+
+    // The holder opens each commitment as g^m * h^s
+    let generators: Vec<Commitment> =
+        serde_wasm_bindgen::from_value(message_commitment_generators().await.unwrap()).unwrap();
+    let g = *generators[0].as_ref();
+    let h = *generators[1].as_ref();
+    for ((commitment, blinding), message) in commitments
+        .iter()
+        .zip(blinding_factors.iter())
+        .zip(hashed[1..].iter())
+    {
+        let mut opening = g;
+        opening.mul_assign(*message.as_ref());
+        let mut h_s = h;
+        h_s.mul_assign(*blinding.as_ref());
+        opening.add_assign(&h_s);
+        assert_eq!(commitment.as_ref(), &opening);
+        assert_ne!(commitment.as_ref(), &G1::zero());
+    }
+
+    // The commitments are bound to the committed indices and part of the proof
+    let result = verify(&proof, &pk, Some(vec![2, 1])).await;
+    assert!(!result.verified);
+    assert!(result.commitments.is_none());
+    assert!(!verify(&proof, &pk, Some(vec![1])).await.verified);
+    assert!(!verify(&proof, &pk, None).await.verified);
+
+    let mut tampered = PoKOfSignatureProofWrapper::try_from(proof.to_bytes().as_slice()).unwrap();
+    let first = G1_COMPRESSED_SIZE * 2 + FR_COMPRESSED_SIZE;
+    let (one, two) = tampered.statements.split_at_mut(first);
+    one.swap_with_slice(two);
+    assert!(!verify(&tampered, &pk, Some(vec![1, 2])).await.verified);
+}
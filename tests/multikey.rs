@@ -0,0 +1,196 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Test suite for the Web and headless browsers.
+
+#![cfg(target_arch = "wasm32")]
+extern crate wasm_bindgen_test;
+use bbs::prelude::*;
+use std::convert::TryInto;
+use wasm::prelude::*;
+use wasm::BbsVerifyResponse;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+pub async fn multikey_tests() {
+    let g2: BlsKeyPair = bls_generate_g2_key(None).await.unwrap().try_into().unwrap();
+    let g2_public_key = g2.publicKey.unwrap();
+    let multikey = encode_multikey(&g2_public_key).unwrap();
+    assert!(multikey.starts_with("zUC7"));
+    assert_eq!(decode_multikey(&multikey).unwrap(), g2_public_key);
+    let did = format!("did:key:{}", multikey);
+    assert_eq!(decode_multikey(&did).unwrap(), g2_public_key);
+    assert_eq!(
+        decode_multikey(&format!("{}#{}", did, multikey)).unwrap(),
+        g2_public_key
+    );
+
+    let g1: BlsKeyPair = bls_generate_g1_key(None).await.unwrap().try_into().unwrap();
+    let g1_public_key = g1.publicKey.unwrap();
+    let multikey = encode_multikey(&g1_public_key).unwrap();
+    assert!(multikey.starts_with("z3t"));
+    assert_eq!(decode_multikey(&multikey).unwrap(), g1_public_key);
+
+    let js_value = encode_multikey_js(g1_public_key.clone()).await.unwrap();
+    assert_eq!(js_value.as_string().unwrap(), multikey);
+    let decoded = decode_multikey_js(multikey.clone()).await.unwrap();
+    assert_eq!(js_sys::Uint8Array::new(&decoded).to_vec(), g1_public_key);
+
+    // Other encodings, codecs and truncated keys are rejected
+    assert!(decode_multikey(&multikey[1..]).is_err());
+    assert!(decode_multikey("z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK").is_err());
+    assert!(decode_multikey(&multikey[..multikey.len() - 2]).is_err());
+    assert!(decode_multikey("zUC70OIl").is_err());
+    assert!(encode_multikey(&g1_public_key[1..]).is_err());
+    assert!(decode_multikey(&"z".repeat(1000)).is_err());
+}
+
+#[wasm_bindgen_test]
+pub async fn did_key_document_tests() {
+    let key_pair: BlsKeyPair = bls_generate_g2_key(None).await.unwrap().try_into().unwrap();
+    let public_key = key_pair.publicKey.unwrap();
+    let multikey = encode_multikey(&public_key).unwrap();
+
+    let document = DidKeyDocument::new(&public_key).unwrap();
+    let did = format!("did:key:{}", multikey);
+    assert_eq!(document.id, did);
+    assert_eq!(
+        document.verificationMethod[0].id,
+        format!("{}#{}", did, multikey)
+    );
+    assert_eq!(document.verificationMethod[0].kind, "Multikey");
+    assert_eq!(document.verificationMethod[0].publicKeyMultibase, multikey);
+    assert_eq!(
+        document.assertionMethod,
+        vec![document.verificationMethod[0].id.clone()]
+    );
+
+    // The document is the same from the bytes, the multikey or the identifier
+    for input in [
+        JsValue::from(js_sys::Uint8Array::from(public_key.as_slice())),
+        JsValue::from_str(&multikey),
+        JsValue::from_str(&did),
+    ] {
+        let js_value = create_did_key_document(input).await.unwrap();
+        let context = js_sys::Reflect::get(&js_value, &JsValue::from_str("@context")).unwrap();
+        assert!(js_sys::Array::is_array(&context));
+        let resolved: DidKeyDocument = js_value.try_into().unwrap();
+        assert_eq!(resolved.id, did);
+    }
+    assert!(create_did_key_document(JsValue::from_str("did:key:z6Mk"))
+        .await
+        .is_err());
+}
+
+#[wasm_bindgen_test]
+pub async fn multikey_verify_tests() {
+    let key_pair: BlsKeyPair = bls_generate_g2_key(None).await.unwrap().try_into().unwrap();
+    let public_key = key_pair.publicKey.clone().unwrap();
+    let multikey = encode_multikey(&public_key).unwrap();
+    let messages = vec![b"message 1".to_vec(), b"message 2".to_vec()];
+    let request = BlsBbsSignRequest {
+        keyPair: key_pair,
+        messages: messages.clone(),
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    let signature = bls_sign(js_value).await.unwrap();
+
+    // The public key of blsVerify can be the multikey or the did:key identifier
+    for key in [multikey.clone(), format!("did:key:{}", multikey)] {
+        let request = js_sys::Object::new();
+        js_sys::Reflect::set(&request, &"publicKey".into(), &key.into()).unwrap();
+        js_sys::Reflect::set(&request, &"signature".into(), &signature).unwrap();
+        js_sys::Reflect::set(
+            &request,
+            &"messages".into(),
+            &serde_wasm_bindgen::to_value(&messages).unwrap(),
+        )
+        .unwrap();
+        let result: BbsVerifyResponse = bls_verify(request.into())
+            .await
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert!(result.verified);
+    }
+
+    // A G1 multikey is not a BBS public key
+    let g1: BlsKeyPair = bls_generate_g1_key(None).await.unwrap().try_into().unwrap();
+    let request = js_sys::Object::new();
+    let g1_multikey = encode_multikey(&g1.publicKey.unwrap()).unwrap();
+    js_sys::Reflect::set(&request, &"publicKey".into(), &g1_multikey.into()).unwrap();
+    js_sys::Reflect::set(&request, &"signature".into(), &signature).unwrap();
+    js_sys::Reflect::set(
+        &request,
+        &"messages".into(),
+        &serde_wasm_bindgen::to_value(&messages).unwrap(),
+    )
+    .unwrap();
+    let result: BbsVerifyResponse = bls_verify(request.into())
+        .await
+        .unwrap()
+        .try_into()
+        .unwrap();
+    assert!(!result.verified);
+    assert!(result.error.is_some());
+
+    // blsVerifyProof accepts the multikey as well
+    let request = BlsCreateProofRequest {
+        signature: serde_wasm_bindgen::from_value(signature).unwrap(),
+        publicKey: decode_public_key(&public_key),
+        messages: messages.clone(),
+        revealed: vec![1],
+        nonce: b"nonce".to_vec(),
+        holderBinding: None,
+        pseudonym: None,
+        accumulator: None,
+        statusList: None,
+        verifiableEncryption: None,
+        committed: None,
+        notEqual: None,
+        setMembership: None,
+        rateLimit: None,
+        designatedVerifier: None,
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    let proof = bls_create_proof(js_value).await.unwrap();
+    let request = js_sys::Object::new();
+    js_sys::Reflect::set(&request, &"proof".into(), &proof).unwrap();
+    js_sys::Reflect::set(&request, &"publicKey".into(), &multikey.into()).unwrap();
+    js_sys::Reflect::set(
+        &request,
+        &"messages".into(),
+        &serde_wasm_bindgen::to_value(&vec![messages[1].clone()]).unwrap(),
+    )
+    .unwrap();
+    js_sys::Reflect::set(
+        &request,
+        &"nonce".into(),
+        &serde_wasm_bindgen::to_value(&b"nonce".to_vec()).unwrap(),
+    )
+    .unwrap();
+    let result: BbsVerifyResponse = bls_verify_proof(request.into())
+        .await
+        .unwrap()
+        .try_into()
+        .unwrap();
+    assert!(result.verified);
+}
+
+fn decode_public_key(public_key: &[u8]) -> DeterministicPublicKey {
+    use std::convert::TryFrom;
+    DeterministicPublicKey::try_from(public_key).unwrap()
+}
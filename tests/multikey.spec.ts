@@ -0,0 +1,50 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+import {
+  generateBls12381G1KeyPair,
+  generateBls12381G2KeyPair,
+  encodeMultikey,
+  decodeMultikey,
+  createDidKeyDocument,
+} from "../lib";
+
+describe("multikey", () => {
+  it("should round trip G1 and G2 public keys through a multikey", async () => {
+    const g1 = await generateBls12381G1KeyPair();
+    const g2 = await generateBls12381G2KeyPair();
+    const g1Multikey = await encodeMultikey(g1.publicKey);
+    const g2Multikey = await encodeMultikey(g2.publicKey);
+    expect(g1Multikey.startsWith("z3t")).toBeTruthy();
+    expect(g2Multikey.startsWith("zUC7")).toBeTruthy();
+    expect(await decodeMultikey(g1Multikey)).toEqual(g1.publicKey);
+    expect(await decodeMultikey(g2Multikey)).toEqual(g2.publicKey);
+  });
+
+  it("should create a did:key document", async () => {
+    const keyPair = await generateBls12381G2KeyPair();
+    const multikey = await encodeMultikey(keyPair.publicKey);
+    const document = await createDidKeyDocument(keyPair.publicKey);
+    expect(document.id).toEqual(`did:key:${multikey}`);
+    expect(document.verificationMethod).toHaveLength(1);
+    expect(document.verificationMethod[0].publicKeyMultibase).toEqual(multikey);
+    expect(document.assertionMethod).toEqual([
+      `did:key:${multikey}#${multikey}`,
+    ]);
+    expect(await createDidKeyDocument(document.id)).toEqual(document);
+  });
+
+  it("should throw error when decoding an invalid multikey", async () => {
+    await expect(decodeMultikey("zInvalid")).rejects.toThrowError();
+  });
+});
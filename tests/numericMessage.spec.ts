@@ -0,0 +1,94 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+import {
+  generateBls12381G2KeyPair,
+  bls12381toBbs,
+  sign,
+  verify,
+  createProof,
+  verifyProof,
+  encodeNumericMessage,
+  decodeNumericMessage,
+} from "../lib";
+import { stringToBytes } from "./utilities";
+
+describe("numericMessage", () => {
+  it("should encode and decode signed 64 bit integers", async () => {
+    const values: [number | string, string][] = [
+      [0, "0"],
+      [42, "42"],
+      [-42, "-42"],
+      ["9223372036854775807", "9223372036854775807"],
+      ["-9223372036854775808", "-9223372036854775808"],
+    ];
+    for (const [value, expected] of values) {
+      const message = await encodeNumericMessage(value);
+      expect(message).toBeInstanceOf(Uint8Array);
+      const decoded = await decodeNumericMessage(message);
+      expect(typeof decoded).toEqual("bigint");
+      expect(String(decoded)).toEqual(expected);
+      // A decoded bigint encodes to the same message
+      expect(await encodeNumericMessage(decoded)).toEqual(message);
+    }
+  });
+
+  it("should throw error for values that are not 64 bit integers", async () => {
+    await expect(encodeNumericMessage(1.5)).rejects.toThrowError();
+    await expect(
+      encodeNumericMessage("9223372036854775808")
+    ).rejects.toThrowError();
+    await expect(encodeNumericMessage("042")).rejects.toThrowError();
+    await expect(
+      decodeNumericMessage(stringToBytes("42"))
+    ).rejects.toThrowError();
+  });
+
+  it("should sign, reveal and verify numeric messages", async () => {
+    const keyPair = await bls12381toBbs({
+      keyPair: await generateBls12381G2KeyPair(),
+      messageCount: 2,
+    });
+    const messages = [
+      stringToBytes("Message1"),
+      await encodeNumericMessage(42),
+    ];
+    const signature = await sign({ keyPair, messages });
+    expect(
+      (await verify({ publicKey: keyPair.publicKey, signature, messages }))
+        .verified
+    ).toBeTruthy();
+
+    const proof = await createProof({
+      signature,
+      publicKey: keyPair.publicKey,
+      messages,
+      revealed: [1],
+      nonce: stringToBytes("nonce"),
+    });
+    const result = await verifyProof({
+      proof,
+      publicKey: keyPair.publicKey,
+      messages: [messages[1]],
+      nonce: stringToBytes("nonce"),
+    });
+    expect(result.verified).toBeTruthy();
+    const other = await verifyProof({
+      proof,
+      publicKey: keyPair.publicKey,
+      messages: [await encodeNumericMessage(43)],
+      nonce: stringToBytes("nonce"),
+    });
+    expect(other.verified).toBeFalsy();
+  });
+});
@@ -0,0 +1,73 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Test suite for the Web and headless browsers.
+
+#![cfg(target_arch = "wasm32")]
+extern crate wasm_bindgen_test;
+use wasm::prelude::*;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+async fn encode(value: JsValue) -> Result<Vec<u8>, JsValue> {
+    encode_numeric_message(value)
+        .await
+        .map(|m| js_sys::Uint8Array::from(m).to_vec())
+}
+
+#[allow(non_snake_case)]
+#[wasm_bindgen_test]
+pub async fn numeric_message_tests() {
+    for value in [0, 1, -1, 85_000, i64::MAX, i64::MIN] {
+        let message = numeric_message(value);
+        assert_eq!(numeric_value(&message), Some(value));
+        let decoded = decode_numeric_message(message.clone()).await.unwrap();
+        assert_eq!(decoded, JsValue::from(js_sys::BigInt::from(value)));
+        assert_eq!(
+            encode(js_sys::BigInt::from(value).into()).await.unwrap(),
+            message
+        );
+        assert_eq!(
+            encode(JsValue::from_str(&value.to_string())).await.unwrap(),
+            message
+        );
+    }
+    assert_eq!(
+        encode(JsValue::from_f64(-17.0)).await.unwrap(),
+        numeric_message(-17)
+    );
+
+    // Only integers in their canonical form are numeric messages
+    for value in [
+        JsValue::from_f64(1.5),
+        JsValue::from_f64(9007199254740992.0),
+        JsValue::from_str("+17"),
+        JsValue::from_str("017"),
+        JsValue::from_str(" 17"),
+        JsValue::from_str("-0"),
+        JsValue::from_str("9223372036854775808"),
+        JsValue::from_bool(true),
+    ] {
+        assert!(encode(value).await.is_err());
+    }
+    assert!(decode_numeric_message(b"17".to_vec()).await.is_err());
+    let mut message = numeric_message(17);
+    message.push(0);
+    assert_eq!(numeric_value(&message), None);
+    // A scalar that is not an integer of 64 bits is not a numeric message
+    let mut message = numeric_message(0);
+    message[NUMERIC_MESSAGE_PREFIX.len()] = 1;
+    assert_eq!(numeric_value(&message), None);
+}
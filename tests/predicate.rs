@@ -0,0 +1,161 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Test suite for the Web and headless browsers.
+
+#![cfg(target_arch = "wasm32")]
+extern crate wasm_bindgen_test;
+use bbs::prelude::*;
+use std::convert::TryFrom;
+use wasm::prelude::*;
+use wasm::{BbsVerifyProofResponse, PoKOfSignatureProofWrapper};
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+fn not_equal(predicates: &[(usize, &str)]) -> Vec<NotEqualPredicate> {
+    predicates
+        .iter()
+        .map(|(i, v)| NotEqualPredicate {
+            messageIndex: *i,
+            value: v.as_bytes().to_vec(),
+        })
+        .collect()
+}
+
+async fn prove(
+    signature: &Signature,
+    pk: &PublicKey,
+    messages: &[Vec<u8>],
+    predicates: &[NotEqualPredicate],
+    committed: Option<Vec<MessageCommitmentOptions>>,
+) -> Result<JsValue, JsValue> {
+    let request = CreateProofRequest {
+        signature: signature.clone(),
+        publicKey: pk.clone(),
+        messages: messages.to_vec(),
+        revealed: vec![0],
+        nonce: b"nonce".to_vec(),
+        holderBinding: None,
+        pseudonym: None,
+        accumulator: None,
+        statusList: None,
+        verifiableEncryption: None,
+        committed,
+        notEqual: Some(predicates.to_vec()),
+        setMembership: None,
+        rateLimit: None,
+        designatedVerifier: None,
+    };
+    bbs_create_proof(serde_wasm_bindgen::to_value(&request).unwrap()).await
+}
+
+async fn verify(
+    proof: &JsValue,
+    pk: &PublicKey,
+    predicates: &[NotEqualPredicate],
+    committed: Option<Vec<usize>>,
+) -> BbsVerifyProofResponse {
+    let proof: PoKOfSignatureProofWrapper = serde_wasm_bindgen::from_value(proof.clone()).unwrap();
+    let request = VerifyProofContext {
+        proof: PoKOfSignatureProofWrapper::try_from(proof.to_bytes().as_slice()).unwrap(),
+        publicKey: pk.clone(),
+        messages: vec![b"Message1".to_vec()],
+        nonce: b"nonce".to_vec(),
+        holderBinding: None,
+        pseudonym: None,
+        accumulator: None,
+        statusList: None,
+        verifiableEncryption: None,
+        committed,
+        notEqual: Some(predicates.to_vec()),
+        setMembership: None,
+        rateLimit: None,
+        designatedVerifier: None,
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    serde_wasm_bindgen::from_value(bbs_verify_proof(js_value).await.unwrap()).unwrap()
+}
+
+#[allow(non_snake_case)]
+#[wasm_bindgen_test]
+pub async fn predicate_tests() {
+    let (pk, sk) = generate(3).unwrap();
+    let messages = vec![
+        b"Message1".to_vec(),
+        b"did:example:alice".to_vec(),
+        b"active".to_vec(),
+    ];
+    let hashed: Vec<SignatureMessage> = messages.iter().map(SignatureMessage::hash).collect();
+    let signature = Signature::new(hashed.as_slice(), &sk, &pk).unwrap();
+
+    let predicates = not_equal(&[
+        (1, "did:example:mallory"),
+        (1, "did:example:eve"),
+        (2, "suspended"),
+    ]);
+    let proof = prove(&signature, &pk, &messages, &predicates, None)
+        .await
+        .unwrap();
+    assert!(verify(&proof, &pk, &predicates, None).await.verified);
+
+    // The predicates must match the ones the proof was created for
+    assert!(
+        !verify(&proof, &pk, &not_equal(&[(1, "did:example:mallory")]), None)
+            .await
+            .verified
+    );
+    let changed = not_equal(&[
+        (1, "did:example:mallory"),
+        (1, "did:example:alice"),
+        (2, "suspended"),
+    ]);
+    assert!(!verify(&proof, &pk, &changed, None).await.verified);
+
+    // The predicates combine with the other statements about the same hidden messages
+    let committed = vec![MessageCommitmentOptions {
+        messageIndex: 1,
+        blindingFactor: SignatureBlinding::random(),
+    }];
+    let proof = prove(&signature, &pk, &messages, &predicates, Some(committed))
+        .await
+        .unwrap();
+    let result = verify(&proof, &pk, &predicates, Some(vec![1])).await;
+    assert!(result.verified);
+    assert_eq!(result.commitments.unwrap().len(), 1);
+    assert!(!verify(&proof, &pk, &predicates, None).await.verified);
+
+    // No predicates is a proof of knowledge of the signature without statements
+    let proof = prove(&signature, &pk, &messages, &[], None).await.unwrap();
+    assert!(verify(&proof, &pk, &[], None).await.verified);
+
+    assert!(prove(
+        &signature,
+        &pk,
+        &messages,
+        &not_equal(&[(2, "active")]),
+        None
+    )
+    .await
+    .is_err());
+    assert!(prove(
+        &signature,
+        &pk,
+        &messages,
+        &not_equal(&[(0, "active")]),
+        None
+    )
+    .await
+    .is_err());
+}
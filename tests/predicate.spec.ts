@@ -0,0 +1,101 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+import {
+  generateBls12381G2KeyPair,
+  bls12381toBbs,
+  sign,
+  createProof,
+  verifyProof,
+  generateBlindingFactor,
+  BbsKeyPair,
+  NotEqualPredicate,
+} from "../lib";
+import { stringToBytes } from "./utilities";
+
+describe("predicate", () => {
+  const messages = [
+    stringToBytes("Message1"),
+    stringToBytes("did:example:alice"),
+    stringToBytes("Message3"),
+  ];
+  let keyPair: BbsKeyPair;
+  let signature: Uint8Array;
+
+  beforeAll(async () => {
+    keyPair = await bls12381toBbs({
+      keyPair: await generateBls12381G2KeyPair(),
+      messageCount: 3,
+    });
+    signature = await sign({ keyPair, messages });
+  });
+
+  const notEqual = (value: string): NotEqualPredicate[] => [
+    { messageIndex: 1, value: stringToBytes(value) },
+  ];
+
+  const prove = (value: string): Promise<Uint8Array> =>
+    createProof({
+      signature,
+      publicKey: keyPair.publicKey,
+      messages,
+      revealed: [0],
+      nonce: stringToBytes("nonce"),
+      notEqual: notEqual(value),
+    });
+
+  const verify = async (proof: Uint8Array, value: string): Promise<boolean> =>
+    (
+      await verifyProof({
+        proof,
+        publicKey: keyPair.publicKey,
+        messages: [messages[0]],
+        nonce: stringToBytes("nonce"),
+        notEqual: notEqual(value),
+      })
+    ).verified;
+
+  it("should prove a hidden message is not equal to a value", async () => {
+    const proof = await prove("did:example:mallory");
+    expect(await verify(proof, "did:example:mallory")).toBeTruthy();
+    expect(await verify(proof, "did:example:eve")).toBeFalsy();
+  });
+
+  it("should combine with the other statements", async () => {
+    const proof = await createProof({
+      signature,
+      publicKey: keyPair.publicKey,
+      messages,
+      revealed: [0],
+      nonce: stringToBytes("nonce"),
+      committed: [
+        { messageIndex: 1, blindingFactor: await generateBlindingFactor() },
+      ],
+      notEqual: notEqual("did:example:mallory"),
+    });
+    const result = await verifyProof({
+      proof,
+      publicKey: keyPair.publicKey,
+      messages: [messages[0]],
+      nonce: stringToBytes("nonce"),
+      committed: [1],
+      notEqual: notEqual("did:example:mallory"),
+    });
+    expect(result.verified).toBeTruthy();
+    expect(result.commitments).toHaveLength(1);
+  });
+
+  it("should throw error when the hidden message equals the value", async () => {
+    await expect(prove("did:example:alice")).rejects.toThrowError();
+  });
+});
@@ -0,0 +1,206 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Test suite for the Web and headless browsers.
+
+#![cfg(target_arch = "wasm32")]
+extern crate wasm_bindgen_test;
+use bbs::prelude::*;
+use std::convert::{TryFrom, TryInto};
+use wasm::prelude::*;
+use wasm::{BbsVerifyProofResponse, PoKOfSignatureProofWrapper};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+async fn prove(
+    signature: &Signature,
+    pk: &PublicKey,
+    messages: &[Vec<u8>],
+    scope: &[u8],
+) -> PoKOfSignatureProofWrapper {
+    let request = CreateProofRequest {
+        signature: signature.clone(),
+        publicKey: pk.clone(),
+        messages: messages.to_vec(),
+        revealed: vec![1],
+        nonce: b"nonce".to_vec(),
+        holderBinding: None,
+        pseudonym: Some(PseudonymOptions {
+            scope: scope.to_vec(),
+            secretIndex: 0,
+        }),
+        accumulator: None,
+        statusList: None,
+        verifiableEncryption: None,
+        committed: None,
+        notEqual: None,
+        setMembership: None,
+        rateLimit: None,
+        designatedVerifier: None,
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    serde_wasm_bindgen::from_value(bbs_create_proof(js_value).await.unwrap()).unwrap()
+}
+
+async fn verify(
+    proof: &PoKOfSignatureProofWrapper,
+    pk: &PublicKey,
+    scope: &[u8],
+    secret_index: usize,
+) -> BbsVerifyProofResponse {
+    let request = VerifyProofContext {
+        proof: PoKOfSignatureProofWrapper::try_from(proof.to_bytes().as_slice()).unwrap(),
+        publicKey: pk.clone(),
+        messages: vec![b"Message2".to_vec()],
+        nonce: b"nonce".to_vec(),
+        holderBinding: None,
+        pseudonym: Some(PseudonymOptions {
+            scope: scope.to_vec(),
+            secretIndex: secret_index,
+        }),
+        accumulator: None,
+        statusList: None,
+        verifiableEncryption: None,
+        committed: None,
+        notEqual: None,
+        setMembership: None,
+        rateLimit: None,
+        designatedVerifier: None,
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    serde_wasm_bindgen::from_value(bbs_verify_proof(js_value).await.unwrap()).unwrap()
+}
+
+#[allow(non_snake_case)]
+#[wasm_bindgen_test]
+pub async fn pseudonym_tests() {
+    let (pk, sk) = generate(3).unwrap();
+    let messages = vec![
+        b"link secret".to_vec(),
+        b"Message2".to_vec(),
+        b"Message3".to_vec(),
+    ];
+    let hashed: Vec<SignatureMessage> = messages.iter().map(SignatureMessage::hash).collect();
+    let signature = Signature::new(hashed.as_slice(), &sk, &pk).unwrap();
+
+    let proof1 = prove(&signature, &pk, &messages, b"verifier1").await;
+    let proof2 = prove(&signature, &pk, &messages, b"verifier1").await;
+    let proof3 = prove(&signature, &pk, &messages, b"verifier2").await;
+    let result1 = verify(&proof1, &pk, b"verifier1", 0).await;
+    let result2 = verify(&proof2, &pk, b"verifier1", 0).await;
+    let result3 = verify(&proof3, &pk, b"verifier2", 0).await;
+    assert!(result1.verified && result2.verified && result3.verified);
+
+    // Stable for a scope, unlinkable across scopes
+    assert!(result1.pseudonym.is_some());
+    assert_eq!(result1.pseudonym, result2.pseudonym);
+    assert_ne!(result1.pseudonym, result3.pseudonym);
+
+    let result = verify(&proof1, &pk, b"verifier2", 0).await;
+    assert!(!result.verified);
+    assert!(result.pseudonym.is_none());
+    assert!(!verify(&proof1, &pk, b"verifier1", 2).await.verified);
+
+    // A pseudonym that is not derived from the signed secret does not verify
+    let mut forged = PoKOfSignatureProofWrapper::try_from(proof1.to_bytes().as_slice()).unwrap();
+    forged.statements[..G1_COMPRESSED_SIZE]
+        .copy_from_slice(&proof3.statements[..G1_COMPRESSED_SIZE]);
+    assert!(!verify(&forged, &pk, b"verifier1", 0).await.verified);
+}
+
+#[allow(non_snake_case)]
+#[wasm_bindgen_test]
+pub async fn pseudonym_with_holder_binding_tests() {
+    let (pk, sk) = generate(3).unwrap();
+    let link_secret = b"link secret".to_vec();
+    let messages = vec![b"Message2".to_vec(), b"Message3".to_vec()];
+    let mut signed = messages.clone();
+    signed.insert(0, link_secret.clone());
+    let hashed: Vec<SignatureMessage> = signed.iter().map(SignatureMessage::hash).collect();
+    let signature = Signature::new(hashed.as_slice(), &sk, &pk).unwrap();
+    let request = LinkSecretCommitmentRequest {
+        linkSecret: link_secret.clone(),
+    };
+    let registered: LinkSecretCommitment =
+        create_link_secret_commitment(serde_wasm_bindgen::to_value(&request).unwrap())
+            .await
+            .unwrap()
+            .try_into()
+            .unwrap();
+
+    // One presentation binds the link secret and derives the pseudonym from it
+    let request = CreateProofRequest {
+        signature,
+        publicKey: pk.clone(),
+        messages,
+        revealed: vec![1],
+        nonce: b"nonce".to_vec(),
+        holderBinding: Some(HolderBindingProofOptions {
+            linkSecret: link_secret,
+            linkSecretIndex: 0,
+            commitment: registered.commitment,
+            blindingFactor: registered.blindingFactor,
+        }),
+        pseudonym: Some(PseudonymOptions {
+            scope: b"verifier1".to_vec(),
+            secretIndex: 0,
+        }),
+        accumulator: None,
+        statusList: None,
+        verifiableEncryption: None,
+        committed: None,
+        notEqual: None,
+        setMembership: None,
+        rateLimit: None,
+        designatedVerifier: None,
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    let proof: PoKOfSignatureProofWrapper =
+        serde_wasm_bindgen::from_value(bbs_create_proof(js_value).await.unwrap()).unwrap();
+
+    let verify_request = |holder_binding| VerifyProofContext {
+        proof: PoKOfSignatureProofWrapper::try_from(proof.to_bytes().as_slice()).unwrap(),
+        publicKey: pk.clone(),
+        messages: vec![b"Message2".to_vec()],
+        nonce: b"nonce".to_vec(),
+        holderBinding: holder_binding,
+        pseudonym: Some(PseudonymOptions {
+            scope: b"verifier1".to_vec(),
+            secretIndex: 0,
+        }),
+        accumulator: None,
+        statusList: None,
+        verifiableEncryption: None,
+        committed: None,
+        notEqual: None,
+        setMembership: None,
+        rateLimit: None,
+        designatedVerifier: None,
+    };
+    let request = verify_request(Some(HolderBindingVerifyOptions {
+        linkSecretIndex: 0,
+        commitment: registered.commitment,
+    }));
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    let result: BbsVerifyProofResponse =
+        serde_wasm_bindgen::from_value(bbs_verify_proof(js_value).await.unwrap()).unwrap();
+    assert!(result.verified);
+    assert!(result.pseudonym.is_some());
+
+    // Every statement of the proof must be checked
+    let js_value = serde_wasm_bindgen::to_value(&verify_request(None)).unwrap();
+    let result: BbsVerifyProofResponse =
+        serde_wasm_bindgen::from_value(bbs_verify_proof(js_value).await.unwrap()).unwrap();
+    assert!(!result.verified);
+}
@@ -0,0 +1,79 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+import {
+  generateBls12381G2KeyPair,
+  bls12381toBbs,
+  sign,
+  createProof,
+  verifyProof,
+  BbsKeyPair,
+} from "../lib";
+import { stringToBytes } from "./utilities";
+
+describe("pseudonym", () => {
+  const messages = [
+    stringToBytes("link secret"),
+    stringToBytes("Message2"),
+    stringToBytes("Message3"),
+  ];
+  let keyPair: BbsKeyPair;
+  let signature: Uint8Array;
+
+  beforeAll(async () => {
+    keyPair = await bls12381toBbs({
+      keyPair: await generateBls12381G2KeyPair(),
+      messageCount: 3,
+    });
+    signature = await sign({ keyPair, messages });
+  });
+
+  const prove = (scope: string): Promise<Uint8Array> =>
+    createProof({
+      signature,
+      publicKey: keyPair.publicKey,
+      messages,
+      revealed: [1],
+      nonce: stringToBytes("nonce"),
+      pseudonym: { scope: stringToBytes(scope), secretIndex: 0 },
+    });
+
+  const verify = (proof: Uint8Array, scope: string, secretIndex = 0) =>
+    verifyProof({
+      proof,
+      publicKey: keyPair.publicKey,
+      messages: [messages[1]],
+      nonce: stringToBytes("nonce"),
+      pseudonym: { scope: stringToBytes(scope), secretIndex },
+    });
+
+  it("should return a pseudonym that is stable for a scope", async () => {
+    const result1 = await verify(await prove("verifier1"), "verifier1");
+    const result2 = await verify(await prove("verifier1"), "verifier1");
+    const result3 = await verify(await prove("verifier2"), "verifier2");
+    expect(result1.verified).toBeTruthy();
+    expect(result2.verified).toBeTruthy();
+    expect(result3.verified).toBeTruthy();
+    expect(result1.pseudonym).toBeInstanceOf(Uint8Array);
+    expect(result1.pseudonym).toEqual(result2.pseudonym);
+    expect(result1.pseudonym).not.toEqual(result3.pseudonym);
+  });
+
+  it("should not verify for another scope or secret index", async () => {
+    const proof = await prove("verifier1");
+    const otherScope = await verify(proof, "verifier2");
+    expect(otherScope.verified).toBeFalsy();
+    expect(otherScope.pseudonym).toBeUndefined();
+    expect((await verify(proof, "verifier1", 2)).verified).toBeFalsy();
+  });
+});
@@ -0,0 +1,97 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+import {
+  generateBls12381G2KeyPair,
+  bls12381toBbs,
+  sign,
+  createProof,
+  verifyProof,
+  BbsKeyPair,
+  BbsVerifyProofResult,
+} from "../lib";
+import { stringToBytes } from "./utilities";
+
+describe("rateLimit", () => {
+  const messages = [
+    stringToBytes("holder secret"),
+    stringToBytes("Message2"),
+    stringToBytes("Message3"),
+  ];
+  let keyPair: BbsKeyPair;
+  let signature: Uint8Array;
+
+  beforeAll(async () => {
+    keyPair = await bls12381toBbs({
+      keyPair: await generateBls12381G2KeyPair(),
+      messageCount: 3,
+    });
+    signature = await sign({ keyPair, messages });
+  });
+
+  const prove = (epoch: string, counter: number): Promise<Uint8Array> =>
+    createProof({
+      signature,
+      publicKey: keyPair.publicKey,
+      messages,
+      revealed: [1],
+      nonce: stringToBytes("nonce"),
+      rateLimit: {
+        secretIndex: 0,
+        epoch: stringToBytes(epoch),
+        counter,
+        limit: 3,
+      },
+    });
+
+  const verify = (
+    proof: Uint8Array,
+    epoch: string,
+    limit: number
+  ): Promise<BbsVerifyProofResult> =>
+    verifyProof({
+      proof,
+      publicKey: keyPair.publicKey,
+      messages: [messages[1]],
+      nonce: stringToBytes("nonce"),
+      rateLimit: { secretIndex: 0, epoch: stringToBytes(epoch), limit },
+    });
+
+  const tag = async (epoch: string, counter: number): Promise<Uint8Array> => {
+    const result = await verify(await prove(epoch, counter), epoch, 3);
+    expect(result.verified).toBeTruthy();
+    expect(result.tag).toBeInstanceOf(Uint8Array);
+    return result.tag!;
+  };
+
+  it("should give one tag for each counter in an epoch", async () => {
+    const tag0 = await tag("2024-06-01", 0);
+    const tag1 = await tag("2024-06-01", 1);
+    expect(tag0).not.toEqual(tag1);
+
+    expect(await tag("2024-06-01", 0)).toEqual(tag0);
+    expect(await tag("2024-06-02", 0)).not.toEqual(tag0);
+  });
+
+  it("should not verify for another epoch or limit", async () => {
+    const proof = await prove("2024-06-01", 0);
+    const result = await verify(proof, "2024-06-02", 3);
+    expect(result.verified).toBeFalsy();
+    expect(result.tag).toBeUndefined();
+    expect((await verify(proof, "2024-06-01", 2)).verified).toBeFalsy();
+  });
+
+  it("should throw error when the counter reaches the limit", async () => {
+    await expect(prove("2024-06-01", 3)).rejects.toThrowError();
+  });
+});
@@ -0,0 +1,168 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Test suite for the Web and headless browsers.
+
+#![cfg(target_arch = "wasm32")]
+extern crate wasm_bindgen_test;
+use bbs::prelude::*;
+use std::convert::TryFrom;
+use wasm::prelude::*;
+use wasm::{BbsVerifyProofResponse, PoKOfSignatureProofWrapper};
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+async fn prove(
+    signature: &Signature,
+    pk: &PublicKey,
+    messages: &[Vec<u8>],
+    epoch: &[u8],
+    counter: usize,
+    pseudonym: Option<PseudonymOptions>,
+) -> Result<JsValue, JsValue> {
+    let request = CreateProofRequest {
+        signature: signature.clone(),
+        publicKey: pk.clone(),
+        messages: messages.to_vec(),
+        revealed: vec![1],
+        nonce: b"nonce".to_vec(),
+        holderBinding: None,
+        pseudonym,
+        accumulator: None,
+        statusList: None,
+        verifiableEncryption: None,
+        committed: None,
+        notEqual: None,
+        setMembership: None,
+        rateLimit: Some(RateLimitProofOptions {
+            secretIndex: 0,
+            epoch: epoch.to_vec(),
+            counter,
+            limit: 3,
+        }),
+        designatedVerifier: None,
+    };
+    bbs_create_proof(serde_wasm_bindgen::to_value(&request).unwrap()).await
+}
+
+async fn verify(
+    proof: &JsValue,
+    pk: &PublicKey,
+    epoch: &[u8],
+    limit: usize,
+    pseudonym: Option<PseudonymOptions>,
+) -> BbsVerifyProofResponse {
+    let proof: PoKOfSignatureProofWrapper = serde_wasm_bindgen::from_value(proof.clone()).unwrap();
+    let request = VerifyProofContext {
+        proof: PoKOfSignatureProofWrapper::try_from(proof.to_bytes().as_slice()).unwrap(),
+        publicKey: pk.clone(),
+        messages: vec![b"Message2".to_vec()],
+        nonce: b"nonce".to_vec(),
+        holderBinding: None,
+        pseudonym,
+        accumulator: None,
+        statusList: None,
+        verifiableEncryption: None,
+        committed: None,
+        notEqual: None,
+        setMembership: None,
+        rateLimit: Some(RateLimitVerifyOptions {
+            secretIndex: 0,
+            epoch: epoch.to_vec(),
+            limit,
+        }),
+        designatedVerifier: None,
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    serde_wasm_bindgen::from_value(bbs_verify_proof(js_value).await.unwrap()).unwrap()
+}
+
+async fn tag(
+    signature: &Signature,
+    pk: &PublicKey,
+    messages: &[Vec<u8>],
+    epoch: &[u8],
+    counter: usize,
+) -> Commitment {
+    let proof = prove(signature, pk, messages, epoch, counter, None)
+        .await
+        .unwrap();
+    let result = verify(&proof, pk, epoch, 3, None).await;
+    assert!(result.verified);
+    result.tag.unwrap()
+}
+
+#[allow(non_snake_case)]
+#[wasm_bindgen_test]
+pub async fn rate_limit_tests() {
+    let (pk, sk) = generate(3).unwrap();
+    let messages = vec![
+        b"holder secret".to_vec(),
+        b"Message2".to_vec(),
+        b"Message3".to_vec(),
+    ];
+    let hashed: Vec<SignatureMessage> = messages.iter().map(SignatureMessage::hash).collect();
+    let signature = Signature::new(hashed.as_slice(), &sk, &pk).unwrap();
+
+    let mut tags = Vec::new();
+    for counter in 0..3 {
+        tags.push(tag(&signature, &pk, &messages, b"2024-06-01", counter).await);
+    }
+    assert_ne!(tags[0], tags[1]);
+    assert_ne!(tags[1], tags[2]);
+
+    // Reusing a counter in the same epoch gives the same tag, but not across epochs
+    assert_eq!(
+        tag(&signature, &pk, &messages, b"2024-06-01", 0).await,
+        tags[0]
+    );
+    assert_ne!(
+        tag(&signature, &pk, &messages, b"2024-06-02", 0).await,
+        tags[0]
+    );
+
+    // The epoch and limit must match the ones the proof was created for
+    let proof = prove(&signature, &pk, &messages, b"2024-06-01", 0, None)
+        .await
+        .unwrap();
+    let result = verify(&proof, &pk, b"2024-06-02", 3, None).await;
+    assert!(!result.verified);
+    assert!(result.tag.is_none());
+    assert!(!verify(&proof, &pk, b"2024-06-01", 2, None).await.verified);
+
+    // The tag combines with the other statements about the same secret
+    let pseudonym = || PseudonymOptions {
+        scope: b"https://verifier.example".to_vec(),
+        secretIndex: 0,
+    };
+    let proof = prove(
+        &signature,
+        &pk,
+        &messages,
+        b"2024-06-01",
+        1,
+        Some(pseudonym()),
+    )
+    .await
+    .unwrap();
+    let result = verify(&proof, &pk, b"2024-06-01", 3, Some(pseudonym())).await;
+    assert!(result.verified);
+    assert_eq!(result.tag.unwrap(), tags[1]);
+    assert!(result.pseudonym.is_some());
+
+    assert!(prove(&signature, &pk, &messages, b"2024-06-01", 3, None)
+        .await
+        .is_err());
+}
@@ -0,0 +1,98 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+import {
+  generateBls12381G2KeyPair,
+  bls12381toBbs,
+  sign,
+  createProof,
+  verifyProof,
+  BbsKeyPair,
+  NotEqualPredicate,
+} from "../lib";
+import { stringToBytes } from "./utilities";
+
+describe("setMembership", () => {
+  const messages = [
+    stringToBytes("Message1"),
+    stringToBytes("NZ"),
+    stringToBytes("Message3"),
+  ];
+  const countries = (values: string[]): Uint8Array[] =>
+    values.map(stringToBytes);
+  let keyPair: BbsKeyPair;
+  let signature: Uint8Array;
+
+  beforeAll(async () => {
+    keyPair = await bls12381toBbs({
+      keyPair: await generateBls12381G2KeyPair(),
+      messageCount: 3,
+    });
+    signature = await sign({ keyPair, messages });
+  });
+
+  const prove = (
+    set: Uint8Array[],
+    notEqual?: NotEqualPredicate[]
+  ): Promise<Uint8Array> =>
+    createProof({
+      signature,
+      publicKey: keyPair.publicKey,
+      messages,
+      revealed: [0],
+      nonce: stringToBytes("nonce"),
+      setMembership: { messageIndex: 1, set },
+      notEqual,
+    });
+
+  const verify = async (
+    proof: Uint8Array,
+    set: Uint8Array[],
+    notEqual?: NotEqualPredicate[]
+  ): Promise<boolean> =>
+    (
+      await verifyProof({
+        proof,
+        publicKey: keyPair.publicKey,
+        messages: [messages[0]],
+        nonce: stringToBytes("nonce"),
+        setMembership: { messageIndex: 1, set },
+        notEqual,
+      })
+    ).verified;
+
+  it("should prove a hidden message is in a set", async () => {
+    const set = countries(["AU", "NZ", "US"]);
+    const proof = await prove(set);
+    expect(await verify(proof, set)).toBeTruthy();
+  });
+
+  it("should not verify against a different set", async () => {
+    const proof = await prove(countries(["AU", "NZ", "US"]));
+    expect(await verify(proof, countries(["AU", "NZ"]))).toBeFalsy();
+    expect(await verify(proof, countries(["AU", "DE", "US"]))).toBeFalsy();
+    expect(await verify(proof, countries(["NZ", "AU", "US"]))).toBeFalsy();
+  });
+
+  it("should combine with the other statements", async () => {
+    const set = countries(["AU", "NZ", "US"]);
+    const notEqual = [{ messageIndex: 1, value: stringToBytes("US") }];
+    const proof = await prove(set, notEqual);
+    expect(await verify(proof, set, notEqual)).toBeTruthy();
+    expect(await verify(proof, set)).toBeFalsy();
+  });
+
+  it("should throw error when the hidden message is not in the set", async () => {
+    await expect(prove(countries(["AU", "US"]))).rejects.toThrowError();
+  });
+});
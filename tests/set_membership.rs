@@ -0,0 +1,130 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Test suite for the Web and headless browsers.
+
+#![cfg(target_arch = "wasm32")]
+extern crate wasm_bindgen_test;
+use bbs::prelude::*;
+use std::convert::TryFrom;
+use wasm::prelude::*;
+use wasm::{BbsVerifyProofResponse, PoKOfSignatureProofWrapper};
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+fn set(values: &[&str]) -> SetMembershipOptions {
+    SetMembershipOptions {
+        messageIndex: 1,
+        set: values.iter().map(|v| v.as_bytes().to_vec()).collect(),
+    }
+}
+
+async fn prove(
+    signature: &Signature,
+    pk: &PublicKey,
+    messages: &[Vec<u8>],
+    set: &SetMembershipOptions,
+    not_equal: Option<Vec<NotEqualPredicate>>,
+) -> Result<JsValue, JsValue> {
+    let request = CreateProofRequest {
+        signature: signature.clone(),
+        publicKey: pk.clone(),
+        messages: messages.to_vec(),
+        revealed: vec![0],
+        nonce: b"nonce".to_vec(),
+        holderBinding: None,
+        pseudonym: None,
+        accumulator: None,
+        statusList: None,
+        verifiableEncryption: None,
+        committed: None,
+        notEqual: not_equal,
+        setMembership: Some(set.clone()),
+        rateLimit: None,
+        designatedVerifier: None,
+    };
+    bbs_create_proof(serde_wasm_bindgen::to_value(&request).unwrap()).await
+}
+
+async fn verify(
+    proof: &JsValue,
+    pk: &PublicKey,
+    set: &SetMembershipOptions,
+    not_equal: Option<Vec<NotEqualPredicate>>,
+) -> bool {
+    let proof: PoKOfSignatureProofWrapper = serde_wasm_bindgen::from_value(proof.clone()).unwrap();
+    let request = VerifyProofContext {
+        proof: PoKOfSignatureProofWrapper::try_from(proof.to_bytes().as_slice()).unwrap(),
+        publicKey: pk.clone(),
+        messages: vec![b"Message1".to_vec()],
+        nonce: b"nonce".to_vec(),
+        holderBinding: None,
+        pseudonym: None,
+        accumulator: None,
+        statusList: None,
+        verifiableEncryption: None,
+        committed: None,
+        notEqual: not_equal,
+        setMembership: Some(set.clone()),
+        rateLimit: None,
+        designatedVerifier: None,
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    let result: BbsVerifyProofResponse =
+        serde_wasm_bindgen::from_value(bbs_verify_proof(js_value).await.unwrap()).unwrap();
+    result.verified
+}
+
+#[allow(non_snake_case)]
+#[wasm_bindgen_test]
+pub async fn set_membership_tests() {
+    let (pk, sk) = generate(3).unwrap();
+    let messages = vec![b"Message1".to_vec(), b"NZ".to_vec(), b"Message3".to_vec()];
+    let hashed: Vec<SignatureMessage> = messages.iter().map(SignatureMessage::hash).collect();
+    let signature = Signature::new(hashed.as_slice(), &sk, &pk).unwrap();
+
+    let allowed = set(&["AU", "NZ", "US"]);
+    let proof = prove(&signature, &pk, &messages, &allowed, None)
+        .await
+        .unwrap();
+    assert!(verify(&proof, &pk, &allowed, None).await);
+
+    // The verifier must use the same set
+    assert!(!verify(&proof, &pk, &set(&["AU", "NZ"]), None).await);
+    assert!(!verify(&proof, &pk, &set(&["AU", "DE", "US"]), None).await);
+    assert!(!verify(&proof, &pk, &set(&["NZ", "AU", "US"]), None).await);
+
+    // A single member set is an equality proof
+    let single = set(&["NZ"]);
+    let proof = prove(&signature, &pk, &messages, &single, None)
+        .await
+        .unwrap();
+    assert!(verify(&proof, &pk, &single, None).await);
+
+    // Set membership combines with the other statements about the same message
+    let not_us = Some(vec![NotEqualPredicate {
+        messageIndex: 1,
+        value: b"US".to_vec(),
+    }]);
+    let proof = prove(&signature, &pk, &messages, &allowed, not_us.clone())
+        .await
+        .unwrap();
+    assert!(verify(&proof, &pk, &allowed, not_us).await);
+    assert!(!verify(&proof, &pk, &allowed, None).await);
+
+    assert!(prove(&signature, &pk, &messages, &set(&["AU", "US"]), None)
+        .await
+        .is_err());
+}
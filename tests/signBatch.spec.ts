@@ -0,0 +1,66 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+import {
+  generateBls12381G2KeyPair,
+  bls12381toBbs,
+  signBatch,
+  verify,
+  BbsSignBatchRequest,
+} from "../lib";
+import { stringToBytes } from "./utilities";
+
+describe("signBatch", () => {
+  it("should sign each message list with the same key pair", async () => {
+    const keyPair = await bls12381toBbs({
+      keyPair: await generateBls12381G2KeyPair(),
+      messageCount: 2,
+    });
+    const messages = [
+      [stringToBytes("Credential1"), stringToBytes("Alice")],
+      [stringToBytes("Credential2"), stringToBytes("Bob")],
+      [stringToBytes("Credential3"), stringToBytes("Carol")],
+    ];
+    const request: BbsSignBatchRequest = { keyPair, messages };
+
+    const signatures = await signBatch(request);
+    expect(signatures).toHaveLength(3);
+    for (let i = 0; i < signatures.length; i++) {
+      expect(signatures[i]).toBeInstanceOf(Uint8Array);
+      const result = await verify({
+        publicKey: keyPair.publicKey,
+        signature: signatures[i],
+        messages: messages[i],
+      });
+      expect(result.verified).toBeTruthy();
+    }
+    const swapped = await verify({
+      publicKey: keyPair.publicKey,
+      signature: signatures[0],
+      messages: messages[1],
+    });
+    expect(swapped.verified).toBeFalsy();
+  });
+
+  it("should throw error when a message list does not match the key pair", async () => {
+    const keyPair = await bls12381toBbs({
+      keyPair: await generateBls12381G2KeyPair(),
+      messageCount: 2,
+    });
+    const request: BbsSignBatchRequest = {
+      keyPair,
+      messages: [[stringToBytes("Credential1")]],
+    };
+    await expect(signBatch(request)).rejects.toThrowError();
+  });
+});
@@ -0,0 +1,126 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+import {
+  generateBls12381G2KeyPair,
+  bls12381toBbs,
+  sign,
+  createProof,
+  verifyProof,
+  encodeNumericMessage,
+  signStatusList,
+  BbsCreateProofRequest,
+  BbsVerifyProofRequest,
+  StatusListGap,
+} from "../lib";
+import { gzipSync } from "zlib";
+import { stringToBytes } from "./utilities";
+
+/**
+ * Encodes a status list of `length` bytes with the `set` indices
+ */
+const encodeStatusList = (set: number[], length: number): string => {
+  const bits = new Uint8Array(length);
+  set.forEach((i) => (bits[i >> 3] |= 0x80 >> (i % 8)));
+  const base64url = gzipSync(bits)
+    .toString("base64")
+    .replace(/\+/g, "-")
+    .replace(/\//g, "_")
+    .replace(/=+$/, "");
+  return `u${base64url}`;
+};
+
+const gapOf = (gaps: StatusListGap[], index: number): StatusListGap =>
+  gaps.filter((gap) => gap.start < index && index < gap.end)[0];
+
+describe("statusList", () => {
+  it("should sign the gaps between the set bits of a status list", async () => {
+    const publisher = await generateBls12381G2KeyPair();
+    const gaps = await signStatusList({
+      secretKey: publisher.secretKey,
+      encodedList: encodeStatusList([3, 41, 43, 100], 16),
+    });
+    expect(gaps.map((gap) => [gap.start, gap.end])).toEqual([
+      [-1, 3],
+      [3, 41],
+      [41, 43],
+      [43, 100],
+      [100, 128],
+    ]);
+    gaps.forEach((gap) => expect(gap.signature).toBeInstanceOf(Uint8Array));
+  });
+
+  it("should prove the hidden status list index is unset", async () => {
+    const publisher = await generateBls12381G2KeyPair();
+    const encodedList = encodeStatusList([3, 41, 43, 100], 16);
+    const gaps = await signStatusList({
+      secretKey: publisher.secretKey,
+      encodedList,
+    });
+    const keyPair = await bls12381toBbs({
+      keyPair: await generateBls12381G2KeyPair(),
+      messageCount: 2,
+    });
+    const messages = [
+      stringToBytes("Message1"),
+      await encodeNumericMessage(42),
+    ];
+    const signature = await sign({ keyPair, messages });
+
+    const request: BbsCreateProofRequest = {
+      signature,
+      publicKey: keyPair.publicKey,
+      messages,
+      revealed: [0],
+      nonce: stringToBytes("nonce"),
+      statusList: {
+        statusMessageIndex: 1,
+        encodedList,
+        publicKey: publisher.publicKey,
+        gap: gapOf(gaps, 42),
+      },
+    };
+    const proof = await createProof(request);
+    const verifyRequest: BbsVerifyProofRequest = {
+      proof,
+      publicKey: keyPair.publicKey,
+      messages: [messages[0]],
+      nonce: stringToBytes("nonce"),
+      statusList: {
+        statusMessageIndex: 1,
+        encodedList,
+        publicKey: publisher.publicKey,
+      },
+    };
+    expect((await verifyProof(verifyRequest)).verified).toBeTruthy();
+
+    // The list changed after the proof was created
+    const changed = await verifyProof({
+      ...verifyRequest,
+      statusList: {
+        statusMessageIndex: 1,
+        encodedList: encodeStatusList([3, 41, 43, 101], 16),
+        publicKey: publisher.publicKey,
+      },
+    });
+    expect(changed.verified).toBeFalsy();
+
+    // A gap that does not hold the index cannot be used
+    await expect(
+      createProof({
+        ...request,
+        statusList: { ...request.statusList!, gap: gapOf(gaps, 50) },
+      })
+    ).rejects.toThrowError();
+  });
+});
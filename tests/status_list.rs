@@ -0,0 +1,232 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Test suite for the Web and headless browsers.
+
+#![cfg(target_arch = "wasm32")]
+extern crate wasm_bindgen_test;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use bbs::prelude::*;
+use flate2::{write::GzEncoder, Compression};
+use std::{convert::TryFrom, io::Write};
+use wasm::prelude::*;
+use wasm::{BbsVerifyResponse, PoKOfSignatureProofWrapper};
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+fn encoded_list(set: &[usize], length: usize) -> String {
+    let mut bits = vec![0u8; length];
+    for i in set {
+        bits[i / 8] |= 0x80 >> (i % 8);
+    }
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&bits).unwrap();
+    format!("u{}", URL_SAFE_NO_PAD.encode(encoder.finish().unwrap()))
+}
+
+async fn sign_list(sk: &SecretKey, encoded_list: &str) -> Result<Vec<StatusListGap>, JsValue> {
+    let request = StatusListSignRequest {
+        secretKey: sk.clone(),
+        encodedList: encoded_list.to_string(),
+    };
+    let gaps = sign_status_list(serde_wasm_bindgen::to_value(&request).unwrap()).await?;
+    Ok(serde_wasm_bindgen::from_value(gaps).unwrap())
+}
+
+fn gap_of(gaps: &[StatusListGap], index: i64) -> StatusListGap {
+    gaps.iter()
+        .find(|g| g.start < index && index < g.end)
+        .unwrap()
+        .clone()
+}
+
+async fn credential(status: Vec<u8>) -> (Signature, PublicKey, Vec<Vec<u8>>) {
+    let (pk, sk) = generate(3).unwrap();
+    let messages = vec![b"Message1".to_vec(), status, b"Message3".to_vec()];
+    let request = BbsSignRequest {
+        keyPair: BbsKeyPair {
+            publicKey: pk.clone(),
+            secretKey: Some(sk),
+            messageCount: 3,
+        },
+        messages: messages.clone(),
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    let signature = serde_wasm_bindgen::from_value(bbs_sign(js_value).await.unwrap()).unwrap();
+    (signature, pk, messages)
+}
+
+async fn prove(
+    signature: &Signature,
+    pk: &PublicKey,
+    messages: &[Vec<u8>],
+    status_list: StatusListProofOptions,
+) -> Result<PoKOfSignatureProofWrapper, JsValue> {
+    let request = CreateProofRequest {
+        signature: signature.clone(),
+        publicKey: pk.clone(),
+        messages: messages.to_vec(),
+        revealed: vec![0],
+        nonce: b"nonce".to_vec(),
+        holderBinding: None,
+        pseudonym: None,
+        accumulator: None,
+        statusList: Some(status_list),
+        verifiableEncryption: None,
+        committed: None,
+        notEqual: None,
+        setMembership: None,
+        rateLimit: None,
+        designatedVerifier: None,
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    Ok(serde_wasm_bindgen::from_value(bbs_create_proof(js_value).await?).unwrap())
+}
+
+async fn verify(
+    proof: &PoKOfSignatureProofWrapper,
+    pk: &PublicKey,
+    status_list: Option<StatusListVerifyOptions>,
+) -> bool {
+    let request = VerifyProofContext {
+        proof: PoKOfSignatureProofWrapper::try_from(proof.to_bytes().as_slice()).unwrap(),
+        publicKey: pk.clone(),
+        messages: vec![b"Message1".to_vec()],
+        nonce: b"nonce".to_vec(),
+        holderBinding: None,
+        pseudonym: None,
+        accumulator: None,
+        statusList: status_list,
+        verifiableEncryption: None,
+        committed: None,
+        notEqual: None,
+        setMembership: None,
+        rateLimit: None,
+        designatedVerifier: None,
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    let result = bbs_verify_proof(js_value).await.unwrap();
+    let result: BbsVerifyResponse = serde_wasm_bindgen::from_value(result).unwrap();
+    result.verified
+}
+
+fn proof_options(
+    dpk: &DeterministicPublicKey,
+    encoded_list: &str,
+    gap: StatusListGap,
+) -> StatusListProofOptions {
+    StatusListProofOptions {
+        statusMessageIndex: 1,
+        encodedList: encoded_list.to_string(),
+        publicKey: dpk.clone(),
+        gap,
+    }
+}
+
+fn verify_options(dpk: &DeterministicPublicKey, encoded_list: &str) -> StatusListVerifyOptions {
+    StatusListVerifyOptions {
+        statusMessageIndex: 1,
+        encodedList: encoded_list.to_string(),
+        publicKey: dpk.clone(),
+    }
+}
+
+#[allow(non_snake_case)]
+#[wasm_bindgen_test]
+pub async fn status_list_tests() {
+    let (dpk, list_sk) = DeterministicPublicKey::new(None);
+    let (signature, pk, messages) = credential(numeric_message(42)).await;
+
+    let list = encoded_list(&[3, 41, 43, 100], 16);
+    let gaps = sign_list(&list_sk, &list).await.unwrap();
+    let bounds: Vec<(i64, i64)> = gaps.iter().map(|g| (g.start, g.end)).collect();
+    assert_eq!(
+        bounds,
+        vec![(-1, 3), (3, 41), (41, 43), (43, 100), (100, 128)]
+    );
+    let gap = gap_of(&gaps, 42);
+    let proof = prove(
+        &signature,
+        &pk,
+        &messages,
+        proof_options(&dpk, &list, gap.clone()),
+    )
+    .await
+    .unwrap();
+    assert!(verify(&proof, &pk, Some(verify_options(&dpk, &list))).await);
+    let unprefixed = list.trim_start_matches('u');
+    assert!(verify(&proof, &pk, Some(verify_options(&dpk, unprefixed))).await);
+
+    // The status proof is part of the proof, which does not verify without it
+    assert!(!verify(&proof, &pk, None).await);
+
+    // The list changed after the proof was created
+    let changed = encoded_list(&[3, 41, 43, 101], 16);
+    assert!(!verify(&proof, &pk, Some(verify_options(&dpk, &changed))).await);
+    let (other_dpk, _) = DeterministicPublicKey::new(None);
+    assert!(!verify(&proof, &pk, Some(verify_options(&other_dpk, &list))).await);
+
+    // The size of the proof does not depend on the number of set bits
+    let crowded: Vec<usize> = (0..128).filter(|i| *i != 42).collect();
+    let crowded = encoded_list(&crowded, 16);
+    let crowded_gaps = sign_list(&list_sk, &crowded).await.unwrap();
+    assert_eq!(crowded_gaps.len(), 1);
+    let crowded_proof = prove(
+        &signature,
+        &pk,
+        &messages,
+        proof_options(&dpk, &crowded, crowded_gaps[0].clone()),
+    )
+    .await
+    .unwrap();
+    assert!(verify(&crowded_proof, &pk, Some(verify_options(&dpk, &crowded))).await);
+    assert_eq!(crowded_proof.to_bytes().len(), proof.to_bytes().len());
+}
+
+#[allow(non_snake_case)]
+#[wasm_bindgen_test]
+pub async fn status_list_rejection_tests() {
+    let (dpk, list_sk) = DeterministicPublicKey::new(None);
+    let (signature, pk, messages) = credential(numeric_message(42)).await;
+    let list = encoded_list(&[3, 41, 43, 100], 16);
+    let gaps = sign_list(&list_sk, &list).await.unwrap();
+
+    // A gap that does not hold the index cannot be used
+    let options = proof_options(&dpk, &list, gap_of(&gaps, 50));
+    assert!(prove(&signature, &pk, &messages, options).await.is_err());
+
+    // A set bit is in no gap, and the gaps of an older list are not signed for it
+    let revoked = encoded_list(&[3, 42], 16);
+    let revoked_gaps = sign_list(&list_sk, &revoked).await.unwrap();
+    assert!(revoked_gaps.iter().all(|g| g.start >= 42 || g.end <= 42));
+    let options = proof_options(&dpk, &revoked, gap_of(&gaps, 42));
+    assert!(prove(&signature, &pk, &messages, options).await.is_err());
+
+    // The index must be a numeric message within the list
+    let (hashed, hashed_pk, hashed_messages) = credential(b"42".to_vec()).await;
+    let options = proof_options(&dpk, &list, gap_of(&gaps, 42));
+    assert!(prove(&hashed, &hashed_pk, &hashed_messages, options)
+        .await
+        .is_err());
+    let (outside, outside_pk, outside_messages) = credential(numeric_message(128)).await;
+    let options = proof_options(&dpk, &list, gap_of(&gaps, 101));
+    assert!(prove(&outside, &outside_pk, &outside_messages, options)
+        .await
+        .is_err());
+
+    // Decompression is capped
+    let oversized = encoded_list(&[], 4 * 1024 * 1024);
+    assert!(sign_list(&list_sk, &oversized).await.is_err());
+}
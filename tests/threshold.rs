@@ -0,0 +1,214 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Test suite for the Web and headless browsers.
+
+#![cfg(target_arch = "wasm32")]
+extern crate wasm_bindgen_test;
+use bbs::prelude::*;
+use wasm::prelude::*;
+use wasm::BbsVerifyResponse;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const THRESHOLD: usize = 2;
+const PARTICIPANTS: usize = 3;
+
+/// Runs the key generation ceremony locally for all participants
+async fn key_gen() -> Vec<ThresholdKeyShare> {
+    let mut dealings = Vec::new();
+    for index in 1..=PARTICIPANTS {
+        let request = ThresholdKeyGenRound1Request {
+            threshold: THRESHOLD,
+            participants: PARTICIPANTS,
+            index,
+        };
+        let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+        let dealing = threshold_key_gen_round_1(js_value).await.unwrap();
+        dealings.push(serde_wasm_bindgen::from_value::<ThresholdKeyDealing>(dealing).unwrap());
+    }
+
+    let mut key_shares = Vec::new();
+    for index in 1..=PARTICIPANTS {
+        let request = ThresholdKeyGenRound2Request {
+            threshold: THRESHOLD,
+            participants: PARTICIPANTS,
+            index,
+            dealings: dealings
+                .iter()
+                .map(|d| ThresholdReceivedKeyDealing {
+                    from: d.index,
+                    commitments: d.commitments.clone(),
+                    share: d.shares[index - 1].clone(),
+                })
+                .collect(),
+        };
+        let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+        let key_share = threshold_key_gen_round_2(js_value).await.unwrap();
+        key_shares.push(serde_wasm_bindgen::from_value::<ThresholdKeyShare>(key_share).unwrap());
+    }
+    key_shares
+}
+
+/// Runs the presigning ceremony locally for all participants
+fn presign(key_shares: &[ThresholdKeyShare]) -> Vec<ThresholdPresignature> {
+    let parties: Vec<usize> = key_shares.iter().map(|k| k.index).collect();
+    let dealings: Vec<ThresholdPresignDealing> = parties
+        .iter()
+        .map(|i| deal_presign_shares(THRESHOLD, *i, &parties).unwrap())
+        .collect();
+
+    let round2: Vec<ThresholdPresignRound2Response> = key_shares
+        .iter()
+        .enumerate()
+        .map(|(k, key_share)| {
+            let shares: Vec<ThresholdPresignShare> =
+                dealings.iter().map(|d| d.shares[k].clone()).collect();
+            reshare_presign_products(THRESHOLD, &parties, key_share, &shares).unwrap()
+        })
+        .collect();
+
+    let round3: Vec<ThresholdPresignRound3Response> = round2
+        .iter()
+        .enumerate()
+        .map(|(k, r)| {
+            let reshares: Vec<ThresholdPresignReshare> =
+                round2.iter().map(|o| o.reshares[k].clone()).collect();
+            reduce_presign_reshares(THRESHOLD, &parties, &r.state, &reshares).unwrap()
+        })
+        .collect();
+
+    let openings: Vec<ThresholdPresignOpening> = round3.iter().map(|r| r.opening.clone()).collect();
+    round3
+        .iter()
+        .map(|r| finalize_presignature(THRESHOLD, &r.state, &openings).unwrap())
+        .collect()
+}
+
+#[allow(non_snake_case)]
+#[wasm_bindgen_test]
+pub async fn threshold_key_gen_tests() {
+    let key_shares = key_gen().await;
+    assert_eq!(key_shares.len(), PARTICIPANTS);
+    for key_share in &key_shares {
+        assert_eq!(key_share.publicKey, key_shares[0].publicKey);
+    }
+
+    // A share that does not match the dealer's commitments is rejected
+    let mut dealings: Vec<ThresholdKeyDealing> = (1..=PARTICIPANTS)
+        .map(|i| deal_key_shares(THRESHOLD, PARTICIPANTS, i).unwrap())
+        .collect();
+    dealings[1].shares[0] = dealings[2].shares[0].clone();
+    let received: Vec<ThresholdReceivedKeyDealing> = dealings
+        .iter()
+        .map(|d| ThresholdReceivedKeyDealing {
+            from: d.index,
+            commitments: d.commitments.clone(),
+            share: d.shares[0].clone(),
+        })
+        .collect();
+    assert!(combine_key_dealings(THRESHOLD, PARTICIPANTS, 1, &received).is_err());
+
+    assert!(deal_key_shares(0, PARTICIPANTS, 1).is_err());
+    assert!(deal_key_shares(PARTICIPANTS + 1, PARTICIPANTS, 1).is_err());
+    assert!(deal_key_shares(THRESHOLD, PARTICIPANTS, PARTICIPANTS + 1).is_err());
+}
+
+#[allow(non_snake_case)]
+#[wasm_bindgen_test]
+pub async fn threshold_sign_tests() {
+    let key_shares = key_gen().await;
+    let public_key = key_shares[0].publicKey.clone();
+    let messages = vec![b"message1".to_vec(), b"message2".to_vec()];
+
+    // Every subset of THRESHOLD parties produces a valid signature
+    for signers in [[0, 1], [0, 2], [1, 2]] {
+        let presignatures = presign(&key_shares);
+        let mut partials = Vec::new();
+        for k in signers {
+            let request = ThresholdPartialSignRequest {
+                presignature: presignatures[k].clone(),
+                publicKey: public_key.clone(),
+                messages: messages.clone(),
+            };
+            let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+            let partial = threshold_partial_sign(js_value).await.unwrap();
+            partials.push(
+                serde_wasm_bindgen::from_value::<ThresholdPartialSignature>(partial).unwrap(),
+            );
+        }
+
+        let request = ThresholdCombineRequest {
+            threshold: THRESHOLD,
+            publicKey: public_key.clone(),
+            messages: messages.clone(),
+            partialSignatures: partials.clone(),
+        };
+        let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+        let signature = threshold_combine_signatures(js_value).await.unwrap();
+        let signature = serde_wasm_bindgen::from_value::<Signature>(signature).unwrap();
+
+        let request = BlsBbsVerifyRequest {
+            publicKey: public_key.clone(),
+            signature,
+            messages: messages.clone(),
+        };
+        let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+        let result = bls_verify(js_value).await.unwrap();
+        let result: BbsVerifyResponse = serde_wasm_bindgen::from_value(result).unwrap();
+        assert!(result.verified);
+
+        // Fewer than THRESHOLD partial signatures cannot be combined
+        let request = ThresholdCombineRequest {
+            threshold: THRESHOLD,
+            publicKey: public_key.clone(),
+            messages: messages.clone(),
+            partialSignatures: partials[..1].to_vec(),
+        };
+        let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+        assert!(threshold_combine_signatures(js_value).await.is_err());
+    }
+
+    // Partial signatures over different messages do not combine
+    let presignatures = presign(&key_shares);
+    let pk = public_key.to_public_key(messages.len()).unwrap();
+    let partials = vec![
+        partial_sign(
+            &presignatures[0],
+            &[
+                SignatureMessage::hash(b"message1"),
+                SignatureMessage::hash(b"message2"),
+            ],
+            &pk,
+        )
+        .unwrap(),
+        partial_sign(
+            &presignatures[1],
+            &[
+                SignatureMessage::hash(b"message1"),
+                SignatureMessage::hash(b"message3"),
+            ],
+            &pk,
+        )
+        .unwrap(),
+    ];
+    let request = ThresholdCombineRequest {
+        threshold: THRESHOLD,
+        publicKey: public_key,
+        messages,
+        partialSignatures: partials,
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    assert!(threshold_combine_signatures(js_value).await.is_err());
+}
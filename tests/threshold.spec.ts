@@ -0,0 +1,148 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+import {
+  thresholdKeyGenRound1,
+  thresholdKeyGenRound2,
+  thresholdPresignRound1,
+  thresholdPresignRound2,
+  thresholdPresignRound3,
+  thresholdPresignFinalize,
+  thresholdPartialSign,
+  thresholdCombineSignatures,
+  blsVerify,
+  ThresholdKeyShare,
+  ThresholdPresignature,
+  ThresholdPartialSignature,
+} from "../lib";
+import { stringToBytes } from "./utilities";
+
+const threshold = 2;
+const participants = 3;
+
+const keyGen = async (): Promise<ThresholdKeyShare[]> => {
+  const indices = [1, 2, 3];
+  const dealings = await Promise.all(
+    indices.map((index) =>
+      thresholdKeyGenRound1({ threshold, participants, index })
+    )
+  );
+  return Promise.all(
+    indices.map((index) =>
+      thresholdKeyGenRound2({
+        threshold,
+        participants,
+        index,
+        dealings: dealings.map((dealing) => ({
+          from: dealing.index,
+          commitments: dealing.commitments,
+          share: dealing.shares[index - 1],
+        })),
+      })
+    )
+  );
+};
+
+const presign = async (
+  keyShares: ThresholdKeyShare[]
+): Promise<ThresholdPresignature[]> => {
+  const parties = keyShares.map((keyShare) => keyShare.index);
+  const dealings = await Promise.all(
+    parties.map((index) =>
+      thresholdPresignRound1({ threshold, index, parties })
+    )
+  );
+  const round2 = await Promise.all(
+    keyShares.map((keyShare, k) =>
+      thresholdPresignRound2({
+        threshold,
+        parties,
+        keyShare,
+        shares: dealings.map((dealing) => dealing.shares[k]),
+      })
+    )
+  );
+  const round3 = await Promise.all(
+    round2.map((response) =>
+      thresholdPresignRound3({
+        threshold,
+        parties,
+        state: response.state,
+        reshares: round2.map(
+          (other) => other.reshares[parties.indexOf(response.state.index)]
+        ),
+      })
+    )
+  );
+  const openings = round3.map((response) => response.opening);
+  return Promise.all(
+    round3.map((response) =>
+      thresholdPresignFinalize({ threshold, state: response.state, openings })
+    )
+  );
+};
+
+describe("thresholdBbs", () => {
+  const messages = [stringToBytes("Message1"), stringToBytes("Message2")];
+  let keyShares: ThresholdKeyShare[];
+
+  beforeAll(async () => {
+    keyShares = await keyGen();
+  });
+
+  it("should generate key shares for one public key", async () => {
+    expect(keyShares).toHaveLength(participants);
+    keyShares.forEach((keyShare) => {
+      expect(keyShare.publicKey).toEqual(keyShares[0].publicKey);
+      expect(keyShare.secretKeyShare).toBeInstanceOf(Uint8Array);
+    });
+  });
+
+  it("should combine threshold partial signatures into a valid signature", async () => {
+    const publicKey = keyShares[0].publicKey;
+    const presignatures = await presign(keyShares);
+    const partialSignatures: ThresholdPartialSignature[] = await Promise.all(
+      [presignatures[0], presignatures[2]].map((presignature) =>
+        thresholdPartialSign({ presignature, publicKey, messages })
+      )
+    );
+    const signature = await thresholdCombineSignatures({
+      threshold,
+      publicKey,
+      messages,
+      partialSignatures,
+    });
+    expect(signature).toBeInstanceOf(Uint8Array);
+    expect(
+      (await blsVerify({ publicKey, signature, messages })).verified
+    ).toBeTruthy();
+
+    await expect(
+      thresholdCombineSignatures({
+        threshold,
+        publicKey,
+        messages,
+        partialSignatures: partialSignatures.slice(0, 1),
+      })
+    ).rejects.toThrowError();
+  });
+
+  it("should throw error for an invalid threshold", async () => {
+    await expect(
+      thresholdKeyGenRound1({ threshold: 0, participants, index: 1 })
+    ).rejects.toThrowError();
+    await expect(
+      thresholdKeyGenRound1({ threshold: 4, participants, index: 1 })
+    ).rejects.toThrowError();
+  });
+});
@@ -0,0 +1,107 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+import {
+  generateBls12381G1KeyPair,
+  generateBls12381G2KeyPair,
+  bls12381toBbs,
+  sign,
+  createProof,
+  verifyProof,
+  encodeNumericMessage,
+  decryptVerifiableEncryption,
+  BbsKeyPair,
+  BlsKeyPair,
+} from "../lib";
+import { stringToBytes } from "./utilities";
+
+describe("verifiableEncryption", () => {
+  let auditor: BlsKeyPair;
+  let keyPair: BbsKeyPair;
+
+  beforeAll(async () => {
+    auditor = await generateBls12381G1KeyPair();
+    keyPair = await bls12381toBbs({
+      keyPair: await generateBls12381G2KeyPair(),
+      messageCount: 3,
+    });
+  });
+
+  const proveAndVerify = async (hidden: Uint8Array) => {
+    const messages = [
+      stringToBytes("Message1"),
+      hidden,
+      stringToBytes("Message3"),
+    ];
+    const verifiableEncryption = {
+      messageIndex: 1,
+      auditorPublicKey: auditor.publicKey,
+    };
+    const proof = await createProof({
+      signature: await sign({ keyPair, messages }),
+      publicKey: keyPair.publicKey,
+      messages,
+      revealed: [0],
+      nonce: stringToBytes("nonce"),
+      verifiableEncryption,
+    });
+    return verifyProof({
+      proof,
+      publicKey: keyPair.publicKey,
+      messages: [messages[0]],
+      nonce: stringToBytes("nonce"),
+      verifiableEncryption,
+    });
+  };
+
+  it("should let the auditor decrypt a numeric message", async () => {
+    const salary = await encodeNumericMessage(-123456789012);
+    const result = await proveAndVerify(salary);
+    expect(result.verified).toBeTruthy();
+    expect(result.ciphertext).toHaveLength(16);
+
+    const decrypted = await decryptVerifiableEncryption({
+      secretKey: auditor.secretKey as Uint8Array,
+      ciphertext: result.ciphertext!,
+    });
+    expect(decrypted.message).toBeInstanceOf(Uint8Array);
+    expect(decrypted.numericMessage).toEqual(salary);
+  });
+
+  it("should let the auditor match a message against candidates", async () => {
+    const result = await proveAndVerify(stringToBytes("did:example:alice"));
+    expect(result.verified).toBeTruthy();
+
+    const decrypted = await decryptVerifiableEncryption({
+      secretKey: auditor.secretKey as Uint8Array,
+      ciphertext: result.ciphertext!,
+      candidates: [
+        stringToBytes("did:example:bob"),
+        stringToBytes("did:example:alice"),
+      ],
+    });
+    expect(decrypted.candidateIndex).toEqual(1);
+    expect(decrypted.numericMessage).toBeUndefined();
+  });
+
+  it("should throw error when decrypting with another key", async () => {
+    const result = await proveAndVerify(await encodeNumericMessage(42));
+    const other = await generateBls12381G1KeyPair();
+    await expect(
+      decryptVerifiableEncryption({
+        secretKey: other.secretKey as Uint8Array,
+        ciphertext: result.ciphertext!,
+      })
+    ).rejects.toThrowError();
+  });
+});
@@ -0,0 +1,195 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Test suite for the Web and headless browsers.
+
+#![cfg(target_arch = "wasm32")]
+extern crate wasm_bindgen_test;
+use bbs::prelude::*;
+use std::convert::{TryFrom, TryInto};
+use wasm::prelude::*;
+use wasm::{BbsVerifyProofResponse, PoKOfSignatureProofWrapper};
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+async fn auditor_key() -> (AuditorPublicKey, SecretKey) {
+    let key: BlsKeyPair = bls_generate_g1_key(None).await.unwrap().try_into().unwrap();
+    (
+        AuditorPublicKey::try_from(key.publicKey.unwrap().as_slice()).unwrap(),
+        key.secretKey.unwrap(),
+    )
+}
+
+async fn prove(
+    messages: &[Vec<u8>],
+    auditor_public_key: &AuditorPublicKey,
+) -> (PoKOfSignatureProofWrapper, PublicKey) {
+    let (pk, sk) = generate(messages.len()).unwrap();
+    let request = BbsSignRequest {
+        keyPair: BbsKeyPair {
+            publicKey: pk.clone(),
+            secretKey: Some(sk),
+            messageCount: messages.len(),
+        },
+        messages: messages.to_vec(),
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    let signature: Signature =
+        serde_wasm_bindgen::from_value(bbs_sign(js_value).await.unwrap()).unwrap();
+
+    let request = CreateProofRequest {
+        signature,
+        publicKey: pk.clone(),
+        messages: messages.to_vec(),
+        revealed: vec![0],
+        nonce: b"nonce".to_vec(),
+        holderBinding: None,
+        pseudonym: None,
+        accumulator: None,
+        statusList: None,
+        verifiableEncryption: Some(VerifiableEncryptionOptions {
+            messageIndex: 1,
+            auditorPublicKey: *auditor_public_key,
+        }),
+        committed: None,
+        notEqual: None,
+        setMembership: None,
+        rateLimit: None,
+        designatedVerifier: None,
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    let proof = serde_wasm_bindgen::from_value(bbs_create_proof(js_value).await.unwrap()).unwrap();
+    (proof, pk)
+}
+
+async fn verify(
+    proof: &PoKOfSignatureProofWrapper,
+    pk: &PublicKey,
+    auditor_public_key: Option<&AuditorPublicKey>,
+) -> BbsVerifyProofResponse {
+    let request = VerifyProofContext {
+        proof: PoKOfSignatureProofWrapper::try_from(proof.to_bytes().as_slice()).unwrap(),
+        publicKey: pk.clone(),
+        messages: vec![b"Message1".to_vec()],
+        nonce: b"nonce".to_vec(),
+        holderBinding: None,
+        pseudonym: None,
+        accumulator: None,
+        statusList: None,
+        verifiableEncryption: auditor_public_key.map(|key| VerifiableEncryptionOptions {
+            messageIndex: 1,
+            auditorPublicKey: *key,
+        }),
+        committed: None,
+        notEqual: None,
+        setMembership: None,
+        rateLimit: None,
+        designatedVerifier: None,
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    serde_wasm_bindgen::from_value(bbs_verify_proof(js_value).await.unwrap()).unwrap()
+}
+
+async fn decrypt(
+    secret_key: &SecretKey,
+    ciphertext: &[ElGamalCiphertext],
+    candidates: Option<Vec<Vec<u8>>>,
+) -> Result<VerifiableEncryptionDecryptResponse, JsValue> {
+    let request = VerifiableEncryptionDecryptRequest {
+        secretKey: secret_key.clone(),
+        ciphertext: ciphertext.to_vec(),
+        candidates,
+    };
+    let js_value = serde_wasm_bindgen::to_value(&request).unwrap();
+    Ok(decrypt_verifiable_encryption(js_value)
+        .await?
+        .try_into()
+        .unwrap())
+}
+
+#[allow(non_snake_case)]
+#[wasm_bindgen_test]
+pub async fn verifiable_encryption_tests() {
+    let (auditor_public_key, auditor_secret_key) = auditor_key().await;
+    let salary = numeric_message(-123456789012);
+    let messages = vec![b"Message1".to_vec(), salary.clone(), b"Message3".to_vec()];
+    let (proof, pk) = prove(&messages, &auditor_public_key).await;
+
+    let result = verify(&proof, &pk, Some(&auditor_public_key)).await;
+    assert!(result.verified);
+    let ciphertext = result.ciphertext.unwrap();
+    assert_eq!(ciphertext.len(), 16);
+
+    // The auditor recovers a numeric message in full
+    let decrypted = decrypt(&auditor_secret_key, &ciphertext, None)
+        .await
+        .unwrap();
+    assert_eq!(decrypted.numericMessage, Some(salary));
+    assert_eq!(decrypted.candidateIndex, None);
+
+    // The ciphertext is bound to the auditor and part of the proof
+    let (other_public_key, other_secret_key) = auditor_key().await;
+    let result = verify(&proof, &pk, Some(&other_public_key)).await;
+    assert!(!result.verified);
+    assert!(result.ciphertext.is_none());
+    assert!(!verify(&proof, &pk, None).await.verified);
+    assert!(decrypt(&other_secret_key, &ciphertext, None).await.is_err());
+
+    // A ciphertext that is not the one proven does not verify
+    let mut forged = PoKOfSignatureProofWrapper::try_from(proof.to_bytes().as_slice()).unwrap();
+    let masked_message = G1_COMPRESSED_SIZE..2 * G1_COMPRESSED_SIZE;
+    forged.statements[masked_message]
+        .copy_from_slice(&auditor_public_key.to_bytes_compressed_form());
+    assert!(
+        !verify(&forged, &pk, Some(&auditor_public_key))
+            .await
+            .verified
+    );
+}
+
+#[allow(non_snake_case)]
+#[wasm_bindgen_test]
+pub async fn verifiable_encryption_hashed_message_tests() {
+    let (auditor_public_key, auditor_secret_key) = auditor_key().await;
+    let messages = vec![
+        b"Message1".to_vec(),
+        b"national id 123456".to_vec(),
+        b"Message3".to_vec(),
+    ];
+    let (proof, pk) = prove(&messages, &auditor_public_key).await;
+    let result = verify(&proof, &pk, Some(&auditor_public_key)).await;
+    assert!(result.verified);
+
+    // Any other message is recovered as its hash, identified among candidates
+    let candidates = vec![
+        b"national id 000000".to_vec(),
+        b"national id 123456".to_vec(),
+    ];
+    let decrypted = decrypt(
+        &auditor_secret_key,
+        &result.ciphertext.unwrap(),
+        Some(candidates),
+    )
+    .await
+    .unwrap();
+    assert_eq!(decrypted.message, SignatureMessage::hash(&messages[1]));
+    assert_eq!(decrypted.numericMessage, None);
+    assert_eq!(decrypted.candidateIndex, Some(1));
+
+    // The identity is not an auditor public key
+    let mut identity = [0u8; G1_COMPRESSED_SIZE];
+    identity[0] = 0xc0;
+    assert!(AuditorPublicKey::try_from(&identity[..]).is_err());
+}
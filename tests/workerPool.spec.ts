@@ -0,0 +1,135 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+import * as bbs from "../lib";
+import {
+  createWorkerPool,
+  generateBls12381G2KeyPair,
+  bls12381toBbs,
+  BbsWorkerPool,
+} from "../lib";
+import { stringToBytes } from "./utilities";
+
+interface WorkerMessage {
+  id: number;
+  operation: string;
+  request: unknown;
+}
+
+interface Operations {
+  [name: string]: (request: unknown) => Promise<unknown>;
+}
+
+interface GlobalScope {
+  Worker?: unknown;
+}
+
+// Stands in for a dedicated worker running worker.js, serving the
+// operations on the main thread
+class FakeWorker {
+  onmessage?: (event: { data: unknown }) => void;
+  onerror?: (error: Error) => void;
+  broken: boolean;
+  terminated = false;
+
+  constructor(url: string) {
+    // A worker whose script fails to load never answers and raises an error
+    this.broken = url === "broken.js";
+    if (this.broken) {
+      setTimeout(() => this.onerror?.(new Error("Failed to load worker")));
+    }
+  }
+
+  postMessage({ id, operation, request }: WorkerMessage): void {
+    if (this.broken) {
+      return;
+    }
+    const operations = bbs as unknown as Operations;
+    operations[operation](request).then(
+      (result) => this.onmessage?.({ data: { id, result } }),
+      (ex: Error) => this.onmessage?.({ data: { id, error: ex.message } })
+    );
+  }
+
+  terminate(): void {
+    this.terminated = true;
+  }
+}
+
+describe("workerPool", () => {
+  const messages = [stringToBytes("Message1"), stringToBytes("Message2")];
+  const globalScope = global as unknown as GlobalScope;
+
+  afterEach(() => {
+    delete globalScope.Worker;
+  });
+
+  it("should throw error when Web Workers are not supported", () => {
+    expect(() => createWorkerPool({ workerUrl: "worker.js" })).toThrowError(
+      "ERROR: Web Workers are not supported in this environment"
+    );
+  });
+
+  it("should run operations on the workers", async () => {
+    globalScope.Worker = FakeWorker;
+    const pool: BbsWorkerPool = createWorkerPool({
+      workerUrl: "worker.js",
+      size: 3,
+    });
+    expect(pool.size).toEqual(3);
+
+    const keyPair = await bls12381toBbs({
+      keyPair: await generateBls12381G2KeyPair(),
+      messageCount: 2,
+    });
+    const signatures = await Promise.all(
+      [0, 1, 2, 3].map(() => pool.sign({ keyPair, messages }))
+    );
+    for (const signature of signatures) {
+      expect(
+        (
+          await pool.verify({
+            publicKey: keyPair.publicKey,
+            signature,
+            messages,
+          })
+        ).verified
+      ).toBeTruthy();
+    }
+    await expect(
+      pool.sign({
+        keyPair: { publicKey: keyPair.publicKey, messageCount: 2 },
+        messages,
+      })
+    ).rejects.toThrowError();
+
+    await pool.terminate();
+    await expect(pool.sign({ keyPair, messages })).rejects.toThrowError(
+      "ERROR: The worker pool was terminated"
+    );
+  });
+
+  it("should reject operations once every worker failed", async () => {
+    globalScope.Worker = FakeWorker;
+    const keyPair = await generateBls12381G2KeyPair();
+    const pool = createWorkerPool({ workerUrl: "broken.js", size: 2 });
+    await expect(pool.blsSign({ keyPair, messages })).rejects.toThrowError(
+      "Failed to load worker"
+    );
+    // Once both workers raised their errors
+    await new Promise((resolve) => setTimeout(resolve));
+    await expect(pool.blsSign({ keyPair, messages })).rejects.toThrowError(
+      "ERROR: Every worker of the worker pool failed"
+    );
+  });
+});
@@ -0,0 +1,68 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// Minimal CommonJS loader so the built package in `lib` can be exercised in
+// a browser page and its workers without a bundler
+(function (scope) {
+  const cache = {};
+
+  // Packages the built lib depends on that are stubbed with browser natives
+  const builtins = {
+    "@stablelib/random": {
+      randomBytes: (length) =>
+        scope.crypto.getRandomValues(new Uint8Array(length)),
+    },
+  };
+
+  // Only the base64 decoding used by the packed WASM bytes is required
+  const Buffer = {
+    from: (value, encoding) => {
+      if (encoding !== "base64") {
+        throw new Error(`Unsupported encoding ${encoding}`);
+      }
+      return Uint8Array.from(scope.atob(value), (c) => c.charCodeAt(0));
+    },
+  };
+
+  const resolve = (base, path) => {
+    const url = new URL(path, new URL(base, scope.location.href));
+    return url.pathname.endsWith(".js") ? url.href : `${url.href}.js`;
+  };
+
+  const load = (url) => {
+    if (cache[url]) {
+      return cache[url].exports;
+    }
+    const request = new XMLHttpRequest();
+    request.open("GET", url, false);
+    request.send();
+    if (request.status !== 200) {
+      throw new Error(`Failed to load ${url}`);
+    }
+    const module = { exports: {} };
+    cache[url] = module;
+    const require = (path) => builtins[path] || load(resolve(url, path));
+    const factory = new Function(
+      "module",
+      "exports",
+      "require",
+      "Buffer",
+      request.responseText
+    );
+    factory(module, module.exports, require, Buffer);
+    return module.exports;
+  };
+
+  scope.requireModule = (path) =>
+    builtins[path] || load(resolve(scope.location.href, path));
+})(self);
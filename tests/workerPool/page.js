@@ -0,0 +1,131 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+const {
+  createWorkerPool,
+  generateBls12381G2KeyPair,
+  blsVerify,
+} = requireModule("/lib/index.web.js");
+
+const stringToBytes = (str) => new TextEncoder().encode(str);
+
+const tests = {
+  "should sign and verify in workers": async (pool) => {
+    const keyPair = await pool.generateBls12381G2KeyPair();
+    const messages = [stringToBytes("message1"), stringToBytes("message2")];
+    const signature = await pool.blsSign({ keyPair, messages });
+    const result = await pool.blsVerify({
+      publicKey: keyPair.publicKey,
+      messages,
+      signature,
+    });
+    return signature instanceof Uint8Array && result.verified;
+  },
+  "should produce signatures the main thread can verify": async (pool) => {
+    const keyPair = await generateBls12381G2KeyPair();
+    const messages = [stringToBytes("message1")];
+    const signature = await pool.blsSign({ keyPair, messages });
+    const result = await blsVerify({
+      publicKey: keyPair.publicKey,
+      messages,
+      signature,
+    });
+    return result.verified;
+  },
+  "should create and verify proofs concurrently": async (pool) => {
+    const keyPair = await generateBls12381G2KeyPair();
+    const messages = [...Array(10).keys()].map((i) =>
+      stringToBytes(`message${i}`)
+    );
+    const signature = await pool.blsSign({ keyPair, messages });
+    const nonce = stringToBytes("nonce");
+    const proofs = await Promise.all(
+      [0, 1, 2, 3].map((i) =>
+        pool.blsCreateProof({
+          signature,
+          publicKey: keyPair.publicKey,
+          messages,
+          nonce,
+          revealed: [i],
+        })
+      )
+    );
+    const results = await Promise.all(
+      proofs.map((proof, i) =>
+        pool.blsVerifyProof({
+          proof,
+          publicKey: keyPair.publicKey,
+          messages: [messages[i]],
+          nonce,
+        })
+      )
+    );
+    return results.every((result) => result.verified);
+  },
+  "should reject when an operation fails": async (pool) => {
+    try {
+      const { publicKey } = await pool.generateBls12381G2KeyPair();
+      await pool.blsSign({
+        keyPair: { publicKey },
+        messages: [stringToBytes("message1")],
+      });
+      return false;
+    } catch (ex) {
+      return ex instanceof Error;
+    }
+  },
+  "should drop workers that fail to load": async () => {
+    const pool = createWorkerPool({ workerUrl: "./missing.js", size: 2 });
+    try {
+      await pool.generateBls12381G2KeyPair();
+      return false;
+    } catch (ex) {
+      try {
+        await pool.generateBls12381G2KeyPair();
+        return false;
+      } catch (ex) {
+        return ex instanceof Error;
+      }
+    } finally {
+      await pool.terminate();
+    }
+  },
+  "should reject operations after being terminated": async () => {
+    const pool = createWorkerPool({ workerUrl: "./worker.js", size: 1 });
+    await pool.terminate();
+    try {
+      await pool.generateBls12381G2KeyPair();
+      return false;
+    } catch (ex) {
+      return ex instanceof Error;
+    }
+  },
+};
+
+const main = async () => {
+  const pool = createWorkerPool({ workerUrl: "./worker.js", size: 2 });
+  const results = {};
+  for (const [name, test] of Object.entries(tests)) {
+    try {
+      results[name] = (await test(pool)) ? "passed" : "failed";
+    } catch (ex) {
+      results[name] = `failed: ${ex.message}`;
+    }
+  }
+  await pool.terminate();
+  document.getElementById("result").textContent = JSON.stringify(results);
+  // Report back to the harness that launched this page
+  await fetch("/results", { method: "POST", body: JSON.stringify(results) });
+};
+
+main();
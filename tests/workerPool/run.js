@@ -0,0 +1,102 @@
+/*
+ * Copyright 2020 - MATTR Limited
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// Serves the built package and the worker pool test page, then drives the
+// page with a local headless browser and reports the results it posts back
+const { spawn, spawnSync } = require("child_process");
+const fs = require("fs");
+const http = require("http");
+const os = require("os");
+const path = require("path");
+
+const ROOT = path.resolve(__dirname, "../..");
+const TIMEOUT = 120000;
+const CONTENT_TYPES = {
+  ".html": "text/html",
+  ".js": "application/javascript",
+};
+
+const isInstalled = (name) =>
+  spawnSync("which", [name], { stdio: "ignore" }).status === 0;
+
+const browser =
+  process.env.CHROME_BIN ||
+  ["google-chrome", "chromium", "chromium-browser"].find(isInstalled);
+
+if (!browser) {
+  console.error("No headless browser found, set CHROME_BIN");
+  process.exit(1);
+}
+
+if (!fs.existsSync(path.join(ROOT, "lib", "worker.js"))) {
+  console.error("Package not built, run `yarn build` first");
+  process.exit(1);
+}
+
+let chrome;
+
+const finish = (code) => {
+  if (chrome) {
+    chrome.kill();
+  }
+  server.close();
+  process.exit(code);
+};
+
+const server = http.createServer((req, res) => {
+  if (req.method === "POST" && req.url === "/results") {
+    let body = "";
+    req.on("data", (chunk) => (body += chunk));
+    req.on("end", () => {
+      res.end();
+      const results = JSON.parse(body);
+      let failed = 0;
+      for (const [name, result] of Object.entries(results)) {
+        const passed = result === "passed";
+        console.log(`${passed ? "✓" : "✕"} ${name} (${result})`);
+        if (!passed) {
+          failed++;
+        }
+      }
+      finish(failed === 0 ? 0 : 1);
+    });
+    return;
+  }
+  const file = path.join(ROOT, decodeURIComponent(req.url.split("?")[0]));
+  if (!file.startsWith(ROOT) || !fs.existsSync(file)) {
+    res.statusCode = 404;
+    res.end();
+    return;
+  }
+  res.setHeader(
+    "Content-Type",
+    CONTENT_TYPES[path.extname(file)] || "application/octet-stream"
+  );
+  fs.createReadStream(file).pipe(res);
+});
+
+server.listen(0, "127.0.0.1", () => {
+  const { port } = server.address();
+  chrome = spawn(browser, [
+    "--headless=new",
+    "--disable-gpu",
+    "--no-sandbox",
+    "--no-first-run",
+    `--user-data-dir=${fs.mkdtempSync(path.join(os.tmpdir(), "bbs-"))}`,
+    `http://127.0.0.1:${port}/tests/workerPool/index.html`,
+  ]);
+  setTimeout(() => {
+    console.error("Timed out waiting for worker pool test results");
+    finish(1);
+  }, TIMEOUT);
+});